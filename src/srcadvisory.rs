@@ -0,0 +1,425 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+extern crate alloc; // String, Vec için
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+use core::cmp::Ordering;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu serileştirme/deserileştirme
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+// Güvenlik bildirisi akışı. Depo, yayınlanırken paket dizinindeki akışı üretim ve geçerlilik zamanlarıyla
+// damgalayıp indeksin yanına "guvenlik_bildirileri.bin" olarak koyar ve paketler.bin gibi ayrık bir imzayla imzalar
+// (bkz. DepoYayinlayici::yayinla, DepoYoneticisi::guvenlik_bildirilerini_al). Süresi dolmuş bir akış kabul
+// edilmez; böylece eski (ör. boş) ama imzalı bir akışın yeniden sunulması bildirileri gizleyemez.
+// Şema OSV'yi izler: her bildiri, etkilenen paketleri sürüm aralıkları (giriş / düzeltildi / son etkilenen)
+// veya tek tek sürümlerle tanımlar. `audit` komutu kurulu paketleri ve planlanan işlemleri bu akışla
+// denetler; kurulum politikası (guvenlik.kurulum_politikasi) bilinen açıklı sürümlerin kurulmasını engeller.
+
+// Bir bildirinin önem derecesi. Sıralama karşılaştırması eşik filtrelemesinde kullanılır.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Onem {
+    Dusuk,
+    Orta,
+    Yuksek,
+    Kritik,
+}
+
+impl Onem {
+    // Türkçe adları ve OSV/CVSS'teki İngilizce karşılıklarını kabul eder.
+    pub fn ayristir(metin: &str) -> Option<Onem> {
+        match metin.trim().to_ascii_lowercase().as_str() {
+            "dusuk" | "low" => Some(Onem::Dusuk),
+            "orta" | "medium" | "moderate" => Some(Onem::Orta),
+            "yuksek" | "high" => Some(Onem::Yuksek),
+            "kritik" | "critical" => Some(Onem::Kritik),
+            _ => None,
+        }
+    }
+
+    pub fn ad(self) -> &'static str {
+        match self {
+            Onem::Dusuk => "dusuk",
+            Onem::Orta => "orta",
+            Onem::Yuksek => "yuksek",
+            Onem::Kritik => "kritik",
+        }
+    }
+}
+
+impl fmt::Display for Onem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.ad())
+    }
+}
+
+// Kurulum sırasında akışla eşleşen sürümlere ne yapılacağı (guvenlik.kurulum_politikasi).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KurulumPolitikasi {
+    Kapali, // Akış hiç okunmaz
+    Uyar, // Bulgular yazdırılır, kurulum sürer
+    Engelle, // Eşiği aşan bulgu varsa kurulum yapılmaz
+}
+
+impl KurulumPolitikasi {
+    pub fn ayristir(metin: &str) -> Option<KurulumPolitikasi> {
+        match metin.trim() {
+            "kapali" => Some(KurulumPolitikasi::Kapali),
+            "uyar" => Some(KurulumPolitikasi::Uyar),
+            "engelle" => Some(KurulumPolitikasi::Engelle),
+            _ => None,
+        }
+    }
+}
+
+// Etkilenen sürüm aralığı. Sürüm, giriş <= sürüm < düzeltildi ve sürüm <= son_etkilenen ise etkilenir;
+// verilmeyen sınır kısıt getirmez.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Aralik {
+    pub giris: Option<String>,
+    pub duzeltildi: Option<String>,
+    pub son_etkilenen: Option<String>,
+}
+
+impl Aralik {
+    pub fn icerir(&self, surum: &str) -> bool {
+        let giris_tamam = self.giris.as_deref().map_or(true, |g| surum_karsilastir(surum, g) != Ordering::Less);
+        let duzeltildi_tamam = self.duzeltildi.as_deref().map_or(true, |d| surum_karsilastir(surum, d) == Ordering::Less);
+        let son_tamam = self.son_etkilenen.as_deref().map_or(true, |s| surum_karsilastir(surum, s) != Ordering::Greater);
+        giris_tamam && duzeltildi_tamam && son_tamam
+    }
+}
+
+// Bir bildirinin etkilediği paket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EtkilenenPaket {
+    pub paket: String,
+    pub araliklar: Vec<Aralik>,
+    #[serde(default)]
+    pub surumler: Vec<String>, // Aralık dışında tek tek listelenen etkilenen sürümler
+}
+
+impl EtkilenenPaket {
+    pub fn etkiler_mi(&self, surum: &str) -> bool {
+        self.surumler.iter().any(|s| surum_karsilastir(s, surum) == Ordering::Equal)
+            || self.araliklar.iter().any(|a| a.icerir(surum))
+    }
+
+    // Sürümü içeren aralığın düzeltildiği sürüm (bilinmiyorsa None).
+    pub fn duzeltilen_surum(&self, surum: &str) -> Option<&str> {
+        self.araliklar.iter().filter(|a| a.icerir(surum)).find_map(|a| a.duzeltildi.as_deref())
+    }
+}
+
+// Tek bir güvenlik bildirisi.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Bildiri {
+    pub kimlik: String, // Örn. "SAHNE-2024-0001"
+    #[serde(default)]
+    pub diger_adlar: Vec<String>, // Örn. CVE kimlikleri
+    pub ozet: String,
+    pub onem: Onem,
+    pub etkilenenler: Vec<EtkilenenPaket>,
+    #[serde(default)]
+    pub referanslar: Vec<String>,
+}
+
+// Bir paket sürümünün bir bildiriyle eşleşmesi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bulgu {
+    pub paket: String,
+    pub surum: String,
+    pub kimlik: String,
+    pub diger_adlar: Vec<String>,
+    pub onem: Onem,
+    pub ozet: String,
+    pub duzeltildi: Option<String>,
+}
+
+// Yayınlayıcının akışa varsayılan olarak verdiği geçerlilik süresi (7 gün).
+pub const VARSAYILAN_GECERLILIK_SN: u64 = 7 * 24 * 60 * 60;
+// Üretim zamanı istemci saatinin bu kadar ilerisinde olabilir (saat kayması payı, mikrosaniye).
+const SAAT_KAYMASI_PAYI_US: u64 = 5 * 60 * 1_000_000;
+
+// Deponun yayınladığı bildirilerin tamamı.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct BildiriAkisi {
+    pub bildiriler: Vec<Bildiri>,
+    // Yayınlayıcının akışı damgaladığı zaman ve akışın geçerli sayıldığı son an (mikrosaniye).
+    // Yayınlanırken doldurulur (bkz. damgala); kaynak akıştaki değerler yok sayılır.
+    pub uretildi_us: u64,
+    pub son_gecerlilik_us: u64,
+}
+
+impl BildiriAkisi {
+    // Akışı üretim zamanı ve geçerlilik süresiyle damgalar.
+    pub fn damgala(&mut self, simdi_us: u64, gecerlilik_sn: u64) {
+        self.uretildi_us = simdi_us;
+        self.son_gecerlilik_us = simdi_us.saturating_add(gecerlilik_sn.saturating_mul(1_000_000));
+    }
+
+    // Akışın verilen anda geçerli olup olmadığını denetler. Damgasız, süresi dolmuş veya (saat kayması
+    // payından fazla) gelecekte üretilmiş akışlar reddedilir.
+    pub fn tazelik_denetle(&self, simdi_us: u64) -> Result<(), String> {
+        if self.son_gecerlilik_us <= self.uretildi_us {
+            return Err(String::from("akışın geçerlilik süresi yok"));
+        }
+        if self.uretildi_us > simdi_us.saturating_add(SAAT_KAYMASI_PAYI_US) {
+            return Err(String::from("akışın üretim zamanı gelecekte"));
+        }
+        if simdi_us >= self.son_gecerlilik_us {
+            return Err(format!(
+                "akışın süresi {} sn önce doldu", (simdi_us - self.son_gecerlilik_us) / 1_000_000
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn cozumle(veri: &[u8]) -> Result<BildiriAkisi, PaketYoneticisiHatasi> {
+        postcard::from_bytes_copy::<BildiriAkisi>(veri).map_err(PaketYoneticisiHatasi::DeserializationError)
+    }
+
+    // Yayınlamadan önce akışın tutarlılığını denetler: kimlikler boş olmamalı ve tekrarlanmamalı,
+    // her etkilenen paket en az bir aralık veya sürüm içermeli.
+    pub fn dogrula(&self) -> Result<(), String> {
+        let mut kimlikler: Vec<&str> = Vec::new();
+        for bildiri in &self.bildiriler {
+            if bildiri.kimlik.trim().is_empty() {
+                return Err(String::from("kimliği boş bir bildiri var"));
+            }
+            if kimlikler.contains(&bildiri.kimlik.as_str()) {
+                return Err(format!("'{}' kimliği birden fazla kez kullanılmış", bildiri.kimlik));
+            }
+            kimlikler.push(&bildiri.kimlik);
+            if bildiri.etkilenenler.is_empty() {
+                return Err(format!("'{}' hiçbir paketi etkilemiyor", bildiri.kimlik));
+            }
+            for etkilenen in &bildiri.etkilenenler {
+                if etkilenen.araliklar.is_empty() && etkilenen.surumler.is_empty() {
+                    return Err(format!("'{}' bildirisinde '{}' için aralık veya sürüm yok", bildiri.kimlik, etkilenen.paket));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Verilen paket sürümünü etkileyen, önemi en az asgari_onem olan bildiriler.
+    pub fn eslesenler(&self, paket: &str, surum: &str, asgari_onem: Onem) -> Vec<Bulgu> {
+        let mut bulgular = Vec::new();
+        for bildiri in self.bildiriler.iter().filter(|b| b.onem >= asgari_onem) {
+            for etkilenen in bildiri.etkilenenler.iter().filter(|e| e.paket == paket) {
+                if etkilenen.etkiler_mi(surum) {
+                    bulgular.push(Bulgu {
+                        paket: String::from(paket),
+                        surum: String::from(surum),
+                        kimlik: bildiri.kimlik.clone(),
+                        diger_adlar: bildiri.diger_adlar.clone(),
+                        onem: bildiri.onem,
+                        ozet: bildiri.ozet.clone(),
+                        duzeltildi: etkilenen.duzeltilen_surum(surum).map(String::from),
+                    });
+                    break; // Aynı bildiri bir paket sürümü için bir kez raporlanır
+                }
+            }
+        }
+        bulgular
+    }
+
+    // (paket, sürüm) çiftlerinin tümünü denetler. Bulgular önem derecesine göre azalan sıradadır.
+    pub fn denetle<'a, I>(&self, paketler: I, asgari_onem: Onem) -> Vec<Bulgu>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut bulgular: Vec<Bulgu> = paketler
+            .into_iter()
+            .flat_map(|(paket, surum)| self.eslesenler(paket, surum, asgari_onem))
+            .collect();
+        bulgular.sort_by(|a, b| b.onem.cmp(&a.onem).then_with(|| a.paket.cmp(&b.paket)).then_with(|| a.kimlik.cmp(&b.kimlik)));
+        bulgular
+    }
+}
+
+// İki sürüm dizesini karşılaştırır. Sürüm, ayraçlarla ('.', '-', '_', '+', '~') bölünmüş sayısal ve
+// alfabetik parçalar olarak ele alınır: sayılar sayı olarak, harfler sözlük sırasıyla karşılaştırılır.
+// Biri bittiğinde diğerinde kalan sayısal parça sürümü büyütür ("1.0" < "1.0.1"), alfabetik parça ise
+// ön sürüm sayılır ve küçültür ("1.0rc1" < "1.0").
+pub fn surum_karsilastir(a: &str, b: &str) -> Ordering {
+    let mut a = SurumParcalari::yeni(a);
+    let mut b = SurumParcalari::yeni(b);
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(p), None) => return if p.sayisal_mi() { Ordering::Greater } else { Ordering::Less },
+            (None, Some(p)) => return if p.sayisal_mi() { Ordering::Less } else { Ordering::Greater },
+            (Some(x), Some(y)) => {
+                let sonuc = x.karsilastir(&y);
+                if sonuc != Ordering::Equal {
+                    return sonuc;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Parca<'a> {
+    Sayi(&'a str), // Baştaki sıfırlar atılmış rakamlar ("0" -> "")
+    Metin(&'a str),
+}
+
+impl Parca<'_> {
+    fn sayisal_mi(&self) -> bool {
+        matches!(self, Parca::Sayi(_))
+    }
+
+    fn karsilastir(&self, diger: &Parca<'_>) -> Ordering {
+        match (self, diger) {
+            // Baştaki sıfırlar atıldığı için önce uzunluk, sonra rakamlar karşılaştırılır (taşma olmaz).
+            (Parca::Sayi(x), Parca::Sayi(y)) => x.len().cmp(&y.len()).then_with(|| x.cmp(y)),
+            (Parca::Metin(x), Parca::Metin(y)) => x.cmp(y),
+            (Parca::Sayi(_), Parca::Metin(_)) => Ordering::Greater,
+            (Parca::Metin(_), Parca::Sayi(_)) => Ordering::Less,
+        }
+    }
+}
+
+struct SurumParcalari<'a> {
+    kalan: &'a str,
+}
+
+impl<'a> SurumParcalari<'a> {
+    fn yeni(surum: &'a str) -> Self {
+        SurumParcalari { kalan: surum.trim() }
+    }
+}
+
+impl<'a> Iterator for SurumParcalari<'a> {
+    type Item = Parca<'a>;
+
+    fn next(&mut self) -> Option<Parca<'a>> {
+        self.kalan = self.kalan.trim_start_matches(['.', '-', '_', '+', '~']);
+        let ilk = self.kalan.chars().next()?;
+        let sayisal = ilk.is_ascii_digit();
+        let son = self.kalan
+            .find(|c: char| matches!(c, '.' | '-' | '_' | '+' | '~') || c.is_ascii_digit() != sayisal)
+            .unwrap_or(self.kalan.len());
+        let (parca, kalan) = self.kalan.split_at(son);
+        self.kalan = kalan;
+        Some(if sayisal { Parca::Sayi(parca.trim_start_matches('0')) } else { Parca::Metin(parca) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aralik(giris: Option<&str>, duzeltildi: Option<&str>, son_etkilenen: Option<&str>) -> Aralik {
+        Aralik {
+            giris: giris.map(String::from),
+            duzeltildi: duzeltildi.map(String::from),
+            son_etkilenen: son_etkilenen.map(String::from),
+        }
+    }
+
+    fn bildiri(kimlik: &str, onem: Onem, paket: &str, araliklar: Vec<Aralik>) -> Bildiri {
+        Bildiri {
+            kimlik: String::from(kimlik),
+            diger_adlar: Vec::new(),
+            ozet: String::from("örnek"),
+            onem,
+            etkilenenler: alloc::vec![EtkilenenPaket { paket: String::from(paket), araliklar, surumler: Vec::new() }],
+            referanslar: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_surum_karsilastir() {
+        assert_eq!(surum_karsilastir("1.2.3", "1.2.3"), Ordering::Equal);
+        assert_eq!(surum_karsilastir("1.2", "1.10"), Ordering::Less);
+        assert_eq!(surum_karsilastir("1.0", "1.0.1"), Ordering::Less);
+        assert_eq!(surum_karsilastir("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(surum_karsilastir("1.0-rc2", "1.0-rc10"), Ordering::Less);
+        assert_eq!(surum_karsilastir("1.01", "1.1"), Ordering::Equal);
+        assert_eq!(surum_karsilastir("2.0", "10.0"), Ordering::Less);
+        assert_eq!(surum_karsilastir("99999999999999999999999", "1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_aralik_eslesmesi() {
+        let a = aralik(Some("1.0"), Some("1.4.2"), None);
+        assert!(!a.icerir("0.9"));
+        assert!(a.icerir("1.0"));
+        assert!(a.icerir("1.4.1"));
+        assert!(!a.icerir("1.4.2"));
+
+        let b = aralik(None, None, Some("2.0"));
+        assert!(b.icerir("2.0"));
+        assert!(!b.icerir("2.0.1"));
+
+        let etkilenen = EtkilenenPaket {
+            paket: String::from("openssl"),
+            araliklar: alloc::vec![a],
+            surumler: alloc::vec![String::from("3.0.0")],
+        };
+        assert!(etkilenen.etkiler_mi("3.0.0"));
+        assert_eq!(etkilenen.duzeltilen_surum("1.2"), Some("1.4.2"));
+        assert_eq!(etkilenen.duzeltilen_surum("3.0.0"), None);
+    }
+
+    #[test]
+    fn test_onem_filtresi_ve_siralama() {
+        let akis = BildiriAkisi {
+            bildiriler: alloc::vec![
+                bildiri("SAHNE-1", Onem::Dusuk, "zlib", alloc::vec![aralik(None, Some("1.3"), None)]),
+                bildiri("SAHNE-2", Onem::Kritik, "zlib", alloc::vec![aralik(Some("1.2"), Some("1.2.13"), None)]),
+                bildiri("SAHNE-3", Onem::Yuksek, "curl", alloc::vec![aralik(None, Some("8.0"), None)]),
+            ],
+            ..BildiriAkisi::default()
+        };
+        assert!(akis.dogrula().is_ok());
+
+        let hepsi = akis.denetle([("zlib", "1.2.11"), ("curl", "7.88")], Onem::Dusuk);
+        let kimlikler: Vec<&str> = hepsi.iter().map(|b| b.kimlik.as_str()).collect();
+        assert_eq!(kimlikler, ["SAHNE-2", "SAHNE-3", "SAHNE-1"]);
+        assert_eq!(hepsi[0].duzeltildi.as_deref(), Some("1.2.13"));
+
+        let yuksek = akis.denetle([("zlib", "1.2.11"), ("curl", "8.1")], Onem::Yuksek);
+        assert_eq!(yuksek.len(), 1);
+        assert_eq!(yuksek[0].kimlik, "SAHNE-2");
+
+        assert_eq!(Onem::ayristir("high"), Some(Onem::Yuksek));
+        assert_eq!(Onem::ayristir("bilinmeyen"), None);
+    }
+
+    #[test]
+    fn test_tekrarlanan_kimlik_reddedilir() {
+        let akis = BildiriAkisi {
+            bildiriler: alloc::vec![
+                bildiri("SAHNE-1", Onem::Orta, "zlib", alloc::vec![aralik(None, Some("1.3"), None)]),
+                bildiri("SAHNE-1", Onem::Orta, "curl", alloc::vec![aralik(None, Some("8.0"), None)]),
+            ],
+            ..BildiriAkisi::default()
+        };
+        assert!(akis.dogrula().is_err());
+    }
+
+    #[test]
+    fn test_akis_tazeligi() {
+        const SN: u64 = 1_000_000;
+        let mut akis = BildiriAkisi::default();
+        assert!(akis.tazelik_denetle(1000 * SN).is_err()); // Damgasız akış
+
+        akis.damgala(1000 * SN, 60);
+        assert!(akis.tazelik_denetle(1000 * SN).is_ok());
+        assert!(akis.tazelik_denetle(1059 * SN).is_ok());
+        assert!(akis.tazelik_denetle(1060 * SN).is_err());
+        // Küçük saat kayması kabul edilir, çok ilerideki üretim zamanı edilmez
+        assert!(akis.tazelik_denetle(999 * SN).is_ok());
+        assert!(akis.tazelik_denetle(100 * SN).is_err());
+    }
+}
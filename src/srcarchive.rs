@@ -3,6 +3,7 @@ extern crate alloc; // Bellek ayırma için alloc crate'i
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use alloc::collections::BTreeSet;
 use alloc::boxed::Box; // ZipArchive::new için Box gerekebilir
 
 use zip::{ZipArchive, result::ZipError};
@@ -21,10 +22,16 @@ use crate::Handle; // Sahne64'ün Handle türü
 // Özel hata enum'ımızı içe aktar (güncellenmiş haliyle)
 use crate::paket_yoneticisi_hata::PaketYoneticisiHata;
 
+// tar.* arşivleri ve biçim seçimi için
+use crate::srctar;
+use crate::srcfeatures::{CompressionAlgorithm, Feature, FeatureSet};
+// Çıkarılan girdilerin kurulu dosya denetimi için manifestosu
+use crate::srcverify::ManifestGirdisi;
+
 // Sahne64 resource::read üzerine kurulu basit bir Read implementasyonu
 // zip crate'inin tam olarak ne beklediğine göre bu struct ve trait değişebilir.
 // Varsayım: zip crate'i Read trait'inin core::io veya benzeri bir no_std versiyonunu kullanıyor.
-struct SahneResourceReader {
+pub(crate) struct SahneResourceReader {
     handle: Handle,
     // Okuma pozisyonu takip edilebilir, ancak resource::read offset argümanı almıyorsa
     // bu Reader her zaman baştan okur veya Kaynak seek edilebilir olmalıdır.
@@ -34,7 +41,7 @@ struct SahneResourceReader {
 }
 
 impl SahneResourceReader {
-    fn new(handle: Handle) -> Self {
+    pub(crate) fn new(handle: Handle) -> Self {
         Self { handle }
     }
 }
@@ -137,205 +144,482 @@ fn sahne_create_resource_recursive(resource_id: &str) -> Result<(), PaketYonetic
 }
 
 
-// Verilen ZIP arşivini belirtilen Kaynak ID'si altına açar.
+// Çıkarma sırasında uygulanacak sınırlar (zip bombası koruması).
+// Varsayılan değerler tipik paketler için fazlasıyla yeterlidir; daha büyük paketler için
+// çağıran taraf (örn. yapılandırmadan okunan değerlerle) kendi sınırlarını geçebilir.
+#[derive(Debug, Clone, Copy)]
+pub struct CikarmaSinirlari {
+    pub azami_toplam_boyut: u64, // Tüm girdilerin açılmış toplam boyutu (bayt)
+    pub azami_girdi_sayisi: usize, // Arşivdeki azami girdi sayısı
+    pub azami_sikistirma_orani: u64, // Tek bir girdi için açılmış/sıkıştırılmış boyut oranı
+}
+
+impl Default for CikarmaSinirlari {
+    fn default() -> Self {
+        CikarmaSinirlari {
+            azami_toplam_boyut: 4 * 1024 * 1024 * 1024, // 4 GiB
+            azami_girdi_sayisi: 100_000,
+            azami_sikistirma_orani: 1000,
+        }
+    }
+}
+
+// Bir girdinin neden çıkarılmadığını belirtir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedNedeni {
+    BosYol,                 // Girdi adı boş veya sadece ayraçlardan oluşuyor
+    MutlakYol,              // "/etc/passwd" veya "C:\..." gibi mutlak yol
+    UstDizinBileseni,       // ".." bileşeni içeriyor
+    GecersizKarakter,       // NUL veya ters eğik çizgi içeriyor
+    GuvensizSembolikBag(String), // Hedefi kurulum kökünün dışına çıkan sembolik bağ (hedef ile)
+    SembolikBagUzerinden(String), // Bağın yolu arşivdeki başka bir bağdan geçiyor veya çıkarılmış bir girdinin yerini alıyor (bağ ile)
+    DesteklenmeyenTur,      // Aygıt dosyası, FIFO vb.
+}
+
+impl core::fmt::Display for RedNedeni {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RedNedeni::BosYol => write!(f, "boş yol"),
+            RedNedeni::MutlakYol => write!(f, "mutlak yol"),
+            RedNedeni::UstDizinBileseni => write!(f, "'..' bileşeni"),
+            RedNedeni::GecersizKarakter => write!(f, "geçersiz karakter"),
+            RedNedeni::GuvensizSembolikBag(hedef) => write!(f, "kök dışına işaret eden sembolik bağ -> {}", hedef),
+            RedNedeni::SembolikBagUzerinden(bag) => write!(f, "sembolik bağ üzerinden geçen yol ({})", bag),
+            RedNedeni::DesteklenmeyenTur => write!(f, "desteklenmeyen dosya türü"),
+        }
+    }
+}
+
+// Reddedilen bir arşiv girdisi.
+#[derive(Debug, Clone)]
+pub struct ReddedilenGirdi {
+    pub ad: String, // Arşivdeki ham girdi adı
+    pub neden: RedNedeni,
+}
+
+// Çıkarma işleminin sonucu. Reddedilen girdiler işlemi durdurmaz ama burada raporlanır.
+#[derive(Debug, Default)]
+pub struct CikarmaRaporu {
+    pub cikarilan: Vec<String>, // Kurulum köküne göre temizlenmiş yollar
+    pub reddedilen: Vec<ReddedilenGirdi>,
+    pub toplam_boyut: u64, // Açılmış toplam bayt
+    pub manifesto: Vec<ManifestGirdisi>, // Çıkarılan girdilerin tür, boyut, özet ve izinleri (bkz. srcverify)
+}
+
+// Unix mod bitleri (zip "external attributes" alanının üst 16 biti).
+pub(crate) const S_IFMT: u32 = 0o170000;
+pub(crate) const S_IFREG: u32 = 0o100000;
+pub(crate) const S_IFDIR: u32 = 0o040000;
+pub(crate) const S_IFLNK: u32 = 0o120000;
+// Korunacak izin bitleri. setuid/setgid/sticky bitleri paket arşivlerinden kabul edilmez.
+pub(crate) const IZIN_MASKESI: u32 = 0o0777;
+
+// İzin ve sembolik bağ işlemleri için Sahne64 Kaynak kontrol komutları (Varsayımsal).
+// Gerçek Sahne64 API'sında tanımlanmalıdır.
+pub(crate) const RESOURCE_CONTROL_CMD_SET_MODE: u64 = 4; // args: u32 izin bitleri (little-endian)
+pub(crate) const RESOURCE_CONTROL_CMD_MAKE_SYMLINK: u64 = 5; // args: UTF-8 bağ hedefi
+
+// Arşiv girdi adını kurulum köküne göre güvenli, göreli bir yola çevirir.
+// "." bileşenleri ve tekrarlanan ayraçlar atılır; mutlak yollar, ".." ve geçersiz karakterler reddedilir.
+// Dizin girdileri için sondaki '/' korunur.
+pub fn girdi_yolunu_temizle(ad: &str) -> Result<String, RedNedeni> {
+    if ad.contains('\0') || ad.contains('\\') {
+        return Err(RedNedeni::GecersizKarakter);
+    }
+    if ad.starts_with('/') {
+        return Err(RedNedeni::MutlakYol);
+    }
+    // "C:" veya "C:/..." gibi sürücü önekleri (Windows'ta üretilmiş arşivler). "a:b" gibi iki nokta
+    // içeren sıradan adlar kabul edilir.
+    let bytes = ad.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && (bytes.len() == 2 || bytes[2] == b'/') {
+        return Err(RedNedeni::MutlakYol);
+    }
+
+    let mut bilesenler: Vec<&str> = Vec::new(); // alloc
+    for bilesen in ad.split('/') {
+        match bilesen {
+            "" | "." => continue,
+            ".." => return Err(RedNedeni::UstDizinBileseni),
+            diger => bilesenler.push(diger),
+        }
+    }
+    if bilesenler.is_empty() {
+        return Err(RedNedeni::BosYol);
+    }
+
+    let mut temiz = bilesenler.join("/"); // alloc
+    if ad.ends_with('/') {
+        temiz.push('/');
+    }
+    Ok(temiz)
+}
+
+// Sembolik bağ hedefinin, bağın bulunduğu dizinden çözüldüğünde kurulum kökünde kalıp kalmadığını kontrol eder.
+// girdi_yolu: Temizlenmiş bağ yolu (örn. "lib/libfoo.so").
+// hedef: Bağın işaret ettiği yol (örn. "libfoo.so.1" veya "../share/x").
+pub fn sembolik_hedef_guvenli_mi(girdi_yolu: &str, hedef: &str) -> bool {
+    if hedef.is_empty() || hedef.starts_with('/') || hedef.contains('\0') || hedef.contains('\\') {
+        return false;
+    }
+    // Bağın bulunduğu dizinin derinliğinden başla ve hedef bileşenlerini uygula.
+    let mut derinlik: usize = girdi_yolu.trim_end_matches('/').split('/').count() - 1;
+    for bilesen in hedef.split('/') {
+        match bilesen {
+            "" | "." => continue,
+            ".." => {
+                if derinlik == 0 {
+                    return false; // Kökün üstüne çıkıyor
+                }
+                derinlik -= 1;
+            }
+            _ => derinlik += 1,
+        }
+    }
+    true
+}
+
+// Arşivdeki sembolik bağları izler. Yol ve hedef denetimleri sözcükseldir (dosya sistemine bakılmaz), bu
+// yüzden tek başına güvenli görünen "d/l -> .." gibi bir bağla birlikte "d/l/l2 -> .." veya "x -> d/l/../.."
+// girdileri kökün dışına çıkabilir; bağların arşivdeki sırası da önemsizdir. Bu yüzden bağlar normal
+// girdilerden sonra oluşturulur (bkz. bekleyen_baglari_olustur) ve her biri arşivdeki bağların tamamına göre
+// denetlenir: yolu veya hedefi başka bir bağın üzerinden geçen bağlar reddedilir.
+#[derive(Debug, Default)]
+pub struct SembolikBagIzleyici {
+    baglar: BTreeSet<String>, // Temizlenmiş bağ yolları (sonda '/' olmadan)
+}
+
+impl SembolikBagIzleyici {
+    pub fn ekle(&mut self, girdi_yolu: &str) {
+        self.baglar.insert(girdi_yolu.trim_end_matches('/').to_string()); // alloc
+    }
+
+    // Girdi yolunun kendisi veya ebeveynlerinden biri çıkarılmış bir bağsa o bağı döndürür.
+    pub fn gecilen_bag(&self, girdi_yolu: &str) -> Option<&str> {
+        let yol = girdi_yolu.trim_end_matches('/');
+        let mut bitis = 0;
+        loop {
+            bitis = yol[bitis..].find('/').map_or(yol.len(), |i| bitis + i);
+            if let Some(bag) = self.baglar.get(&yol[..bitis]) {
+                return Some(bag);
+            }
+            if bitis == yol.len() {
+                return None;
+            }
+            bitis += 1;
+        }
+    }
+
+    // sembolik_hedef_guvenli_mi'ye ek olarak hedef, bağın dizininden çözülürken (son bileşeni dışında)
+    // çıkarılmış bir bağın üzerinden geçmemelidir.
+    pub fn hedef_guvenli_mi(&self, girdi_yolu: &str, hedef: &str) -> bool {
+        if !sembolik_hedef_guvenli_mi(girdi_yolu, hedef) {
+            return false;
+        }
+        let mut yol: Vec<&str> = girdi_yolu.trim_end_matches('/').split('/').collect(); // alloc
+        yol.pop(); // Bağın kendi adı
+        let bilesenler: Vec<&str> = hedef.split('/').filter(|b| !b.is_empty() && *b != ".").collect(); // alloc
+        for (i, bilesen) in bilesenler.iter().enumerate() {
+            if *bilesen == ".." {
+                yol.pop();
+                continue;
+            }
+            yol.push(bilesen);
+            if i + 1 < bilesenler.len() && self.baglar.contains(&yol.join("/")) {
+                return false;
+            }
+        }
+        true
+    }
+
+    // İzleyici arşivdeki tüm bağları içerirken bir bağın oluşturulup oluşturulamayacağını denetler.
+    // cikarilan: Çıkarılmış girdiler; bağ bunlardan birinin yerini alamaz veya ebeveyni olamaz (içine
+    // yazılmış bir dizin bağla değiştirilemez).
+    // Dönüş değeri: Reddedilecekse nedeni.
+    pub fn bag_reddi(&self, girdi_yolu: &str, hedef: &str, cikarilan: &[String]) -> Option<RedNedeni> {
+        let yol = girdi_yolu.trim_end_matches('/');
+        if let Some(bag) = yol.rfind('/').and_then(|i| self.gecilen_bag(&yol[..i])) {
+            return Some(RedNedeni::SembolikBagUzerinden(bag.to_string()));
+        }
+        let altinda = |c: &String| {
+            let c = c.trim_end_matches('/');
+            c == yol || (c.starts_with(yol) && c.as_bytes().get(yol.len()) == Some(&b'/'))
+        };
+        if cikarilan.iter().any(altinda) {
+            return Some(RedNedeni::SembolikBagUzerinden(yol.to_string()));
+        }
+        if !self.hedef_guvenli_mi(yol, hedef) {
+            return Some(RedNedeni::GuvensizSembolikBag(hedef.to_string()));
+        }
+        None
+    }
+}
+
+// Çıkarma sırasında ertelenen bir sembolik bağ girdisi.
+pub(crate) struct BekleyenBag {
+    pub ad: String, // Arşivdeki ham girdi adı
+    pub yol: String, // Temizlenmiş yol
+    pub hedef: String,
+}
+
+// Normal girdiler çıkarıldıktan sonra ertelenen sembolik bağları oluşturur. Bağlar çıkarma sırasında henüz
+// bulunmadığından hiçbir dosya bir bağın içinden yazılmamış olur; her bağ arşivdeki bağların tamamına göre
+// denetlenir (bkz. SembolikBagIzleyici::bag_reddi).
+pub(crate) fn bekleyen_baglari_olustur(
+    bekleyenler: Vec<BekleyenBag>,
+    cikartma_base_path: &str,
+    rapor: &mut CikarmaRaporu,
+) -> Result<(), PaketYoneticisiHatasi> {
+    let mut baglar = SembolikBagIzleyici::default();
+    for bag in &bekleyenler {
+        baglar.ekle(&bag.yol);
+    }
+    for bag in bekleyenler {
+        if let Some(neden) = baglar.bag_reddi(&bag.yol, &bag.hedef, &rapor.cikarilan) {
+            eprintln!("Güvensiz sembolik bağ atlandı ({} -> {}): {}", bag.ad, bag.hedef, neden);
+            rapor.reddedilen.push(ReddedilenGirdi { ad: bag.ad, neden });
+            continue;
+        }
+        let bag_resource_id = alloc::format!("{}{}", cikartma_base_path, bag.yol);
+        if let Some(ebeveyn_path) = bag_resource_id.rfind('/').map(|idx| &bag_resource_id[..idx]) {
+            let handle = resource::acquire(ebeveyn_path, resource::MODE_CREATE).map_err(PaketYoneticisiHatasi::from_sahne_error)?;
+            let _ = resource::release(handle);
+        }
+        // Bağ Kaynağını oluştur ve hedefini control komutu ile ayarla (Varsayımsal API).
+        let bag_sonucu = resource::acquire(&bag_resource_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
+            .and_then(|bag_handle| {
+                let sonuc = resource::control(bag_handle, RESOURCE_CONTROL_CMD_MAKE_SYMLINK, bag.hedef.as_bytes());
+                let _ = resource::release(bag_handle);
+                sonuc
+            });
+        if let Err(e) = bag_sonucu {
+            eprintln!("Sembolik bağ oluşturma hatası ({} -> {}): {:?}", bag_resource_id, bag.hedef, e);
+            return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+        }
+        rapor.manifesto.push(ManifestGirdisi::sembolik_bag(&bag.yol, &bag.hedef));
+        rapor.cikarilan.push(bag.yol);
+    }
+    Ok(())
+}
+
+// Verilen ZIP arşivini belirtilen Kaynak ID'si altına varsayılan sınırlarla açar.
 // arsiv_resource_id: Açılacak ZIP arşivinin Sahne64 Kaynak ID'si (örn. "sahne://downloads/paket.zip")
 // cikartma_base_resource_id: Paket içeriğinin çıkarılacağı ana dizin gibi davranan Sahne64 Kaynak ID'si (örn. "sahne://installed_packages/my_package/")
-pub fn zip_ac(arsiv_resource_id: &str, cikartma_base_resource_id: &str) -> Result<(), PaketYoneticisiHata> {
+pub fn zip_ac(arsiv_resource_id: &str, cikartma_base_resource_id: &str) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
+    zip_ac_sinirli(arsiv_resource_id, cikartma_base_resource_id, &CikarmaSinirlari::default())
+}
+
+// Verilen ZIP arşivini belirtilen sınırlarla açar.
+// Güvensiz girdiler (path traversal, mutlak yol, kök dışına işaret eden sembolik bağ, yolu veya hedefi başka
+// bir bağın üzerinden geçen bağ) atlanır ve raporlanır. Sembolik bağlar diğer girdilerden sonra oluşturulur.
+// Sınırlar aşılırsa işlem ArsivSiniriAsildi hatasıyla durur; o ana kadar yazılanlar çağıranın
+// hazırlık (staging) alanında kalır ve temizlenmelidir.
+pub fn zip_ac_sinirli(
+    arsiv_resource_id: &str,
+    cikartma_base_resource_id: &str,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
     // 1. ZIP Arşiv Kaynağını Aç
     let arsiv_handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
-        .map_err(|e| PaketYoneticisiHata::from_sahne_error(e))?; // SahneError'ı kendi hatamıza çevir
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?; // SahneError'ı kendi hatamıza çevir
 
     // 2. ZIP Arşivini Okumak için SahneResourceReader kullanma (Varsayımsal)
-    // zip crate'inin ZipArchive::new fonksiyonu std::io::Read bekler.
-    // No_std ortamında bu ya zip crate'inin no_std Read trait'i ile,
-    // ya da kendi Read adaptörümüzle çözülmeli.
-    // Geçici olarak zip crate'inin Raw handle alabildiğini veya bizim Reader'ımızı kabul ettiğini varsayalım.
-    // Gerçek zip crate kullanımı muhtemelen bir SahneResourceReader instance'ı yaratıp bunu zip'e vermeyi içerir.
-
-    // ZipArchive::new std::io::Read beklediği için, SahneResourceReader'ın std::io::Read implement etmesi lazım.
-    // No_std'de std::io implementasyonu olamaz. Ya zip crate'inin no_std::io'su var, ya da bu yaklaşım baştan hatalı.
-    // Alternatif: ZIP dosyasının tamamını belleğe oku ve oradan aç (büyük dosyalar için uygun değil).
-    // En olası çözüm: zip crate'inin no_std adaptörünü kullanmak veya farklı kütüphane.
-
-    // VEYA: ZIP dosyasını RAM'e oku (büyük bellek gerektirir!)
-    // let mut arsiv_data = Vec::new();
-    // // Sahne64 resource::read offset almadığı için, tüm dosyayı tek seferde okumak zor olabilir
-    // // veya kaynağın Seek edilebilir olması (resource::control ile?) gerekir.
-    // // En kötü ihtimalle parçalar halinde okuma döngüsü.
-    // // Basitlik adına tüm dosyayı okuduğumuzu varsayalım (Seek mümkünse veya dosya küçükse).
-    // // Bu kısım ciddi bir Sahne64-spesifik IO implementasyonu gerektirir.
-    // // Şimdilik sadece placeholder bırakalım.
-    //
-    // // OKUMA İMPLEMENTASYONU GEREKİYOR: zip crate'inin beklediği arayüze göre resource::read'i kullanma
-    //
-    // // Örnek bir Okuma Döngüsü (resource::read offset almazsa)
-     let mut temp_buffer = [0u8; 4096]; // Küçük okuma tamponu
-     loop {
-         match resource::read(arsiv_handle, &mut temp_buffer) {
-             Ok(0) => break, // EOF
-             Ok(n) => arsiv_data.extend_from_slice(&temp_buffer[..n]),
-             Err(e) => {
-                 let _ = resource::release(arsiv_handle);
-                 return Err(PaketYoneticisiHata::from_sahne_error(e));
-             }
-         }
-     }
-     let arsiv_kullanilabilir_veri = std::io::Cursor::new(arsiv_data); // std::io::Cursor kullanamayız no_std'de
+    // zip crate'inin no_std uyumlu ZipArchive::new fonksiyonunun SahneResourceReader'ı kabul ettiğini varsayıyoruz.
+    let arsiv_reader = SahneResourceReader::new(arsiv_handle);
+    let mut arsiv = ZipArchive::new(arsiv_reader).map_err(PaketYoneticisiHatasi::ZipHatasi)?;
 
-    // **GERÇEK ÇÖZÜM:** zip crate'inin no_std desteği varsa, o desteğe uygun bir Reader implementasyonu yazmak.
-    // zip crate'i `by_index` metodu ile doğrudan bir `ZipFile` struct'ı döner, bu struct'ın da
-    // Read implementasyonu olmalıdır. Bu implementasyon da yine alttan Sahne64 resource::read'i kullanmalıdır.
+    // Girdi sayısı sınırı, herhangi bir şey yazılmadan önce kontrol edilir.
+    if arsiv.len() > sinirlar.azami_girdi_sayisi {
+        let _ = resource::release(arsiv_handle);
+        return Err(PaketYoneticisiHatasi::ArsivSiniriAsildi(alloc::format!(
+            "{} girdi (sınır {})", arsiv.len(), sinirlar.azami_girdi_sayisi
+        )));
+    }
 
-    // Varsayım: zip crate'inin no_std uyumlu ZipArchive::new fonksiyonu var ve SahneResourceReader gibi bir şeyi alabiliyor.
-    let arsiv_reader = SahneResourceReader::new(arsiv_handle);
-    let mut arsiv = ZipArchive::new(arsiv_reader).map_err(PaketYoneticisiHata::ZipHatasi)?;
+    // Temel yol her zaman '/' ile biter, böylece birleştirme ve önek kontrolü tutarlı olur.
+    let cikartma_base_path = if cikartma_base_resource_id.ends_with('/') {
+        String::from(cikartma_base_resource_id)
+    } else {
+        alloc::format!("{}/", cikartma_base_resource_id)
+    };
 
-    // 3. Dosyaları Çıkar
-    let cikartma_base_path = String::from(cikartma_base_resource_id); // String olarak tutalım
+    let mut rapor = CikarmaRaporu::default();
+    let mut bekleyen_baglar: Vec<BekleyenBag> = Vec::new(); // alloc
 
+    // 3. Dosyaları Çıkar (sembolik bağlar ertelenir)
     for i in 0..arsiv.len() {
-        let mut arsiv_dosyasi = arsiv.by_index(i)?; // Bu ZipFile struct'ı Read implement etmeli
-
-        let dosya_adi = arsiv_dosyasi.name();
-        // Zip entry isimleri bazen mutlak yol veya '..' içerebilir, temizlemek gerekir.
-        // Bu basit temizlik örneği, daha kapsamlı bir path sanitization gerekebilir.
-        let temizlenmis_dosya_adi = dosya_adi.replace("..", "_").replace("//", "/"); // Basit sanitization
-
-        // Hedef kaynak ID'sini oluştur
-        let cikartma_resource_id = if cikartma_base_path.ends_with('/') || temizlenmis_dosya_adi.starts_with('/') {
-            // Eğer base path '/' ile bitiyorsa veya dosya adı '/' ile başlıyorsa doğrudan birleştir
-            // (Başlangıç '/' zip formatında nadir olsa da ihtimale karşı)
-             let mut path = String::from(&cikartma_base_path);
-             if temizlenmis_dosya_adi.starts_with('/') {
-                 path.push_str(&temizlenmis_dosya_adi[1..]); // Başlangıç '/' karakterini atla
-             } else {
-                 path.push_str(&temizlenmis_dosya_adi);
-             }
-             path
-        } else {
-            // Base path '/' ile bitmiyorsa ve dosya adı '/' ile başlamıyorsa araya '/' ekle
-            alloc::format!("{}/{}", cikartma_base_path, temizlenmis_dosya_adi)
+        let mut arsiv_dosyasi = match arsiv.by_index(i) {
+            Ok(dosya) => dosya,
+            Err(e) => {
+                let _ = resource::release(arsiv_handle);
+                return Err(PaketYoneticisiHatasi::ZipHatasi(e));
+            }
+        };
+
+        let dosya_adi = arsiv_dosyasi.name().to_string(); // alloc
+
+        // Yol temizleme: güvensiz girdiler atlanır ve raporlanır.
+        let temizlenmis_dosya_adi = match girdi_yolunu_temizle(&dosya_adi) {
+            Ok(yol) => yol,
+            Err(neden) => {
+                eprintln!("Güvensiz arşiv girdisi atlandı ({}): {}", dosya_adi, neden);
+                rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden });
+                continue;
+            }
         };
 
+        let cikartma_resource_id = alloc::format!("{}{}", cikartma_base_path, temizlenmis_dosya_adi);
 
-        // Güvenlik kontrolü (Sahne64 Kaynak ID'leri için):
-        // Çıkarma yolunun, belirtilen temel resource ID'si altında olduğundan emin ol.
+        // Güvenlik kontrolü (savunma derinliği): temizlenmiş yol her zaman temel yolun altında olmalı.
+        // Sahne64 çekirdeği de acquire sırasında yetki/güvenlik kontrolü yapmalıdır.
         if !cikartma_resource_id.starts_with(&cikartma_base_path) {
-             // Bu kontrol string manipülasyonu ile yapılıyor,
-             // ancak Sahne64'ün kendi path/naming kurallarına tam uyumlu olmayabilir.
-             // Sahne64 çekirdeği de acquire sırasında yetki/güvenlik kontrolü yapmalıdır.
-             eprintln!("Güvenlik hatası: Geçersiz çıkarma yolu denemesi: {}", cikartma_resource_id);
-             // Mevcut handle'ı bırakıp hata dönebiliriz.
-             let _ = resource::release(arsiv_handle);
-             return Err(PaketYoneticisiHata::ZipHatasi(ZipError::InvalidPath(String::from("Güvenlik sebebiyle geçersiz çıkarma yolu"))));
-        }
-
-
-        if temizlenmis_dosya_adi.ends_with('/') {
-            // Klasör Kaynağı ise (varsayım) oluşturmayı deneyelim.
-            // Sahne64'te 'klasör' resource'u nasıl temsil edilir ve oluşturulur?
-            // Varsayım 1: resource::acquire(path_ends_with('/'), MODE_CREATE) işe yarar.
-            // Varsayım 2: Sahne64 resource modelinde 'klasör' diye bir şey yok, sadece dosya gibi resource'lar var.
-            // Varsayım 3: resource::control(parent_handle, MKDIR_COMMAND, ...) gibi bir mekanizma var.
-            // Varsayım 4: Dosya yazarken parent resource'lar otomatik oluşturulur.
-
-            // Eğer Varsayım 1 veya 3 geçerliyse, burada o çağrı yapılmalı.
-            // Şimdilik Varsayım 4'ü temel alıp, dosya yazmadan önce parent'ı 'acquire' etmeyi deneyelim
-            // (Bu da Varsayım 1'e benzer bir etki yaratır).
-
-            // `sahne_create_resource_recursive` fonksiyonu artık burada tam yolu alıp parent'ları deneyecek
-             if temizlenmis_dosya_adi.len() > 1 { // Sadece kök '/' değilse işlem yap
-                 let dir_resource_id = &cikartma_resource_id; // Zaten '/' ile bitiyor (muhtemelen zip formatına göre)
-                 // Sadece varlığını sağlamak için acquire ve hemen release.
-                 // resource::MODE_CREATE yeterli olmalı, klasöre özel flag gerekmeyebilir.
-                 match resource::acquire(dir_resource_id, resource::MODE_CREATE) {
-                      Ok(dir_handle) => {
-                          let _ = resource::release(dir_handle); // Handle'ı hemen bırak
-                      }
-                      Err(e) => {
-                           eprintln!("Dizin Kaynağı oluşturma hatası ({}): {:?}", dir_resource_id, e);
-                           let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                           return Err(PaketYoneticisiHata::from_sahne_error(e));
-                      }
-                 }
-             }
+            eprintln!("Güvenlik hatası: Geçersiz çıkarma yolu denemesi: {}", cikartma_resource_id);
+            rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden: RedNedeni::UstDizinBileseni });
+            continue;
+        }
+
+        // Dosya türünü ve izinleri unix mod bitlerinden al.
+        // Unix bilgisi olmayan arşivlerde (örn. DOS'ta üretilmiş) türü addan çıkarırız.
+        let mod_bitleri = arsiv_dosyasi.unix_mode();
+        let tur = match mod_bitleri {
+            Some(m) => m & S_IFMT,
+            None if arsiv_dosyasi.is_dir() => S_IFDIR,
+            None => S_IFREG,
+        };
 
+        // Zip bombası koruması: başlıktaki boyutlar yalan söyleyebilir, yine de erken kontrol ucuzdur.
+        let kalan = sinirlar.azami_toplam_boyut.saturating_sub(rapor.toplam_boyut);
+        if arsiv_dosyasi.size() > kalan
+            || arsiv_dosyasi.size() > arsiv_dosyasi.compressed_size().max(1).saturating_mul(sinirlar.azami_sikistirma_orani)
+        {
+            let _ = resource::release(arsiv_handle);
+            return Err(PaketYoneticisiHatasi::ArsivSiniriAsildi(alloc::format!(
+                "{}: {} bayt açılmış boyut ({} bayt sıkıştırılmış)", dosya_adi, arsiv_dosyasi.size(), arsiv_dosyasi.compressed_size()
+            )));
+        }
 
-        } else {
-            // Dosya Kaynağı ise çıkar
-            if let Some(ebeveyn_path) = cikartma_resource_id.rfind('/').map(|idx| &cikartma_resource_id[..idx]) {
-                 if !ebeveyn_path.is_empty() {
-                     // Ebeveyn dizin/kaynak yolunu sağlamaya çalış.
-                     // Varsayım: resource::acquire(parent_path, MODE_CREATE) parent resource'u oluşturur.
-                     match resource::acquire(ebeveyn_path, resource::MODE_CREATE) {
-                          Ok(parent_handle) => {
-                              let _ = resource::release(parent_handle); // Handle'ı hemen bırak
-                          }
-                          Err(e) => {
-                               eprintln!("Ebeveyn Kaynağı oluşturma hatası ({}): {:?}", ebeveyn_path, e);
-                               let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                               return Err(PaketYoneticisiHata::from_sahne_error(e));
-                          }
-                     }
-                 }
+        if tur == S_IFDIR || temizlenmis_dosya_adi.ends_with('/') {
+            // Klasör Kaynağı: varlığını sağlamak için acquire(MODE_CREATE) ve hemen release.
+            // (Bkz. sahne_create_resource_recursive üzerindeki varsayımlar.)
+            match resource::acquire(&cikartma_resource_id, resource::MODE_CREATE) {
+                Ok(dir_handle) => {
+                    if let Some(m) = mod_bitleri {
+                        izinleri_uygula(dir_handle, m, &cikartma_resource_id);
+                    }
+                    let _ = resource::release(dir_handle); // Handle'ı hemen bırak
+                }
+                Err(e) => {
+                    eprintln!("Dizin Kaynağı oluşturma hatası ({}): {:?}", cikartma_resource_id, e);
+                    let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+                    return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+                }
             }
+            rapor.manifesto.push(ManifestGirdisi::dizin(&temizlenmis_dosya_adi, mod_bitleri));
+            rapor.cikarilan.push(temizlenmis_dosya_adi);
+            continue;
+        }
 
+        if tur != S_IFREG && tur != S_IFLNK {
+            // Aygıt dosyaları, FIFO'lar vb. paketlerden kabul edilmez.
+            rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden: RedNedeni::DesteklenmeyenTur });
+            continue;
+        }
 
-            // Dosya Kaynağını oluştur ve aç
-            let cikartma_dosyasi_handle = resource::acquire(
-                &cikartma_resource_id,
-                resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE // Yazma, Oluştur, Varsa içeriği sil
-            ).map_err(|e| {
-                 eprintln!("Çıkarma Dosya Kaynağı acquire hatası ({}): {:?}", cikartma_resource_id, e);
-                 let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                 PaketYoneticisiHata::from_sahne_error(e)
-            })?;
+        // Girdi içeriğini oku. Okuma "kalan + 1" bayt ile sınırlanır, böylece başlığı yalan söyleyen
+        // girdiler de belleği tüketmeden yakalanır.
+        let mut buffer = Vec::new(); // alloc::vec::Vec kullanılıyor
+        if let Err(e) = (&mut arsiv_dosyasi).take(kalan.saturating_add(1)).read_to_end(&mut buffer) {
+            eprintln!("Zip dosyasından okuma hatası ({}): {:?}", dosya_adi, e);
+            let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+            return Err(PaketYoneticisiHatasi::ZipHatasi(e));
+        }
+        if buffer.len() as u64 > kalan {
+            let _ = resource::release(arsiv_handle);
+            return Err(PaketYoneticisiHatasi::ArsivSiniriAsildi(alloc::format!(
+                "toplam açılmış boyut {} bayt sınırını aşıyor ({})", sinirlar.azami_toplam_boyut, dosya_adi
+            )));
+        }
+        rapor.toplam_boyut += buffer.len() as u64;
 
-            // Arşiv dosyasının içeriğini tampona oku (bu da zip crate'inin Read implementasyonunu kullanır)
-            let mut buffer = Vec::new(); // alloc::vec::Vec kullanılıyor
-            // arsiv_dosyasi, zip::read::ZipFile olmalı ve Read implement etmeli.
-            // Bu read çağrısı alttan SahneResourceReader'ı kullanmalı (varsayım).
-            match arsiv_dosyasi.read_to_end(&mut buffer) {
-                 Ok(_) => {
-                      // Tampondaki veriyi Sahne64 Kaynağına yaz
-                      match resource::write(cikartma_dosyasi_handle, &buffer) {
-                           Ok(_) => {
-                                // Yazma başarılı
-                           }
-                           Err(e) => {
-                                eprintln!("Çıkarma Kaynağına yazma hatası ({}): {:?}", cikartma_resource_id, e);
-                                let _ = resource::release(cikartma_dosyasi_handle); // Dosya handle'ını temizle
-                                let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                                return Err(PaketYoneticisiHatasi::from_sahne_error(e));
-                           }
-                      }
-                 }
-                 Err(e) => {
-                      eprintln!("Zip dosyasından okuma hatası ({}): {:?}", dosya_adi, e);
-                      let _ = resource::release(cikartma_dosyasi_handle); // Dosya handle'ını temizle
-                      let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                      // ZipError'ı PaketYoneticisiHata'ya map etmeliyiz.
-                      return Err(PaketYoneticisiHatasi::ZipHatasi(e)); // ZipError::IoError SahneError'dan gelebilir
-                 }
+        if tur == S_IFLNK {
+            // Sembolik bağ: içerik, bağın hedefidir. Bağ, tüm bağlar bilindiğinde denetlenip oluşturulur.
+            match core::str::from_utf8(&buffer) {
+                Ok(hedef) => bekleyen_baglar.push(BekleyenBag { ad: dosya_adi, yol: temizlenmis_dosya_adi, hedef: hedef.to_string() }),
+                Err(_) => rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden: RedNedeni::GecersizKarakter }),
+            }
+            continue;
+        }
+
+        // Ebeveyn dizin/kaynak yolunu sağlamaya çalış.
+        // Varsayım: resource::acquire(parent_path, MODE_CREATE) parent resource'u oluşturur.
+        if let Some(ebeveyn_path) = cikartma_resource_id.rfind('/').map(|idx| &cikartma_resource_id[..idx]) {
+            if !ebeveyn_path.is_empty() {
+                match resource::acquire(ebeveyn_path, resource::MODE_CREATE) {
+                    Ok(parent_handle) => { let _ = resource::release(parent_handle); }
+                    Err(e) => {
+                        eprintln!("Ebeveyn Kaynağı oluşturma hatası ({}): {:?}", ebeveyn_path, e);
+                        let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+                        return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+                    }
+                }
+            }
+        }
+
+        // Normal dosya: Kaynağı oluştur, içeriği yaz, izinleri uygula.
+        let cikartma_dosyasi_handle = match resource::acquire(
+            &cikartma_resource_id,
+            resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE // Yazma, Oluştur, Varsa içeriği sil
+        ) {
+            Ok(handle) => handle,
+            Err(e) => {
+                eprintln!("Çıkarma Dosya Kaynağı acquire hatası ({}): {:?}", cikartma_resource_id, e);
+                let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+                return Err(PaketYoneticisiHatasi::from_sahne_error(e));
             }
+        };
 
-            // Çıkarma Dosya Kaynağı Handle'ını serbest bırak
-            match resource::release(cikartma_dosyasi_handle) {
-                 Ok(_) => {}, // Başarılı
-                 Err(e) => {
-                      eprintln!("Çıkarma Dosya Kaynağı release hatası ({}): {:?}", cikartma_resource_id, e);
-                      // Hata olsa bile devam etmeye çalışabiliriz, ama handle'ı bırakamamak sorun.
-                      // Ciddi bir hata olarak dönebiliriz.
-                      let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
-                      return Err(PaketYoneticisiHatasi::from_sahne_error(e));
-                 }
+        let mut written = 0;
+        while written < buffer.len() {
+            match resource::write(cikartma_dosyasi_handle, &buffer[written..]) {
+                Ok(0) => {
+                    let _ = resource::release(cikartma_dosyasi_handle);
+                    let _ = resource::release(arsiv_handle);
+                    return Err(PaketYoneticisiHatasi::from_sahne_error(SahneError::InvalidOperation));
+                }
+                Ok(n) => written += n,
+                Err(e) => {
+                    eprintln!("Çıkarma Kaynağına yazma hatası ({}): {:?}", cikartma_resource_id, e);
+                    let _ = resource::release(cikartma_dosyasi_handle); // Dosya handle'ını temizle
+                    let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+                    return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+                }
             }
         }
+
+        if let Some(m) = mod_bitleri {
+            izinleri_uygula(cikartma_dosyasi_handle, m, &cikartma_resource_id);
+        }
+
+        // Çıkarma Dosya Kaynağı Handle'ını serbest bırak
+        if let Err(e) = resource::release(cikartma_dosyasi_handle) {
+            eprintln!("Çıkarma Dosya Kaynağı release hatası ({}): {:?}", cikartma_resource_id, e);
+            let _ = resource::release(arsiv_handle); // Arşiv handle'ını temizle
+            return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+        }
+
+        rapor.manifesto.push(ManifestGirdisi::dosya(&temizlenmis_dosya_adi, &buffer, mod_bitleri));
+        rapor.cikarilan.push(temizlenmis_dosya_adi);
     }
 
-    // 4. ZIP Arşiv Handle'ını Serbest Bırak
+    // 4. Ertelenen sembolik bağları oluştur
+    if let Err(e) = bekleyen_baglari_olustur(bekleyen_baglar, &cikartma_base_path, &mut rapor) {
+        let _ = resource::release(arsiv_handle);
+        return Err(e);
+    }
+
+    // 5. ZIP Arşiv Handle'ını Serbest Bırak
     match resource::release(arsiv_handle) {
-        Ok(_) => Ok(()), // Başarılı, tüm işlemler bitti
+        Ok(_) => Ok(rapor), // Başarılı, tüm işlemler bitti
         Err(e) => {
             eprintln!("Arşiv Kaynağı release hatası ({}): {:?}", arsiv_resource_id, e);
             Err(PaketYoneticisiHatasi::from_sahne_error(e)) // Hata döndür
@@ -343,6 +627,15 @@ pub fn zip_ac(arsiv_resource_id: &str, cikartma_base_resource_id: &str) -> Resul
     }
 }
 
+// Helper: Unix izin bitlerini (maskelenmiş) Kaynağa uygular.
+// İzin uygulanamaması kurulumu durdurmaz, sadece loglanır (Kaynak izin desteklemiyor olabilir).
+pub(crate) fn izinleri_uygula(handle: Handle, mod_bitleri: u32, resource_id: &str) {
+    let izinler = mod_bitleri & IZIN_MASKESI;
+    if let Err(e) = resource::control(handle, RESOURCE_CONTROL_CMD_SET_MODE, &izinler.to_le_bytes()) {
+        eprintln!("İzinler uygulanamadı ({}, {:o}): {:?}", resource_id, izinler, e);
+    }
+}
+
 
 // Verilen ZIP arşivinin içeriğini listeleyen fonksiyon.
 // arsiv_resource_id: Listelenecek ZIP arşivinin Sahne64 Kaynak ID'si.
@@ -386,6 +679,162 @@ pub fn zip_icerik_listele(arsiv_resource_id: &str) -> Result<Vec<String>, PaketY
     }
 }
 
+// Desteklenen paket arşivi biçimleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArsivBicimi {
+    Zip,
+    Tar(Option<CompressionAlgorithm>), // None: sıkıştırılmamış tar
+}
+
+// Biçim algılama için arşivin başından okunacak bayt sayısı (tar "ustar" imzası 257. bayttadır).
+const BICIM_ALGILAMA_BOYUTU: usize = 512;
+
+// Arşivin ilk baytlarına (magic bytes) bakarak biçimini algılar.
+// Sıkıştırılmış akışların içinde tar olduğu varsayılır (paket arşivleri için tek anlamlı durum).
+pub fn arsiv_bicimini_algila(baslik: &[u8]) -> Option<ArsivBicimi> {
+    if baslik.starts_with(b"PK\x03\x04") || baslik.starts_with(b"PK\x05\x06") {
+        Some(ArsivBicimi::Zip)
+    } else if baslik.starts_with(&[0x1f, 0x8b]) {
+        Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Gzip)))
+    } else if baslik.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Zstd)))
+    } else if baslik.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Xz)))
+    } else if baslik.starts_with(b"BZh") {
+        Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Bzip2)))
+    } else if baslik.len() >= 262 && &baslik[257..262] == b"ustar" {
+        Some(ArsivBicimi::Tar(None))
+    } else {
+        None
+    }
+}
+
+// Biçimin etkin özellik kümesiyle açılıp açılamayacağını kontrol eder.
+// Zip ve düz tar her zaman desteklenir; sıkıştırılmış tar'lar ilgili Compression özelliğinin etkin olmasını gerektirir.
+pub fn bicim_etkin_mi(bicim: ArsivBicimi, ozellikler: &FeatureSet) -> bool {
+    match bicim {
+        ArsivBicimi::Zip | ArsivBicimi::Tar(None) => true,
+        ArsivBicimi::Tar(Some(algoritma)) => ozellikler.is_enabled(&Feature::Compression(algoritma)),
+    }
+}
+
+// Paket arşivleri için varsayılan özellik kümesi: çözücüsü bulunan tüm tar sıkıştırmaları etkin.
+pub fn varsayilan_arsiv_ozellikleri() -> FeatureSet {
+    let mut ozellikler = FeatureSet::new();
+    for algoritma in [CompressionAlgorithm::Gzip, CompressionAlgorithm::Zstd, CompressionAlgorithm::Xz, CompressionAlgorithm::Bzip2] {
+        ozellikler.enable(Feature::Compression(algoritma));
+    }
+    ozellikler
+}
+
+// Arşiv Kaynağının ilk baytlarını okuyup biçimini algılar ve özellik kümesine göre doğrular.
+fn arsiv_bicimini_belirle(arsiv_resource_id: &str, ozellikler: &FeatureSet) -> Result<ArsivBicimi, PaketYoneticisiHatasi> {
+    let handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    // Kısa okumalar olabileceği için tampon dolana veya EOF'a kadar oku.
+    let mut baslik = [0u8; BICIM_ALGILAMA_BOYUTU];
+    let mut okunan = 0;
+    let okuma_sonucu = loop {
+        if okunan == baslik.len() {
+            break Ok(());
+        }
+        match resource::read(handle, &mut baslik[okunan..]) {
+            Ok(0) => break Ok(()),
+            Ok(n) => okunan += n,
+            Err(e) => break Err(e),
+        }
+    };
+    let _ = resource::release(handle);
+    okuma_sonucu.map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    let bicim = arsiv_bicimini_algila(&baslik[..okunan]).ok_or_else(|| {
+        PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(alloc::format!("{}: tanınmayan arşiv imzası", arsiv_resource_id))
+    })?;
+
+    if !bicim_etkin_mi(bicim, ozellikler) {
+        return Err(PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(alloc::format!(
+            "{}: {:?} biçimi etkin özelliklerde kapalı", arsiv_resource_id, bicim
+        )));
+    }
+    Ok(bicim)
+}
+
+// Paket arşivini biçimini algılayarak varsayılan sınırlarla açar (zip veya tar.*).
+// ozellikler: Hangi sıkıştırma algoritmalarına izin verildiğini belirleyen özellik kümesi.
+pub fn arsiv_ac(arsiv_resource_id: &str, cikartma_base_resource_id: &str, ozellikler: &FeatureSet) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
+    arsiv_ac_sinirli(arsiv_resource_id, cikartma_base_resource_id, ozellikler, &CikarmaSinirlari::default())
+}
+
+// Paket arşivini biçimini algılayarak belirtilen sınırlarla açar.
+pub fn arsiv_ac_sinirli(
+    arsiv_resource_id: &str,
+    cikartma_base_resource_id: &str,
+    ozellikler: &FeatureSet,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
+    match arsiv_bicimini_belirle(arsiv_resource_id, ozellikler)? {
+        ArsivBicimi::Zip => zip_ac_sinirli(arsiv_resource_id, cikartma_base_resource_id, sinirlar),
+        ArsivBicimi::Tar(sikistirma) => srctar::tar_ac_sinirli(arsiv_resource_id, sikistirma, cikartma_base_resource_id, sinirlar),
+    }
+}
+
+// Paket arşivinin içeriğini biçimini algılayarak listeler.
+pub fn arsiv_icerik_listele(arsiv_resource_id: &str, ozellikler: &FeatureSet) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+    match arsiv_bicimini_belirle(arsiv_resource_id, ozellikler)? {
+        ArsivBicimi::Zip => zip_icerik_listele(arsiv_resource_id),
+        ArsivBicimi::Tar(sikistirma) => srctar::tar_icerik_listele(arsiv_resource_id, sikistirma),
+    }
+}
+
+// Paket arşivindeki tek bir girdinin içeriğini biçimini algılayarak belleğe okur (zip veya tar.*).
+pub fn arsiv_girdisini_oku(arsiv_resource_id: &str, girdi_adi: &str, ozellikler: &FeatureSet) -> Result<Option<Vec<u8>>, PaketYoneticisiHatasi> {
+    match arsiv_bicimini_belirle(arsiv_resource_id, ozellikler)? {
+        ArsivBicimi::Zip => zip_girdisini_oku(arsiv_resource_id, girdi_adi),
+        ArsivBicimi::Tar(sikistirma) => srctar::tar_girdisini_oku(arsiv_resource_id, sikistirma, girdi_adi),
+    }
+}
+
+// Verilen ZIP arşivindeki tek bir girdinin içeriğini belleğe okur.
+// Paket arşivlerine gömülü meta veriyi (örn. ".PAKET/paket.bin") çıkarmak için kullanılır.
+// arsiv_resource_id: Okunacak ZIP arşivinin Sahne64 Kaynak ID'si.
+// girdi_adi: Arşiv içindeki girdinin tam adı.
+// Dönüş değeri: Girdi bulunursa Some(içerik), bulunamazsa None, veya hata.
+pub fn zip_girdisini_oku(arsiv_resource_id: &str, girdi_adi: &str) -> Result<Option<Vec<u8>>, PaketYoneticisiHatasi> {
+    // 1. ZIP Arşiv Kaynağını Aç
+    let arsiv_handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    // 2. ZIP Arşivini Okumak için SahneResourceReader kullanma (Varsayımsal, zip_ac ile aynı)
+    let arsiv_reader = SahneResourceReader::new(arsiv_handle);
+    let mut arsiv = ZipArchive::new(arsiv_reader).map_err(PaketYoneticisiHatasi::ZipHatasi)?;
+
+    // 3. Girdiyi ada göre bul ve oku
+    // zip crate'inin by_name metodu girdi yoksa ZipError::FileNotFound döner.
+    let sonuc = match arsiv.by_name(girdi_adi) {
+        Ok(mut arsiv_dosyasi) => {
+            let mut buffer = Vec::new(); // alloc::vec::Vec kullanılıyor
+            match arsiv_dosyasi.read_to_end(&mut buffer) {
+                Ok(_) => Ok(Some(buffer)),
+                Err(e) => {
+                    eprintln!("Zip girdisi okuma hatası ({} içinde {}): {:?}", arsiv_resource_id, girdi_adi, e);
+                    Err(PaketYoneticisiHatasi::ZipHatasi(e))
+                }
+            }
+        }
+        Err(ZipError::FileNotFound) => Ok(None), // Girdi yoksa hata değil, None dön
+        Err(e) => Err(PaketYoneticisiHatasi::ZipHatasi(e)),
+    };
+
+    // 4. ZIP Arşiv Handle'ını Serbest Bırak
+    if let Err(e) = resource::release(arsiv_handle) {
+        eprintln!("Arşiv Kaynağı release hatası ({}): {:?}", arsiv_resource_id, e);
+        // Okuma başarılıysa release hatası sadece loglanır.
+    }
+
+    sonuc
+}
+
 // --- PaketYoneticisiHata enum'ının SahneError'ı içerecek şekilde güncellenmesi ---
 // (Bu enum tanımı muhtemelen başka bir dosyadadır, ancak burada nasıl görüneceğine dair bir taslak)
 
@@ -397,6 +846,9 @@ pub enum PaketYoneticisiHatasi {
     SahneApiHatasi(SahneError), // Genel Sahne64 API hataları için
     GecersizParametre(String), // Fonksiyona geçersiz parametre geçilmesi
     PathTraversalHatasi(String), // Güvenlik: Path traversal denemesi
+    ArsivSiniriAsildi(String), // Güvenlik: Zip bombası (boyut/girdi sayısı/oran sınırı aşıldı)
+    TarHatasi(String), // tar veya sıkıştırma çözücüsü hataları
+    DesteklenmeyenArsivBicimi(String), // Tanınmayan veya özellik kümesinde kapalı arşiv biçimi
     // ... diğer paket yöneticisi özel hataları ...
 }
 
@@ -431,3 +883,105 @@ impl PaketYoneticisiHatasi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_girdi_yolunu_temizle_normal() {
+        assert_eq!(girdi_yolunu_temizle("bin/araç").unwrap(), "bin/araç");
+        assert_eq!(girdi_yolunu_temizle("./lib//libx.so").unwrap(), "lib/libx.so");
+        assert_eq!(girdi_yolunu_temizle("share/doc/").unwrap(), "share/doc/");
+        assert_eq!(girdi_yolunu_temizle("a:b").unwrap(), "a:b");
+        assert_eq!(girdi_yolunu_temizle("1:/x").unwrap(), "1:/x");
+        assert_eq!(girdi_yolunu_temizle("man/x:y.3").unwrap(), "man/x:y.3");
+    }
+
+    #[test]
+    fn test_girdi_yolunu_temizle_guvensiz() {
+        assert_eq!(girdi_yolunu_temizle("../etc/passwd"), Err(RedNedeni::UstDizinBileseni));
+        assert_eq!(girdi_yolunu_temizle("lib/../../x"), Err(RedNedeni::UstDizinBileseni));
+        assert_eq!(girdi_yolunu_temizle("/etc/passwd"), Err(RedNedeni::MutlakYol));
+        assert_eq!(girdi_yolunu_temizle("C:/Windows"), Err(RedNedeni::MutlakYol));
+        assert_eq!(girdi_yolunu_temizle("c:"), Err(RedNedeni::MutlakYol));
+        assert_eq!(girdi_yolunu_temizle("a\\..\\b"), Err(RedNedeni::GecersizKarakter));
+        assert_eq!(girdi_yolunu_temizle("./"), Err(RedNedeni::BosYol));
+    }
+
+    #[test]
+    fn test_arsiv_bicimini_algila() {
+        assert_eq!(arsiv_bicimini_algila(b"PK\x03\x04rest"), Some(ArsivBicimi::Zip));
+        assert_eq!(arsiv_bicimini_algila(&[0x1f, 0x8b, 0x08]), Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Gzip))));
+        assert_eq!(arsiv_bicimini_algila(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]), Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Zstd))));
+        assert_eq!(arsiv_bicimini_algila(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]), Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Xz))));
+        assert_eq!(arsiv_bicimini_algila(b"BZh91AY"), Some(ArsivBicimi::Tar(Some(CompressionAlgorithm::Bzip2))));
+
+        let mut duz_tar = [0u8; 512];
+        duz_tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(arsiv_bicimini_algila(&duz_tar), Some(ArsivBicimi::Tar(None)));
+
+        assert_eq!(arsiv_bicimini_algila(b"bilinmeyen"), None);
+        assert_eq!(arsiv_bicimini_algila(&[]), None);
+    }
+
+    #[test]
+    fn test_bicim_etkin_mi() {
+        let bos = FeatureSet::new();
+        assert!(bicim_etkin_mi(ArsivBicimi::Zip, &bos));
+        assert!(bicim_etkin_mi(ArsivBicimi::Tar(None), &bos));
+        assert!(!bicim_etkin_mi(ArsivBicimi::Tar(Some(CompressionAlgorithm::Zstd)), &bos));
+
+        let varsayilan = varsayilan_arsiv_ozellikleri();
+        assert!(bicim_etkin_mi(ArsivBicimi::Tar(Some(CompressionAlgorithm::Xz)), &varsayilan));
+        assert!(!bicim_etkin_mi(ArsivBicimi::Tar(Some(CompressionAlgorithm::Lz4)), &varsayilan));
+    }
+
+    #[test]
+    fn test_sembolik_hedef_guvenli_mi() {
+        assert!(sembolik_hedef_guvenli_mi("lib/libx.so", "libx.so.1"));
+        assert!(sembolik_hedef_guvenli_mi("bin/arac", "../lib/arac"));
+        assert!(!sembolik_hedef_guvenli_mi("bin/arac", "../../etc/shadow"));
+        assert!(!sembolik_hedef_guvenli_mi("arac", "../x"));
+        assert!(!sembolik_hedef_guvenli_mi("bin/arac", "/usr/bin/arac"));
+    }
+
+    #[test]
+    fn test_sembolik_bag_uzerinden_kacis() {
+        let mut baglar = SembolikBagIzleyici::default();
+        // "d/l -> .." tek başına kökte kalır
+        assert!(baglar.hedef_guvenli_mi("d/l", ".."));
+        baglar.ekle("d/l");
+
+        // Bağın altındaki yollar reddedilir
+        assert_eq!(baglar.gecilen_bag("d/l/x"), Some("d/l"));
+        assert_eq!(baglar.gecilen_bag("d/l/l2"), Some("d/l"));
+        assert_eq!(baglar.gecilen_bag("d/l"), Some("d/l"));
+        assert_eq!(baglar.gecilen_bag("d/lib/x"), None);
+        assert_eq!(baglar.gecilen_bag("d/"), None);
+
+        // Hedefi bağın üzerinden geçen bağlar reddedilir; bağın kendisine işaret etmek serbesttir
+        assert!(!baglar.hedef_guvenli_mi("y", "d/l/.."));
+        assert!(!baglar.hedef_guvenli_mi("e/y", "../d/l/x"));
+        assert!(baglar.hedef_guvenli_mi("d/y", "l"));
+        assert!(baglar.hedef_guvenli_mi("y", "d/lib/x"));
+    }
+
+    #[test]
+    fn test_bag_sirasi_kacisi() {
+        // "l1 -> d/l2/../.." önce, "d/l2 -> .." sonra gelir: ilk bağ denetlenirken ikincisi henüz yoktu.
+        // Bağlar tüm bağlar bilindiğinde denetlendiğinden sıra önemsizdir.
+        let mut baglar = SembolikBagIzleyici::default();
+        baglar.ekle("l1");
+        baglar.ekle("d/l2");
+        assert_eq!(baglar.bag_reddi("l1", "d/l2/../..", &[]), Some(RedNedeni::GuvensizSembolikBag("d/l2/../..".to_string())));
+        assert_eq!(baglar.bag_reddi("d/l2", "..", &[]), None);
+
+        // Bir bağın altındaki bağ ve çıkarılmış bir dizinin yerini alan bağ reddedilir.
+        baglar.ekle("d/l2/l3");
+        assert_eq!(baglar.bag_reddi("d/l2/l3", "x", &[]), Some(RedNedeni::SembolikBagUzerinden("d/l2".to_string())));
+        let cikarilan = vec!["d/l2/".to_string(), "d/l2/dosya".to_string()];
+        assert_eq!(baglar.bag_reddi("d/l2", "..", &cikarilan), Some(RedNedeni::SembolikBagUzerinden("d/l2".to_string())));
+        assert_eq!(baglar.bag_reddi("d/l2", "..", &["d/l22".to_string()]), None);
+    }
+}
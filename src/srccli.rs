@@ -24,17 +24,28 @@ mod pkg_manager {
 
     // Kurulu paket listesinin saklandığı Kaynak ID'si (varsayımsal)
     const INSTALLED_PACKAGES_LIST_RESOURCE: &str = "sahne://config/installed_packages.list";
-    // Kurulum/kaldırma scriptlerinin bulunabileceği temel Kaynak Yolu (varsayımsal)
-    const PACKAGE_SCRIPT_BASE_RESOURCE: &str = "sahne://system/package_scripts/";
     // Kurulu paketlerin dosyalarının saklandığı temel Kaynak Yolu (varsayımsal)
      const INSTALLED_FILES_BASE_RESOURCE: &str = "sahne://installed_packages/"; // Önceki srccache/srcarchive'dan hatırlatma
 
+    // Komutların çalıştığı kapsam.
+    // kok: --root ile verilen kök; yerel durum bu kökün altında tutulur.
+    // kullanici: --kullanici verildiyse kurulum dizini, önbellek, veritabanı ve kilit yapılandırmadaki
+    // kullanıcı kökünün (kullanici.kok) altında tutulur; sistem paketlerine dokunulmaz.
+    // komut_satiri: İşlem geçmişine yazılan, program adı hariç tüm argümanlar.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Kapsam<'a> {
+        pub kok: Option<&'a str>,
+        pub kullanici: bool,
+        pub komut_satiri: &'a str,
+    }
+
     // Kurulu paketleri listeler.
-    pub fn list_packages() -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+    pub fn list_packages(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
         println!("Kurulu paketler listeleniyor...");
+        let liste_id = kok_altinda(kapsam, INSTALLED_PACKAGES_LIST_RESOURCE)?;
 
         // Kurulu paketler listesini içeren Kaynağı oku
-        match resource::acquire(INSTALLED_PACKAGES_LIST_RESOURCE, resource::MODE_READ) {
+        match resource::acquire(&liste_id, resource::MODE_READ) {
             Ok(handle) => {
                 let mut buffer = Vec::new(); // alloc::vec::Vec kullanılıyor
                 let mut temp_buffer = [0u8; 512]; // Okuma tamponu (stack'te)
@@ -49,7 +60,7 @@ mod pkg_manager {
                         Err(e) => {
                             // Okuma hatası durumunda handle'ı serbest bırakıp hata dön
                             let _ = resource::release(handle);
-                            eprintln!("Paket listesi okuma hatası (Kaynak: {}): {:?}", INSTALLED_PACKAGES_LIST_RESOURCE, e);
+                            eprintln!("Paket listesi okuma hatası (Kaynak: {}): {:?}", liste_id, e);
                             return Err(PaketYoneticisiHatasi::from(e)); // SahneError'ı PaketYoneticisiHatasi'na çevir
                         }
                     }
@@ -58,7 +69,7 @@ mod pkg_manager {
                 // Handle'ı serbest bırak
                 let release_result = resource::release(handle);
                  if let Err(e) = release_result {
-                      eprintln!("Paket listesi Kaynağı release hatası ({}): {:?}", INSTALLED_PACKAGES_LIST_RESOURCE, e);
+                      eprintln!("Paket listesi Kaynağı release hatası ({}): {:?}", liste_id, e);
                       // Release hatası kritik olmayabilir, loglayıp devam edebiliriz.
                  }
 
@@ -84,7 +95,7 @@ mod pkg_manager {
             }
             Err(e) => {
                 // Diğer Sahne64 hataları
-                eprintln!("Paket listesi Kaynağı acquire hatası ({}): {:?}", INSTALLED_PACKAGES_LIST_RESOURCE, e);
+                eprintln!("Paket listesi Kaynağı acquire hatası ({}): {:?}", liste_id, e);
                 Err(PaketYoneticisiHatasi::from(e)) // SahneError'ı PaketYoneticisiHatasi'na çevir
             }
         }
@@ -94,11 +105,12 @@ mod pkg_manager {
     // Gerçek ekleme mantığı (dosyaları kopyalama, veritabanını güncelleme) burada veya başka bir modülde olur.
     // Burada sadece örnekteki gibi bir "kurulum scripti" çalıştırmayı taklit edelim.
     // package_name: Eklenecek paketin adı.
-    pub fn add_package(package_name: &str) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+    pub fn add_package(package_name: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
         println!("{} paketi ekleniyor...", package_name);
 
         // Kurulum scripti Kaynak ID'sini oluştur (varsayımsal)
-        let install_script_resource_id = format!("{}{}/install.sh", PACKAGE_SCRIPT_BASE_RESOURCE, package_name);
+        let betik_dizini = yapilandirma(kapsam)?.betik_dizini;
+        let install_script_resource_id = format!("{}/{}/install.sh", betik_dizini.trim_end_matches('/'), package_name);
 
         // Kurulum scripti Kaynağına erişim Handle'ı edin (çalıştırma izniyle?)
         // Sahne64 API'sında 'çalıştırma izni' Mode flag'i olmayabilir.
@@ -146,51 +158,240 @@ mod pkg_manager {
         }
     }
 
-    // Bir paketi kaldırır.
+    // Yapılandırmayı katmanlarıyla yükler (varsayılanlar, sistem ve kullanıcı TOML dosyaları, SAHNE_PKG_* ortamı).
+    // Varsayım: Sahne64 task modülü görevin ortam değişkenlerini task::env_vars() ile (ad, değer) çiftleri olarak verir.
+    fn yapilandirma_katmanlari() -> Result<crate::srcconfig::KatmanliYapilandirma, PaketYoneticisiHatasi> {
+        crate::srcconfig::KatmanliYapilandirma::yukle(
+            crate::srcconfig::SISTEM_YAPILANDIRMA_KAYNAGI,
+            Some(crate::srcconfig::KULLANICI_YAPILANDIRMA_KAYNAGI),
+            &task::env_vars(),
+        )
+    }
+
+    // Birleştirilmiş yapılandırma, kapsama göre yerel yolları taşınmış haliyle. Yapılandırmanın
+    // kendisi her zaman çalışan sistemden okunur.
+    fn yapilandirma(kapsam: Kapsam) -> Result<crate::srcconfig::Yapilandirma, PaketYoneticisiHatasi> {
+        Ok(kapsama_uygula(yapilandirma_katmanlari()?.yapilandirma, kapsam))
+    }
+
+    // Günlüklemeyi birleştirilmiş yapılandırmaya göre başlatır. Yapılandırma okunamazsa varsayılanlar
+    // kullanılır; hatayı komutun kendisi ayrıca bildirir.
+    pub fn gunlukleme_baslat(kapsam: Kapsam) {
+        let yapilandirma = yapilandirma(kapsam).unwrap_or_else(|_| kapsama_uygula(crate::srcconfig::Yapilandirma::default(), kapsam));
+        if let Err(e) = crate::srclogging::baslat_gunlukleme(&yapilandirma) {
+            eprintln!("Günlükleme başlatılamadı: {:?}", e);
+        }
+    }
+
+    // Önce kullanıcı kapsamı, sonra --root uygulanır; böylece kullanıcı kökü de kökün altında kalır.
+    fn kapsama_uygula(mut yapilandirma: crate::srcconfig::Yapilandirma, kapsam: Kapsam) -> crate::srcconfig::Yapilandirma {
+        if kapsam.kullanici {
+            yapilandirma = yapilandirma.kullanici_kapsami();
+        }
+        if let Some(kok) = kapsam.kok {
+            yapilandirma = yapilandirma.kok_altinda(kok);
+        }
+        yapilandirma
+    }
+
+    // Yapılandırmada yer almayan sabit Kaynak ID'lerini kapsama göre (kullanıcı kökü, --root) taşır.
+    fn kok_altinda(kapsam: Kapsam, resource_id: &str) -> Result<String, PaketYoneticisiHatasi> {
+        let mut yol = resource_id.to_owned();
+        if kapsam.kullanici {
+            yol = crate::srcconfig::kok_altinda_yol(&yapilandirma_katmanlari()?.yapilandirma.kullanici_koku, &yol);
+        }
+        if let Some(kok) = kapsam.kok {
+            yol = crate::srcconfig::kok_altinda_yol(kok, &yol);
+        }
+        Ok(yol)
+    }
+
+    // Kurulum/kaldırma işlemleri için yapılandırmadaki Kaynak yollarıyla bir KurulumYoneticisi oluşturur.
+    // Yapılandırmada yer almayan güven listeleri kapsama göre taşınır (bkz. kok_altinda).
+    // İşlem geçmişine yazılan kullanıcı USER (yoksa LOGNAME) ortam değişkeninden alınır.
+    fn kurulum_yoneticisi(yapilandirma: &crate::srcconfig::Yapilandirma, kapsam: Kapsam) -> Result<crate::srcinstaller::KurulumYoneticisi, PaketYoneticisiHatasi> {
+        let mut kurulum = crate::srcinstaller::KurulumYoneticisi::yeni(
+            yapilandirma.depo_url.clone(),
+            yapilandirma.kurulum_dizini.clone(),
+            yapilandirma.onbellek_dizini.clone(),
+        );
+        kurulum.veritabani_resource_id = yapilandirma.veritabani_kaynagi.clone();
+        kurulum.islem_gunlugu_resource_id = yapilandirma.islem_gunlugu_kaynagi.clone();
+        kurulum.betik_zaman_asimi_us = yapilandirma.betik_zaman_asimi_sn.saturating_mul(1_000_000);
+        kurulum.gecmis_resource_id = yapilandirma.gecmis_kaynagi.clone();
+        kurulum.yapilandirma_dosyalari_dizini = yapilandirma.yapilandirma_dosyalari_dizini.clone();
+        kurulum.betik_base_resource_id = yapilandirma.betik_dizini.clone();
+        kurulum.guvenilen_yayincilar_resource_id = kok_altinda(kapsam, crate::srctrust::VARSAYILAN_YAYINCILAR_KAYNAGI)?;
+        kurulum.guvenilen_paketler_resource_id = kok_altinda(kapsam, crate::srctrust::VARSAYILAN_PAKETLER_KAYNAGI)?;
+        let ortam = task::env_vars();
+        kurulum.kullanici = ["USER", "LOGNAME"].iter()
+            .find_map(|ad| ortam.iter().find(|(d, _)| d == ad).map(|(_, deger)| deger.clone()))
+            .unwrap_or_else(|| String::from("bilinmiyor"));
+        kurulum.komut_satiri = kapsam.komut_satiri.to_owned();
+        let yetenekler = crate::srcfeature_flags::YetenekKaydi::from_config(yapilandirma);
+        kurulum.arsiv_ozellikleri = yetenekler.ozellikler().clone();
+        kurulum.sandbox.etkin = yetenekler.sandbox();
+        Ok(kurulum)
+    }
+
+    // Kurulu sistemi değiştiren komutlar için (kökün) kurulum kilidini alır; kilit dönen değer
+    // düşürülünce bırakılır.
+    fn kilitle(yapilandirma: &crate::srcconfig::Yapilandirma) -> Result<crate::srclockfile::KilitYoneticisi, PaketYoneticisiHatasi> {
+        let mut kilit = crate::srclockfile::KilitYoneticisi::yeni(&yapilandirma.kilit_kaynagi)?;
+        kilit.kilit_al()?;
+        Ok(kilit)
+    }
+
+    // Depo adresinin protokolü etkin ağ protokolleri arasında olmalıdır; imza doğrulaması yetenek kaydına göre açılır
+    // ve imzalar guvenlik.guvenilen_anahtarlar listesindeki anahtarlarla doğrulanır.
+    fn depo_yoneticisi(yapilandirma: &crate::srcconfig::Yapilandirma) -> Result<crate::srcrepository::DepoYoneticisi, PaketYoneticisiHatasi> {
+        let yetenekler = crate::srcfeature_flags::YetenekKaydi::from_config(yapilandirma);
+        crate::srcnetwork::protokol_denetle(&yapilandirma.depo_url, yetenekler.ozellikler())?;
+        let mut depo = crate::srcrepository::DepoYoneticisi::yeni(yapilandirma.depo_url.clone(), yapilandirma.yerel_depo_yolu.clone());
+        depo.imza_dogrulama = yetenekler.imza_dogrulama();
+        if depo.imza_dogrulama {
+            depo.guvenilen_anahtarlar = crate::srctrust::guvenilen_anahtarlari_oku(&yapilandirma.guvenilen_anahtarlar_kaynagi)
+                .map_err(|e| PaketYoneticisiHatasi::SecurityError(crate::srcsecurity::SecurityError::InvalidSignatureFile(format!(
+                    "{}: {}", yapilandirma.guvenilen_anahtarlar_kaynagi, e
+                ))))?;
+        }
+        Ok(depo)
+    }
+
+    // Bir paketi kaldırır (kaldırma betiği, kurulu dosyalar ve veritabanı kaydı).
     // package_name: Kaldırılacak paketin adı.
-    pub fn remove_package(package_name: &str) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+    // bagimliliklarla: true ise, paketle birlikte artık gerekmeyen otomatik kurulmuş bağımlılıkları da kaldırılır.
+    pub fn remove_package(package_name: &str, bagimliliklarla: bool, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
         println!("{} paketi kaldırılıyor...", package_name);
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let kurulum = kurulum_yoneticisi(&yapilandirma, kapsam)?;
+        if bagimliliklarla {
+            let kaldirilanlar = kurulum.bagimliliklariyla_kaldir(package_name)?;
+            println!("Kaldırılan paketler: {}", kaldirilanlar.join(", "));
+            Ok(())
+        } else {
+            kurulum.paketi_kaldir(package_name)
+        }
+    }
 
-        // Kaldırma scripti Kaynak ID'sini oluştur (varsayımsal)
-        let uninstall_script_resource_id = format!("{}{}/uninstall.sh", PACKAGE_SCRIPT_BASE_RESOURCE, package_name);
+    // Otomatik kurulmuş ve artık hiçbir açık kurulmuş paketin gerektirmediği (yetim) paketleri listeler.
+    pub fn list_orphans(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yetimler = kurulum_yoneticisi(&yapilandirma(kapsam)?, kapsam)?.yetimleri_listele()?;
+        if yetimler.is_empty() {
+            println!("Yetim paket yok.");
+        } else {
+            println!("Yetim paketler ({}):", yetimler.len());
+            for ad in &yetimler {
+                println!(" - {}", ad);
+            }
+        }
+        Ok(())
+    }
 
-        // Kaldırma scripti Kaynağına erişim Handle'ı edin
-        match resource::acquire(&uninstall_script_resource_id, resource::MODE_READ) { // Yine çalıştırma için Handle edinme
-             Ok(script_handle) => {
-                 println!("Kaldırma scripti Kaynağı edinildi: {}", uninstall_script_resource_id);
+    // Tüm yetim paketleri kaldırır.
+    pub fn autoremove(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let kaldirilanlar = kurulum_yoneticisi(&yapilandirma, kapsam)?.yetimleri_kaldir()?;
+        if kaldirilanlar.is_empty() {
+            println!("Kaldırılacak yetim paket yok.");
+        } else {
+            println!("Kaldırılan yetim paketler: {}", kaldirilanlar.join(", "));
+        }
+        Ok(())
+    }
 
-                 // Yeni bir görev (task) olarak scripti başlat
-                 let task_args: &[u8] = b""; // Kaldırma scriptine argüman geçilebilir.
+    // Bir kurulu pakete bağımlı olan paketleri listeler.
+    // tumu: true ise dolaylı bağımlılar da listelenir.
+    pub fn reverse_dependencies(package_name: &str, tumu: bool, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma(kapsam)?.veritabani_kaynagi)?;
+        if !kurulu.kurulu_mu(package_name) {
+            return Err(PaketYoneticisiHatasi::PaketBulunamadi(package_name.to_owned()));
+        }
+        let bagimlilar = if tumu {
+            crate::srcdependency::BagimlilikYoneticisi::tum_ters_bagimliliklar(&kurulu, package_name)
+        } else {
+            crate::srcdependency::BagimlilikYoneticisi::ters_bagimliliklar(&kurulu, package_name)
+        };
+        if bagimlilar.is_empty() {
+            println!("'{}' paketine bağımlı kurulu paket yok.", package_name);
+        } else {
+            println!("'{}' paketine bağımlı paketler:", package_name);
+            for ad in &bagimlilar {
+                println!(" - {}", ad);
+            }
+        }
+        Ok(())
+    }
 
-                 match task::spawn(script_handle, task_args) {
-                      Ok(new_tid) => {
-                          println!("Paket kaldırma görevi başlatıldı, TaskId: {:?}", new_tid);
-                           let _ = resource::release(script_handle); // Handle'ı serbest bırak
-                          // Görevin bitmesini beklemek gerekebilir (task::wait eksikliği).
-                          Ok(())
-                      }
-                      Err(e) => {
-                          let _ = resource::release(script_handle); // Hata durumunda handle'ı temizle
-                          eprintln!("Paket kaldırma görevi başlatılamadı (Kaynak: {}): {:?}", uninstall_script_resource_id, e);
-                          Err(PaketYoneticisiHatasi::from(e)) // SahneError'ı PaketYoneticisiHatasi'na çevir
-                      }
-                 }
-             }
-             Err(SahneError::ResourceNotFound) => {
-                  // Kaldırma scripti yoksa paket belki de düzgün kurulmamıştır veya script gerektirmiyordur.
-                  // Veya bu bir hata olabilir. Şimdilik loglayıp devam edelim veya özel hata dönelim.
-                  eprintln!("Kaldırma scripti bulunamadı ({}). Paket dosyaları manuel silinmeli?", uninstall_script_resource_id);
-                  // Paketin dosyalarının ve veritabanı kaydının silinmesi burada ele alınmalı.
-                  // Bu, resource::delete veya resource::control kullanımı gerektirir (eksik fonksiyonellik).
-                  // Varsayım: Paket yöneticisi, kurulu dosyaların listesini tutar ve silme işlemi bu listeye göre yapılır.
-                  // Bu da resource::delete'in varlığını gerektirir.
-                  // Şimdilik sadece script çalıştırmayı taklit ettiğimiz için, script yoksa hata dönmek yerine uyarı verelim.
-                  Ok(()) // Script yoksa hata değil, uyarı verip devam et
-             }
-             Err(e) => {
-                 eprintln!("Kaldırma scripti Kaynağı acquire hatası ({}): {:?}", uninstall_script_resource_id, e);
-                 Err(PaketYoneticisiHatasi::from(e)) // SahneError'ı PaketYoneticisiHatasi'na çevir
-             }
+    // Bir paketin neden kurulu olduğunu, açık kurulmuş bir paketten ona uzanan bağımlılık zinciriyle açıklar.
+    pub fn why_installed(package_name: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma(kapsam)?.veritabani_kaynagi)?;
+        if !kurulu.kurulu_mu(package_name) {
+            return Err(PaketYoneticisiHatasi::PaketBulunamadi(package_name.to_owned()));
+        }
+        match crate::srcdependency::BagimlilikYoneticisi::neden_kurulu(&kurulu, package_name) {
+            Some(zincir) if zincir.len() == 1 => println!("'{}' açıkça (kullanıcı isteğiyle) kurulmuş.", package_name),
+            Some(zincir) => println!("{}", zincir.join(" -> ")),
+            None => println!("'{}' hiçbir açık kurulmuş paket tarafından gerekmiyor (yetim).", package_name),
+        }
+        Ok(())
+    }
+
+    // Bağımlılık grafını DOT veya JSON olarak dışa aktarır.
+    // hedef: "--kurulu" (tüm kurulu küme), "--depo" (tüm depo) veya virgülle ayrılmış paket adları (depo dilimi).
+    // json: true ise JSON, değilse Graphviz DOT üretilir.
+    // cikti: Verilirse çıktı bu Kaynağa yazılır, verilmezse ekrana basılır.
+    pub fn export_graph(hedef: &str, secenekler: &crate::srcgraph::GrafSecenekleri, json: bool, cikti: Option<&str>, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        use crate::srcgraph::BagimlilikGrafi;
+        let yapilandirma = yapilandirma(kapsam)?;
+        let graf = if hedef == "--kurulu" {
+            let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+            BagimlilikGrafi::kurulu_paketlerden(&kurulu, &[], secenekler)
+        } else {
+            let mut depo = depo_yoneticisi(&yapilandirma)?;
+            let paketler = depo.paket_listesini_al()?;
+            let kokler: Vec<&str> = if hedef == "--depo" { Vec::new() } else { hedef.split(',').collect() };
+            if let Some(eksik) = kokler.iter().find(|k| !paketler.iter().any(|p| p.ad == **k)) {
+                return Err(PaketYoneticisiHatasi::PaketBulunamadi((*eksik).to_owned()));
+            }
+            BagimlilikGrafi::depodan(&paketler, &kokler, secenekler)
+        };
+
+        if secenekler.donguleri_vurgula {
+            for dongu in graf.donguler() {
+                eprintln!("Uyarı: Bağımlılık döngüsü: {}", dongu.join(" -> "));
+            }
+        }
+        let metin = if json { graf.json_olustur(secenekler) } else { graf.dot_olustur(secenekler) };
+
+        match cikti {
+            None => {
+                println!("{}", metin);
+                Ok(())
+            }
+            Some(kaynak_id) => {
+                let handle = resource::acquire(kaynak_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)?;
+                let veri = metin.as_bytes();
+                let mut yazilan = 0;
+                while yazilan < veri.len() {
+                    match resource::write(handle, &veri[yazilan..]) {
+                        Ok(0) => {
+                            let _ = resource::release(handle);
+                            return Err(PaketYoneticisiHatasi::from(SahneError::InvalidOperation));
+                        }
+                        Ok(n) => yazilan += n,
+                        Err(e) => {
+                            let _ = resource::release(handle);
+                            return Err(PaketYoneticisiHatasi::from(e));
+                        }
+                    }
+                }
+                resource::release(handle)?;
+                println!("Graf yazıldı: {} ({} düğüm, {} kenar)", kaynak_id, graf.dugumler.len(), graf.kenarlar.len());
+                Ok(())
+            }
         }
     }
 
@@ -209,67 +410,629 @@ mod pkg_manager {
 
     // Bir paketi kurar.
     // package_name: Kurulacak paketin adı.
-    // Kurulum genellikle: İndir -> Sağlamasını Kontrol Et -> Çıkar -> Ekle (script çalıştır + DB kaydı) adımlarını içerir.
-    pub fn install_package(package_name: &str) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+    // secenekler: Öneri/tavsiye ve özellik seçimleri (komut satırı bayraklarından).
+    // sistemi_golgele: Kullanıcı kapsamında, sistemde kurulu paketlerin kullanıcı kopyalarının kurulmasına izin verir.
+    // Kurulum: İndir -> Sağlamasını Kontrol Et -> Çıkar -> Betikler + veritabanı kaydı (bkz. KurulumYoneticisi::plani_uygula).
+    pub fn install_package(package_name: &str, secenekler: &crate::srcdependency::KurulumSecenekleri, sistemi_golgele: bool, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
         println!("{} paketi kuruluyor...", package_name);
-        // Burada indirme (ağ kaynağı resource'u?), checksum doğrulama (srcchecksum),
-        // arşivden çıkarma (srcarchive) adımları çağrılmalı.
-        // Ardından add_package fonksiyonu (veya onun içeriği) çalıştırılmalı.
-
-        // Örnek Akış (Placeholder):
-        // 1. Paketi indir (örn. "sahne://remoterepo/packages/my_package.zip" Kaynağından "sahne://cache/packages/my_package.zip" Kaynağına)
-            let download_source = format!("sahne://remoterepo/packages/{}.zip", package_name);
-            let download_dest = format!("sahne://cache/packages/{}.zip", package_name);
-        //    // İndirme fonksiyonu burada çağrılır (ağ kaynakları için özel resource type?).
-        //    // İndirme işlemi resource::read/write ile olabilir, veya özel bir download syscall'u?
-        //    // Geçici olarak indirme başarılı oldu varsayalım.
-            println!("{} paketi indirildi (varsayımsal olarak).", package_name);
-
-
-        // 2. İndirilen dosyanın checksum'ını doğrula
-            let expected_md5 = "..."; // Paket meta verisinden alınmalı
-            match crate::srcchecksum::dogrula_md5(&download_dest, expected_md5) {
-                 Ok(true) => println!("Checksum doğrulama başarılı."),
-                 Ok(false) => {
-                     eprintln!("Checksum doğrulama BAŞARISIZ.");
-                     // İndirilen dosyayı temizle?
-                      resource::delete(&download_dest); // Eksik fonksiyonellik
-                     return Err(PaketYoneticisiHatasi::PaketKurulumHatasi(String::from("Checksum doğrulaması başarısız")));
-                 }
-                 Err(e) => {
-                      eprintln!("Checksum hesaplama/doğrulama hatası: {:?}", e);
-                      return Err(e); // Hata zaten doğru türde
-                 }
+
+        // Depo indeksi ve kurulu paketlerle bağımlılık planını çıkar (özellikler, öneriler, sanal paketler).
+        // Kullanıcı kapsamında bağımlılıklar sistemde kurulu paketlerle de karşılanabilir.
+        let sistem = if kapsam.kullanici {
+            crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma(Kapsam { kullanici: false, ..kapsam })?.veritabani_kaynagi)?
+        } else {
+            crate::srcdatabase::KuruluVeritabani::default()
+        };
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let mut depo = depo_yoneticisi(&yapilandirma)?;
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+        let paketler = depo.paket_listesini_al()?;
+        let plan = crate::srcdependency::BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &[package_name], &sistem.birlestir(&kurulu), secenekler)?;
+        // Kullanıcı kapsamı sistem paketlerini kaldıramaz; açıkça istenmedikçe onları gölgeleyemez de.
+        if let Some(ad) = plan.kaldirilacaklar.iter().find(|ad| sistem.kurulu_mu(ad)) {
+            return Err(PaketYoneticisiHatasi::ConflictError(format!("'{}' sistem paketi kullanıcı kapsamından kaldırılamaz", ad)));
+        }
+        let golgelenenler: Vec<&str> = plan.kurulacaklar.iter().filter(|ad| sistem.kurulu_mu(ad)).map(|ad| ad.as_str()).collect();
+        if !golgelenenler.is_empty() && !sistemi_golgele {
+            return Err(PaketYoneticisiHatasi::ConflictError(format!(
+                "sistemde kurulu paketler gölgelenecek: {} (izin vermek için --sistemi-golgele)",
+                golgelenenler.join(", ")
+            )));
+        }
+        for ad in &golgelenenler {
+            eprintln!("Uyarı: '{}' sistem paketi kullanıcı kapsamında gölgeleniyor.", ad);
+        }
+        println!("Kurulacak paketler: {}", plan.kurulacaklar.join(", "));
+        kurulum_politikasini_uygula(&yapilandirma, &mut depo, &plan.kurulacaklar)?;
+        for grup in plan.kurulum_gruplari.iter().filter(|g| g.len() > 1) {
+            println!(" - Birlikte kurulacak döngüsel grup: {}", grup.join(", "));
+        }
+        if !plan.donguler.is_empty() {
+            eprintln!("{}", crate::srcdependency::donguleri_bicimlendir(&plan.donguler));
+        }
+        for (ad, ozellikler) in &plan.etkin_ozellikler {
+            println!(" - {} özellikleri: {}", ad, ozellikler.join(", "));
+        }
+        if !plan.kurulmayan_oneriler.is_empty() {
+            println!("Kurulmayan öneriler (--onerilenlerle / --tavsiyelerle): {}", plan.kurulmayan_oneriler.join(", "));
+        }
+
+        let kurulum = kurulum_yoneticisi(&yapilandirma, kapsam)?;
+        // Önceki çalıştırmalardan kalan yarım hazırlık alanlarını temizle.
+        if let Err(e) = kurulum.hazirlik_artiklarini_temizle() {
+            eprintln!("Uyarı: Hazırlık artıkları temizlenemedi: {:?}", e);
+        }
+        // Plan tek bir işlem olarak uygulanır: eksik arşivler indirilip doğrulanır, paketler bağımlılık
+        // sırasıyla betikleriyle kurulur ve kaydedilir; hata olursa tüm işlem geri alınır.
+        kurulum.plani_uygula(&plan, &paketler)?;
+
+        println!("{} paketi başarıyla kuruldu.", package_name);
+        Ok(())
+    }
+
+    // guvenlik.kurulum_politikasi'na göre kurulacak paket sürümlerini güvenlik bildirisi akışıyla denetler.
+    // uyar: eşleşmeler yazdırılır; engelle: guvenlik.engelleme_onemi ve üstünde eşleşme varsa kurulum durur.
+    // Akış alınamazsa (süresi dolmuş yerel kopya dahil) uyar politikasında kurulum sürer, engelle politikasında
+    // durur. İmza hatası veya süresi dolmuş uzak akış her iki politikada da kurulumu durdurur.
+    fn kurulum_politikasini_uygula(
+        yapilandirma: &crate::srcconfig::Yapilandirma,
+        depo: &mut crate::srcrepository::DepoYoneticisi,
+        kurulacaklar: &[String],
+    ) -> Result<(), PaketYoneticisiHatasi> {
+        use crate::srcadvisory::{KurulumPolitikasi, Onem};
+        // Değerler yapılandırma okunurken denetlendi
+        let politika = KurulumPolitikasi::ayristir(&yapilandirma.kurulum_politikasi).unwrap_or(KurulumPolitikasi::Uyar);
+        if politika == KurulumPolitikasi::Kapali {
+            return Ok(());
+        }
+        let esik = Onem::ayristir(&yapilandirma.engelleme_onemi).unwrap_or(Onem::Yuksek);
+
+        let akis = match depo.guvenlik_bildirilerini_al() {
+            Ok(akis) => akis,
+            Err(e @ PaketYoneticisiHatasi::SecurityError(_)) => return Err(e),
+            Err(e) if politika == KurulumPolitikasi::Uyar => {
+                eprintln!("Uyarı: Güvenlik bildirileri alınamadı, kurulacak sürümler denetlenmedi: {:?}", e);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Güvenlik bildirileri alınamadı; 'engelle' politikasında denetlenmeyen paket kurulmaz.");
+                return Err(e);
             }
+        };
 
-        // 3. Paketi önbellekten/indirilen yerden kurulu alana çıkar (srcarchive)
-            let archive_resource_id = download_dest;
-            let extract_dest_resource_id = format!("sahne://installed_packages/{}/", package_name);
-            match crate::srcarchive::zip_ac(&archive_resource_id, &extract_dest_resource_id) {
-                 Ok(_) => println!("Paket dosyaları çıkarıldı."),
-                 Err(e) => {
-                     eprintln!("Paket dosyaları çıkarma hatası: {:?}", e);
-        //             // Çıkarılan dosyaları temizle?
-                      resource::delete(&extract_dest_resource_id); // Eksik fonksiyonellik
-                     return Err(e); // Hata zaten doğru türde
-                 }
+        let depo_paketleri = depo.paket_listesini_al()?;
+        let surumler = kurulacak_surumler(&depo_paketleri, kurulacaklar);
+        let bulgular = akis.denetle(surumler.iter().map(|(ad, surum)| (*ad, *surum)), esik);
+        if bulgular.is_empty() {
+            return Ok(());
+        }
+        guvenlik_bulgularini_yaz(&bulgular);
+        if politika == KurulumPolitikasi::Engelle {
+            return Err(PaketYoneticisiHatasi::SecurityError(crate::srcsecurity::SecurityError::VulnerabilityScanError(format!(
+                "{} güvenlik bildirisi kurulacak sürümleri etkiliyor (guvenlik.kurulum_politikasi = engelle)", bulgular.len()
+            ))));
+        }
+        eprintln!("Uyarı: Kurulacak sürümleri etkileyen {} güvenlik bildirisi var.", bulgular.len());
+        Ok(())
+    }
+
+    // Kurulu paketleri veya (paketler verildiyse) bu paketlerin kurulum planını güvenlik bildirisi akışıyla
+    // denetler. asgari_onem altındaki bildiriler raporlanmaz. Eşleşme varsa hata döner.
+    pub fn audit(paketler: &[&str], asgari_onem: crate::srcadvisory::Onem, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let mut depo = depo_yoneticisi(&yapilandirma)?;
+        let akis = depo.guvenlik_bildirilerini_al()?;
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+
+        let (bulgular, denetlenen) = if paketler.is_empty() {
+            let bulgular = akis.denetle(kurulu.paketler.values().map(|k| (k.ad.as_str(), k.surum.as_str())), asgari_onem);
+            (bulgular, kurulu.paketler.len())
+        } else {
+            let depo_paketleri = depo.paket_listesini_al()?;
+            let plan = crate::srcdependency::BagimlilikYoneticisi::cozum_plani_olustur(
+                &depo_paketleri, paketler, &kurulu, &crate::srcdependency::KurulumSecenekleri::default(),
+            )?;
+            println!("Planlanan işlem: {}", plan.kurulacaklar.join(", "));
+            let surumler = kurulacak_surumler(&depo_paketleri, &plan.kurulacaklar);
+            let bulgular = akis.denetle(surumler.iter().map(|(ad, surum)| (*ad, *surum)), asgari_onem);
+            (bulgular, surumler.len())
+        };
+
+        if bulgular.is_empty() {
+            println!("{} paket denetlendi, {} bildiri içinde eşleşme yok.", denetlenen, akis.bildiriler.len());
+            return Ok(());
+        }
+        guvenlik_bulgularini_yaz(&bulgular);
+        Err(PaketYoneticisiHatasi::SecurityError(crate::srcsecurity::SecurityError::VulnerabilityScanError(format!(
+            "{} güvenlik bildirisi eşleşti", bulgular.len()
+        ))))
+    }
+
+    // Plandaki paket adlarını depodaki sürümleriyle eşler; depoda olmayanlar atlanır.
+    fn kurulacak_surumler<'a>(depo_paketleri: &'a [crate::package::Paket], kurulacaklar: &[String]) -> Vec<(&'a str, &'a str)> {
+        kurulacaklar
+            .iter()
+            .filter_map(|ad| depo_paketleri.iter().find(|p| &p.ad == ad))
+            .map(|p| (p.ad.as_str(), p.surum.as_str()))
+            .collect()
+    }
+
+    fn guvenlik_bulgularini_yaz(bulgular: &[crate::srcadvisory::Bulgu]) {
+        for bulgu in bulgular {
+            let diger = if bulgu.diger_adlar.is_empty() { String::new() } else { format!(" ({})", bulgu.diger_adlar.join(", ")) };
+            println!("{}@{}: {}{} [{}] {}", bulgu.paket, bulgu.surum, bulgu.kimlik, diger, bulgu.onem, bulgu.ozet);
+            if let Some(surum) = &bulgu.duzeltildi {
+                println!("    düzeltildiği sürüm: {}", surum);
             }
+        }
+    }
+
+    // Bir dizindeki paket arşivlerinden depo indeksini (paketler.bin, imza ve delta) üretir.
+    // İndeks depo.imza_anahtari ile imzalanır; arşivlerin ayrık imzaları bu anahtarla ve
+    // guvenlik.guvenilen_anahtarlar listesiyle doğrulanır.
+    // packages_dir: Paket arşivlerinin bulunduğu dizin Kaynağı.
+    // output_dir: Statik olarak sunulacak depo dizini Kaynağı.
+    pub fn publish_repository(packages_dir: &str, output_dir: &str) -> Result<(), PaketYoneticisiHatasi> {
+        println!("Depo oluşturuluyor: {} -> {}", packages_dir, output_dir);
+
+        let yapilandirma = yapilandirma_katmanlari()?.yapilandirma;
+        let imza_anahtari = crate::srcsigning::gizli_anahtari_oku(&yapilandirma.imza_anahtari_kaynagi).map_err(|e| {
+            eprintln!("Depo imza anahtarı okunamadı ({}): {}", yapilandirma.imza_anahtari_kaynagi, e);
+            PaketYoneticisiHatasi::from(e)
+        })?;
+        let mut yayinlayici = crate::srcrepositorypublish::DepoYayinlayici::yeni(packages_dir, output_dir, imza_anahtari);
+        match crate::srctrust::guvenilen_anahtarlari_oku(&yapilandirma.guvenilen_anahtarlar_kaynagi) {
+            Ok(anahtarlar) => yayinlayici.guvenilen_anahtarlar.extend(anahtarlar),
+            Err(e) => eprintln!("Uyarı: Güvenilen anahtarlar okunamadı, arşiv imzaları yalnızca depo anahtarıyla doğrulanacak: {}", e),
+        }
+        let rapor = yayinlayici.yayinla()?;
 
-        // 4. Paketi sisteme ekle (script çalıştır, veritabanı kaydı oluştur, vb.)
-        //    Bu genellikle add_package fonksiyonunun içeriği olur.
-            add_package(package_name)?; // Eğer add_package sadece script çalıştırıyorsa
+        for reddedilen in &rapor.reddedilen {
+            eprintln!("Reddedildi: {} ({})", reddedilen.dosya_adi, reddedilen.neden);
+        }
+        for atlanan in &rapor.atlanan {
+            println!("Atlandı: {} ({})", atlanan.dosya_adi, atlanan.neden);
+        }
+        if let Some(sayi) = rapor.bildiri_sayisi {
+            println!("Güvenlik bildirileri yayınlandı: {} bildiri", sayi);
+        }
 
-        // Eğer kurulum süreci add_package'in ötesinde adımlar içeriyorsa, onlar burada olur.
-        // Örn: Veritabanına paket bilgilerini kaydetme.
-        // Bu, kurulu paketler listesi Kaynağına (INSTALLED_PACKAGES_LIST_RESOURCE) yazmayı gerektirir.
-        // Bu kaynağın LOCK edilmesi, içeriğinin okunup güncellenmesi ve tekrar yazılması lazım.
-        // Bu işlemler için sync::lock_* ve resource::* kullanılır.
+        // Reddedilen arşiv varsa indeks yine yazılır, ama çıkış kodu hatayı yansıtmalı.
+        if rapor.reddedilen.is_empty() {
+            Ok(())
+        } else {
+            Err(PaketYoneticisiHatasi::DepoYayinlamaHatasi(format!("{} arşiv reddedildi", rapor.reddedilen.len())))
+        }
+    }
+
+    // Birleştirilmiş yapılandırmanın tüm anahtarlarını TOML satırları olarak listeler.
+    // koken: true ise her değerin geldiği katman (varsayılan, dosya:satır veya ortam değişkeni) de gösterilir.
+    // kapsam: --root veya --kullanici verildiyse yerel yollar taşınmış halleriyle gösterilir.
+    pub fn config_show(koken: bool, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let katmanli = yapilandirma_katmanlari()?;
+        let gecerli = kapsama_uygula(katmanli.yapilandirma.clone(), kapsam);
+        for tanim in crate::srcconfig::ANAHTARLAR {
+            let deger = match gecerli.deger(tanim.ad) {
+                Some(deger) => deger,
+                None => continue,
+            };
+            if koken && gecerli.deger(tanim.ad) != katmanli.yapilandirma.deger(tanim.ad) {
+                println!("{} = {}  # {}, kapsama göre taşındı", tanim.ad, deger, katmanli.koken(tanim.ad));
+            } else if koken {
+                println!("{} = {}  # {}", tanim.ad, deger, katmanli.koken(tanim.ad));
+            } else {
+                println!("{} = {}", tanim.ad, deger);
+            }
+        }
+        Ok(())
+    }
+
+    // Yapılandırmadan çıkan etkin yetenek kümesini kategori kategori gösterir.
+    pub fn show_features(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yetenekler = crate::srcfeature_flags::YetenekKaydi::from_config(&yapilandirma(kapsam)?);
+        for kategori in ["sikistirma", "ag", "guvenlik", "gunluk"] {
+            let bayrak = if yetenekler.bayraklar.kategori_etkin_mi(kategori) { "açık" } else { "kapalı" };
+            println!("[{}] (bayrak: {})", kategori, bayrak);
+            for ozellik in crate::srcfeatures::TUM_OZELLIKLER.iter().filter(|o| o.kategori() == kategori) {
+                println!("  {} {}", if yetenekler.etkin_mi(*ozellik) { "+" } else { "-" }, ozellik.ad());
+            }
+        }
+        Ok(())
+    }
 
-        // Şimdilik sadece add_package çağrısı yapalım (örnekteki gibi script çalıştırmayı taklit eden).
-        add_package(package_name)?; // Script çalıştırma adımı
+    // İşlem geçmişini eskiden yeniye listeler: kimlik, başlangıç zamanı, kullanıcı, sonuç, değişen paket
+    // sayısı ve komut satırı. Sonradan geri alınmış işlemler işaretlenir.
+    pub fn history_list(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let gecmis = crate::srchistory::IslemGecmisi::oku(&yapilandirma(kapsam)?.gecmis_kaynagi)?;
+        if gecmis.kayitlar.is_empty() {
+            println!("İşlem geçmişi boş.");
+            return Ok(());
+        }
+        for kayit in &gecmis.kayitlar {
+            let geri_alindi = match gecmis.geri_alan(kayit.kimlik) {
+                Some(geri_alan) => format!(" (geri alındı: {})", crate::srchistory::kimlik_metni(geri_alan)),
+                None => String::new(),
+            };
+            println!(
+                "{}  {}  {}  {}{}  {} paket  {}",
+                crate::srchistory::kimlik_metni(kayit.kimlik),
+                crate::srclogging::zaman_damgasi(kayit.baslangic_us),
+                kayit.kullanici,
+                kayit.sonuc.ad(),
+                geri_alindi,
+                kayit.degisiklikler.len(),
+                kayit.komut_satiri,
+            );
+        }
+        Ok(())
+    }
 
-        println!("{} paketi başarıyla kuruldu (varsayımsal).", package_name);
+    // Tek bir işlemin ayrıntılarını ve paket değişikliklerini gösterir.
+    // kimlik: İşlem kimliği veya başı (en az 4 hane).
+    pub fn history_show(kimlik: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let gecmis = crate::srchistory::IslemGecmisi::oku(&yapilandirma(kapsam)?.gecmis_kaynagi)?;
+        let kayit = gecmis.bul(kimlik)?;
+        println!("İşlem: {}", crate::srchistory::kimlik_metni(kayit.kimlik));
+        println!("Başlangıç: {}", crate::srclogging::zaman_damgasi(kayit.baslangic_us));
+        println!("Bitiş: {}", crate::srclogging::zaman_damgasi(kayit.bitis_us));
+        println!("Kullanıcı: {}", kayit.kullanici);
+        println!("Komut: {}", kayit.komut_satiri);
+        println!("Sonuç: {}", kayit.sonuc.ad());
+        if let Some(hata) = &kayit.hata {
+            println!("Hata: {}", hata);
+        }
+        if let Some(geri_alinan) = kayit.geri_alinan {
+            println!("Geri aldığı işlem: {}", crate::srchistory::kimlik_metni(geri_alinan));
+        }
+        if let Some(geri_alan) = gecmis.geri_alan(kayit.kimlik) {
+            println!("Geri alan işlem: {}", crate::srchistory::kimlik_metni(geri_alan));
+        }
+        println!("Değişiklikler ({}):", kayit.degisiklikler.len());
+        for degisiklik in &kayit.degisiklikler {
+            println!(" - {}: {}", degisiklik.ad, degisiklik.ozet());
+        }
+        Ok(())
+    }
 
+    // Bir işlemi tersini uygulayarak geri alır: işlemin kurduğu paketler kaldırılır, kaldırdığı veya
+    // güncellediği paketlerin önceki sürümleri önbellekteki arşivlerinden yeniden kurulur. Arşiv adı
+    // kaydedilmemiş eski kayıtlar için depoda aynı sürüm aranır.
+    pub fn history_undo(kimlik: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let gecmis = crate::srchistory::IslemGecmisi::oku(&yapilandirma.gecmis_kaynagi)?;
+        let kayit = gecmis.bul(kimlik)?;
+        if let Some(geri_alan) = gecmis.geri_alan(kayit.kimlik) {
+            return Err(PaketYoneticisiHatasi::ConflictError(format!(
+                "{} işlemi zaten {} işlemiyle geri alınmış",
+                crate::srchistory::kimlik_metni(kayit.kimlik),
+                crate::srchistory::kimlik_metni(geri_alan)
+            )));
+        }
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+        let ters = crate::srchistory::ters_islem(kayit, &kurulu).map_err(PaketYoneticisiHatasi::ConflictError)?;
+        let kurulacaklar = geri_yuklenecek_paketler(&yapilandirma, &ters)?;
+        kurulum_yoneticisi(&yapilandirma, kapsam)?.durumu_geri_yukle(Some(kayit.kimlik), &kurulacaklar, &ters.kaldirilacaklar)?;
+        println!("{} işlemi geri alındı.", crate::srchistory::kimlik_metni(kayit.kimlik));
+        Ok(())
+    }
+
+    // Geri yüklenecek kayıtları kurulum paketleriyle eşleştirir ve yapılacakları listeler. Depo indeksi
+    // sadece arşiv adı kayıtlı olmayan eski sürümler için okunur.
+    fn geri_yuklenecek_paketler(
+        yapilandirma: &crate::srcconfig::Yapilandirma,
+        ters: &crate::srchistory::TersIslem,
+    ) -> Result<Vec<(crate::package::Paket, crate::srcdatabase::KuruluPaket)>, PaketYoneticisiHatasi> {
+        let depo_paketleri = if ters.kurulacaklar.iter().any(|k| k.arsiv.is_none()) {
+            depo_yoneticisi(yapilandirma)?.paket_listesini_al()?
+        } else {
+            Vec::new()
+        };
+        let mut kurulacaklar = Vec::new();
+        for onceki in &ters.kurulacaklar {
+            let paket = crate::srchistory::kurulum_paketi(onceki, &depo_paketleri).ok_or_else(|| {
+                PaketYoneticisiHatasi::PaketBulunamadi(format!("{} {} (arşivi önbellekte ve depoda yok)", onceki.ad, onceki.surum))
+            })?;
+            kurulacaklar.push((paket, onceki.clone()));
+        }
+
+        for (paket, _) in &kurulacaklar {
+            println!("Geri yüklenecek: {} {}", paket.ad, paket.surum);
+        }
+        for ad in &ters.kaldirilacaklar {
+            println!("Kaldırılacak: {}", ad);
+        }
+        Ok(kurulacaklar)
+    }
+
+    // Kurulu paket kümesinin adlandırılmış anlık görüntüsünü alır; görüntüdeki sürümlerin arşivleri
+    // görüntü dizinindeki havuza kopyalanır.
+    pub fn snapshot_create(ad: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        use crate::srcsnapshot::{self, AnlikGoruntu, AnlikGoruntuler};
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let mut goruntuler = AnlikGoruntuler::oku(&yapilandirma.anlik_goruntu_dizini)?;
+        let goruntu = AnlikGoruntu {
+            ad: ad.to_owned(),
+            olusturma_us: task::current_time_us().map_err(PaketYoneticisiHatasi::from)?,
+            veritabani: crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?,
+        };
+        let paket_sayisi = goruntu.veritabani.paketler.len();
+        let eksikler = srcsnapshot::arsivleri_sakla(&yapilandirma.anlik_goruntu_dizini, &yapilandirma.onbellek_dizini, &goruntu)?;
+        goruntuler.ekle(goruntu)?;
+        goruntuler.yaz(&yapilandirma.anlik_goruntu_dizini)?;
+        for eksik in &eksikler {
+            eprintln!("Uyarı: {} arşivi saklanamadı; bu sürüme dönüşte depo kullanılacak", eksik);
+        }
+        println!("'{}' anlık görüntüsü alındı ({} paket).", ad, paket_sayisi);
+        Ok(())
+    }
+
+    // Anlık görüntüleri eskiden yeniye listeler.
+    pub fn snapshot_list(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let goruntuler = crate::srcsnapshot::AnlikGoruntuler::oku(&yapilandirma(kapsam)?.anlik_goruntu_dizini)?;
+        if goruntuler.goruntuler.is_empty() {
+            println!("Anlık görüntü yok.");
+        }
+        for goruntu in &goruntuler.goruntuler {
+            println!("{}  {}  {} paket", goruntu.ad, crate::srclogging::zaman_damgasi(goruntu.olusturma_us), goruntu.veritabani.paketler.len());
+        }
+        Ok(())
+    }
+
+    // İki anlık görüntü arasındaki paket farkını gösterir.
+    // sonraki: Verilmezse görüntü şimdiki kurulu durumla karşılaştırılır.
+    pub fn snapshot_diff(onceki: &str, sonraki: Option<&str>, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let goruntuler = crate::srcsnapshot::AnlikGoruntuler::oku(&yapilandirma.anlik_goruntu_dizini)?;
+        let oncesi = &goruntuler.bul(onceki)?.veritabani;
+        let simdiki;
+        let sonrasi = match sonraki {
+            Some(ad) => &goruntuler.bul(ad)?.veritabani,
+            None => {
+                simdiki = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+                &simdiki
+            }
+        };
+        let degisiklikler = crate::srchistory::degisiklikleri_bul(oncesi, sonrasi);
+        if degisiklikler.is_empty() {
+            println!("Fark yok.");
+        }
+        for degisiklik in &degisiklikler {
+            println!(" - {}: {}", degisiklik.ad, degisiklik.ozet());
+        }
+        Ok(())
+    }
+
+    // Kurulu paketleri tek bir işlemle anlık görüntüdeki sürümlere döndürür: görüntüde olmayan paketler
+    // kaldırılır, farklı sürümde olanlar ve eksikler görüntüdeki sürümleriyle yeniden kurulur.
+    pub fn snapshot_rollback(ad: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let goruntuler = crate::srcsnapshot::AnlikGoruntuler::oku(&yapilandirma.anlik_goruntu_dizini)?;
+        let goruntu = goruntuler.bul(ad)?;
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+        let plan = crate::srcsnapshot::donus_plani(&goruntu.veritabani, &kurulu);
+        if plan == crate::srchistory::TersIslem::default() {
+            println!("Kurulu paketler zaten '{}' anlık görüntüsüyle aynı.", ad);
+            return Ok(());
+        }
+        crate::srcsnapshot::arsivleri_onbellege_koy(&yapilandirma.anlik_goruntu_dizini, &yapilandirma.onbellek_dizini, &plan.kurulacaklar)?;
+        let kurulacaklar = geri_yuklenecek_paketler(&yapilandirma, &plan)?;
+        kurulum_yoneticisi(&yapilandirma, kapsam)?.durumu_geri_yukle(None, &kurulacaklar, &plan.kaldirilacaklar)?;
+        println!("'{}' anlık görüntüsüne dönüldü.", ad);
+        Ok(())
+    }
+
+    // Anlık görüntüyü ve artık hiçbir görüntünün kullanmadığı havuz arşivlerini siler.
+    pub fn snapshot_delete(ad: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let mut goruntuler = crate::srcsnapshot::AnlikGoruntuler::oku(&yapilandirma.anlik_goruntu_dizini)?;
+        let silinen = goruntuler.sil(ad)?;
+        goruntuler.yaz(&yapilandirma.anlik_goruntu_dizini)?;
+        let arsivler = crate::srcsnapshot::kullanilmayan_arsivler(&silinen, &goruntuler.goruntuler);
+        crate::srcsnapshot::arsivleri_sil(&yapilandirma.anlik_goruntu_dizini, &arsivler);
+        println!("'{}' anlık görüntüsü silindi.", ad);
+        Ok(())
+    }
+
+    // Kurulu paketlerin dosyalarını kurulumda kaydedilen manifestoyla karşılaştırır; eksik, değiştirilmiş ve
+    // fazla dosyaları listeler. Sorun bulunursa komut hata koduyla biter.
+    // paket: Verilmezse tüm kurulu paketler denetlenir.
+    pub fn verify_packages(paket: Option<&str>, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+        let kayitlar = denetlenecek_kayitlar(&kurulu, paket)?;
+        let mut sorunlu = 0;
+        for kayit in &kayitlar {
+            let rapor = crate::srcverify::paketi_dogrula(&yapilandirma.kurulum_dizini, kayit)?;
+            dogrulama_raporunu_yaz(&rapor);
+            if !rapor.temiz_mi() {
+                sorunlu += 1;
+            }
+        }
+        if paket.is_none() {
+            for ad in crate::srcverify::kayitsiz_dizinler(&yapilandirma.kurulum_dizini, &kurulu)? {
+                println!("{}: veritabanı kaydı ve manifestosu yok, doğrulanamadı (paketi yeniden kurun)", ad);
+                sorunlu += 1;
+            }
+        }
+        if sorunlu == 0 {
+            println!("{} paket doğrulandı, sorun yok.", kayitlar.len());
+            Ok(())
+        } else {
+            Err(PaketYoneticisiHatasi::DogrulamaHatasi(format!("{} paketin dosyalarında sorun var", sorunlu)))
+        }
+    }
+
+    // Eksik ve değiştirilmiş dosyaları paketin arşivinden yeniden çıkarır; arşiv önbellekte yoksa depodan
+    // aynı sürüm yeniden indirilip depodaki checksum ile doğrulanır. Fazla dosyalara dokunulmaz, sadece listelenir.
+    pub fn repair_packages(paket: Option<&str>, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        let kurulum = kurulum_yoneticisi(&yapilandirma, kapsam)?;
+        let kurulu = crate::srcdatabase::KuruluVeritabani::oku(&yapilandirma.veritabani_kaynagi)?;
+        let mut depo_paketleri: Option<Vec<crate::package::Paket>> = None;
+        let mut onarilamayan = 0;
+        for kayit in denetlenecek_kayitlar(&kurulu, paket)? {
+            let rapor = crate::srcverify::paketi_dogrula(&yapilandirma.kurulum_dizini, kayit)?;
+            dogrulama_raporunu_yaz(&rapor);
+            let onarilacaklar = rapor.onarilacaklar();
+            if onarilacaklar.is_empty() {
+                continue;
+            }
+
+            let mut kaynak = kayit.clone();
+            let arsiv_onbellekte = kayit.arsiv.as_ref().map_or(false, |arsiv| kaynak_var_mi(&format!("{}/{}", yapilandirma.onbellek_dizini, arsiv)));
+            if !arsiv_onbellekte {
+                if depo_paketleri.is_none() {
+                    depo_paketleri = Some(depo_yoneticisi(&yapilandirma)?.paket_listesini_al()?);
+                }
+                let depodaki = depo_paketleri.iter().flatten().find(|p| p.ad == kayit.ad && p.surum == kayit.surum).ok_or_else(|| {
+                    PaketYoneticisiHatasi::PaketBulunamadi(format!("{} {} (arşivi önbellekte ve depoda yok)", kayit.ad, kayit.surum))
+                })?;
+                // Depodaki checksum ile doğrulanmadan önbelleğe hiçbir şey yazılmaz; uyuşmazlıkta onarım durur.
+                kurulum.paketi_dogrulayarak_indir(depodaki)?;
+                kaynak.arsiv = depodaki.dosya_adi.clone();
+            }
+
+            let onarilan = kurulum.dosyalari_onar(&kaynak, &onarilacaklar)?;
+            let sonra = crate::srcverify::paketi_dogrula(&yapilandirma.kurulum_dizini, kayit)?;
+            if sonra.onarilacaklar().is_empty() {
+                println!("{}: {} dosya onarıldı.", kayit.ad, onarilan);
+            } else {
+                dogrulama_raporunu_yaz(&sonra);
+                onarilamayan += 1;
+            }
+        }
+        if onarilamayan == 0 {
+            Ok(())
+        } else {
+            Err(PaketYoneticisiHatasi::DogrulamaHatasi(format!("{} paket tam onarılamadı", onarilamayan)))
+        }
+    }
+
+    fn denetlenecek_kayitlar<'a>(
+        kurulu: &'a crate::srcdatabase::KuruluVeritabani,
+        paket: Option<&str>,
+    ) -> Result<Vec<&'a crate::srcdatabase::KuruluPaket>, PaketYoneticisiHatasi> {
+        match paket {
+            Some(ad) => Ok(Vec::from([kurulu.paket(ad).ok_or_else(|| PaketYoneticisiHatasi::PaketBulunamadi(ad.to_owned()))?])),
+            None => Ok(kurulu.paketler.values().collect()),
+        }
+    }
+
+    fn dogrulama_raporunu_yaz(rapor: &crate::srcverify::DogrulamaRaporu) {
+        use crate::srcverify::SorunTuru;
+        if rapor.manifestsiz {
+            println!("{}: manifesto yok, sadece dosyaların varlığı denetlendi", rapor.paket);
+        }
+        for sorun in &rapor.sorunlar {
+            match &sorun.tur {
+                SorunTuru::Eksik => println!("{}: eksik {}", rapor.paket, sorun.yol),
+                SorunTuru::Degistirilmis(farklar) => println!("{}: değiştirilmiş {} ({})", rapor.paket, sorun.yol, farklar.join(", ")),
+                SorunTuru::Fazla => println!("{}: fazla {}", rapor.paket, sorun.yol),
+            }
+        }
+    }
+
+    fn kaynak_var_mi(resource_id: &str) -> bool {
+        match resource::acquire(resource_id, resource::MODE_READ) {
+            Ok(handle) => {
+                let _ = resource::release(handle);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    // Güncellemede korunan ve yanına yeni varsayılanı (".yeni") yazılan yapılandırma dosyalarını listeler.
+    pub fn config_merges_list(kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let bekleyenler = crate::srcconffiles::BekleyenBirlestirmeler::oku(&yapilandirma.yapilandirma_dosyalari_dizini)?;
+        if bekleyenler.girdiler.is_empty() {
+            println!("Bekleyen yapılandırma birleştirmesi yok.");
+            return Ok(());
+        }
+        for girdi in &bekleyenler.girdiler {
+            let yeni_id = format!("{}/{}/{}{}", yapilandirma.kurulum_dizini, girdi.paket, girdi.yol, crate::srcconffiles::YENI_UZANTISI);
+            let not = if !kaynak_var_mi(&yeni_id) {
+                " (.yeni dosyası yok)"
+            } else if !girdi.taban_var {
+                " (taban yok)"
+            } else {
+                ""
+            };
+            println!("{} {}: {} -> {}{}", girdi.paket, girdi.yol, girdi.eski_surum.as_deref().unwrap_or("?"), girdi.yeni_surum, not);
+        }
+        Ok(())
+    }
+
+    // Bekleyen bir birleştirmenin eski varsayılan / kurulu / yeni varsayılan üç yollu farkını gösterir.
+    // Taban saklanmamışsa kurulu dosya taban alınır; sadece yeni sürümdeki değişiklikler görünür.
+    pub fn config_merges_diff(paket: &str, yol: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let dizin = &yapilandirma.yapilandirma_dosyalari_dizini;
+        let bekleyenler = crate::srcconffiles::BekleyenBirlestirmeler::oku(dizin)?;
+        let girdi = bekleyenler.bul(paket, yol.trim_matches('/')).ok_or_else(|| {
+            PaketYoneticisiHatasi::GecersizParametre(format!("'{}/{}' için bekleyen birleştirme yok", paket, yol))
+        })?;
+        let metinler = crate::srcconffiles::metinleri_oku(dizin, &yapilandirma.kurulum_dizini, girdi)?;
+        if metinler.taban.is_none() {
+            println!("Not: eski varsayılan saklanmamış; kurulu dosya taban alındı.");
+        }
+        let taban = metinler.taban.as_deref().unwrap_or(&metinler.kurulu);
+        let parcalar = crate::srcconffiles::uc_yollu_fark(taban, &metinler.kurulu, &metinler.yeni);
+        let taban_etiketi = format!("taban ({})", girdi.eski_surum.as_deref().unwrap_or("?"));
+        let yeni_etiketi = format!("yeni ({})", girdi.yeni_surum);
+        println!("{}", crate::srcconffiles::bicimlendir(&parcalar, &taban_etiketi, &yeni_etiketi).trim_end_matches('\n'));
+        let cakisma = parcalar.iter().filter(|p| p.cakisma_mi()).count();
+        if cakisma > 0 {
+            println!("{} çakışma: kurulu dosya elle birleştirildikten sonra 'birlestir coz {} {}' çalıştırın.", cakisma, paket, yol);
+        }
+        Ok(())
+    }
+
+    // Bekleyen birleştirmeyi kapatır. yeniyi_al: Yeni varsayılan kurulu dosyanın yerine geçer; aksi halde
+    // (elle birleştirilmiş) kurulu dosya kalır ve ".yeni" silinir.
+    pub fn config_merges_resolve(paket: &str, yol: &str, yeniyi_al: bool, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        let yapilandirma = yapilandirma(kapsam)?;
+        let _kilit = kilitle(&yapilandirma)?;
+        crate::srcconffiles::birlestirmeyi_coz(&yapilandirma.yapilandirma_dosyalari_dizini, &yapilandirma.kurulum_dizini, paket, yol, yeniyi_al)?;
+        println!("Birleştirme çözüldü: {}/{} ({})", paket, yol, if yeniyi_al { "yeni varsayılan kuruldu" } else { "kurulu dosya korundu" });
+        Ok(())
+    }
+
+    // Tek bir anahtarın birleştirilmiş değerini basar (metinler tırnaksız).
+    pub fn config_get(anahtar: &str, kapsam: Kapsam) -> Result<(), PaketYoneticisiHatasi> {
+        match yapilandirma(kapsam)?.deger(anahtar) {
+            Some(crate::srcconfig::Deger::Metin(metin)) => println!("{}", metin),
+            Some(deger) => println!("{}", deger),
+            None => return Err(PaketYoneticisiHatasi::YapilandirmaHatasi(format!("bilinmeyen anahtar '{}'", anahtar))),
+        }
+        Ok(())
+    }
+
+    // Bir anahtarı sistem (varsayılan) veya kullanıcı (--kullanici) yapılandırma dosyasında ayarlar.
+    pub fn config_set(anahtar: &str, deger: &str, kullanici: bool) -> Result<(), PaketYoneticisiHatasi> {
+        let kaynak_id = if kullanici {
+            crate::srcconfig::KULLANICI_YAPILANDIRMA_KAYNAGI
+        } else {
+            crate::srcconfig::SISTEM_YAPILANDIRMA_KAYNAGI
+        };
+        crate::srcconfig::dosyada_ayarla(kaynak_id, anahtar, deger)?;
+        println!("{} ayarlandı ({}).", anahtar, kaynak_id);
+
+        // Daha öncelikli bir katman bu değeri eziyorsa kullanıcıyı uyar.
+        let katmanli = yapilandirma_katmanlari()?;
+        if let crate::srcconfig::DegerKaynagi::Dosya { kaynak, .. } = katmanli.koken(anahtar) {
+            if kaynak == kaynak_id {
+                return Ok(());
+            }
+        }
+        eprintln!("Uyarı: '{}' için geçerli değer başka bir katmandan geliyor: {}", anahtar, katmanli.koken(anahtar));
         Ok(())
     }
 }
@@ -330,7 +1093,34 @@ pub extern "C" fn main(argc: usize, argv: *const *const u8) -> i32 { // C ABI'si
 
 
     let arguments = Arguments::new(&args);
-    let mut arg_iter = arguments.iter();
+
+    // --root <dizin> (veya --root=<dizin>) her komutla birlikte, herhangi bir konumda verilebilir.
+    // Kurulum dizini, önbellek, veritabanı, kilit ve işlem günlüğü bu kökün altında kullanılır
+    // (örn. temiz bir ana makinede sistem imajı hazırlamak için).
+    // --kullanici da her konumda verilebilir: komut yetki gerektirmeyen kullanıcı kapsamında çalışır.
+    let komut_satiri = args.get(1..).unwrap_or_default().join(" ");
+    let mut kapsam = pkg_manager::Kapsam { komut_satiri: &komut_satiri, ..pkg_manager::Kapsam::default() };
+    let mut komut_argumanlari: Vec<&str> = Vec::new();
+    let mut ham_argumanlar = arguments.iter();
+    while let Some(arguman) = ham_argumanlar.next() {
+        if let Some(dizin) = arguman.strip_prefix("--root=") {
+            kapsam.kok = Some(dizin);
+        } else if arguman == "--kullanici" {
+            kapsam.kullanici = true;
+        } else if arguman == "--root" {
+            match ham_argumanlar.next() {
+                Some(dizin) => kapsam.kok = Some(dizin),
+                None => {
+                    eprintln!("'--root' bayrağı dizin gerektirir.");
+                    return -1;
+                }
+            }
+        } else {
+            komut_argumanlari.push(arguman);
+        }
+    }
+    pkg_manager::gunlukleme_baslat(kapsam);
+    let mut arg_iter = komut_argumanlari.iter().copied();
 
     // Basit argüman ayrıştırma (clap yerine manuel yaklaşım)
     let command = arg_iter.next(); // İlk argüman komut olmalı (listele, kur, kaldir vb.)
@@ -339,7 +1129,7 @@ pub extern "C" fn main(argc: usize, argv: *const *const u8) -> i32 { // C ABI'si
         Some("listele") => {
             // listele komutu argüman almaz (şimdilik)
             if arg_iter.next().is_none() {
-                pkg_manager::list_packages()
+                pkg_manager::list_packages(kapsam)
             } else {
                  eprintln!("'listele' komutu fazladan argüman alamaz.");
                  Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
@@ -349,7 +1139,7 @@ pub extern "C" fn main(argc: usize, argv: *const *const u8) -> i32 { // C ABI'si
             // ekle komutu 1 argüman alır (paket adı)
             if let Some(package_name) = arg_iter.next() {
                 if arg_iter.next().is_none() {
-                    pkg_manager::add_package(package_name)
+                    pkg_manager::add_package(package_name, kapsam)
                 } else {
                     eprintln!("'ekle' komutu fazladan argüman alamaz.");
                     Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
@@ -360,10 +1150,15 @@ pub extern "C" fn main(argc: usize, argv: *const *const u8) -> i32 { // C ABI'si
             }
         }
         Some("kaldir") => {
-            // kaldir komutu 1 argüman alır (paket adı)
+            // kaldir komutu 1 argüman (paket adı) ve isteğe bağlı --bagimliliklarla bayrağını alır
              if let Some(package_name) = arg_iter.next() {
-                if arg_iter.next().is_none() {
-                    pkg_manager::remove_package(package_name)
+                let bagimliliklarla = match arg_iter.next() {
+                    None => Some(false),
+                    Some("--bagimliliklarla") => Some(true),
+                    Some(_) => None,
+                };
+                if let (Some(bagimliliklarla), None) = (bagimliliklarla, arg_iter.next()) {
+                    pkg_manager::remove_package(package_name, bagimliliklarla, kapsam)
                 } else {
                     eprintln!("'kaldir' komutu fazladan argüman alamaz.");
                     Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
@@ -388,28 +1183,258 @@ pub extern "C" fn main(argc: usize, argv: *const *const u8) -> i32 { // C ABI'si
             }
         }
          Some("kur") => {
-            // kur komutu 1 argüman alır (paket adı)
+            // kur komutu 1 argüman (paket adı) ve isteğe bağlı bayraklar alır:
+            // --onerilenlerle, --tavsiyelerle, --varsayilan-ozelliksiz, --ozellik <ad | paket/ad> (tekrarlanabilir),
+            // --sistemi-golgele (yalnızca --kullanici ile anlamlı)
              if let Some(package_name) = arg_iter.next() {
-                if arg_iter.next().is_none() {
-                    pkg_manager::install_package(package_name)
-                } else {
-                    eprintln!("'kur' komutu fazladan argüman alamaz.");
-                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+                let mut secenekler = crate::srcdependency::KurulumSecenekleri::default();
+                let mut sistemi_golgele = false;
+                let mut hata = None;
+                while let Some(bayrak) = arg_iter.next() {
+                    match bayrak {
+                        "--onerilenlerle" => secenekler.onerilenlerle = true,
+                        "--tavsiyelerle" => secenekler.tavsiyelerle = true,
+                        "--varsayilan-ozelliksiz" => secenekler.varsayilan_ozelliksiz = true,
+                        "--sistemi-golgele" => sistemi_golgele = true,
+                        "--ozellik" => match arg_iter.next() {
+                            Some(ozellik) => secenekler.ozellikler.push(ozellik.to_owned()),
+                            None => {
+                                eprintln!("'--ozellik' bayrağı özellik adı gerektirir.");
+                                hata = Some(PaketYoneticisiHatasi::GecersizParametre(String::from("özellik adı eksik")));
+                                break;
+                            }
+                        },
+                        diger => {
+                            eprintln!("'kur' komutu için bilinmeyen argüman: '{}'", diger);
+                            hata = Some(PaketYoneticisiHatasi::GecersizParametre(format!("bilinmeyen argüman: {}", diger)));
+                            break;
+                        }
+                    }
+                }
+                match hata {
+                    Some(e) => Err(e),
+                    None => pkg_manager::install_package(package_name, &secenekler, sistemi_golgele, kapsam),
                 }
             } else {
                 eprintln!("'kur' komutu paket adı gerektirir.");
                 Err(PaketYoneticisiHatasi::GecersizParametre(String::from("paket adı eksik")))
             }
         }
+        Some("neden") => {
+            // neden komutu 1 argüman alır (paket adı)
+            match (arg_iter.next(), arg_iter.next()) {
+                (Some(package_name), None) => pkg_manager::why_installed(package_name, kapsam),
+                (Some(_), Some(_)) => {
+                    eprintln!("'neden' komutu fazladan argüman alamaz.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+                }
+                (None, _) => {
+                    eprintln!("'neden' komutu paket adı gerektirir.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("paket adı eksik")))
+                }
+            }
+        }
+        Some("ters-bagimliliklar") => {
+            // ters-bagimliliklar komutu 1 argüman (paket adı) ve isteğe bağlı --tumu bayrağını alır
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (Some(package_name), None, None) => pkg_manager::reverse_dependencies(package_name, false, kapsam),
+                (Some(package_name), Some("--tumu"), None) => pkg_manager::reverse_dependencies(package_name, true, kapsam),
+                (Some(_), _, _) => {
+                    eprintln!("'ters-bagimliliklar' komutu sadece --tumu bayrağını alabilir.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+                }
+                (None, _, _) => {
+                    eprintln!("'ters-bagimliliklar' komutu paket adı gerektirir.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("paket adı eksik")))
+                }
+            }
+        }
+        Some("graf") => {
+            // graf <paket[,paket...] | --kurulu | --depo> [--json] [--derinlik N] [--donguler] [--cakismalar] [--cikti <kaynak>]
+            if let Some(hedef) = arg_iter.next() {
+                let mut secenekler = crate::srcgraph::GrafSecenekleri::default();
+                let mut json = false;
+                let mut cikti = None;
+                let mut hata = None;
+                while let Some(bayrak) = arg_iter.next() {
+                    match bayrak {
+                        "--json" => json = true,
+                        "--donguler" => secenekler.donguleri_vurgula = true,
+                        "--cakismalar" => secenekler.cakismalari_goster = true,
+                        "--derinlik" => match arg_iter.next().and_then(|d| d.parse::<usize>().ok()) {
+                            Some(derinlik) => secenekler.azami_derinlik = Some(derinlik),
+                            None => {
+                                hata = Some(PaketYoneticisiHatasi::GecersizParametre(String::from("--derinlik sayı gerektirir")));
+                                break;
+                            }
+                        },
+                        "--cikti" => match arg_iter.next() {
+                            Some(kaynak) => cikti = Some(kaynak),
+                            None => {
+                                hata = Some(PaketYoneticisiHatasi::GecersizParametre(String::from("--cikti Kaynak ID'si gerektirir")));
+                                break;
+                            }
+                        },
+                        diger => {
+                            hata = Some(PaketYoneticisiHatasi::GecersizParametre(format!("bilinmeyen argüman: {}", diger)));
+                            break;
+                        }
+                    }
+                }
+                match hata {
+                    Some(e) => {
+                        eprintln!("'graf' komutu argüman hatası: {:?}", e);
+                        Err(e)
+                    }
+                    None => pkg_manager::export_graph(hedef, &secenekler, json, cikti, kapsam),
+                }
+            } else {
+                eprintln!("'graf' komutu paket adı, --kurulu veya --depo gerektirir.");
+                Err(PaketYoneticisiHatasi::GecersizParametre(String::from("graf hedefi eksik")))
+            }
+        }
+        Some("yetimler") => {
+            // yetimler komutu argüman almaz
+            if arg_iter.next().is_none() {
+                pkg_manager::list_orphans(kapsam)
+            } else {
+                eprintln!("'yetimler' komutu fazladan argüman alamaz.");
+                Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+            }
+        }
+        Some("autoremove") => {
+            // autoremove komutu argüman almaz
+            if arg_iter.next().is_none() {
+                pkg_manager::autoremove(kapsam)
+            } else {
+                eprintln!("'autoremove' komutu fazladan argüman alamaz.");
+                Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+            }
+        }
+        Some("depo-olustur") => {
+            // depo-olustur komutu 2 argüman alır (paket dizini, çıktı dizini)
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (Some(packages_dir), Some(output_dir), None) => pkg_manager::publish_repository(packages_dir, output_dir),
+                (_, _, Some(_)) => {
+                    eprintln!("'depo-olustur' komutu fazladan argüman alamaz.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+                }
+                _ => {
+                    eprintln!("'depo-olustur' komutu paket dizini ve çıktı dizini gerektirir.");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("dizin eksik")))
+                }
+            }
+        }
+        Some("config") => {
+            // config show [--origin] | config get <anahtar> | config set <anahtar> <değer> [--kullanici]
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (Some("show"), None, ..) => pkg_manager::config_show(false, kapsam),
+                (Some("show"), Some("--origin"), None, ..) => pkg_manager::config_show(true, kapsam),
+                (Some("get"), Some(anahtar), None, ..) => pkg_manager::config_get(anahtar, kapsam),
+                (Some("set"), Some(anahtar), Some(deger), None, _) => pkg_manager::config_set(anahtar, deger, kapsam.kullanici),
+                _ => {
+                    eprintln!("Kullanım: config show [--origin] | config get <anahtar> | config set <anahtar> <değer> [--kullanici]");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("config argümanları geçersiz")))
+                }
+            }
+        }
+        Some("ozellikler") => {
+            // ozellikler komutu argüman almaz
+            if arg_iter.next().is_none() {
+                pkg_manager::show_features(kapsam)
+            } else {
+                eprintln!("'ozellikler' komutu fazladan argüman alamaz.");
+                Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+            }
+        }
+        Some("gecmis") => {
+            // gecmis | gecmis goster <kimlik> | gecmis geri-al <kimlik>
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (None, ..) => pkg_manager::history_list(kapsam),
+                (Some("goster"), Some(kimlik), None) => pkg_manager::history_show(kimlik, kapsam),
+                (Some("geri-al"), Some(kimlik), None) => pkg_manager::history_undo(kimlik, kapsam),
+                _ => {
+                    eprintln!("Kullanım: gecmis | gecmis goster <kimlik> | gecmis geri-al <kimlik>");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("gecmis argümanları geçersiz")))
+                }
+            }
+        }
+        Some("anlik") => {
+            // anlik [listele] | anlik olustur <ad> | anlik fark <ad> [<ad>] | anlik geri-don <ad> | anlik sil <ad>
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (None, ..) | (Some("listele"), None, ..) => pkg_manager::snapshot_list(kapsam),
+                (Some("olustur"), Some(ad), None, _) => pkg_manager::snapshot_create(ad, kapsam),
+                (Some("fark"), Some(onceki), sonraki, None) => pkg_manager::snapshot_diff(onceki, sonraki, kapsam),
+                (Some("geri-don"), Some(ad), None, _) => pkg_manager::snapshot_rollback(ad, kapsam),
+                (Some("sil"), Some(ad), None, _) => pkg_manager::snapshot_delete(ad, kapsam),
+                _ => {
+                    eprintln!("Kullanım: anlik [listele] | anlik olustur <ad> | anlik fark <ad> [<ad>] | anlik geri-don <ad> | anlik sil <ad>");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("anlik argümanları geçersiz")))
+                }
+            }
+        }
+        Some("birlestir") => {
+            // birlestir [listele] | birlestir fark <paket> <yol> | birlestir coz <paket> <yol> [yerel|yeni]
+            match (arg_iter.next(), arg_iter.next(), arg_iter.next(), arg_iter.next(), arg_iter.next()) {
+                (None, ..) | (Some("listele"), None, ..) => pkg_manager::config_merges_list(kapsam),
+                (Some("fark"), Some(paket), Some(yol), None, _) => pkg_manager::config_merges_diff(paket, yol, kapsam),
+                (Some("coz"), Some(paket), Some(yol), secim @ (None | Some("yerel") | Some("yeni")), None) => {
+                    pkg_manager::config_merges_resolve(paket, yol, secim == Some("yeni"), kapsam)
+                }
+                _ => {
+                    eprintln!("Kullanım: birlestir [listele] | birlestir fark <paket> <yol> | birlestir coz <paket> <yol> [yerel|yeni]");
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("birlestir argümanları geçersiz")))
+                }
+            }
+        }
+        Some("audit") => {
+            // audit [--onem <seviye>] [<paket>...]: paket verilmezse kurulu paketler, verilirse kurulum planı denetlenir
+            let mut asgari_onem = crate::srcadvisory::Onem::Dusuk;
+            let mut paketler = Vec::new();
+            let mut hata = None;
+            while let Some(arg) = arg_iter.next() {
+                match arg {
+                    "--onem" => match arg_iter.next().and_then(crate::srcadvisory::Onem::ayristir) {
+                        Some(onem) => asgari_onem = onem,
+                        None => {
+                            eprintln!("'--onem' bayrağı dusuk, orta, yuksek veya kritik gerektirir.");
+                            hata = Some(PaketYoneticisiHatasi::GecersizParametre(String::from("önem derecesi geçersiz")));
+                            break;
+                        }
+                    },
+                    diger if diger.starts_with("--") => {
+                        eprintln!("Kullanım: audit [--onem <dusuk|orta|yuksek|kritik>] [<paket>...]");
+                        hata = Some(PaketYoneticisiHatasi::GecersizParametre(format!("bilinmeyen argüman: {}", diger)));
+                        break;
+                    }
+                    paket => paketler.push(paket),
+                }
+            }
+            match hata {
+                Some(e) => Err(e),
+                None => pkg_manager::audit(&paketler, asgari_onem, kapsam),
+            }
+        }
+        Some(komut @ ("dogrula" | "onar")) => {
+            // dogrula [paket] | onar [paket]
+            match (arg_iter.next(), arg_iter.next()) {
+                (paket, None) if komut == "dogrula" => pkg_manager::verify_packages(paket, kapsam),
+                (paket, None) => pkg_manager::repair_packages(paket, kapsam),
+                _ => {
+                    eprintln!("'{}' komutu en fazla bir paket adı alır.", komut);
+                    Err(PaketYoneticisiHatasi::GecersizParametre(String::from("fazladan argüman")))
+                }
+            }
+        }
         Some(cmd) => {
-            eprintln!("Bilinmeyen komut: '{}'. Bilinen komutlar: listele, ekle, kaldir, ara, kur", cmd);
+            eprintln!("Bilinmeyen komut: '{}'. Bilinen komutlar: listele, ekle, kaldir, ara, kur, neden, ters-bagimliliklar, graf, yetimler, autoremove, depo-olustur, config, ozellikler, gecmis, anlik, dogrula, onar, birlestir, audit", cmd);
              Err(PaketYoneticisiHatasi::GecersizParametre(format!("bilinmeyen komut: {}", cmd)))
         }
         None => {
             // Hiç argüman yoksa (sadece program adı) kullanım bilgisini göster
             println!("Paket Yöneticisi (Sahne64)");
-            println!("Kullanım: paket_yoneticisi <komut> [argümanlar]");
-            println!("Komutlar: listele, ekle, kaldir, ara, kur");
+            println!("Kullanım: paket_yoneticisi [--root <dizin>] [--kullanici] <komut> [argümanlar]");
+            println!("Komutlar: listele, ekle, kaldir, ara, kur, neden, ters-bagimliliklar, graf, yetimler, autoremove, depo-olustur, config, ozellikler, gecmis, anlik, dogrula, onar, birlestir, audit");
              Ok(()) // Bilgi mesajı başarı sayılır
         }
     };
@@ -0,0 +1,585 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+extern crate alloc; // String, Vec için
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu serileştirme/deserileştirme
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::task;
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::package::Paket;
+use crate::srcarchive;
+use crate::srcdatabase::KuruluPaket;
+use crate::srcstaging::{kaynagi_sil, kaynagi_tasi};
+use crate::srcverify::{self, GirdiTuru, ManifestGirdisi};
+
+// no_std uyumlu print makroları
+use crate::print_macros::{println, eprintln};
+
+// Paketlerin yapılandırma dosyaları (Paket::yapilandirma_dosyalari). Kurulu bir yapılandırma dosyası,
+// kurulu sürümün varsayılanından (manifestodaki özet) farklıysa kullanıcı tarafından değiştirilmiş sayılır.
+// Güncellemede değiştirilmemiş dosyalar yeni varsayılanla değiştirilir; değiştirilmiş olanlar korunur ve
+// yeni varsayılan yanına "<yol>.yeni" olarak yazılır. Bu bekleyen birleştirmeler `birlestir` komutuyla
+// listelenir, eski varsayılan / kurulu / yeni varsayılan arasındaki üç yollu fark gösterilir ve çözülür.
+// Kaldırmada değiştirilmiş dosyalar saklanır; paket yeniden kurulursa kurulu dosya gibi ele alınır.
+//
+// Dizin düzeni:
+//   <dizin>/bekleyenler.bin           bekleyen birleştirmeler (postcard)
+//   <dizin>/varsayilan/<paket>/<yol>  kurulu sürümün varsayılanı (sonraki güncellemede farkın tabanı)
+//   <dizin>/taban/<paket>/<yol>       bekleyen birleştirmenin tabanı (kullanıcının değiştirdiği varsayılan)
+//   <dizin>/korunan/<paket>/<yol>     paket kaldırılırken saklanan değiştirilmiş dosyalar
+
+// Yapılandırma dosyaları dizininin varsayılan Kaynak ID'si.
+pub const VARSAYILAN_YAPILANDIRMA_DOSYALARI_DIZINI: &str = "sahne://config/yapilandirma_dosyalari";
+
+// Korunan dosyanın yanına yazılan yeni varsayılanın uzantısı.
+pub const YENI_UZANTISI: &str = ".yeni";
+
+const BEKLEYENLER_DOSYASI: &str = "bekleyenler.bin";
+
+// Yapılandırma dizininde saklanan kopyalar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Saklanan {
+    Varsayilan,
+    Taban,
+    Korunan,
+}
+
+impl Saklanan {
+    pub fn yolu(self, dizin: &str, paket: &str, yol: &str) -> String {
+        let bolum = match self {
+            Saklanan::Varsayilan => "varsayilan",
+            Saklanan::Taban => "taban",
+            Saklanan::Korunan => "korunan",
+        };
+        format!("{}/{}/{}/{}", dizin, bolum, paket, yol.trim_matches('/'))
+    }
+}
+
+// Kurulan bir yapılandırma dosyası için verilen karar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Karar {
+    Kur, // Kurulu hali yok veya değiştirilmemiş: yeni varsayılan kurulur
+    Koru, // Değiştirilmiş ama varsayılan bu sürümde değişmemiş: kurulu hali aynen kalır
+    YanindaYaz, // Değiştirilmiş ve varsayılan da değişmiş: kurulu hali kalır, yeni varsayılan "<yol>.yeni" olur
+}
+
+// eski_varsayilan: Kurulu sürümün bu dosya için varsayılanının özeti (bilinmiyorsa None).
+// kurulu: Kurulu dosyanın özeti (dosya yoksa None).
+// yeni_varsayilan: Kurulacak sürümün varsayılanının özeti.
+pub fn karar_ver(eski_varsayilan: Option<&str>, kurulu: Option<&str>, yeni_varsayilan: &str) -> Karar {
+    match kurulu {
+        None => Karar::Kur,
+        Some(kurulu) if kurulu == yeni_varsayilan || Some(kurulu) == eski_varsayilan => Karar::Kur,
+        // Varsayılanı bilinmeyen (manifestosuz kayıttaki) dosyalar değiştirilmiş sayılır.
+        Some(_) if eski_varsayilan == Some(yeni_varsayilan) => Karar::Koru,
+        Some(_) => Karar::YanindaYaz,
+    }
+}
+
+// Kullanıcının çözmesi beklenen, yanına yeni varsayılanı yazılmış bir yapılandırma dosyası.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BekleyenBirlestirme {
+    pub paket: String,
+    pub yol: String, // Paket dizinine göre yol
+    pub eski_surum: Option<String>, // Tabanın (kullanıcının değiştirdiği varsayılanın) sürümü
+    pub yeni_surum: String, // ".yeni" dosyasının sürümü
+    pub taban_var: bool, // Taban saklanamadıysa (eski kurulumlar) sadece iki yollu fark gösterilebilir
+    pub olusturma_us: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BekleyenBirlestirmeler {
+    pub girdiler: Vec<BekleyenBirlestirme>,
+}
+
+impl BekleyenBirlestirmeler {
+    // Listeyi okur; dizin veya dosya yoksa boş liste döner.
+    pub fn oku(dizin: &str) -> Result<Self, PaketYoneticisiHatasi> {
+        let kaynak_id = format!("{}/{}", dizin, BEKLEYENLER_DOSYASI);
+        if !kaynak_var_mi(&kaynak_id)? {
+            return Ok(BekleyenBirlestirmeler::default());
+        }
+        let veri = kaynagi_oku(&kaynak_id)?;
+        postcard::from_bytes_copy(&veri).map_err(|e| {
+            eprintln!("Bekleyen birleştirmeler çözümlenemedi ({}): {:?}", kaynak_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })
+    }
+
+    pub fn yaz(&self, dizin: &str) -> Result<(), PaketYoneticisiHatasi> {
+        let veri = postcard::to_postcard(self).map_err(PaketYoneticisiHatasi::from)?;
+        atomik_yaz(&format!("{}/{}", dizin, BEKLEYENLER_DOSYASI), &veri).map_err(PaketYoneticisiHatasi::from)
+    }
+
+    pub fn bul(&self, paket: &str, yol: &str) -> Option<&BekleyenBirlestirme> {
+        self.girdiler.iter().find(|g| g.paket == paket && g.yol == yol)
+    }
+
+    // Aynı dosyanın önceki girdisinin yerine geçer.
+    pub fn ekle(&mut self, girdi: BekleyenBirlestirme) {
+        self.kaldir(&girdi.paket, &girdi.yol);
+        self.girdiler.push(girdi);
+    }
+
+    pub fn kaldir(&mut self, paket: &str, yol: &str) -> Option<BekleyenBirlestirme> {
+        let konum = self.girdiler.iter().position(|g| g.paket == paket && g.yol == yol)?;
+        Some(self.girdiler.remove(konum))
+    }
+}
+
+// Bir kurulumun yapılandırma dosyası kararları. Hazırlık alanı etkinleştirilmeden önce kuruluma_hazirla
+// ile üretilir, etkinleştirmeden sonra kaydet ile varsayılanlar ve bekleyen birleştirmeler güncellenir.
+#[derive(Debug, Clone)]
+pub struct KurulumKararlari {
+    pub paket: String,
+    pub eski_surum: Option<String>,
+    pub yeni_surum: String,
+    pub kararlar: Vec<(String, Karar)>,
+}
+
+// Hazırlık alanına çıkarılmış paketin yapılandırma dosyaları için karar verir ve hazırlık alanını buna göre
+// düzenler: korunan dosyaların kurulu hali hazırlık alanına kopyalanır, gerekiyorsa yeni varsayılan
+// "<yol>.yeni" adına taşınır. Paket kurulu değilse, kaldırılırken saklanan kopya kurulu hal sayılır.
+// dizin: Yapılandırma dosyaları dizini.
+// onceki: Paketin şimdiki kaydı (yeni kurulumda None).
+// hazirlik_yolu, hedef_kok: Hazırlık alanının ve paketin kurulum dizininin Kaynak ID'leri ('/' ile biter).
+// manifesto: Çıkarmada üretilen manifesto (yeni varsayılanların özetleri için).
+pub fn kuruluma_hazirla(
+    dizin: &str,
+    onceki: Option<&KuruluPaket>,
+    paket: &Paket,
+    hazirlik_yolu: &str,
+    hedef_kok: &str,
+    manifesto: &[ManifestGirdisi],
+) -> Result<KurulumKararlari, PaketYoneticisiHatasi> {
+    let mut sonuc = KurulumKararlari {
+        paket: paket.ad.clone(),
+        eski_surum: onceki.map(|k| k.surum.clone()),
+        yeni_surum: paket.surum.clone(),
+        kararlar: Vec::new(),
+    };
+    if paket.yapilandirma_dosyalari.is_empty() {
+        return Ok(sonuc);
+    }
+    let bekleyenler = BekleyenBirlestirmeler::oku(dizin)?;
+    for yol in paket.yapilandirma_dosyalari.iter().map(|y| y.trim_matches('/')) {
+        let (yeni, izinler) = match manifesto.iter().find(|g| g.yol == yol) {
+            Some(ManifestGirdisi { tur: GirdiTuru::Dosya { sha256, .. }, izinler, .. }) => (sha256, *izinler),
+            _ => {
+                eprintln!("Uyarı: '{}' paketinin yapılandırma dosyası '{}' arşivde düzenli dosya olarak yok", paket.ad, yol);
+                continue;
+            }
+        };
+        let kurulu_id = match onceki {
+            Some(_) => format!("{}{}", hedef_kok, yol),
+            None => Saklanan::Korunan.yolu(dizin, &paket.ad, yol),
+        };
+        let kurulu = srcverify::dosya_ozeti(&kurulu_id)?;
+        let eski = match onceki.and_then(|k| k.manifesto.iter().find(|g| g.yol == yol)) {
+            Some(ManifestGirdisi { tur: GirdiTuru::Dosya { sha256, .. }, .. }) => Some(sha256.clone()),
+            _ => srcverify::dosya_ozeti(&Saklanan::Varsayilan.yolu(dizin, &paket.ad, yol))?,
+        };
+        let mut karar = karar_ver(eski.as_deref(), kurulu.as_deref(), yeni);
+        // Bekleyen birleştirmenin ".yeni" dosyası eski paket diziniyle gider; yeniden yazılmalı.
+        if karar == Karar::Koru && bekleyenler.bul(&paket.ad, yol).is_some() {
+            karar = Karar::YanindaYaz;
+        }
+
+        let hazirlik_id = format!("{}{}", hazirlik_yolu, yol);
+        if karar == Karar::YanindaYaz {
+            kaynagi_tasi(&hazirlik_id, &format!("{}{}", hazirlik_id, YENI_UZANTISI))?;
+        }
+        if karar != Karar::Kur {
+            kaynagi_kopyala(&kurulu_id, &hazirlik_id)?;
+            if let Some(izinler) = izinler {
+                izinleri_ayarla(&hazirlik_id, izinler)?;
+            }
+        }
+        sonuc.kararlar.push((yol.to_string(), karar));
+    }
+    Ok(sonuc)
+}
+
+impl KurulumKararlari {
+    // Etkinleştirilen kurulumun varsayılan kopyalarını ve bekleyen birleştirmelerini günceller.
+    // hedef_kok: Paketin kurulum dizini ('/' ile biter).
+    pub fn kaydet(&self, dizin: &str, hedef_kok: &str) -> Result<(), PaketYoneticisiHatasi> {
+        if self.kararlar.is_empty() {
+            return Ok(());
+        }
+        let mut bekleyenler = BekleyenBirlestirmeler::oku(dizin)?;
+        let zaman = task::current_time_us().unwrap_or(0);
+        for (yol, karar) in &self.kararlar {
+            let varsayilan_id = Saklanan::Varsayilan.yolu(dizin, &self.paket, yol);
+            let taban_id = Saklanan::Taban.yolu(dizin, &self.paket, yol);
+            match karar {
+                Karar::Kur => {
+                    // Kurulu dosya bir varsayılanla aynıydı: bekleyen birleştirme kalmadı.
+                    if bekleyenler.kaldir(&self.paket, yol).is_some() {
+                        sil_yoksa_gec(&taban_id)?;
+                    }
+                    kaynagi_kopyala(&format!("{}{}", hedef_kok, yol), &varsayilan_id)?;
+                }
+                Karar::Koru => println!("Değiştirilmiş yapılandırma dosyası korundu: {}/{}", self.paket, yol),
+                Karar::YanindaYaz => {
+                    // Birleştirme zaten bekliyorsa taban, kullanıcının asıl değiştirdiği varsayılan olarak kalır.
+                    let (eski_surum, taban_var) = match bekleyenler.bul(&self.paket, yol) {
+                        Some(onceki) => (onceki.eski_surum.clone(), onceki.taban_var),
+                        None => {
+                            let taban_var = kaynak_var_mi(&varsayilan_id)? && kaynagi_kopyala(&varsayilan_id, &taban_id).is_ok();
+                            (self.eski_surum.clone(), taban_var)
+                        }
+                    };
+                    kaynagi_kopyala(&format!("{}{}{}", hedef_kok, yol, YENI_UZANTISI), &varsayilan_id)?;
+                    bekleyenler.ekle(BekleyenBirlestirme {
+                        paket: self.paket.clone(),
+                        yol: yol.clone(),
+                        eski_surum,
+                        yeni_surum: self.yeni_surum.clone(),
+                        taban_var,
+                        olusturma_us: zaman,
+                    });
+                    println!("Değiştirilmiş yapılandırma dosyası korundu: {}/{} (yeni varsayılan: {}{})", self.paket, yol, yol, YENI_UZANTISI);
+                }
+            }
+            // Kaldırılırken saklanan kopya (varsa) bu kurulumda kullanıldı.
+            sil_yoksa_gec(&Saklanan::Korunan.yolu(dizin, &self.paket, yol))?;
+        }
+        bekleyenler.yaz(dizin)
+    }
+}
+
+// Kaldırılan paketin değiştirilmiş yapılandırma dosyalarını saklar; değiştirilmemiş olanların varsayılan
+// kopyaları ve paketin bekleyen birleştirmeleri silinir. Paket dizini taşınmadan önce çağrılmalıdır.
+// paket_kok: Paketin kurulum dizini ('/' ile biter).
+// Dönüş değeri: Saklanan yollar.
+pub fn kaldirirken_sakla(dizin: &str, kayit: &KuruluPaket, paket_kok: &str) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+    let mut saklananlar = Vec::new();
+    if kayit.yapilandirma_dosyalari.is_empty() {
+        return Ok(saklananlar);
+    }
+    for yol in kayit.yapilandirma_dosyalari.iter().map(|y| y.trim_matches('/')) {
+        let varsayilan_id = Saklanan::Varsayilan.yolu(dizin, &kayit.ad, yol);
+        let kurulu = match srcverify::dosya_ozeti(&format!("{}{}", paket_kok, yol))? {
+            Some(ozet) => ozet,
+            None => continue,
+        };
+        let varsayilan = match kayit.manifesto.iter().find(|g| g.yol == yol) {
+            Some(ManifestGirdisi { tur: GirdiTuru::Dosya { sha256, .. }, .. }) => Some(sha256.clone()),
+            _ => srcverify::dosya_ozeti(&varsayilan_id)?,
+        };
+        if varsayilan.as_deref() == Some(kurulu.as_str()) {
+            sil_yoksa_gec(&varsayilan_id)?;
+        } else {
+            kaynagi_kopyala(&format!("{}{}", paket_kok, yol), &Saklanan::Korunan.yolu(dizin, &kayit.ad, yol))?;
+            println!("Değiştirilmiş yapılandırma dosyası saklandı: {}/{}", kayit.ad, yol);
+            saklananlar.push(yol.to_string());
+        }
+    }
+    let mut bekleyenler = BekleyenBirlestirmeler::oku(dizin)?;
+    let oncesi = bekleyenler.girdiler.len();
+    for girdi in bekleyenler.girdiler.iter().filter(|g| g.paket == kayit.ad) {
+        sil_yoksa_gec(&Saklanan::Taban.yolu(dizin, &girdi.paket, &girdi.yol))?;
+    }
+    bekleyenler.girdiler.retain(|g| g.paket != kayit.ad);
+    if bekleyenler.girdiler.len() != oncesi {
+        bekleyenler.yaz(dizin)?;
+    }
+    Ok(saklananlar)
+}
+
+// Bekleyen bir birleştirmenin üç hali (bkz. uc_yollu_fark). Taban saklanmamışsa None.
+#[derive(Debug, Clone)]
+pub struct BirlestirmeMetinleri {
+    pub taban: Option<String>,
+    pub kurulu: String,
+    pub yeni: String,
+}
+
+// kurulum_base: Paketlerin kurulduğu temel Kaynak ID'si (paket "<kurulum_base>/<ad>/" altında).
+pub fn metinleri_oku(dizin: &str, kurulum_base: &str, girdi: &BekleyenBirlestirme) -> Result<BirlestirmeMetinleri, PaketYoneticisiHatasi> {
+    let metin = |resource_id: &str| -> Result<String, PaketYoneticisiHatasi> {
+        Ok(String::from_utf8_lossy(&kaynagi_oku(resource_id)?).into_owned())
+    };
+    let kurulu_id = format!("{}/{}/{}", kurulum_base, girdi.paket, girdi.yol);
+    let taban = match girdi.taban_var {
+        true => Some(metin(&Saklanan::Taban.yolu(dizin, &girdi.paket, &girdi.yol))?),
+        false => None,
+    };
+    Ok(BirlestirmeMetinleri { taban, kurulu: metin(&kurulu_id)?, yeni: metin(&format!("{}{}", kurulu_id, YENI_UZANTISI))? })
+}
+
+// Bekleyen birleştirmeyi kapatır. yeniyi_al: ".yeni" dosyası kurulu dosyanın yerine geçer; verilmezse
+// kurulu dosya (elle birleştirilmiş hali) kalır ve ".yeni" silinir.
+pub fn birlestirmeyi_coz(dizin: &str, kurulum_base: &str, paket: &str, yol: &str, yeniyi_al: bool) -> Result<(), PaketYoneticisiHatasi> {
+    let yol = yol.trim_matches('/');
+    let mut bekleyenler = BekleyenBirlestirmeler::oku(dizin)?;
+    if bekleyenler.kaldir(paket, yol).is_none() {
+        return Err(PaketYoneticisiHatasi::InvalidParameter(format!("'{}/{}' için bekleyen birleştirme yok", paket, yol)));
+    }
+    let kurulu_id = format!("{}/{}/{}", kurulum_base, paket, yol);
+    let yeni_id = format!("{}{}", kurulu_id, YENI_UZANTISI);
+    if yeniyi_al {
+        if !kaynak_var_mi(&yeni_id)? {
+            return Err(PaketYoneticisiHatasi::InvalidParameter(format!("'{}' bulunamadı", yeni_id)));
+        }
+        sil_yoksa_gec(&kurulu_id)?;
+        kaynagi_tasi(&yeni_id, &kurulu_id)?;
+    } else {
+        sil_yoksa_gec(&yeni_id)?;
+    }
+    sil_yoksa_gec(&Saklanan::Taban.yolu(dizin, paket, yol))?;
+    bekleyenler.yaz(dizin)
+}
+
+// Üç yollu farkın bir bölümü: üç metinde de ortak bir satır veya ortak satırlar arasındaki değişiklik.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Parca<'a> {
+    Ortak(&'a str),
+    Degisiklik { taban: Vec<&'a str>, kurulu: Vec<&'a str>, yeni: Vec<&'a str> },
+}
+
+impl Parca<'_> {
+    // Kurulu dosya ve yeni varsayılan tabanı farklı biçimde değiştirmişse çakışmadır.
+    pub fn cakisma_mi(&self) -> bool {
+        match self {
+            Parca::Ortak(_) => false,
+            Parca::Degisiklik { taban, kurulu, yeni } => kurulu != taban && yeni != taban && kurulu != yeni,
+        }
+    }
+}
+
+// Satır tabanlı üç yollu fark (diff3): taban hem kurulu dosyayla hem yeni varsayılanla en uzun ortak alt
+// dizi üzerinden eşlenir; üçünde de eşlenen satırlar ortaktır, aralarındaki bölümler değişikliktir.
+pub fn uc_yollu_fark<'a>(taban: &'a str, kurulu: &'a str, yeni: &'a str) -> Vec<Parca<'a>> {
+    let t: Vec<&str> = taban.lines().collect();
+    let k: Vec<&str> = kurulu.lines().collect();
+    let y: Vec<&str> = yeni.lines().collect();
+    let kurulu_esi = eslestir(&t, &k);
+    let yeni_esi = eslestir(&t, &y);
+
+    let mut parcalar = Vec::new();
+    let (mut i, mut a, mut b) = (0, 0, 0);
+    loop {
+        if i < t.len() && kurulu_esi[i] == Some(a) && yeni_esi[i] == Some(b) {
+            parcalar.push(Parca::Ortak(t[i]));
+            i += 1;
+            a += 1;
+            b += 1;
+            continue;
+        }
+        // Üç metinde de eşlenen sonraki satıra (yoksa sona) kadar olan bölüm.
+        let (si, sa, sb) = (i..t.len())
+            .find_map(|s| Some((s, kurulu_esi[s]?, yeni_esi[s]?)))
+            .unwrap_or((t.len(), k.len(), y.len()));
+        if (si, sa, sb) == (i, a, b) {
+            break;
+        }
+        parcalar.push(Parca::Degisiklik { taban: t[i..si].to_vec(), kurulu: k[a..sa].to_vec(), yeni: y[b..sb].to_vec() });
+        i = si;
+        a = sa;
+        b = sb;
+    }
+    parcalar
+}
+
+// a'nın her satırının b'deki eşi (en uzun ortak alt dizide), eşi yoksa None.
+fn eslestir(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let genislik = b.len() + 1;
+    // uzunluk[i * genislik + j]: a[i..] ile b[j..] arasındaki en uzun ortak alt dizinin uzunluğu
+    let mut uzunluk = alloc::vec![0usize; (a.len() + 1) * genislik];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            uzunluk[i * genislik + j] = if a[i] == b[j] {
+                uzunluk[(i + 1) * genislik + j + 1] + 1
+            } else {
+                uzunluk[(i + 1) * genislik + j].max(uzunluk[i * genislik + j + 1])
+            };
+        }
+    }
+    let mut esler = alloc::vec![None; a.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            esler[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if uzunluk[(i + 1) * genislik + j] >= uzunluk[i * genislik + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    esler
+}
+
+// Üç yollu farkı metne döker. Ortak satırlar iki boşlukla, tek tarafta (veya iki tarafta aynı biçimde)
+// değişen bölümler "-"/"+" satırlarıyla, çakışmalar diff3 birleştirme işaretleriyle gösterilir.
+pub fn bicimlendir(parcalar: &[Parca], taban_etiketi: &str, yeni_etiketi: &str) -> String {
+    let mut metin = String::new();
+    for parca in parcalar {
+        match parca {
+            Parca::Ortak(satir) => satirlari_ekle(&mut metin, "  ", &[satir]),
+            Parca::Degisiklik { taban, kurulu, yeni } if parca.cakisma_mi() => {
+                metin.push_str("<<<<<<< kurulu\n");
+                satirlari_ekle(&mut metin, "", kurulu);
+                metin.push_str(&format!("||||||| {}\n", taban_etiketi));
+                satirlari_ekle(&mut metin, "", taban);
+                metin.push_str("=======\n");
+                satirlari_ekle(&mut metin, "", yeni);
+                metin.push_str(&format!(">>>>>>> {}\n", yeni_etiketi));
+            }
+            Parca::Degisiklik { taban, kurulu, yeni } => {
+                let (baslik, sonrasi) = if kurulu == taban {
+                    (format!("@@ {} değişikliği\n", yeni_etiketi), yeni)
+                } else if yeni == taban {
+                    (String::from("@@ yerel değişiklik\n"), kurulu)
+                } else {
+                    (String::from("@@ iki tarafta aynı değişiklik\n"), kurulu)
+                };
+                metin.push_str(&baslik);
+                satirlari_ekle(&mut metin, "- ", taban);
+                satirlari_ekle(&mut metin, "+ ", sonrasi);
+            }
+        }
+    }
+    metin
+}
+
+fn satirlari_ekle(metin: &mut String, onek: &str, satirlar: &[&str]) {
+    for satir in satirlar {
+        metin.push_str(onek);
+        metin.push_str(satir);
+        metin.push('\n');
+    }
+}
+
+fn kaynak_var_mi(resource_id: &str) -> Result<bool, SahneError> {
+    match resource::acquire(resource_id, resource::MODE_READ) {
+        Ok(handle) => {
+            let _ = resource::release(handle);
+            Ok(true)
+        }
+        Err(SahneError::ResourceNotFound) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn kaynagi_oku(resource_id: &str) -> Result<Vec<u8>, SahneError> {
+    let handle = resource::acquire(resource_id, resource::MODE_READ)?;
+    let mut buffer = Vec::new();
+    let mut temp_buffer = [0u8; 4096];
+    loop {
+        match resource::read(handle, &mut temp_buffer) {
+            Ok(0) => break,
+            Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+            Err(e) => {
+                let _ = resource::release(handle);
+                return Err(e);
+            }
+        }
+    }
+    let _ = resource::release(handle);
+    Ok(buffer)
+}
+
+fn yaz(resource_id: &str, veri: &[u8]) -> Result<(), SahneError> {
+    let handle = resource::acquire(resource_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)?;
+    let mut written = 0;
+    while written < veri.len() {
+        match resource::write(handle, &veri[written..]) {
+            Ok(0) => {
+                let _ = resource::release(handle);
+                return Err(SahneError::InvalidOperation);
+            }
+            Ok(n) => written += n,
+            Err(e) => {
+                let _ = resource::release(handle);
+                return Err(e);
+            }
+        }
+    }
+    resource::release(handle)
+}
+
+// Veriyi geçici bir Kaynağa yazıp hedefin yerine taşır; yarım yazılmış bir liste hiç oluşmaz.
+fn atomik_yaz(resource_id: &str, veri: &[u8]) -> Result<(), SahneError> {
+    let gecici_id = format!("{}.gecici", resource_id);
+    yaz(&gecici_id, veri)?;
+    kaynagi_tasi(&gecici_id, resource_id)
+}
+
+// Yapılandırma dosyaları küçüktür; tamamı bellekte kopyalanır. Hedefin yanına geçici Kaynak yazılmaz:
+// hazırlık alanında "<yol>.yeni" yeni varsayılanın kendisidir.
+fn kaynagi_kopyala(kaynak_id: &str, hedef_id: &str) -> Result<(), SahneError> {
+    let veri = kaynagi_oku(kaynak_id)?;
+    yaz(hedef_id, &veri)
+}
+
+// Kopyalanan dosya varsayılan izinlerle oluşur; paketin istediği izinler ayrıca uygulanır.
+fn izinleri_ayarla(resource_id: &str, izinler: u32) -> Result<(), SahneError> {
+    let handle = resource::acquire(resource_id, resource::MODE_WRITE)?;
+    srcarchive::izinleri_uygula(handle, izinler, resource_id);
+    resource::release(handle)
+}
+
+fn sil_yoksa_gec(resource_id: &str) -> Result<(), SahneError> {
+    match kaynagi_sil(resource_id, false) {
+        Err(SahneError::ResourceNotFound) => Ok(()),
+        sonuc => sonuc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_karar_ver() {
+        // Değiştirilmemiş veya eksik dosya yeni varsayılanla değiştirilir.
+        assert_eq!(karar_ver(Some("v1"), Some("v1"), "v2"), Karar::Kur);
+        assert_eq!(karar_ver(Some("v1"), None, "v2"), Karar::Kur);
+        assert_eq!(karar_ver(Some("v1"), Some("v2"), "v2"), Karar::Kur);
+        // Değiştirilmiş dosya korunur; varsayılan da değiştiyse yanına yazılır.
+        assert_eq!(karar_ver(Some("v1"), Some("kullanici"), "v1"), Karar::Koru);
+        assert_eq!(karar_ver(Some("v1"), Some("kullanici"), "v2"), Karar::YanindaYaz);
+        assert_eq!(karar_ver(None, Some("kullanici"), "v2"), Karar::YanindaYaz);
+    }
+
+    #[test]
+    fn test_uc_yollu_fark() {
+        let parcalar = uc_yollu_fark("a\nb\nc\nd\n", "a\nB\nc\nd\n", "a\nb\nc\nD\ne\n");
+        assert_eq!(parcalar, vec![
+            Parca::Ortak("a"),
+            Parca::Degisiklik { taban: vec!["b"], kurulu: vec!["B"], yeni: vec!["b"] },
+            Parca::Ortak("c"),
+            Parca::Degisiklik { taban: vec!["d"], kurulu: vec!["d"], yeni: vec!["D", "e"] },
+        ]);
+        assert!(!parcalar.iter().any(|p| p.cakisma_mi()));
+        assert_eq!(
+            bicimlendir(&parcalar, "1.0", "1.1"),
+            "  a\n@@ yerel değişiklik\n- b\n+ B\n  c\n@@ 1.1 değişikliği\n- d\n+ D\n+ e\n"
+        );
+    }
+
+    #[test]
+    fn test_cakisma() {
+        let parcalar = uc_yollu_fark("port=80\nkullanici=www\n", "port=8080\nkullanici=www\n", "port=443\nkullanici=www\n");
+        assert!(parcalar[0].cakisma_mi());
+        assert_eq!(parcalar[1], Parca::Ortak("kullanici=www"));
+        assert_eq!(
+            bicimlendir(&parcalar[..1], "taban (1.0)", "yeni (1.1)"),
+            "<<<<<<< kurulu\nport=8080\n||||||| taban (1.0)\nport=80\n=======\nport=443\n>>>>>>> yeni (1.1)\n"
+        );
+    }
+}
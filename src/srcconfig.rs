@@ -1,219 +1,966 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
 extern crate alloc; // String, Vec, format! için
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::format;
-use alloc::boxed::Box; // postcard'ın dönüş türleri Box kullanabilir
-
-use serde::{Deserialize, Serialize};
-use postcard; // no_std uyumlu serileştirme/deserileştirme
-use postcard::Error as PostcardError; // Postcard hatasını yeniden adlandır
+use alloc::collections::BTreeMap;
 
 // Sahne64 API modüllerini içe aktarın
 use crate::resource;
 use crate::SahneError;
-use crate::Handle;
 
 // Özel hata enum'ımızı içe aktar (no_std uyumlu ve SahneError'ı içeren haliyle)
 use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
-// SahneError ve PostcardError'dan dönüşüm From implementasyonları ile sağlanacak
+// Yapılandırma dosyaları geçici Kaynağa yazılıp taşınarak güncellenir
+use crate::srcstaging::kaynagi_tasi;
+// Özellik listesi anahtarları srcfeatures adlarıyla doğrulanır
+use crate::srcfeatures::ozellik_listesi;
+// Veritabanı, işlem günlüğü ve kilit Kaynaklarının varsayılanları
+use crate::srcdatabase::VARSAYILAN_VERITABANI_KAYNAGI;
+use crate::srctransaction::VARSAYILAN_ISLEM_GUNLUGU_KAYNAGI;
+use crate::srclockfile::VARSAYILAN_KILIT_KAYNAGI;
+use crate::srchistory::VARSAYILAN_GECMIS_KAYNAGI;
+use crate::srcsnapshot::VARSAYILAN_ANLIK_GORUNTU_DIZINI;
+use crate::srcconffiles::VARSAYILAN_YAPILANDIRMA_DOSYALARI_DIZINI;
+use crate::srcscript::VARSAYILAN_BETIK_DIZINI;
+use crate::srcadvisory::{KurulumPolitikasi, Onem};
+use crate::srctrust::VARSAYILAN_ANAHTARLAR_KAYNAGI;
+
+// no_std uyumlu print makroları
+use crate::print_macros::eprintln;
+
+// Yapılandırma katmanları (öncelik sırası düşükten yükseğe):
+// 1. Varsayılan değerler
+// 2. Sistem yapılandırma dosyası (SISTEM_YAPILANDIRMA_KAYNAGI)
+// 3. Kullanıcı yapılandırma dosyası (KULLANICI_YAPILANDIRMA_KAYNAGI)
+// 4. SAHNE_PKG_<BÖLÜM>_<ANAHTAR> ortam değişkenleri (örn. depo.url -> SAHNE_PKG_DEPO_URL)
+// Sonraki katman öncekinin değerini ezer; dosyası olmayan katmanlar atlanır.
+pub const SISTEM_YAPILANDIRMA_KAYNAGI: &str = "sahne://config/paket_yoneticisi.toml";
+pub const KULLANICI_YAPILANDIRMA_KAYNAGI: &str = "sahne://user/config/paket_yoneticisi.toml";
+pub const ORTAM_ONEKI: &str = "SAHNE_PKG_";
+
+// Geçerli günlük seviyeleri (log::LevelFilter adları).
+pub const GUNLUK_SEVIYELERI: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+// Bir yapılandırma anahtarının değer türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegerTuru {
+    Metin,
+    Tamsayi,
+    Mantiksal,
+}
+
+impl DegerTuru {
+    pub fn ad(self) -> &'static str {
+        match self {
+            DegerTuru::Metin => "metin",
+            DegerTuru::Tamsayi => "tamsayı",
+            DegerTuru::Mantiksal => "mantıksal (true/false)",
+        }
+    }
+}
+
+// Yapılandırma dosyasındaki veya komut satırındaki bir değer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deger {
+    Metin(String),
+    Tamsayi(i64),
+    Mantiksal(bool),
+}
+
+impl Deger {
+    pub fn tur(&self) -> DegerTuru {
+        match self {
+            Deger::Metin(_) => DegerTuru::Metin,
+            Deger::Tamsayi(_) => DegerTuru::Tamsayi,
+            Deger::Mantiksal(_) => DegerTuru::Mantiksal,
+        }
+    }
+
+    // Tırnaksız bir metni (ortam değişkeni, komut satırı argümanı) verilen türe göre ayrıştırır.
+    pub fn metinden(tur: DegerTuru, metin: &str) -> Result<Deger, String> {
+        match tur {
+            DegerTuru::Metin => Ok(Deger::Metin(metin.to_string())),
+            DegerTuru::Tamsayi => tamsayi_ayristir(metin.trim()).map(Deger::Tamsayi).ok_or_else(|| format!("'{}' bir tamsayı değil", metin)),
+            DegerTuru::Mantiksal => match metin.trim() {
+                "true" | "1" => Ok(Deger::Mantiksal(true)),
+                "false" | "0" => Ok(Deger::Mantiksal(false)),
+                _ => Err(format!("'{}' true/false değil", metin)),
+            },
+        }
+    }
+}
+
+// TOML biçiminde yazar (metinler tırnaklı ve kaçışlı).
+impl core::fmt::Display for Deger {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Deger::Metin(m) => {
+                f.write_str("\"")?;
+                for c in m.chars() {
+                    match c {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\t' => f.write_str("\\t")?,
+                        '\r' => f.write_str("\\r")?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"")
+            }
+            Deger::Tamsayi(n) => write!(f, "{}", n),
+            Deger::Mantiksal(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+// Şemadaki bir anahtar: "bölüm.ad" biçiminde tam adı, türü ve dosyaya yazılırken eklenen açıklaması.
+pub struct Anahtar {
+    pub ad: &'static str,
+    pub tur: DegerTuru,
+    pub aciklama: &'static str,
+}
+
+// Yapılandırma şeması. Dosyalarda ve ortamda bunların dışındaki anahtarlar hata sayılır.
+pub const ANAHTARLAR: &[Anahtar] = &[
+    Anahtar { ad: "depo.url", tur: DegerTuru::Metin, aciklama: "Uzak paket deposunun Kaynak ID'si" },
+    Anahtar { ad: "depo.yerel_yol", tur: DegerTuru::Metin, aciklama: "Depo indeksinin yerel kopyasının Kaynak ID'si" },
+    Anahtar { ad: "depo.imza_anahtari", tur: DegerTuru::Metin, aciklama: "depo-olustur'un indeksi imzaladığı Ed25519 gizli anahtarının Kaynak ID'si" },
+    Anahtar { ad: "kurulum.dizin", tur: DegerTuru::Metin, aciklama: "Paketlerin kurulduğu temel Kaynak ID'si" },
+    Anahtar { ad: "kurulum.onbellek_dizini", tur: DegerTuru::Metin, aciklama: "İndirilen paket arşivlerinin önbellek Kaynak ID'si" },
+    Anahtar { ad: "kurulum.veritabani", tur: DegerTuru::Metin, aciklama: "Kurulu paketler veritabanının Kaynak ID'si" },
+    Anahtar { ad: "kurulum.islem_gunlugu", tur: DegerTuru::Metin, aciklama: "İşlem günlüğünün Kaynak ID'si" },
+    Anahtar { ad: "kurulum.kilit", tur: DegerTuru::Metin, aciklama: "Eşzamanlı işlemleri önleyen kilidin Kaynak ID'si" },
+    Anahtar { ad: "kurulum.gecmis", tur: DegerTuru::Metin, aciklama: "İşlem geçmişinin Kaynak ID'si" },
+    Anahtar { ad: "kurulum.anlik_goruntuler", tur: DegerTuru::Metin, aciklama: "Anlık görüntülerin ve arşiv havuzlarının dizin Kaynak ID'si" },
+    Anahtar { ad: "kurulum.yapilandirma_dosyalari", tur: DegerTuru::Metin, aciklama: "Paket yapılandırma dosyalarının varsayılanları ve bekleyen birleştirmelerinin dizin Kaynak ID'si" },
+    Anahtar { ad: "kurulum.betik_dizini", tur: DegerTuru::Metin, aciklama: "Paket kurulum/kaldırma betiklerinin dizin Kaynak ID'si" },
+    Anahtar { ad: "kurulum.betik_zaman_asimi_sn", tur: DegerTuru::Tamsayi, aciklama: "Bir paket betiğinin azami çalışma süresi (saniye)" },
+    Anahtar { ad: "kullanici.kok", tur: DegerTuru::Metin, aciklama: "Kullanıcı kapsamındaki kurulumların (--kullanici) kök Kaynak ID'si" },
+    Anahtar { ad: "gunluk.seviye", tur: DegerTuru::Metin, aciklama: "Günlük seviyesi: off, error, warn, info, debug, trace" },
+    Anahtar { ad: "gunluk.hedefler", tur: DegerTuru::Metin, aciklama: "Etkin günlük hedefleri (console_logging, file_logging, event_tracing, syslog_logging)" },
+    Anahtar { ad: "gunluk.modul_seviyeleri", tur: DegerTuru::Metin, aciklama: "Modül bazında seviyeler (örn. \"srcnetwork=debug,srcsandbox=trace\")" },
+    Anahtar { ad: "gunluk.renkli", tur: DegerTuru::Mantiksal, aciklama: "Konsol çıktısı seviyeye göre renklendirilir" },
+    Anahtar { ad: "gunluk.dosya", tur: DegerTuru::Metin, aciklama: "Düz metin günlük dosyasının Kaynak ID'si (file_logging)" },
+    Anahtar { ad: "gunluk.json_dosyasi", tur: DegerTuru::Metin, aciklama: "Satır başına bir JSON kaydı yazılan dosyanın Kaynak ID'si (event_tracing)" },
+    Anahtar { ad: "gunluk.dosya_azami_boyut", tur: DegerTuru::Tamsayi, aciklama: "Günlük dosyası bu boyutu (bayt) aşınca döndürülür" },
+    Anahtar { ad: "gunluk.dosya_sayisi", tur: DegerTuru::Tamsayi, aciklama: "Döndürülünce saklanan eski günlük dosyası sayısı" },
+    Anahtar { ad: "gunluk.syslog_soketi", tur: DegerTuru::Metin, aciklama: "Syslog mesajlarının gönderildiği yerel soketin Kaynak ID'si (syslog_logging)" },
+    Anahtar { ad: "ozellikler.sikistirma", tur: DegerTuru::Mantiksal, aciklama: "Sıkıştırılmış arşiv desteği" },
+    Anahtar { ad: "ozellikler.sikistirma_algoritmalari", tur: DegerTuru::Metin, aciklama: "Kabul edilen arşiv sıkıştırmaları (gzip, zstd, xz, bzip2)" },
+    Anahtar { ad: "ozellikler.ag", tur: DegerTuru::Mantiksal, aciklama: "Ağ üzerinden depo erişimi" },
+    Anahtar { ad: "ozellikler.ag_protokolleri", tur: DegerTuru::Metin, aciklama: "İzin verilen ağ protokolleri (örn. https, http, ftp)" },
+    Anahtar { ad: "ozellikler.guvenlik", tur: DegerTuru::Mantiksal, aciklama: "İmza ve güven denetimleri" },
+    Anahtar { ad: "ozellikler.guvenlik_ozellikleri", tur: DegerTuru::Metin, aciklama: "Uygulanan güvenlik özellikleri (signature_verification, sandbox)" },
+    Anahtar { ad: "guvenlik.kurulum_politikasi", tur: DegerTuru::Metin, aciklama: "Güvenlik bildirisiyle eşleşen sürümler kurulurken: kapali, uyar, engelle" },
+    Anahtar { ad: "guvenlik.engelleme_onemi", tur: DegerTuru::Metin, aciklama: "Kurulum politikasının dikkate aldığı en düşük önem: dusuk, orta, yuksek, kritik" },
+    Anahtar { ad: "guvenlik.guvenilen_anahtarlar", tur: DegerTuru::Metin, aciklama: "Depo imzalarının doğrulandığı Ed25519 açık anahtarları listesinin Kaynak ID'si" },
+];
+
+// Virgülle ayrılmış özellik listesi tutan anahtarlar ve listedeki özelliklerin kategorisi (bkz. srcfeatures).
+const OZELLIK_LISTELERI: [(&str, &str); 4] = [
+    ("gunluk.hedefler", "gunluk"),
+    ("ozellikler.sikistirma_algoritmalari", "sikistirma"),
+    ("ozellikler.ag_protokolleri", "ag"),
+    ("ozellikler.guvenlik_ozellikleri", "guvenlik"),
+];
+
+// "modül=seviye" çiftlerinden oluşan virgülle ayrılmış listeyi ayrıştırır (örn. "srcnetwork=debug, srcsandbox=trace").
+// Seviyeler GUNLUK_SEVIYELERI'nden olmalıdır; boş liste geçerlidir.
+pub fn modul_seviyeleri(metin: &str) -> Result<Vec<(String, String)>, String> {
+    let mut ciftler = Vec::new();
+    for parca in metin.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (modul, seviye) = parca.split_once('=')
+            .map(|(m, s)| (m.trim(), s.trim()))
+            .filter(|(m, _)| !m.is_empty())
+            .ok_or_else(|| format!("'{}' modül=seviye biçiminde değil", parca))?;
+        if !GUNLUK_SEVIYELERI.contains(&seviye) {
+            return Err(format!("'{}' geçersiz; geçerli seviyeler: {}", seviye, GUNLUK_SEVIYELERI.join(", ")));
+        }
+        ciftler.push((modul.to_string(), seviye.to_string()));
+    }
+    Ok(ciftler)
+}
+
+pub fn anahtar_tanimi(ad: &str) -> Option<&'static Anahtar> {
+    ANAHTARLAR.iter().find(|a| a.ad == ad)
+}
 
-// Yapılandırma verilerini tutan struct. Serileştirme/Deserileştirme yapılabilir.
-#[derive(Serialize, Deserialize, Debug)] // Serde derive makroları (no_std uyumlu serde backend ile çalışır)
+// Anahtarın ortam değişkeni adı: "depo.url" -> "SAHNE_PKG_DEPO_URL".
+pub fn ortam_degiskeni_adi(anahtar: &str) -> String {
+    let mut ad = String::from(ORTAM_ONEKI);
+    ad.extend(anahtar.chars().map(|c| if c == '.' { '_' } else { c.to_ascii_uppercase() }));
+    ad
+}
+
+// Paket yöneticisinin tek yapılandırma şeması.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Yapilandirma {
     pub depo_url: String,
-    pub yerel_depo_yolu: String, // Bu artık Sahne64 Kaynak ID formatında olmalı
-    pub kurulum_dizini: String, // Bu artık Sahne64 Kaynak ID formatında olmalı
-    pub onbellek_dizini: String, // Bu artık Sahne64 Kaynak ID formatında olmalı
+    pub yerel_depo_yolu: String, // Sahne64 Kaynak ID formatında
+    pub imza_anahtari_kaynagi: String, // Yalnızca depo yayınlanırken okunur
+    pub kurulum_dizini: String, // Sahne64 Kaynak ID formatında
+    pub onbellek_dizini: String, // Sahne64 Kaynak ID formatında
+    pub veritabani_kaynagi: String,
+    pub islem_gunlugu_kaynagi: String,
+    pub kilit_kaynagi: String,
+    pub gecmis_kaynagi: String,
+    pub anlik_goruntu_dizini: String,
+    pub yapilandirma_dosyalari_dizini: String,
+    pub betik_dizini: String,
+    pub betik_zaman_asimi_sn: u64,
+    pub kullanici_koku: String, // Sahne64 Kaynak ID formatında
+    pub log_level: String,
+    pub gunluk_hedefleri: String,
+    pub gunluk_modul_seviyeleri: String,
+    pub gunluk_renkli: bool,
+    pub gunluk_dosyasi: String,
+    pub gunluk_json_dosyasi: String,
+    pub gunluk_dosya_azami_boyut: u64,
+    pub gunluk_dosya_sayisi: u32,
+    pub syslog_soketi: String,
+    // Özellik bayrakları (bkz. srcfeature_flags::FeatureFlags); bayrak kapalıysa ilgili listenin tamamı kapalıdır
+    pub compression: bool,
+    pub sikistirma_algoritmalari: String,
+    pub network: bool,
+    pub ag_protokolleri: String,
+    pub security: bool,
+    pub guvenlik_ozellikleri: String,
+    // Güvenlik bildirisi akışı (bkz. srcadvisory)
+    pub kurulum_politikasi: String,
+    pub engelleme_onemi: String,
+    pub guvenilen_anahtarlar_kaynagi: String,
 }
 
-impl Yapilandirma {
-    pub fn yeni(depo_url: String, yerel_depo_yolu: String, kurulum_dizini: String, onbellek_dizini: String) -> Yapilandirma {
+impl Default for Yapilandirma {
+    fn default() -> Self {
         Yapilandirma {
-            depo_url,
-            // Bu yollar artık Sahne64 Kaynak ID'leri olarak düşünülmeli
-            yerel_depo_yolu,
-            kurulum_dizini,
-            onbellek_dizini,
+            depo_url: String::from("sahne://remoterepo/packages"),
+            yerel_depo_yolu: String::from("sahne://cache/repo"),
+            imza_anahtari_kaynagi: String::from("sahne://config/depo_imza_anahtari"),
+            kurulum_dizini: String::from("sahne://installed_packages"),
+            onbellek_dizini: String::from("sahne://cache/packages"),
+            veritabani_kaynagi: String::from(VARSAYILAN_VERITABANI_KAYNAGI),
+            islem_gunlugu_kaynagi: String::from(VARSAYILAN_ISLEM_GUNLUGU_KAYNAGI),
+            kilit_kaynagi: String::from(VARSAYILAN_KILIT_KAYNAGI),
+            gecmis_kaynagi: String::from(VARSAYILAN_GECMIS_KAYNAGI),
+            anlik_goruntu_dizini: String::from(VARSAYILAN_ANLIK_GORUNTU_DIZINI),
+            yapilandirma_dosyalari_dizini: String::from(VARSAYILAN_YAPILANDIRMA_DOSYALARI_DIZINI),
+            betik_dizini: String::from(VARSAYILAN_BETIK_DIZINI),
+            betik_zaman_asimi_sn: 300,
+            kullanici_koku: String::from("sahne://user/.sahne"),
+            log_level: String::from("info"),
+            gunluk_hedefleri: String::from("console_logging"),
+            gunluk_modul_seviyeleri: String::new(),
+            gunluk_renkli: true,
+            gunluk_dosyasi: String::from("sahne://system/logs/paket_yoneticisi.log"),
+            gunluk_json_dosyasi: String::from("sahne://system/logs/paket_yoneticisi.jsonl"),
+            gunluk_dosya_azami_boyut: 1024 * 1024,
+            gunluk_dosya_sayisi: 5,
+            syslog_soketi: String::from("sahne://dev/log"),
+            compression: true,
+            sikistirma_algoritmalari: String::from("gzip,zstd,xz,bzip2"),
+            network: true,
+            ag_protokolleri: String::from("https"),
+            security: true,
+            guvenlik_ozellikleri: String::from("signature_verification,sandbox"),
+            kurulum_politikasi: String::from("uyar"),
+            engelleme_onemi: String::from("yuksek"),
+            guvenilen_anahtarlar_kaynagi: String::from(VARSAYILAN_ANAHTARLAR_KAYNAGI),
         }
     }
+}
 
-    // Yapılandırma verilerini belirtilen Kaynak ID'sinden okur ve deserialize eder.
-    // resource_id: Yapılandırma verilerini içeren Kaynağın ID'si (örn. "sahne://config/paket_yoneticisi.bin")
-    // Result türü PaketYoneticisiHatasi olmalı
-    pub fn oku(resource_id: &str) -> Result<Yapilandirma, PaketYoneticisiHatasi> {
-        // Kaynağı oku (sadece okuma izniyle)
-        let handle = resource::acquire(resource_id, resource::MODE_READ)
-            .map_err(|e| {
-                 eprintln!("Yapılandırma Kaynağı acquire hatası ({}): {:?}", resource_id, e);
-                 PaketYoneticisiHatasi::from(e) // SahneError -> PaketYoneticisiHatasi
-            })?;
-
-        let mut buffer = Vec::new(); // Kaynak içeriğini tutacak tampon (alloc::vec::Vec)
-        let mut temp_buffer = [0u8; 512]; // Okuma tamponu (stack'te)
-
-        // Kaynağın tüm içeriğini oku (parça parça okuma döngüsü)
-        loop {
-            match resource::read(handle, &mut temp_buffer) {
-                Ok(0) => break, // Kaynak sonu
-                Ok(bytes_read) => {
-                    buffer.extend_from_slice(&temp_buffer[..bytes_read]);
+impl Yapilandirma {
+    pub fn yeni(depo_url: String, yerel_depo_yolu: String, kurulum_dizini: String, onbellek_dizini: String) -> Yapilandirma {
+        Yapilandirma { depo_url, yerel_depo_yolu, kurulum_dizini, onbellek_dizini, ..Yapilandirma::default() }
+    }
+
+    // Şemadaki bir anahtarın değerini döndürür.
+    pub fn deger(&self, anahtar: &str) -> Option<Deger> {
+        let deger = match anahtar {
+            "depo.url" => Deger::Metin(self.depo_url.clone()),
+            "depo.yerel_yol" => Deger::Metin(self.yerel_depo_yolu.clone()),
+            "depo.imza_anahtari" => Deger::Metin(self.imza_anahtari_kaynagi.clone()),
+            "kurulum.dizin" => Deger::Metin(self.kurulum_dizini.clone()),
+            "kurulum.onbellek_dizini" => Deger::Metin(self.onbellek_dizini.clone()),
+            "kurulum.veritabani" => Deger::Metin(self.veritabani_kaynagi.clone()),
+            "kurulum.islem_gunlugu" => Deger::Metin(self.islem_gunlugu_kaynagi.clone()),
+            "kurulum.kilit" => Deger::Metin(self.kilit_kaynagi.clone()),
+            "kurulum.gecmis" => Deger::Metin(self.gecmis_kaynagi.clone()),
+            "kurulum.anlik_goruntuler" => Deger::Metin(self.anlik_goruntu_dizini.clone()),
+            "kurulum.yapilandirma_dosyalari" => Deger::Metin(self.yapilandirma_dosyalari_dizini.clone()),
+            "kurulum.betik_dizini" => Deger::Metin(self.betik_dizini.clone()),
+            "kurulum.betik_zaman_asimi_sn" => Deger::Tamsayi(self.betik_zaman_asimi_sn as i64),
+            "kullanici.kok" => Deger::Metin(self.kullanici_koku.clone()),
+            "gunluk.seviye" => Deger::Metin(self.log_level.clone()),
+            "gunluk.hedefler" => Deger::Metin(self.gunluk_hedefleri.clone()),
+            "gunluk.modul_seviyeleri" => Deger::Metin(self.gunluk_modul_seviyeleri.clone()),
+            "gunluk.renkli" => Deger::Mantiksal(self.gunluk_renkli),
+            "gunluk.dosya" => Deger::Metin(self.gunluk_dosyasi.clone()),
+            "gunluk.json_dosyasi" => Deger::Metin(self.gunluk_json_dosyasi.clone()),
+            "gunluk.dosya_azami_boyut" => Deger::Tamsayi(self.gunluk_dosya_azami_boyut as i64),
+            "gunluk.dosya_sayisi" => Deger::Tamsayi(self.gunluk_dosya_sayisi as i64),
+            "gunluk.syslog_soketi" => Deger::Metin(self.syslog_soketi.clone()),
+            "ozellikler.sikistirma" => Deger::Mantiksal(self.compression),
+            "ozellikler.sikistirma_algoritmalari" => Deger::Metin(self.sikistirma_algoritmalari.clone()),
+            "ozellikler.ag" => Deger::Mantiksal(self.network),
+            "ozellikler.ag_protokolleri" => Deger::Metin(self.ag_protokolleri.clone()),
+            "ozellikler.guvenlik" => Deger::Mantiksal(self.security),
+            "ozellikler.guvenlik_ozellikleri" => Deger::Metin(self.guvenlik_ozellikleri.clone()),
+            "guvenlik.kurulum_politikasi" => Deger::Metin(self.kurulum_politikasi.clone()),
+            "guvenlik.engelleme_onemi" => Deger::Metin(self.engelleme_onemi.clone()),
+            "guvenlik.guvenilen_anahtarlar" => Deger::Metin(self.guvenilen_anahtarlar_kaynagi.clone()),
+            _ => return None,
+        };
+        Some(deger)
+    }
+
+    // Bir anahtarı tür ve değer denetimiyle ayarlar. Hata mesajı kaynak/satır bilgisi olmadan döner.
+    pub fn ayarla(&mut self, anahtar: &str, deger: Deger) -> Result<(), String> {
+        let tanim = anahtar_tanimi(anahtar).ok_or_else(|| format!("bilinmeyen anahtar '{}'", anahtar))?;
+        if deger.tur() != tanim.tur {
+            return Err(format!("'{}' için {} bekleniyor, {} verildi", anahtar, tanim.tur.ad(), deger.tur().ad()));
+        }
+        if let (Some((_, kategori)), Deger::Metin(m)) = (OZELLIK_LISTELERI.iter().find(|(a, _)| *a == anahtar), &deger) {
+            ozellik_listesi(m, kategori).map_err(|e| format!("'{}' geçersiz: {}", anahtar, e))?;
+        }
+        match (anahtar, deger) {
+            // Boş liste: tüm modüller gunluk.seviye'yi kullanır
+            ("gunluk.modul_seviyeleri", Deger::Metin(m)) => {
+                modul_seviyeleri(&m).map_err(|e| format!("'{}' geçersiz: {}", anahtar, e))?;
+                self.gunluk_modul_seviyeleri = m;
+            }
+            (_, Deger::Metin(m)) if m.trim().is_empty() => return Err(format!("'{}' boş olamaz", anahtar)),
+            ("depo.url", Deger::Metin(m)) => self.depo_url = m,
+            ("depo.yerel_yol", Deger::Metin(m)) => self.yerel_depo_yolu = m,
+            ("depo.imza_anahtari", Deger::Metin(m)) => self.imza_anahtari_kaynagi = m,
+            ("kurulum.dizin", Deger::Metin(m)) => self.kurulum_dizini = m,
+            ("kurulum.onbellek_dizini", Deger::Metin(m)) => self.onbellek_dizini = m,
+            ("kurulum.veritabani", Deger::Metin(m)) => self.veritabani_kaynagi = m,
+            ("kurulum.islem_gunlugu", Deger::Metin(m)) => self.islem_gunlugu_kaynagi = m,
+            ("kurulum.kilit", Deger::Metin(m)) => self.kilit_kaynagi = m,
+            ("kurulum.gecmis", Deger::Metin(m)) => self.gecmis_kaynagi = m,
+            ("kurulum.anlik_goruntuler", Deger::Metin(m)) => self.anlik_goruntu_dizini = m,
+            ("kurulum.yapilandirma_dosyalari", Deger::Metin(m)) => self.yapilandirma_dosyalari_dizini = m,
+            ("kurulum.betik_dizini", Deger::Metin(m)) => self.betik_dizini = m,
+            ("kurulum.betik_zaman_asimi_sn", Deger::Tamsayi(n)) => {
+                if n <= 0 {
+                    return Err(format!("'{}' pozitif olmalı, {} verildi", anahtar, n));
+                }
+                self.betik_zaman_asimi_sn = n as u64;
+            }
+            ("kullanici.kok", Deger::Metin(m)) => self.kullanici_koku = m,
+            ("gunluk.seviye", Deger::Metin(m)) => {
+                if !GUNLUK_SEVIYELERI.contains(&m.as_str()) {
+                    return Err(format!("'{}' geçersiz; geçerli seviyeler: {}", m, GUNLUK_SEVIYELERI.join(", ")));
+                }
+                self.log_level = m;
+            }
+            ("gunluk.hedefler", Deger::Metin(m)) => self.gunluk_hedefleri = m,
+            ("gunluk.renkli", Deger::Mantiksal(b)) => self.gunluk_renkli = b,
+            ("gunluk.dosya", Deger::Metin(m)) => self.gunluk_dosyasi = m,
+            ("gunluk.json_dosyasi", Deger::Metin(m)) => self.gunluk_json_dosyasi = m,
+            ("gunluk.dosya_azami_boyut", Deger::Tamsayi(n)) => {
+                if n <= 0 {
+                    return Err(format!("'{}' pozitif olmalı, {} verildi", anahtar, n));
                 }
-                Err(e) => {
-                    // Okuma hatası durumunda handle'ı serbest bırakıp hata dön
-                    let _ = resource::release(handle);
-                     eprintln!("Yapılandırma Kaynağı okuma hatası ({}): {:?}", resource_id, e);
-                    return Err(PaketYoneticisiHatasi::from(e)); // SahneError -> PaketYoneticisiHatasi
+                self.gunluk_dosya_azami_boyut = n as u64;
+            }
+            ("gunluk.dosya_sayisi", Deger::Tamsayi(n)) => {
+                self.gunluk_dosya_sayisi = u32::try_from(n).map_err(|_| format!("'{}' 0 ile {} arasında olmalı, {} verildi", anahtar, u32::MAX, n))?;
+            }
+            ("gunluk.syslog_soketi", Deger::Metin(m)) => self.syslog_soketi = m,
+            ("ozellikler.sikistirma", Deger::Mantiksal(b)) => self.compression = b,
+            ("ozellikler.sikistirma_algoritmalari", Deger::Metin(m)) => self.sikistirma_algoritmalari = m,
+            ("ozellikler.ag", Deger::Mantiksal(b)) => self.network = b,
+            ("ozellikler.ag_protokolleri", Deger::Metin(m)) => self.ag_protokolleri = m,
+            ("ozellikler.guvenlik", Deger::Mantiksal(b)) => self.security = b,
+            ("ozellikler.guvenlik_ozellikleri", Deger::Metin(m)) => self.guvenlik_ozellikleri = m,
+            ("guvenlik.kurulum_politikasi", Deger::Metin(m)) => {
+                if KurulumPolitikasi::ayristir(&m).is_none() {
+                    return Err(format!("'{}' geçersiz; geçerli politikalar: kapali, uyar, engelle", m));
                 }
+                self.kurulum_politikasi = m;
             }
+            ("guvenlik.engelleme_onemi", Deger::Metin(m)) => {
+                if Onem::ayristir(&m).is_none() {
+                    return Err(format!("'{}' geçersiz; geçerli önem dereceleri: dusuk, orta, yuksek, kritik", m));
+                }
+                self.engelleme_onemi = m;
+            }
+            ("guvenlik.guvenilen_anahtarlar", Deger::Metin(m)) => self.guvenilen_anahtarlar_kaynagi = m,
+            _ => unreachable!("şemadaki her anahtar burada ele alınmalı"),
         }
+        Ok(())
+    }
 
-        // Handle'ı serbest bırak
-        let release_result = resource::release(handle);
-         if let Err(e) = release_result {
-              eprintln!("Yapılandırma Kaynağı release hatası ({}): {:?}", resource_id, e);
-              // Release hatası kritik olmayabilir, loglayıp devam edebiliriz.
-         }
+    // Yerel durumu tutan tüm Kaynakları (kurulum dizini, önbellek, yerel depo, veritabanı, işlem günlüğü,
+    // kilit, işlem geçmişi, anlık görüntüler, yapılandırma dosyası varsayılanları, paket betikleri, günlük dosyaları) verilen kökün altına taşınmış bir kopya döndürür; uzak depo adresi,
+    // syslog soketi ve imza anahtarları değişmez (depoya çalışan sistemin güvendiği anahtarlarla güvenilir).
+    // Kurulmakta olan bir sistem imajına veya chroot'a paket kurmak için kullanılır (bkz. kok_altinda_yol).
+    pub fn kok_altinda(&self, kok: &str) -> Yapilandirma {
+        Yapilandirma {
+            yerel_depo_yolu: kok_altinda_yol(kok, &self.yerel_depo_yolu),
+            kurulum_dizini: kok_altinda_yol(kok, &self.kurulum_dizini),
+            onbellek_dizini: kok_altinda_yol(kok, &self.onbellek_dizini),
+            veritabani_kaynagi: kok_altinda_yol(kok, &self.veritabani_kaynagi),
+            islem_gunlugu_kaynagi: kok_altinda_yol(kok, &self.islem_gunlugu_kaynagi),
+            kilit_kaynagi: kok_altinda_yol(kok, &self.kilit_kaynagi),
+            gecmis_kaynagi: kok_altinda_yol(kok, &self.gecmis_kaynagi),
+            anlik_goruntu_dizini: kok_altinda_yol(kok, &self.anlik_goruntu_dizini),
+            yapilandirma_dosyalari_dizini: kok_altinda_yol(kok, &self.yapilandirma_dosyalari_dizini),
+            betik_dizini: kok_altinda_yol(kok, &self.betik_dizini),
+            gunluk_dosyasi: kok_altinda_yol(kok, &self.gunluk_dosyasi),
+            gunluk_json_dosyasi: kok_altinda_yol(kok, &self.gunluk_json_dosyasi),
+            ..self.clone()
+        }
+    }
 
+    // Kullanıcı kapsamı: yetki gerektirmeden kurulum için kurulum dizini, önbellek, yerel depo,
+    // veritabanı, işlem günlüğü ve kilit kullanici_koku altına taşınır (bkz. kok_altinda).
+    // --root ile birlikte kullanılırken önce bu, sonra kok_altinda uygulanmalıdır.
+    pub fn kullanici_kapsami(&self) -> Yapilandirma {
+        self.kok_altinda(&self.kullanici_koku)
+    }
 
-        // Tampondaki binary veriyi Yapilandirma struct'ına deserialize et (postcard ile)
-        // postcard::from_bytes Result<'a, T, Error> döner. T Yapilandirma struct'ımız.
-        // Lifetime 'a, buffer'ın lifetime'ı olmalı. Veya from_bytes_copy kullanmalı.
-        // from_bytes_copy daha uygundur no_std'de.
-        match postcard::from_bytes_copy::<Yapilandirma>(&buffer) {
-            Ok(yapilandirma) => Ok(yapilandirma),
-            Err(e) => {
-                eprintln!("Yapılandırma verisi deserialize hatası ({}): {:?}", resource_id, e);
-                Err(PaketYoneticisiHatasi::from(e)) // PostcardError -> PaketYoneticisiHatasi
+    // Tüm anahtarları açıklamalarıyla birlikte bölümlere ayrılmış TOML metni olarak yazar.
+    pub fn toml_metni(&self) -> String {
+        let mut metin = String::new();
+        let mut son_bolum = "";
+        for tanim in ANAHTARLAR {
+            let (bolum, ad) = anahtari_bol(tanim.ad);
+            if bolum != son_bolum {
+                if !metin.is_empty() {
+                    metin.push('\n');
+                }
+                metin.push_str(&format!("[{}]\n", bolum));
+                son_bolum = bolum;
+            }
+            if let Some(deger) = self.deger(tanim.ad) {
+                metin.push_str(&format!("# {}\n{} = {}\n", tanim.aciklama, ad, deger));
             }
         }
+        metin
+    }
+
+    // Yapılandırmayı tek bir TOML Kaynağından okur (varsayılanların üzerine uygular).
+    // resource_id: Yapılandırma Kaynağının ID'si (örn. SISTEM_YAPILANDIRMA_KAYNAGI)
+    pub fn oku(resource_id: &str) -> Result<Yapilandirma, PaketYoneticisiHatasi> {
+        let mut katmanli = KatmanliYapilandirma::default();
+        if let Some(metin) = kaynak_metni_oku(resource_id)? {
+            katmanli.toml_uygula(resource_id, &metin)?;
+        }
+        Ok(katmanli.yapilandirma)
     }
 
-    // Yapılandırma verilerini serialize eder ve belirtilen Kaynak ID'sine yazar.
-    // resource_id: Yapılandırma verilerinin yazılacağı Kaynağın ID'si.
-    // Result türü PaketYoneticisiHatasi olmalı
+    // Yapılandırmayı TOML olarak Kaynağa yazar (varsa mevcut dosyanın yerine).
     pub fn yaz(&self, resource_id: &str) -> Result<(), PaketYoneticisiHatasi> {
-        // Yapılandırma struct'ını binary veriye serialize et (postcard ile)
-        // to_postcard Result<Vec<u8>, Error> veya Result<Box<[u8]>, Error> döner.
-        // Vec kullanmak alloc gerektirir.
-        let serialized_data = match postcard::to_postcard(self) {
-            Ok(data) => data,
-            Err(e) => {
-                eprintln!("Yapılandırma verisi serialize hatası: {:?}", e);
-                return Err(PaketYoneticisiHatasi::from(e)); // PostcardError -> PaketYoneticisiHatasi
+        kaynak_metni_yaz(resource_id, &self.toml_metni())
+    }
+}
+
+// Bir değerin hangi katmandan geldiği.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DegerKaynagi {
+    Varsayilan,
+    Dosya { kaynak: String, satir: usize },
+    Ortam(String),
+}
+
+impl core::fmt::Display for DegerKaynagi {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DegerKaynagi::Varsayilan => f.write_str("varsayılan"),
+            DegerKaynagi::Dosya { kaynak, satir } => write!(f, "{}:{}", kaynak, satir),
+            DegerKaynagi::Ortam(ad) => write!(f, "ortam: {}", ad),
+        }
+    }
+}
+
+// Katmanlardan birleştirilmiş yapılandırma ve her anahtarın değerini son belirleyen katman.
+#[derive(Debug, Clone, Default)]
+pub struct KatmanliYapilandirma {
+    pub yapilandirma: Yapilandirma,
+    kokenler: BTreeMap<&'static str, DegerKaynagi>,
+}
+
+impl KatmanliYapilandirma {
+    // Tüm katmanları öncelik sırasıyla yükler.
+    // kullanici_id: Kullanıcı yapılandırma Kaynağı (yoksa None).
+    // ortam: Görevin ortam değişkenleri; SAHNE_PKG_ önekli olmayanlar yok sayılır.
+    pub fn yukle(sistem_id: &str, kullanici_id: Option<&str>, ortam: &[(String, String)]) -> Result<Self, PaketYoneticisiHatasi> {
+        let mut katmanli = KatmanliYapilandirma::default();
+        for kaynak_id in core::iter::once(sistem_id).chain(kullanici_id) {
+            if let Some(metin) = kaynak_metni_oku(kaynak_id)? {
+                katmanli.toml_uygula(kaynak_id, &metin)?;
             }
-        };
+        }
+        katmanli.ortam_uygula(ortam.iter().map(|(ad, deger)| (ad.as_str(), deger.as_str())))?;
+        Ok(katmanli)
+    }
 
-        // Kaynağın parent dizinlerini/kaynaklarını oluştur (Sahne64 modeline göre)
-        // Yapılandırma dosyası genellikle belirli bir yerde olur ("sahne://config/"),
-        // bu yolun varlığını sağlamak gerekebilir.
-        // sahne_create_resource_recursive helper fonksiyonu kullanılabilir.
-        // Varsayım: Yapılandırma Kaynağının parent'ı ("sahne://config/") zaten mevcuttur
-        // veya resource::acquire(..., MODE_CREATE) parent'ı da oluşturur.
-        // Eğer oluşturmuyorsa:
-         let parent_resource_id = resource_id.rfind('/').map(|idx| &resource_id[..idx]).unwrap_or("");
-         if !parent_resource_id.is_empty() {
-              sahne_create_resource_recursive(resource_id)?; // resource_id kendisi değil, parent'ları için
-        //     // Doğrudan parent'ı acquire etmeyi deneyelim MODE_CREATE ile:
-              match resource::acquire(parent_resource_id, resource::MODE_CREATE) {
-                   Ok(handle) => { let _ = resource::release(handle); },
-                   Err(e) => { eprintln!("Yapılandırma Kaynağı parent acquire hatası ({}): {:?}", parent_resource_id, e); return Err(PaketYoneticisiHatasi::from(e)); }
-              }
-         }
-
-
-        // Kaynağı yazma izniyle oluştur/aç (varsa sil)
-        // MODE_TRUNCATE içeriği siler, bu yeni yapılandırmayı yazmak için uygun.
-        match resource::acquire(
-            resource_id,
-            resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE
-        ) {
-            Ok(handle) => {
-                // Binary veriyi Kaynağa yaz
-                let write_result = resource::write(handle, &serialized_data); // Vec<u8> dilime dönüştürüldü
-
-                // Handle'ı serbest bırak
-                let release_result = resource::release(handle);
-
-                // Yazma veya release hatalarını kontrol et
-                if let Err(e) = write_result {
-                    eprintln!("Yapılandırma Kaynağı yazma hatası ({}): {:?}", resource_id, e);
-                    // Yazma başarısızsa release'in sonucunu yoksayabiliriz.
-                    return Err(PaketYoneticisiHatasi::from(e)); // SahneError -> PaketYoneticisiHatasi
-                }
-                 if let Err(e) = release_result {
-                      eprintln!("Yapılandırma Kaynağı release hatası ({}): {:?}", resource_id, e);
-                      // Release hatası loglanmalı ama write başarılıysa Ok dönebiliriz?
-                      // Hata yönetim stratejisine bağlı. Şimdilik loglayalım.
-                 }
+    // Anahtarın değerinin geldiği katman.
+    pub fn koken(&self, anahtar: &str) -> DegerKaynagi {
+        self.kokenler.get(anahtar).cloned().unwrap_or(DegerKaynagi::Varsayilan)
+    }
 
+    // Bir TOML metnini doğrulayıp uygular. Hatalar "kaynak:satır: mesaj" biçimindedir ve dosyanın
+    // hiçbir değeri uygulanmaz.
+    pub fn toml_uygula(&mut self, kaynak_id: &str, metin: &str) -> Result<(), PaketYoneticisiHatasi> {
+        let hata = |satir: usize, mesaj: String| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("{}:{}: {}", kaynak_id, satir, mesaj));
+        let girdiler = toml_ayristir(metin).map_err(|(satir, mesaj)| hata(satir, mesaj))?;
 
-                Ok(()) // Hem yazma hem release başarılıysa
+        let mut yeni = self.yapilandirma.clone();
+        let mut kokenler = Vec::new();
+        for girdi in girdiler {
+            let tanim = anahtar_tanimi(&girdi.anahtar).ok_or_else(|| hata(girdi.satir, format!("bilinmeyen anahtar '{}'", girdi.anahtar)))?;
+            yeni.ayarla(tanim.ad, girdi.deger).map_err(|mesaj| hata(girdi.satir, mesaj))?;
+            kokenler.push((tanim.ad, DegerKaynagi::Dosya { kaynak: kaynak_id.to_string(), satir: girdi.satir }));
+        }
+        self.yapilandirma = yeni;
+        self.kokenler.extend(kokenler);
+        Ok(())
+    }
+
+    // SAHNE_PKG_ önekli ortam değişkenlerini uygular; şemada karşılığı olmayanlar hata sayılır.
+    pub fn ortam_uygula<'a>(&mut self, degiskenler: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<(), PaketYoneticisiHatasi> {
+        for (ad, metin) in degiskenler.into_iter().filter(|(ad, _)| ad.starts_with(ORTAM_ONEKI)) {
+            let tanim = ANAHTARLAR.iter()
+                .find(|a| ortam_degiskeni_adi(a.ad) == ad)
+                .ok_or_else(|| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("bilinmeyen ortam değişkeni {}", ad)))?;
+            Deger::metinden(tanim.tur, metin)
+                .and_then(|deger| self.yapilandirma.ayarla(tanim.ad, deger))
+                .map_err(|mesaj| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("{}: {}", ad, mesaj)))?;
+            self.kokenler.insert(tanim.ad, DegerKaynagi::Ortam(ad.to_string()));
+        }
+        Ok(())
+    }
+}
+
+// Bir yapılandırma dosyasındaki anahtarı ayarlar ve dosyayı yazar. Dosyadaki diğer satırlar (yorumlar
+// dahil) korunur; dosya yoksa oluşturulur. Sonuç dosyanın tamamı yeniden doğrulanmadan yazılmaz.
+// deger_metni: Tırnaksız değer (anahtarın türüne göre ayrıştırılır).
+pub fn dosyada_ayarla(kaynak_id: &str, anahtar: &str, deger_metni: &str) -> Result<(), PaketYoneticisiHatasi> {
+    let tanim = anahtar_tanimi(anahtar)
+        .ok_or_else(|| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("bilinmeyen anahtar '{}'", anahtar)))?;
+    let deger = Deger::metinden(tanim.tur, deger_metni)
+        .and_then(|deger| Yapilandirma::default().ayarla(anahtar, deger.clone()).map(|_| deger))
+        .map_err(|mesaj| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("{}: {}", anahtar, mesaj)))?;
+
+    let eski = kaynak_metni_oku(kaynak_id)?.unwrap_or_default();
+    let yeni = toml_degerini_ayarla(&eski, anahtar, &deger);
+    KatmanliYapilandirma::default().toml_uygula(kaynak_id, &yeni)?;
+    kaynak_metni_yaz(kaynak_id, &yeni)
+}
+
+// Bir Kaynak ID'sini alternatif kökün altına taşır: kök "sahne://imaj" (veya "/imaj") iken
+// "sahne://config/a.bin" -> "sahne://imaj/config/a.bin". Kök boşsa veya "/" ise ID değişmez.
+pub fn kok_altinda_yol(kok: &str, resource_id: &str) -> String {
+    let kok = kok.trim_end_matches('/');
+    let kok = match kok.split_once("://") {
+        Some(_) => kok.to_string(),
+        None if kok.is_empty() => return resource_id.to_string(),
+        None => format!("sahne://{}", kok.trim_start_matches('/')),
+    };
+    let yol = resource_id.split_once("://").map_or(resource_id, |(_, yol)| yol);
+    format!("{}/{}", kok, yol.trim_start_matches('/'))
+}
+
+// "bölüm.ad" -> ("bölüm", "ad"); bölümsüz anahtarlar için bölüm boştur.
+fn anahtari_bol(anahtar: &str) -> (&str, &str) {
+    match anahtar.rfind('.') {
+        Some(i) => (&anahtar[..i], &anahtar[i + 1..]),
+        None => ("", anahtar),
+    }
+}
+
+// Ayrıştırılmış bir "anahtar = değer" satırı. anahtar, içinde bulunduğu bölümün adıyla birleştirilmiştir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TomlGirdisi {
+    pub anahtar: String,
+    pub deger: Deger,
+    pub satir: usize, // 1'den başlar
+}
+
+// Yapılandırma dosyaları için TOML alt kümesini ayrıştırır: [bölüm] başlıkları, "anahtar = değer"
+// satırları (değer: "..." veya '...' metin, tamsayı, true/false) ve # yorumları.
+// Hata durumunda (satır numarası, mesaj) döner.
+pub fn toml_ayristir(metin: &str) -> Result<Vec<TomlGirdisi>, (usize, String)> {
+    let mut girdiler: Vec<TomlGirdisi> = Vec::new();
+    let mut bolum = String::new();
+    for (i, ham) in metin.lines().enumerate() {
+        let satir = i + 1;
+        let icerik = yorumu_at(ham).trim();
+        if icerik.is_empty() {
+            continue;
+        }
+        if let Some(baslik) = icerik.strip_prefix('[') {
+            let ad = baslik.strip_suffix(']').ok_or((satir, String::from("bölüm başlığı ']' ile bitmeli")))?.trim();
+            if !gecerli_anahtar(ad) {
+                return Err((satir, format!("geçersiz bölüm adı '{}'", ad)));
             }
-            Err(e) => {
-                 eprintln!("Yapılandırma Kaynağı acquire hatası ({}): {:?}", resource_id, e);
-                Err(PaketYoneticisiHatasi::from(e)) // SahneError -> PaketYoneticisiHatasi
+            bolum = ad.to_string();
+            continue;
+        }
+        let esittir = icerik.find('=').ok_or((satir, String::from("'anahtar = değer' bekleniyor")))?;
+        let ad = icerik[..esittir].trim();
+        if !gecerli_anahtar(ad) {
+            return Err((satir, format!("geçersiz anahtar '{}'", ad)));
+        }
+        let deger = deger_ayristir(icerik[esittir + 1..].trim()).map_err(|mesaj| (satir, mesaj))?;
+        let anahtar = if bolum.is_empty() { ad.to_string() } else { format!("{}.{}", bolum, ad) };
+        if let Some(onceki) = girdiler.iter().find(|g| g.anahtar == anahtar) {
+            return Err((satir, format!("'{}' anahtarı {}. satırda zaten tanımlı", anahtar, onceki.satir)));
+        }
+        girdiler.push(TomlGirdisi { anahtar, deger, satir });
+    }
+    Ok(girdiler)
+}
+
+// Yalın (noktalı olabilen) TOML anahtarı: harf, rakam, '_' ve '-'.
+fn gecerli_anahtar(ad: &str) -> bool {
+    !ad.is_empty() && ad.split('.').all(|parca| !parca.is_empty() && parca.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-'))
+}
+
+// Satırdaki tırnak dışı '#' ve sonrasını atar.
+fn yorumu_at(satir: &str) -> &str {
+    let mut tirnak: Option<char> = None;
+    let mut kacis = false;
+    for (i, c) in satir.char_indices() {
+        match (tirnak, c) {
+            (Some('"'), '\\') if !kacis => {
+                kacis = true;
+                continue;
             }
+            (Some(t), c) if c == t && !kacis => tirnak = None,
+            (None, '"') | (None, '\'') => tirnak = Some(c),
+            (None, '#') => return &satir[..i],
+            _ => {}
         }
+        kacis = false;
     }
+    satir
 }
 
-// --- PaketYoneticisiHatasi enum tanımının no_std uyumlu hale getirilmesi ---
-// (paket_yoneticisi_hata.rs dosyasında veya ilgili modülde olmalı)
+fn tamsayi_ayristir(metin: &str) -> Option<i64> {
+    let rakamlar = metin.strip_prefix('+').unwrap_or(metin);
+    let govde = rakamlar.strip_prefix('-').unwrap_or(rakamlar);
+    if govde.is_empty() || govde.starts_with('_') || govde.ends_with('_') || govde.contains("__") {
+        return None;
+    }
+    rakamlar.replace('_', "").parse().ok()
+}
 
-// paket_yoneticisi_hata.rs (Örnek - no_std uyumlu, Postcard hatası eklenmiş)
-#![no_std]
-extern crate alloc;
+fn deger_ayristir(metin: &str) -> Result<Deger, String> {
+    if let Some(govde) = metin.strip_prefix('"') {
+        let mut sonuc = String::new();
+        let mut karakterler = govde.char_indices();
+        while let Some((i, c)) = karakterler.next() {
+            match c {
+                '"' => {
+                    return if govde[i + 1..].trim().is_empty() {
+                        Ok(Deger::Metin(sonuc))
+                    } else {
+                        Err(format!("metinden sonra beklenmeyen içerik: '{}'", govde[i + 1..].trim()))
+                    };
+                }
+                '\\' => match karakterler.next().map(|(_, k)| k) {
+                    Some('"') => sonuc.push('"'),
+                    Some('\\') => sonuc.push('\\'),
+                    Some('n') => sonuc.push('\n'),
+                    Some('t') => sonuc.push('\t'),
+                    Some('r') => sonuc.push('\r'),
+                    Some(k) => return Err(format!("desteklenmeyen kaçış dizisi '\\{}'", k)),
+                    None => break,
+                },
+                c => sonuc.push(c),
+            }
+        }
+        return Err(String::from("metin kapatılmamış (eksik '\"')"));
+    }
+    if let Some(govde) = metin.strip_prefix('\'') {
+        let son = govde.find('\'').ok_or_else(|| String::from("metin kapatılmamış (eksik \"'\")"))?;
+        if !govde[son + 1..].trim().is_empty() {
+            return Err(format!("metinden sonra beklenmeyen içerik: '{}'", govde[son + 1..].trim()));
+        }
+        return Ok(Deger::Metin(govde[..son].to_string()));
+    }
+    match metin {
+        "true" => Ok(Deger::Mantiksal(true)),
+        "false" => Ok(Deger::Mantiksal(false)),
+        "" => Err(String::from("değer eksik")),
+        _ => tamsayi_ayristir(metin)
+            .map(Deger::Tamsayi)
+            .ok_or_else(|| format!("geçersiz değer '{}' (metinler tırnak içinde yazılmalı)", metin)),
+    }
+}
 
-use alloc::string::String;
-use alloc::format;
-use crate::SahneError;
-use postcard::Error as PostcardError; // Postcard hata türü
+// TOML metninde bir anahtarın değerini değiştirir veya ekler; diğer satırlara dokunmaz.
+// Anahtar, bölümünde (veya kökte noktalı adla) varsa o satır değiştirilir; yoksa bölümün son
+// girdisinden sonra, bölüm de yoksa dosyanın sonuna yeni bölümle eklenir.
+pub fn toml_degerini_ayarla(metin: &str, anahtar: &str, deger: &Deger) -> String {
+    let (hedef_bolum, ad) = anahtari_bol(anahtar);
+    let mut satirlar: Vec<String> = metin.lines().map(|s| s.to_string()).collect();
 
-// ... (ZipHatasi, PaketBulunamadi, PaketKurulumHatasi, OnbellekHatasi, ChecksumResourceError, GecersizParametre, PathTraversalHatasi) ...
+    let mut bolum = String::new();
+    let mut bolum_sonu: Option<usize> = None; // Hedef bölümdeki son dolu satırın indeksi
+    for (i, satir) in satirlar.iter_mut().enumerate() {
+        let icerik = yorumu_at(satir).trim();
+        if let Some(baslik) = icerik.strip_prefix('[').and_then(|b| b.strip_suffix(']')) {
+            bolum = baslik.trim().to_string();
+            if bolum == hedef_bolum {
+                bolum_sonu = Some(i);
+            }
+            continue;
+        }
+        let satir_anahtari = match icerik.find('=') {
+            Some(esittir) => icerik[..esittir].trim(),
+            None => continue,
+        };
+        let eslesir = (bolum == hedef_bolum && satir_anahtari == ad) || (bolum.is_empty() && satir_anahtari == anahtar);
+        if eslesir {
+            let girinti: String = satir.chars().take_while(|c| c.is_whitespace()).collect();
+            *satir = format!("{}{} = {}", girinti, satir_anahtari, deger);
+            return satirlari_birlestir(satirlar);
+        }
+        if bolum == hedef_bolum && !icerik.is_empty() {
+            bolum_sonu = Some(i);
+        }
+    }
 
-#[derive(Debug)]
-pub enum PaketYoneticisiHatasi {
-    // ... (Çeşitli hatalar) ...
+    match bolum_sonu {
+        Some(i) => satirlar.insert(i + 1, format!("{} = {}", ad, deger)),
+        None => {
+            if satirlar.last().is_some_and(|s| !s.trim().is_empty()) {
+                satirlar.push(String::new());
+            }
+            if !hedef_bolum.is_empty() {
+                satirlar.push(format!("[{}]", hedef_bolum));
+            }
+            satirlar.push(format!("{} = {}", ad, deger));
+        }
+    }
+    satirlari_birlestir(satirlar)
+}
 
-    // Sahne64 API'sından gelen genel hatalar
-    SahneApiHatasi(SahneError),
+fn satirlari_birlestir(satirlar: Vec<String>) -> String {
+    let mut metin = satirlar.join("\n");
+    metin.push('\n');
+    metin
+}
+
+// Kaynağın tamamını UTF-8 metin olarak okur. Kaynak yoksa None döner.
+fn kaynak_metni_oku(resource_id: &str) -> Result<Option<String>, PaketYoneticisiHatasi> {
+    let handle = match resource::acquire(resource_id, resource::MODE_READ) {
+        Ok(h) => h,
+        Err(SahneError::ResourceNotFound) => return Ok(None),
+        Err(e) => {
+            eprintln!("Yapılandırma Kaynağı acquire hatası ({}): {:?}", resource_id, e);
+            return Err(PaketYoneticisiHatasi::from(e));
+        }
+    };
 
-    // Serileştirme/Deserileştirme hataları için
-    SerializationError(PostcardError),
-    DeserializationError(PostcardError),
+    let mut buffer = Vec::new(); // alloc
+    let mut temp_buffer = [0u8; 512]; // Okuma tamponu (stack'te)
+    loop {
+        match resource::read(handle, &mut temp_buffer) {
+            Ok(0) => break,
+            Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+            Err(e) => {
+                let _ = resource::release(handle);
+                eprintln!("Yapılandırma Kaynağı okuma hatası ({}): {:?}", resource_id, e);
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        }
+    }
+    let _ = resource::release(handle);
 
-    // ... diğer hatalar ...
-    BilinmeyenHata,
+    String::from_utf8(buffer)
+        .map(Some)
+        .map_err(|_| PaketYoneticisiHatasi::YapilandirmaHatasi(format!("{}: geçerli UTF-8 değil", resource_id)))
 }
 
-// SahneError'dan PaketYoneticisiHatasi::SahneApiHatasi'na dönüşüm
-// Bu From implementasyonu genel SahneApiHatasi için kullanılabilir.
-impl From<SahneError> for PaketYoneticisiHatasi {
-    fn from(err: SahneError) -> Self {
-        // SahneError'ın kaynağını inceleyip daha spesifik hatalara yönlendirme yapılabilir.
-        // Örn: match err { SahneError::ResourceNotFound => PaketYoneticisiHatasi::YapilandirmaDosyasiBulunamadi }
-        match err {
-            // Kaynakla ilgili hataları (özellikle config dosyası okurken/yazarken)
-            // daha spesifik bir YapilandirmaResourceError'a yönlendirebiliriz,
-            // veya genel SahneApiHatasi içinde bırakabiliriz.
-            // Basitlik adına genel SahneApiHatasi içinde tutalım ve loglarken detay verelim.
-             _ => PaketYoneticisiHatasi::SahneApiHatasi(err),
+// Metni önce geçici bir Kaynağa yazar ve taşıma ile yerine koyar (yarıda kalan yazma eski dosyayı bozmaz).
+fn kaynak_metni_yaz(resource_id: &str, metin: &str) -> Result<(), PaketYoneticisiHatasi> {
+    let gecici_id = format!("{}.yeni", resource_id); // alloc
+    let handle = resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
+        .map_err(|e| {
+            eprintln!("Yapılandırma Kaynağı oluşturulamadı ({}): {:?}", gecici_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })?;
+    let veri = metin.as_bytes();
+    let mut written = 0;
+    while written < veri.len() {
+        match resource::write(handle, &veri[written..]) {
+            Ok(0) => {
+                let _ = resource::release(handle);
+                return Err(PaketYoneticisiHatasi::from(SahneError::InvalidOperation));
+            }
+            Ok(n) => written += n,
+            Err(e) => {
+                let _ = resource::release(handle);
+                eprintln!("Yapılandırma Kaynağı yazma hatası ({}): {:?}", gecici_id, e);
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
         }
     }
+    resource::release(handle).map_err(PaketYoneticisiHatasi::from)?;
+
+    kaynagi_tasi(&gecici_id, resource_id).map_err(|e| {
+        eprintln!("Yapılandırma Kaynağı yerine konamadı ({} -> {}): {:?}", gecici_id, resource_id, e);
+        PaketYoneticisiHatasi::from(e)
+    })
 }
 
-// PostcardError'dan PaketYoneticisiHatasi'na dönüşüm
-impl From<PostcardError> for PaketYoneticisiHatasi {
-    fn from(err: PostcardError) -> Self {
-        // Postcard hata türleri genellikle serileştirme veya deserileştirme sırasında
-        // yaşanan sorunları belirtir.
-        match err {
-            // Postcard'ın iç hata tiplerine göre daha detaylı ayırabiliriz.
-            // Şimdilik genel olarak ayırıyoruz.
-            PostcardError::Serialize(_) => PaketYoneticisiHatasi::SerializationError(err),
-            _ => PaketYoneticisiHatasi::DeserializationError(err), // Kalanlar deserialization hatası sayılır
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hata_mesaji(sonuc: Result<(), PaketYoneticisiHatasi>) -> String {
+        match sonuc {
+            Err(PaketYoneticisiHatasi::YapilandirmaHatasi(mesaj)) => mesaj,
+            diger => panic!("YapilandirmaHatasi bekleniyordu: {:?}", diger),
         }
     }
+
+    #[test]
+    fn test_toml_ayristir() {
+        let metin = "# yorum\n[depo]\nurl = \"sahne://ayna/#1\" # satır sonu yorumu\n\n[ozellikler]\nag = false\n[kurulum]\nbetik_zaman_asimi_sn = 1_200\n";
+        let girdiler = toml_ayristir(metin).unwrap();
+        assert_eq!(girdiler, vec![
+            TomlGirdisi { anahtar: "depo.url".to_string(), deger: Deger::Metin("sahne://ayna/#1".to_string()), satir: 3 },
+            TomlGirdisi { anahtar: "ozellikler.ag".to_string(), deger: Deger::Mantiksal(false), satir: 6 },
+            TomlGirdisi { anahtar: "kurulum.betik_zaman_asimi_sn".to_string(), deger: Deger::Tamsayi(1200), satir: 8 },
+        ]);
+    }
+
+    #[test]
+    fn test_hatalar_satir_numarasi_icerir() {
+        let mut katmanli = KatmanliYapilandirma::default();
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[depo]\nurl = sahne://ayna\n"));
+        assert!(mesaj.starts_with("sistem.toml:2: "), "{}", mesaj);
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[depo]\nurl = \"a\"\n\n[kurulum]\ndizn = \"b\"\n"));
+        assert_eq!(mesaj, "sistem.toml:5: bilinmeyen anahtar 'kurulum.dizn'");
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[ozellikler]\nag = \"evet\"\n"));
+        assert!(mesaj.starts_with("sistem.toml:2: 'ozellikler.ag' için mantıksal"), "{}", mesaj);
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[gunluk]\nseviye = \"cok\"\n"));
+        assert!(mesaj.starts_with("sistem.toml:2: 'cok' geçersiz"), "{}", mesaj);
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[ozellikler]\nag_protokolleri = \"https, gzip\"\n"));
+        assert_eq!(mesaj, "sistem.toml:2: 'ozellikler.ag_protokolleri' geçersiz: 'gzip' bir ag özelliği değil");
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "[gunluk]\nmodul_seviyeleri = \"srcnetwork=debug, srcsandbox\"\n"));
+        assert_eq!(mesaj, "sistem.toml:2: 'gunluk.modul_seviyeleri' geçersiz: 'srcsandbox' modül=seviye biçiminde değil");
+
+        let mesaj = hata_mesaji(katmanli.toml_uygula("sistem.toml", "depo.url = \"a\"\n[depo]\nurl = \"b\"\n"));
+        assert_eq!(mesaj, "sistem.toml:3: 'depo.url' anahtarı 1. satırda zaten tanımlı");
+
+        // Hatalı dosyanın hiçbir değeri uygulanmaz.
+        assert_eq!(katmanli.yapilandirma, Yapilandirma::default());
+    }
+
+    #[test]
+    fn test_katman_onceligi_ve_kokenler() {
+        let mut katmanli = KatmanliYapilandirma::default();
+        katmanli.toml_uygula("sistem.toml", "[depo]\nurl = \"sahne://sistem\"\n[ozellikler]\nag = false\n").unwrap();
+        katmanli.toml_uygula("kullanici.toml", "[depo]\nurl = \"sahne://kullanici\"\n").unwrap();
+        katmanli.ortam_uygula(vec![("SAHNE_PKG_OZELLIKLER_AG", "1"), ("PATH", "/bin")]).unwrap();
+
+        assert_eq!(katmanli.yapilandirma.depo_url, "sahne://kullanici");
+        assert!(katmanli.yapilandirma.network);
+        assert_eq!(katmanli.koken("depo.url"), DegerKaynagi::Dosya { kaynak: "kullanici.toml".to_string(), satir: 2 });
+        assert_eq!(katmanli.koken("ozellikler.ag"), DegerKaynagi::Ortam("SAHNE_PKG_OZELLIKLER_AG".to_string()));
+        assert_eq!(katmanli.koken("kurulum.dizin"), DegerKaynagi::Varsayilan);
+
+        let mesaj = hata_mesaji(katmanli.ortam_uygula(vec![("SAHNE_PKG_DEPO_ULR", "x")]));
+        assert_eq!(mesaj, "bilinmeyen ortam değişkeni SAHNE_PKG_DEPO_ULR");
+    }
+
+    #[test]
+    fn test_kok_altinda() {
+        assert_eq!(kok_altinda_yol("sahne://imaj/", "sahne://config/kurulu_paketler.bin"), "sahne://imaj/config/kurulu_paketler.bin");
+        assert_eq!(kok_altinda_yol("/mnt/imaj", "sahne://installed_packages"), "sahne://mnt/imaj/installed_packages");
+        assert_eq!(kok_altinda_yol("/", "sahne://installed_packages"), "sahne://installed_packages");
+
+        let yapilandirma = Yapilandirma::default().kok_altinda("sahne://imaj");
+        assert_eq!(yapilandirma.depo_url, Yapilandirma::default().depo_url);
+        assert_eq!(yapilandirma.kurulum_dizini, "sahne://imaj/installed_packages");
+        assert_eq!(yapilandirma.onbellek_dizini, "sahne://imaj/cache/packages");
+        assert_eq!(yapilandirma.veritabani_kaynagi, format!("sahne://imaj/{}", &VARSAYILAN_VERITABANI_KAYNAGI["sahne://".len()..]));
+        assert_eq!(yapilandirma.kilit_kaynagi, "sahne://imaj/system/pkgmgr.lock");
+        assert_eq!(yapilandirma.gecmis_kaynagi, "sahne://imaj/config/islem_gecmisi.bin");
+        assert_eq!(yapilandirma.anlik_goruntu_dizini, "sahne://imaj/config/anlik_goruntuler");
+        assert_eq!(yapilandirma.yapilandirma_dosyalari_dizini, "sahne://imaj/config/yapilandirma_dosyalari");
+        assert_eq!(yapilandirma.betik_dizini, "sahne://imaj/system/package_scripts");
+        assert_eq!(yapilandirma.gunluk_dosyasi, "sahne://imaj/system/logs/paket_yoneticisi.log");
+        assert_eq!(yapilandirma.syslog_soketi, Yapilandirma::default().syslog_soketi);
+    }
+
+    #[test]
+    fn test_modul_seviyeleri() {
+        assert_eq!(modul_seviyeleri("").unwrap(), Vec::new());
+        assert_eq!(
+            modul_seviyeleri(" srcnetwork = debug, srcsandbox::alt=trace ,").unwrap(),
+            vec![("srcnetwork".to_string(), "debug".to_string()), ("srcsandbox::alt".to_string(), "trace".to_string())]
+        );
+        assert!(modul_seviyeleri("=debug").is_err());
+        assert!(modul_seviyeleri("srcnetwork=cok").unwrap_err().starts_with("'cok' geçersiz"));
+    }
+
+    #[test]
+    fn test_kullanici_kapsami() {
+        let mut yapilandirma = Yapilandirma::default();
+        yapilandirma.ayarla("kullanici.kok", Deger::Metin("sahne://user/ali/.sahne".to_string())).unwrap();
+        let kullanici = yapilandirma.kullanici_kapsami();
+        assert_eq!(kullanici.kurulum_dizini, "sahne://user/ali/.sahne/installed_packages");
+        assert_eq!(kullanici.onbellek_dizini, "sahne://user/ali/.sahne/cache/packages");
+        assert_eq!(kullanici.kilit_kaynagi, "sahne://user/ali/.sahne/system/pkgmgr.lock");
+        assert_ne!(kullanici.veritabani_kaynagi, yapilandirma.veritabani_kaynagi);
+
+        // --root ile: kullanıcı kökü de imajın içinde kalır.
+        let imajda = kullanici.kok_altinda("sahne://imaj");
+        assert_eq!(imajda.kurulum_dizini, "sahne://imaj/user/ali/.sahne/installed_packages");
+    }
+
+    #[test]
+    fn test_toml_metni_geri_okunur() {
+        let mut yapilandirma = Yapilandirma::default();
+        yapilandirma.ayarla("depo.url", Deger::Metin("sahne://\"ayna\"\\depo".to_string())).unwrap();
+        yapilandirma.ayarla("ozellikler.guvenlik", Deger::Mantiksal(false)).unwrap();
+
+        let mut katmanli = KatmanliYapilandirma::default();
+        katmanli.toml_uygula("yeni.toml", &yapilandirma.toml_metni()).unwrap();
+        assert_eq!(katmanli.yapilandirma, yapilandirma);
+    }
+
+    #[test]
+    fn test_guvenlik_politikasi_denetlenir() {
+        let mut yapilandirma = Yapilandirma::default();
+        yapilandirma.ayarla("guvenlik.kurulum_politikasi", Deger::Metin("engelle".to_string())).unwrap();
+        yapilandirma.ayarla("guvenlik.engelleme_onemi", Deger::Metin("critical".to_string())).unwrap();
+        assert_eq!(yapilandirma.kurulum_politikasi, "engelle");
+        assert!(yapilandirma.ayarla("guvenlik.kurulum_politikasi", Deger::Metin("sessiz".to_string())).is_err());
+        assert!(yapilandirma.ayarla("guvenlik.engelleme_onemi", Deger::Metin("acil".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_toml_degerini_ayarla_diger_satirlari_korur() {
+        let metin = "# Sistem yapılandırması\n[depo]\n# ayna\nurl = \"sahne://eski\"\n\n[ozellikler]\nag = true\n";
+        let yeni = toml_degerini_ayarla(metin, "depo.url", &Deger::Metin("sahne://yeni".to_string()));
+        assert_eq!(yeni, "# Sistem yapılandırması\n[depo]\n# ayna\nurl = \"sahne://yeni\"\n\n[ozellikler]\nag = true\n");
+
+        let yeni = toml_degerini_ayarla(&yeni, "depo.yerel_yol", &Deger::Metin("sahne://yerel".to_string()));
+        assert!(yeni.contains("url = \"sahne://yeni\"\nyerel_yol = \"sahne://yerel\"\n\n[ozellikler]"), "{}", yeni);
+
+        let yeni = toml_degerini_ayarla(&yeni, "kurulum.betik_zaman_asimi_sn", &Deger::Tamsayi(60));
+        assert!(yeni.ends_with("ag = true\n\n[kurulum]\nbetik_zaman_asimi_sn = 60\n"), "{}", yeni);
+
+        assert_eq!(toml_degerini_ayarla("", "ozellikler.ag", &Deger::Mantiksal(false)), "[ozellikler]\nag = false\n");
+    }
 }
@@ -13,6 +13,9 @@ use crate::Handle;
 
 // Özel hata enum'ımızı içe aktar (no_std uyumlu ve SahneError'ı içeren haliyle)
 use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+// Dosya çakışması denetimi için paket meta verisi ve kurulu dosyalar veritabanı
+use crate::package::Paket;
+use crate::srcdatabase::KuruluVeritabani;
 // SahneError, ParsingError, ConflictError vb. hatalardan dönüşüm From implementasyonları ile sağlanacak
 
 // Basit bir paket tanımı
@@ -275,12 +278,177 @@ pub fn resolve_conflicts( // pub yapıldı
 }
 
 
+// --- Dosya çakışmaları ---
+// Sürüm çakışmalarından farklı olarak, iki farklı paketin aynı yolu kurmaya çalışmasını denetler.
+// Kurulu paketlerin dosyaları srcdatabase::KuruluVeritabani'ndan, gelen paketlerinkiler Paket.dosyalar'dan alınır.
+
+// İzin verilmemiş bir dosya çakışması.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DosyaCakismasi {
+    pub yol: String,
+    pub gelen: String, // Yolu kurmak isteyen paket
+    pub sahip: String, // Yolun mevcut sahibi (kurulu paket veya aynı işlemdeki başka bir paket)
+}
+
+// Paket meta verisindeki degistirir/cakisir bildirimiyle izin verilen bir dosya devralması.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Devralma {
+    pub yol: String,
+    pub yeni_sahip: String,
+    pub eski_sahip: String,
+}
+
+// Dosya çakışması denetiminin sonucu: uygulanacak devralmalar ve işlemde kaldırılacak paketler.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DosyaPlani {
+    pub devralmalar: Vec<Devralma>,
+    pub kaldirilacaklar: Vec<String>, // Gelen paketlerin degistirir/cakisir listesinde olan kurulu paketler
+}
+
+// 'paket', 'diger' paketin dosyalarını devralmaya yetkili mi?
+fn devralma_izni_var(paket: &Paket, diger: &str) -> bool {
+    paket.degistirir.iter().any(|d| d == diger) || paket.cakisir.iter().any(|c| c == diger)
+}
+
+// Gelen paketlerin dosyalarını kurulu dosyalarla ve birbirleriyle karşılaştırır.
+// - Aynı adlı kurulu paket güncelleniyor sayılır; onun dosyaları çakışma oluşturmaz.
+// - Gelen paketin degistirir veya cakisir listesindeki kurulu paketler kaldırılacaklar listesine girer; dosyaları serbest kalır.
+// - Gelen paket, degistirir veya cakisir listesinde adı geçen paketin dosyalarını devralabilir.
+// - Dizin girdileri ('/' ile biten) paylaşılabilir ve denetlenmez.
+// gelenler: İşlemdeki (kurulacak/güncellenecek) paketler.
+// veritabani: Kurulu paketler veritabanı.
+// Dönüş değeri: İzinli devralmalar ve kaldırılacaklar, veya izin verilmemiş tüm çakışmalar.
+pub fn dosya_cakismalarini_bul(gelenler: &[Paket], veritabani: &KuruluVeritabani) -> Result<DosyaPlani, Vec<DosyaCakismasi>> {
+    let gelen_adlar: BTreeSet<&str> = gelenler.iter().map(|p| p.ad.as_str()).collect();
+
+    let mut plan = DosyaPlani::default();
+    for paket in gelenler {
+        for ad in paket.degistirir.iter().chain(paket.cakisir.iter()) {
+            if veritabani.kurulu_mu(ad) && !gelen_adlar.contains(ad.as_str()) && !plan.kaldirilacaklar.contains(ad) {
+                plan.kaldirilacaklar.push(ad.clone()); // alloc
+            }
+        }
+    }
+
+    // Kurulu dosya sahipleri; güncellenen ve kaldırılacak paketlerin dosyaları hariç.
+    let mut kurulu_sahipler = veritabani.dosya_sahipleri();
+    kurulu_sahipler.retain(|_, sahip| !gelen_adlar.contains(sahip.as_str()) && !plan.kaldirilacaklar.contains(sahip));
+
+    // Bu işlemde şimdiye kadar işlenen paketlerin yolları (yol -> gelenler içindeki indeks).
+    let mut islem_sahipleri: BTreeMap<String, usize> = BTreeMap::new();
+    let mut cakismalar = Vec::new();
+
+    for (indeks, paket) in gelenler.iter().enumerate() {
+        for dosya in paket.dosyalar.iter().filter(|d| !d.ends_with('/')) {
+            let yol = dosya.trim_start_matches('/');
+
+            if let Some(&onceki) = islem_sahipleri.get(yol) {
+                let diger = &gelenler[onceki];
+                if onceki == indeks {
+                    continue; // Aynı paket aynı yolu iki kez listelemiş
+                }
+                if devralma_izni_var(paket, &diger.ad) {
+                    plan.devralmalar.push(Devralma { yol: yol.to_owned(), yeni_sahip: paket.ad.clone(), eski_sahip: diger.ad.clone() });
+                    islem_sahipleri.insert(yol.to_owned(), indeks);
+                } else if !devralma_izni_var(diger, &paket.ad) {
+                    cakismalar.push(DosyaCakismasi { yol: yol.to_owned(), gelen: paket.ad.clone(), sahip: diger.ad.clone() });
+                }
+                // Diğer paket bu paketin dosyalarını devralmaya yetkiliyse yol onda kalır.
+                continue;
+            }
+
+            if let Some(sahip) = kurulu_sahipler.get(yol) {
+                if devralma_izni_var(paket, sahip) {
+                    plan.devralmalar.push(Devralma { yol: yol.to_owned(), yeni_sahip: paket.ad.clone(), eski_sahip: sahip.clone() });
+                } else {
+                    cakismalar.push(DosyaCakismasi { yol: yol.to_owned(), gelen: paket.ad.clone(), sahip: sahip.clone() });
+                    continue;
+                }
+            }
+            islem_sahipleri.insert(yol.to_owned(), indeks); // alloc
+        }
+    }
+
+    if cakismalar.is_empty() {
+        Ok(plan)
+    } else {
+        Err(cakismalar)
+    }
+}
+
+// Çakışma listesini kullanıcıya gösterilecek tek bir metne çevirir.
+pub fn dosya_cakismalarini_bicimlendir(cakismalar: &[DosyaCakismasi]) -> String {
+    let mut metin = format!("{} dosya çakışması:", cakismalar.len()); // alloc
+    for c in cakismalar {
+        metin.push_str(&format!("\n  {} ('{}' kurmak istiyor, sahibi '{}')", c.yol, c.gelen, c.sahip));
+    }
+    metin
+}
+
+
 // #[cfg(test)] bloğu std'ye bağımlı olduğu için kaldırıldı veya devre dışı bırakıldı.
 
 #[cfg(test)]
 mod tests {
     // Bu testler std::fs, tempfile vb. kullandığı için Sahne64'ün no_std ortamında çalışmaz.
     // Bunlar yerine no_std uyumlu in-memory testler veya entegrasyon testleri yazılmalıdır.
+    use super::*;
+    use crate::srcdatabase::KuruluPaket;
+
+    fn paket(ad: &str, dosyalar: &[&str]) -> Paket {
+        let mut p = Paket::yeni(ad.to_string(), "1.0".to_string(), Vec::new());
+        p.dosyalar = dosyalar.iter().map(|d| d.to_string()).collect();
+        p
+    }
+
+    fn veritabani(kayitlar: &[(&str, &[&str])]) -> KuruluVeritabani {
+        let mut vt = KuruluVeritabani::default();
+        for (ad, dosyalar) in kayitlar {
+            vt.ekle(KuruluPaket::paketten(&paket(ad, dosyalar)));
+        }
+        vt
+    }
+
+    #[test]
+    fn test_kurulu_dosya_cakismasi() {
+        let vt = veritabani(&[("busybox", &["bin/ls", "bin/sh"])]);
+        let cakismalar = dosya_cakismalarini_bul(&[paket("coreutils", &["bin/ls", "bin/cp"])], &vt).unwrap_err();
+        assert_eq!(cakismalar, vec![DosyaCakismasi { yol: "bin/ls".to_string(), gelen: "coreutils".to_string(), sahip: "busybox".to_string() }]);
+    }
+
+    #[test]
+    fn test_guncelleme_ve_dizinler_cakismaz() {
+        let vt = veritabani(&[("coreutils", &["bin/ls"]), ("bash", &["bin/"])]);
+        assert!(dosya_cakismalarini_bul(&[paket("coreutils", &["bin/", "bin/ls"])], &vt).is_ok());
+    }
+
+    #[test]
+    fn test_degistirir_kaldirir() {
+        let vt = veritabani(&[("busybox", &["bin/ls"])]);
+        let mut gelen = paket("coreutils", &["bin/ls"]);
+        gelen.degistirir.push("busybox".to_string());
+        let plan = dosya_cakismalarini_bul(&[gelen], &vt).unwrap();
+        assert_eq!(plan.kaldirilacaklar, vec!["busybox".to_string()]);
+        assert!(plan.devralmalar.is_empty());
+    }
+
+    #[test]
+    fn test_cakisir_kaldirir() {
+        let vt = veritabani(&[("postfix", &["sbin/sendmail"])]);
+        let mut gelen = paket("exim", &["sbin/sendmail"]);
+        gelen.cakisir.push("postfix".to_string());
+        let plan = dosya_cakismalarini_bul(&[gelen], &vt).unwrap();
+        assert_eq!(plan.kaldirilacaklar, vec!["postfix".to_string()]);
+        assert!(plan.devralmalar.is_empty()); // Kaldırılan paketin dosyaları serbest kalır
+    }
+
+    #[test]
+    fn test_islem_ici_cakisma() {
+        let vt = KuruluVeritabani::default();
+        let cakismalar = dosya_cakismalarini_bul(&[paket("a", &["lib/x.so"]), paket("b", &["lib/x.so"])], &vt).unwrap_err();
+        assert_eq!(cakismalar[0].gelen, "b");
+        assert_eq!(cakismalar[0].sahip, "a");
+    }
 }
 
 // --- PaketYoneticisiHatasi enum tanımının no_std uyumlu hale getirilmesi ---
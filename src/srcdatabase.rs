@@ -0,0 +1,458 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+extern crate alloc; // String, Vec, BTreeMap için
+
+use alloc::collections::{BTreeMap, BTreeSet}; // Sıralı ve deterministik serileştirme için
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use alloc::borrow::ToOwned; // &str -> String için
+
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu serileştirme/deserileştirme
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::package::Paket;
+use crate::srcstaging::kaynagi_tasi;
+use crate::srcscript::BetikAsamasi;
+use crate::srctrigger::Tetikleyici;
+use crate::srcverify::ManifestGirdisi;
+
+// no_std uyumlu print makroları
+use crate::print_macros::eprintln;
+
+// Kurulu paketler veritabanının varsayılan Kaynak ID'si.
+pub const VARSAYILAN_VERITABANI_KAYNAGI: &str = "sahne://config/kurulu_paketler.bin";
+
+// Bir paketin neden kurulu olduğu.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KurulumNedeni {
+    // Kullanıcı paketi doğrudan istedi. Eski (nedensiz) kayıtlar da bu kabul edilir; yetim sayılmazlar.
+    #[default]
+    Acik,
+    // Paket başka bir paketin bağımlılığı (veya özelliği/önerisi) olarak otomatik kuruldu.
+    Bagimlilik,
+}
+
+// Veritabanındaki tek bir kurulu paket kaydı.
+// Alanlar kurulum anındaki paket meta verisinden kopyalanır; depo indeksi değişse bile kurulu durum korunur.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KuruluPaket {
+    pub ad: String,
+    pub surum: String,
+    pub bagimliliklar: Vec<String>, // Kurulum anındaki bağımlılık adları
+    pub dosyalar: Vec<String>, // Bu pakete ait dosyalar (kurulum dizinine göre yollar)
+    #[serde(default)]
+    pub saglar: Vec<String>, // Sağladığı sanal adlar (bağımlılık karşılama için)
+    #[serde(default)]
+    pub cakisir: Vec<String>, // Kurulu kaldığı sürece kurulamayacak paketler/sanal adlar
+    #[serde(default)]
+    pub etkin_ozellikler: Vec<String>, // Kurulumda seçilen paket özellikleri; güncellemelerde yeniden etkinleşir
+    #[serde(default)]
+    pub ek_bagimliliklar: Vec<String>, // Özellikler ve kurulan öneriler nedeniyle çekilen paketler (yetim hesabında bağımlılık sayılır)
+    #[serde(default)]
+    pub kurulum_nedeni: KurulumNedeni,
+    #[serde(default)]
+    pub betikler: BTreeMap<BetikAsamasi, String>, // Kaldırma/güncelleme betikleri kurulu sürümünkilerden çalıştırılır
+    #[serde(default)]
+    pub tetikleyiciler: Vec<Tetikleyici>, // Sadece kurulu paketlerin tetikleyicileri çalışır
+    #[serde(default)]
+    pub yayinci: Option<String>, // Kaldırma/tetikleyici betiklerinin sandbox profili için güven seviyesi buna göre belirlenir
+    #[serde(default)]
+    pub arsiv: Option<String>, // Önbellekteki arşivin dosya adı; işlem geri alınırken bu sürüm buradan yeniden kurulur
+    #[serde(default)]
+    pub manifesto: Vec<ManifestGirdisi>, // Kurulan girdilerin tür, boyut, özet ve izinleri (bkz. srcverify); eski kayıtlarda boş
+    #[serde(default)]
+    pub yapilandirma_dosyalari: Vec<String>, // Güncelleme/kaldırmada kullanıcı değişiklikleri korunan dosyalar (bkz. srcconffiles)
+}
+
+impl KuruluPaket {
+    // Paket meta verisinden bir kayıt oluşturur.
+    pub fn paketten(paket: &Paket) -> Self {
+        KuruluPaket {
+            ad: paket.ad.clone(),
+            surum: paket.surum.clone(),
+            bagimliliklar: paket.bagimliliklar.clone(),
+            dosyalar: paket.dosyalar.clone(),
+            saglar: paket.saglar.clone(),
+            cakisir: paket.cakisir.clone(),
+            etkin_ozellikler: Vec::new(),
+            ek_bagimliliklar: Vec::new(),
+            kurulum_nedeni: KurulumNedeni::Acik,
+            betikler: paket.betikler.clone(),
+            tetikleyiciler: paket.tetikleyiciler.clone(),
+            yayinci: paket.yazar.clone(),
+            arsiv: paket.dosya_adi.clone(),
+            manifesto: Vec::new(),
+            yapilandirma_dosyalari: paket.yapilandirma_dosyalari.clone(),
+        }
+    }
+
+    // Kaydı önbellekteki arşivinden yeniden kurmak için gereken paket meta verisi (bkz. srchistory).
+    // Arşiv adı bilinmeyen (bu alandan önce kurulmuş) kayıtlar için None döner.
+    pub fn pakete(&self) -> Option<Paket> {
+        let mut paket = Paket::yeni(self.ad.clone(), self.surum.clone(), self.bagimliliklar.clone());
+        paket.dosya_adi = Some(self.arsiv.clone()?);
+        paket.dosyalar = self.dosyalar.clone();
+        paket.saglar = self.saglar.clone();
+        paket.cakisir = self.cakisir.clone();
+        paket.betikler = self.betikler.clone();
+        paket.tetikleyiciler = self.tetikleyiciler.clone();
+        paket.yazar = self.yayinci.clone();
+        paket.yapilandirma_dosyalari = self.yapilandirma_dosyalari.clone();
+        Some(paket)
+    }
+}
+
+// Kurulu paketler veritabanı (ad -> kayıt).
+// postcard ile serileştirilip tek bir Kaynakta saklanır.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct KuruluVeritabani {
+    pub paketler: BTreeMap<String, KuruluPaket>,
+}
+
+impl KuruluVeritabani {
+    // Veritabanını Kaynaktan okur. Kaynak yoksa (henüz hiç paket kurulmamış) boş veritabanı döner.
+    pub fn oku(resource_id: &str) -> Result<Self, PaketYoneticisiHatasi> {
+        let handle = match resource::acquire(resource_id, resource::MODE_READ) {
+            Ok(h) => h,
+            Err(SahneError::ResourceNotFound) => return Ok(KuruluVeritabani::default()),
+            Err(e) => {
+                eprintln!("Veritabanı Kaynağı acquire hatası ({}): {:?}", resource_id, e);
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        };
+
+        let mut buffer = Vec::new(); // alloc
+        let mut temp_buffer = [0u8; 512]; // Okuma tamponu (stack'te)
+        loop {
+            match resource::read(handle, &mut temp_buffer) {
+                Ok(0) => break,
+                Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+                Err(e) => {
+                    let _ = resource::release(handle);
+                    eprintln!("Veritabanı Kaynağı okuma hatası ({}): {:?}", resource_id, e);
+                    return Err(PaketYoneticisiHatasi::from(e));
+                }
+            }
+        }
+        let _ = resource::release(handle);
+
+        if buffer.is_empty() {
+            return Ok(KuruluVeritabani::default());
+        }
+        postcard::from_bytes_copy::<KuruluVeritabani>(&buffer).map_err(|e| {
+            eprintln!("Veritabanı deserialize hatası ({}): {:?}", resource_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })
+    }
+
+    // Veritabanını Kaynağa yazar. Önce geçici bir Kaynağa yazılır ve taşıma ile yerine konur,
+    // böylece yazma yarıda kesilirse eski veritabanı bozulmaz.
+    pub fn yaz(&self, resource_id: &str) -> Result<(), PaketYoneticisiHatasi> {
+        let veri = postcard::to_postcard(self).map_err(PaketYoneticisiHatasi::from)?;
+        let gecici_id = format!("{}.yeni", resource_id); // alloc
+
+        let handle = resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
+            .map_err(PaketYoneticisiHatasi::from)?;
+        let mut written = 0;
+        while written < veri.len() {
+            match resource::write(handle, &veri[written..]) {
+                Ok(0) => {
+                    let _ = resource::release(handle);
+                    return Err(PaketYoneticisiHatasi::from(SahneError::InvalidOperation));
+                }
+                Ok(n) => written += n,
+                Err(e) => {
+                    let _ = resource::release(handle);
+                    eprintln!("Veritabanı yazma hatası ({}): {:?}", gecici_id, e);
+                    return Err(PaketYoneticisiHatasi::from(e));
+                }
+            }
+        }
+        resource::release(handle).map_err(PaketYoneticisiHatasi::from)?;
+
+        kaynagi_tasi(&gecici_id, resource_id).map_err(|e| {
+            eprintln!("Veritabanı yerine konamadı ({} -> {}): {:?}", gecici_id, resource_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })
+    }
+
+    pub fn kurulu_mu(&self, ad: &str) -> bool {
+        self.paketler.contains_key(ad)
+    }
+
+    pub fn paket(&self, ad: &str) -> Option<&KuruluPaket> {
+        self.paketler.get(ad)
+    }
+
+    // Verilen yolun sahibi olan kurulu paketin adını döndürür.
+    pub fn dosya_sahibi(&self, yol: &str) -> Option<&str> {
+        let yol = yol.trim_start_matches('/');
+        self.paketler
+            .values()
+            .find(|k| k.dosyalar.iter().any(|d| d.trim_start_matches('/') == yol))
+            .map(|k| k.ad.as_str())
+    }
+
+    // Yol -> sahip paket eşlemesini oluşturur (çok sayıda yol sorgulanacaksa dosya_sahibi'nden hızlıdır).
+    pub fn dosya_sahipleri(&self) -> BTreeMap<String, String> {
+        let mut sahipler = BTreeMap::new();
+        for kayit in self.paketler.values() {
+            for dosya in &kayit.dosyalar {
+                sahipler.insert(dosya.trim_start_matches('/').to_owned(), kayit.ad.clone()); // alloc
+            }
+        }
+        sahipler
+    }
+
+    // Bir kaydı ekler veya günceller.
+    pub fn ekle(&mut self, kayit: KuruluPaket) {
+        self.paketler.insert(kayit.ad.clone(), kayit);
+    }
+
+    // İki veritabanının birleşimini döndürür; aynı adlı paketlerde 'ust' kaydı geçerli olur.
+    // Kullanıcı kapsamında bağımlılıklar sistem ve kullanıcı veritabanlarına birlikte çözülür.
+    pub fn birlestir(&self, ust: &KuruluVeritabani) -> KuruluVeritabani {
+        let mut birlesik = self.clone();
+        for kayit in ust.paketler.values() {
+            birlesik.ekle(kayit.clone());
+        }
+        birlesik
+    }
+
+    // Bir kaydı kaldırır ve döndürür.
+    pub fn kaldir(&mut self, ad: &str) -> Option<KuruluPaket> {
+        self.paketler.remove(ad)
+    }
+
+    // Bir bağımlılık adını (gerçek ad veya sanal ad) karşılayan kurulu paketler.
+    pub fn karsilayanlar<'a>(&'a self, ad: &'a str) -> impl Iterator<Item = &'a KuruluPaket> + 'a {
+        self.paketler.values().filter(move |k| k.ad == ad || k.saglar.iter().any(|s| s == ad))
+    }
+
+    // Hiçbir kurulu paketin karşılamadığı bağımlılıklar, (paket, bağımlılık) çiftleri olarak.
+    // Bir durumu geri yüklemeden önce hedef durumun tutarlılığını denetlemek için kullanılır.
+    pub fn karsilanmayan_bagimliliklar(&self) -> Vec<(String, String)> {
+        self.paketler
+            .values()
+            .flat_map(|k| k.bagimliliklar.iter().chain(k.ek_bagimliliklar.iter()).map(move |b| (k, b)))
+            .filter(|(_, b)| self.karsilayanlar(b).next().is_none())
+            .map(|(k, b)| (k.ad.clone(), b.clone()))
+            .collect()
+    }
+
+    // Bir kurulu paketin doğrudan bağımlı olduğu kurulu paketlerin adları (bağımlılıklar + ek bağımlılıklar).
+    pub fn dogrudan_bagimliliklar(&self, ad: &str) -> BTreeSet<String> {
+        let mut sonuc = BTreeSet::new();
+        if let Some(kayit) = self.paketler.get(ad) {
+            for bagimlilik in kayit.bagimliliklar.iter().chain(kayit.ek_bagimliliklar.iter()) {
+                for karsilayan in self.karsilayanlar(bagimlilik) {
+                    if karsilayan.ad != ad {
+                        sonuc.insert(karsilayan.ad.clone());
+                    }
+                }
+            }
+        }
+        sonuc
+    }
+
+    // Verilen paketlerden bağımlılıklar üzerinden ulaşılabilen tüm kurulu paketler (başlangıçlar dahil).
+    pub fn ulasilabilenler<'a>(&self, baslangiclar: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+        let mut ziyaret: BTreeSet<String> = BTreeSet::new();
+        let mut yigin: Vec<String> = baslangiclar.into_iter().filter(|a| self.kurulu_mu(a)).map(|a| a.to_owned()).collect();
+        while let Some(ad) = yigin.pop() {
+            if ziyaret.insert(ad.clone()) {
+                yigin.extend(self.dogrudan_bagimliliklar(&ad).into_iter().filter(|b| !ziyaret.contains(b)));
+            }
+        }
+        ziyaret
+    }
+
+    // Otomatik kurulmuş olup artık hiçbir açık kurulmuş paket tarafından (dolaylı olarak da) gerekmeyen paketler.
+    pub fn yetimler(&self) -> Vec<String> {
+        let aciklar = self.paketler.values().filter(|k| k.kurulum_nedeni == KurulumNedeni::Acik).map(|k| k.ad.as_str());
+        let gerekli = self.ulasilabilenler(aciklar);
+        self.paketler.keys().filter(|ad| !gerekli.contains(*ad)).cloned().collect()
+    }
+
+    // Bir paketi ve onunla birlikte gereksiz kalacak bağımlılıklarını kaldırma sırasıyla döndürür
+    // (bağımlı olan paket, bağımlılığından önce). Başka yetimlere dokunulmaz.
+    pub fn bagimliliklariyla_kaldirma_kumesi(&self, ad: &str) -> Vec<String> {
+        if !self.kurulu_mu(ad) {
+            return Vec::new();
+        }
+        let etkilenenler = self.ulasilabilenler(core::iter::once(ad));
+        let mut sonraki = self.clone();
+        sonraki.kaldir(ad);
+        let mut kume: BTreeSet<String> = sonraki.yetimler().into_iter().filter(|y| etkilenenler.contains(y)).collect();
+        kume.insert(ad.to_owned());
+        self.kaldirma_sirasi(&kume)
+    }
+
+    // Verilen paketleri, birbirlerine bağımlı olanlar bağımlılıklarından önce gelecek şekilde sıralar.
+    pub fn kaldirma_sirasi(&self, adlar: &BTreeSet<String>) -> Vec<String> {
+        let mut sira: Vec<String> = Vec::new();
+        let mut ziyaret: BTreeSet<String> = BTreeSet::new();
+        for baslangic in adlar {
+            // Derinlemesine ilk arama, post-order: (ad, bağımlılıkları eklendi mi)
+            let mut yigin: Vec<(String, bool)> = Vec::new();
+            yigin.push((baslangic.clone(), false));
+            while let Some((ad, genisletildi)) = yigin.pop() {
+                if genisletildi {
+                    sira.push(ad);
+                    continue;
+                }
+                if !ziyaret.insert(ad.clone()) {
+                    continue;
+                }
+                yigin.push((ad.clone(), true));
+                for bagimlilik in self.dogrudan_bagimliliklar(&ad) {
+                    if adlar.contains(&bagimlilik) && !ziyaret.contains(&bagimlilik) {
+                        yigin.push((bagimlilik, false));
+                    }
+                }
+            }
+        }
+        sira.reverse(); // Post-order bağımlılıkları önce verir; kaldırmada bağımlı olanlar önce gelir.
+        sira
+    }
+
+    // Dosyanın sahipliğini eski sahibinden alır (yeni sahip eklenirken kendi listesinde taşır).
+    pub fn sahipligi_devret(&mut self, yol: &str, eski_sahip: &str) {
+        if let Some(kayit) = self.paketler.get_mut(eski_sahip) {
+            let yol = yol.trim_start_matches('/');
+            kayit.dosyalar.retain(|d| d.trim_start_matches('/') != yol);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kayit(ad: &str, dosyalar: &[&str]) -> KuruluPaket {
+        KuruluPaket {
+            ad: ad.to_string(),
+            surum: "1.0".to_string(),
+            bagimliliklar: Vec::new(),
+            dosyalar: dosyalar.iter().map(|d| d.to_string()).collect(),
+            saglar: Vec::new(),
+            cakisir: Vec::new(),
+            etkin_ozellikler: Vec::new(),
+            ek_bagimliliklar: Vec::new(),
+            kurulum_nedeni: KurulumNedeni::Acik,
+            betikler: BTreeMap::new(),
+            tetikleyiciler: Vec::new(),
+            yayinci: None,
+            arsiv: None,
+            manifesto: Vec::new(),
+            yapilandirma_dosyalari: Vec::new(),
+        }
+    }
+
+    fn otomatik(ad: &str, bagimliliklar: &[&str]) -> KuruluPaket {
+        let mut k = kayit(ad, &[]);
+        k.bagimliliklar = bagimliliklar.iter().map(|b| b.to_string()).collect();
+        k.kurulum_nedeni = KurulumNedeni::Bagimlilik;
+        k
+    }
+
+    fn acik(ad: &str, bagimliliklar: &[&str]) -> KuruluPaket {
+        let mut k = otomatik(ad, bagimliliklar);
+        k.kurulum_nedeni = KurulumNedeni::Acik;
+        k
+    }
+
+    #[test]
+    fn test_dosya_sahibi() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(kayit("coreutils", &["bin/ls", "/bin/cp"]));
+        assert_eq!(vt.dosya_sahibi("/bin/ls"), Some("coreutils"));
+        assert_eq!(vt.dosya_sahibi("bin/cp"), Some("coreutils"));
+        assert_eq!(vt.dosya_sahibi("bin/mv"), None);
+    }
+
+    #[test]
+    fn test_sahipligi_devret() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(kayit("eski", &["bin/arac", "share/arac"]));
+        vt.sahipligi_devret("/bin/arac", "eski");
+        assert_eq!(vt.dosya_sahibi("bin/arac"), None);
+        assert_eq!(vt.paket("eski").unwrap().dosyalar.len(), 1);
+    }
+
+    #[test]
+    fn test_yetimler() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(acik("editor", &["libyazi"]));
+        vt.ekle(otomatik("libyazi", &["libc"]));
+        vt.ekle(otomatik("libc", &[]));
+        vt.ekle(otomatik("eski-lib", &[]));
+        assert_eq!(vt.yetimler(), vec!["eski-lib".to_string()]);
+
+        vt.kaldir("editor");
+        assert_eq!(vt.yetimler().len(), 3);
+    }
+
+    #[test]
+    fn test_sanal_ve_ek_bagimliliklar_yetim_sayilmaz() {
+        let mut vt = KuruluVeritabani::default();
+        let mut mutt = acik("mutt", &["mail-transport-agent"]);
+        mutt.ek_bagimliliklar.push("gpg".to_string());
+        vt.ekle(mutt);
+        let mut postfix = otomatik("postfix", &[]);
+        postfix.saglar.push("mail-transport-agent".to_string());
+        vt.ekle(postfix);
+        vt.ekle(otomatik("gpg", &[]));
+        assert!(vt.yetimler().is_empty());
+    }
+
+    #[test]
+    fn test_bagimliliklariyla_kaldirma_kumesi() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(acik("editor", &["libyazi", "libc"]));
+        vt.ekle(acik("kabuk", &["libc"]));
+        vt.ekle(otomatik("libyazi", &["libc"]));
+        vt.ekle(otomatik("libc", &[]));
+        vt.ekle(otomatik("alakasiz-yetim", &[]));
+        // libc hâlâ kabuk tarafından gerekiyor; alakasiz-yetim editor'la ilgisiz.
+        assert_eq!(vt.bagimliliklariyla_kaldirma_kumesi("editor"), vec!["editor".to_string(), "libyazi".to_string()]);
+    }
+
+    #[test]
+    fn test_karsilanmayan_bagimliliklar() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(acik("editor", &["libyazi", "yazi-motoru"]));
+        let mut libyazi = otomatik("libyazi", &[]);
+        libyazi.saglar.push("yazi-motoru".to_string());
+        vt.ekle(libyazi);
+        assert!(vt.karsilanmayan_bagimliliklar().is_empty());
+
+        vt.kaldir("libyazi");
+        assert_eq!(vt.karsilanmayan_bagimliliklar(), vec![
+            ("editor".to_string(), "libyazi".to_string()),
+            ("editor".to_string(), "yazi-motoru".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_birlestir() {
+        let mut sistem = KuruluVeritabani::default();
+        sistem.ekle(acik("libc", &[]));
+        sistem.ekle(acik("git", &["libc"]));
+        let mut kullanici = KuruluVeritabani::default();
+        kullanici.ekle(otomatik("git", &["libc", "libssh"]));
+        kullanici.ekle(acik("editor", &["libc"]));
+
+        let birlesik = sistem.birlestir(&kullanici);
+        assert_eq!(birlesik.paketler.len(), 3);
+        assert_eq!(birlesik.paket("git").unwrap().bagimliliklar.len(), 2);
+        assert!(birlesik.kurulu_mu("libc"));
+    }
+}
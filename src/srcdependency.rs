@@ -1,13 +1,22 @@
 #![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
-extern crate alloc; // HashMap, HashSet, String, Vec için
+extern crate alloc; // HashMap, String, Vec için
 
-use alloc::collections::{HashMap, HashSet};
+use alloc::collections::HashMap; // Sağlayıcı tercihleri için
+use alloc::collections::{BTreeMap, BTreeSet}; // Deterministik sağlayıcı seçimi için sıralı koleksiyonlar
+use alloc::collections::VecDeque; // "Neden kurulu" sorgusunda genişlik öncelikli arama için
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::borrow::ToOwned; // to_string() yerine to_owned() daha genel
 
 // 'Paket' struct tanımını içeren modül
 use crate::package::Paket;
+// Kurulu paketler (sanal ad karşılama, değiştirme ve çakışma denetimi için)
+use crate::srcdatabase::{KuruluVeritabani, KurulumNedeni};
+// Topolojik sıralama ve döngü tespiti (güçlü bağlı bileşenler)
+use crate::srcgraph::{BagimlilikGrafi, Dugum, DugumTuru, Kenar, KenarTuru};
+// no_std uyumlu print makroları
+use crate::print_macros::{println, eprintln};
 
 // Özel hata enum'ımızı içe aktar (no_std uyumlu hali)
 use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
@@ -28,122 +37,638 @@ impl BagimlilikYoneticisi {
         BagimlilikYoneticisi {}
     }
 
-    // Bağımlılıkları Derinlemesine İlk Arama (DFS) ile Çözme Fonksiyonu.
-    // Paketin transitive bağımlılıklarını bulur ve kurulum için sıralar.
+    // Bir paketin geçişli bağımlılıklarını bulur ve kurulum için sıralar (bağımlılıklar önce).
+    // Kurulu paketleri ve kullanıcı seçeneklerini dikkate almayan kısa yoldur; sıralama ve döngü
+    // davranışı cozum_plani_olustur ile aynıdır (döngüdeki paketler art arda gelir).
     // paketler: Tüm bilinen paketlerin listesi.
     // baslangic_paketi: Çözümlenmeye başlanacak ana paketin adı.
     // Dönüş değeri: Kurulum sırasına göre paket adlarının listesi.
     pub fn bagimliliklari_coz(paketler: &Vec<Paket>, baslangic_paketi: &str) -> Result<Vec<String>, PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+        let plan = Self::cozum_plani_olustur(paketler, &[baslangic_paketi], &KuruluVeritabani::default(), &KurulumSecenekleri::default())?;
+        Ok(plan.kurulacaklar)
+    }
+}
+
+// --- Sanal paketler, çakışmalar ve değiştirmeler ile çözümleme ---
+
+// Kullanıcının kurulum sırasında yaptığı seçimler (komut satırı bayrakları veya yapılandırma).
+#[derive(Debug, Default, Clone)]
+pub struct KurulumSecenekleri {
+    pub saglayici_tercihleri: HashMap<String, String>, // Sanal ad -> tercih edilen sağlayıcı
+    pub onerilenlerle: bool, // Paket.onerilenler de kurulsun mu?
+    pub tavsiyelerle: bool, // Paket.tavsiyeler de kurulsun mu?
+    pub varsayilan_ozelliksiz: bool, // İstenen paketlerin varsayılan özellikleri kapalı mı?
+    // Etkinleştirilecek özellikler: "ozellik" (istenen paketlerde) veya "paket/ozellik".
+    pub ozellikler: Vec<String>,
+}
 
-        // Bağımlılık çözümleme mantığı temel olarak bellek içi veri yapıları (HashMap, HashSet, Vec, String) üzerinde çalışır.
-        // Bu nedenle, `Sahne64` özgü dosya sistemi (`resource`), görev yönetimi (`task`), IPC (`messaging`) veya çekirdek (`kernel`) fonksiyonlarının
-        // bu özel mantıkta doğrudan bir karşılığı bulunmamaktadır.
-        // Bu fonksiyonun ana gereksinimi `alloc` crate'i tarafından sağlanan heap ayırma yeteneğidir.
+// Bağımlılık çözümlemesinin sonucu.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CozumPlani {
+    pub kurulacaklar: Vec<String>, // Kurulacak/güncellenecek paketler (bağımlılıklar önce)
+    pub kaldirilacaklar: Vec<String>, // Kurulacakların degistirir/cakisir bildirimleri nedeniyle kaldırılacak kurulu paketler
+    pub kaldiranlar: BTreeMap<String, String>, // Kaldırılacak paket -> kaldırılmasını gerektiren (ilk) kurulacak paket
+    pub secilen_saglayicilar: BTreeMap<String, String>, // Sanal ad -> seçilen sağlayıcı paket
+    pub etkin_ozellikler: BTreeMap<String, Vec<String>>, // Paket -> etkin özellikler (veritabanına kaydedilir)
+    pub kurulmayan_oneriler: Vec<String>, // Kurulmayan (istenmeyen veya bulunamayan) öneri/tavsiyeler, bildirim için
+    pub ek_bagimliliklar: BTreeMap<String, Vec<String>>, // Paket -> özelliklerinin ve karşılanan önerilerinin çektiği adlar
+    // Kurulacak paketlerin kurulum nedenleri: istenenler açık; daha önce kurulu olanlar eski nedenini korur;
+    // geri kalanlar bağımlılık olarak kurulur.
+    pub kurulum_nedenleri: BTreeMap<String, KurulumNedeni>,
+    // Kurulum grupları, topolojik sırayla: her grup bir güçlü bağlı bileşendir. Tek paketli gruplar normal
+    // kurulumdur; çok paketli gruplar birbirine döngüsel bağımlı paketlerdir ve birlikte kurulup betikleri
+    // grubun tamamı kurulduktan sonra çalıştırılır. kurulacaklar bu grupların düzleştirilmiş halidir.
+    pub kurulum_gruplari: Vec<Vec<String>>,
+    // Tespit edilen döngüler, her biri kapanan bir yol olarak (örn. ["a", "b", "a"]); tanı mesajı için.
+    pub donguler: Vec<Vec<String>>,
+}
 
-        // Paketleri ada göre hızlı erişim için bir HashMap'e dönüştür
-        // HashMap<Paket Adı: String, Paket Referansı: &Paket>
-        let paket_haritasi: HashMap<String, &Paket> = paketler
-            .iter()
-            // Paketin adını String olarak klonla ve paket referansı ile eşle.
-            // paket.ad.clone() String klonlama (alloc gerektirir).
-            .map(|paket| (paket.ad.clone(), paket))
-            .collect(); // HashMap oluşturma (alloc gerektirir).
+// Plan içi bağımlılık grafı: düğümler kurulacak paketler, kenarlar bağımlılıkların bu plandaki karşılayıcılarına.
+fn plan_grafi(plan: &CozumPlani, paket_haritasi: &BTreeMap<&str, &Paket>) -> BagimlilikGrafi {
+    let mut graf = BagimlilikGrafi::default();
+    for ad in &plan.kurulacaklar {
+        graf.dugumler.insert(ad.clone(), Dugum { ad: ad.clone(), surum: Some(paket_haritasi[ad.as_str()].surum.clone()), tur: DugumTuru::Paket, derinlik: 0 });
+    }
+    for ad in &plan.kurulacaklar {
+        let paket = paket_haritasi[ad.as_str()];
+        let ek = plan.ek_bagimliliklar.get(ad).map(|e| &e[..]).unwrap_or(&[]);
+        for bagimlilik in paket.bagimliliklar.iter().chain(ek.iter()) {
+            // Bu plandaki karşılayıcı: aynı adlı paket, seçilen sağlayıcı veya adı sağlayan başka bir kurulacak paket.
+            let hedef = if graf.dugumler.contains_key(bagimlilik) {
+                Some(bagimlilik.clone())
+            } else if let Some(saglayici) = plan.secilen_saglayicilar.get(bagimlilik) {
+                Some(saglayici.clone())
+            } else {
+                plan.kurulacaklar.iter().find(|k| paket_haritasi[k.as_str()].saglar.contains(bagimlilik)).cloned()
+            };
+            // Kurulu paketlerce karşılanan bağımlılıklar plan içi sıralamayı etkilemez.
+            if let Some(hedef) = hedef {
+                graf.kenarlar.insert(Kenar { kaynak: ad.clone(), hedef, tur: KenarTuru::Bagimlilik });
+            }
+        }
+    }
+    graf
+}
 
-        // Çözülen bağımlılıkların listesi (kurulum sırasına göre, sondan başa doğru eklenecek).
-        let mut cozulen_bagimliliklar = Vec::new(); // alloc::vec::Vec (alloc gerektirir).
+// Döngüleri okunur bir tanı metnine çevirir (her döngü bir satır).
+pub fn donguleri_bicimlendir(donguler: &[Vec<String>]) -> String {
+    let satirlar: Vec<String> = donguler.iter().map(|d| format!("Bağımlılık döngüsü: {}", d.join(" -> "))).collect();
+    satirlar.join("\n")
+}
 
-        // Ziyaret edilen paketleri takip etmek için küme (döngüleri önlemek).
-        // Paket adlarını saklıyoruz: HashSet<Paket Adı: String>.
-        let mut ziyaret_edilenler = HashSet::new(); // alloc::collections::HashSet (alloc gerektirir).
+// Bir paket için etkinleştirilecek özellikleri belirler:
+// varsayılanlar (istenen pakette kapatılmadıysa) + önceki kurulumda seçilmiş olanlar + komut satırı seçimleri.
+// Kullanılan düz (paket adı belirtilmemiş) özellik adları 'kullanilanlar'a eklenir.
+fn ozellik_secimi(
+    paket: &Paket,
+    istenen_mi: bool,
+    kurulu: &KuruluVeritabani,
+    secenekler: &KurulumSecenekleri,
+    kullanilanlar: &mut BTreeSet<String>,
+) -> Vec<String> {
+    let mut secim: Vec<String> = Vec::new();
+    if !(istenen_mi && secenekler.varsayilan_ozelliksiz) {
+        secim.extend(paket.varsayilan_ozellikler.iter().cloned());
+    }
+    if let Some(kayit) = kurulu.paket(&paket.ad) {
+        // Yeni sürümde kaldırılmış özellikler sessizce düşürülür.
+        secim.extend(kayit.etkin_ozellikler.iter().filter(|o| paket.ozellikler.contains_key(*o)).cloned());
+    }
+    for ozellik in &secenekler.ozellikler {
+        match ozellik.split_once('/') {
+            Some((paket_adi, ad)) if paket_adi == paket.ad => secim.push(ad.to_owned()),
+            None if istenen_mi && paket.ozellikler.contains_key(ozellik) => {
+                kullanilanlar.insert(ozellik.clone());
+                secim.push(ozellik.clone());
+            }
+            _ => {}
+        }
+    }
+    secim
+}
+
+// Bir adın (gerçek paket adı veya sanal yetenek), kaldırılmayacak kurulu paketlerce karşılanıp karşılanmadığı.
+fn kurulu_karsilar(ad: &str, kurulu: &KuruluVeritabani, haric: &BTreeSet<String>) -> bool {
+    kurulu.paketler.values().any(|k| !haric.contains(&k.ad) && (k.ad == ad || k.saglar.iter().any(|s| s == ad)))
+}
+
+// Bir bağımlılık adını karşılayacak paketi seçer.
+// Öncelik: aynı adlı gerçek paket > kullanıcı tercihi > kurulu sağlayıcı > bu planda zaten seçilmiş sağlayıcı
+// > tek sağlayıcı > ada göre ilk sağlayıcı (diğer seçenekler bildirilir).
+fn saglayici_sec(
+    ad: &str,
+    paket_haritasi: &BTreeMap<&str, &Paket>,
+    secilenler: &BTreeSet<String>,
+    kurulu: &KuruluVeritabani,
+    tercihler: &HashMap<String, String>,
+) -> Result<String, PaketYoneticisiHatasi> {
+    if paket_haritasi.contains_key(ad) {
+        return Ok(ad.to_owned());
+    }
+
+    // BTreeMap sıralı olduğu için adaylar ada göre sıralıdır.
+    let adaylar: Vec<&str> = paket_haritasi
+        .values()
+        .filter(|p| p.saglar.iter().any(|s| s == ad))
+        .map(|p| p.ad.as_str())
+        .collect();
+
+    if adaylar.is_empty() {
+        return Err(PaketYoneticisiHatasi::BagimlilikBulunamadi(ad.to_owned()));
+    }
+    if let Some(tercih) = tercihler.get(ad) {
+        if adaylar.contains(&tercih.as_str()) {
+            return Ok(tercih.clone());
+        }
+        eprintln!("Uyarı: '{}' için tercih edilen sağlayıcı '{}' bulunamadı, otomatik seçim yapılıyor.", ad, tercih);
+    }
+    if let Some(aday) = adaylar.iter().find(|a| kurulu.kurulu_mu(a)) {
+        return Ok((*aday).to_owned());
+    }
+    if let Some(aday) = adaylar.iter().find(|a| secilenler.contains(**a)) {
+        return Ok((*aday).to_owned());
+    }
+    if adaylar.len() > 1 {
+        println!("'{}' birden fazla paket tarafından sağlanıyor ({}), '{}' seçildi.", ad, adaylar.join(", "), adaylar[0]);
+    }
+    Ok(adaylar[0].to_owned())
+}
 
-        // Ziyaret edilecek paketlerin listesi (DFS için yığın olarak kullanılır).
-        // Paket adlarını saklıyoruz: Vec<Paket Adı: String>.
-        let mut ziyaret_edilecekler = Vec::new(); // alloc::vec::Vec (alloc gerektirir).
+impl BagimlilikYoneticisi {
+    // İstenen paketleri, bağımlılıklarıyla birlikte kurulum planına çevirir.
+    // - Sanal adlara (Paket.saglar) olan bağımlılıklar sağlayıcılardan biri seçilerek karşılanır.
+    // - Kurulu paketlerce karşılanan bağımlılıklar yeniden kurulmaz.
+    // - Kurulacak bir paketin degistirir veya cakisir listesindeki kurulu paketler kaldırılacaklara eklenir.
+    // - Kurulacak iki paket birbiriyle çakışıyorsa veya kurulu kalacak bir paket kurulacak bir paketle
+    //   çakıştığını bildiriyorsa ConflictError döner.
+    // paketler: Depodaki tüm bilinen paketler.
+    // istenenler: Kullanıcının kurmak istediği paket (veya sanal) adları.
+    // kurulu: Kurulu paketler veritabanı.
+    // secenekler: Sağlayıcı tercihleri, öneri/tavsiye ve özellik seçimleri.
+    // Etkin özelliklerin bağımlılıkları zorunlu bağımlılık gibi çözülür; öneriler ve tavsiyeler ise
+    // bulunamazsa hata vermez, kurulmayan_oneriler listesinde bildirilir.
+    pub fn cozum_plani_olustur(
+        paketler: &[Paket],
+        istenenler: &[&str],
+        kurulu: &KuruluVeritabani,
+        secenekler: &KurulumSecenekleri,
+    ) -> Result<CozumPlani, PaketYoneticisiHatasi> {
+        // Kaldırılacak bir paket, başka bir kurulacak paketin bağımlılığını karşılıyor olabilir.
+        // Bu durumda çözümleme o paket yokmuş gibi tekrarlanır; küme sadece büyüdüğü için döngü sonlanır.
+        let mut haric: BTreeSet<String> = BTreeSet::new();
+        loop {
+            let mut plan = Self::plani_coz(paketler, istenenler, kurulu, secenekler, &haric)?;
+            if plan.kaldirilacaklar.iter().all(|k| haric.contains(k)) {
+                Self::plani_sirala(&mut plan, paketler);
+                return Ok(plan);
+            }
+            haric.extend(plan.kaldirilacaklar.iter().cloned());
+        }
+    }
 
-        // Başlangıç paketini ziyaret edilecekler listesine ekle.
-        // baslangic_paketi &str -> String dönüşümü (alloc gerektirir).
-        ziyaret_edilecekler.push(baslangic_paketi.to_owned()); // to_string() yerine to_owned()
+    // Planı topolojik olarak sıralar: güçlü bağlı bileşenler (Tarjan) bağımlılıklar önce gelecek şekilde
+    // gruplanır, kurulacaklar bu sıraya göre yeniden düzenlenir ve döngüler tanı için kaydedilir.
+    fn plani_sirala(plan: &mut CozumPlani, paketler: &[Paket]) {
+        let paket_haritasi: BTreeMap<&str, &Paket> = paketler.iter().map(|p| (p.ad.as_str(), p)).collect();
+        let graf = plan_grafi(plan, &paket_haritasi);
+        plan.kurulum_gruplari = graf.guclu_bagli_bilesenler();
+        plan.kurulacaklar = plan.kurulum_gruplari.iter().flatten().cloned().collect();
+        plan.donguler = graf.donguler().iter().map(|bilesen| graf.dongu_yolu(bilesen)).collect();
+        if !plan.donguler.is_empty() {
+            eprintln!("Uyarı: {}", donguleri_bicimlendir(&plan.donguler));
+            eprintln!("Döngüdeki paketler birlikte kurulacak; betikleri grubun tamamı kurulduktan sonra çalışacak.");
+        }
+    }
 
+    // cozum_plani_olustur'un tek bir geçişi. 'haric' içindeki kurulu paketler yokmuş gibi davranılır.
+    fn plani_coz(
+        paketler: &[Paket],
+        istenenler: &[&str],
+        kurulu: &KuruluVeritabani,
+        secenekler: &KurulumSecenekleri,
+        haric: &BTreeSet<String>,
+    ) -> Result<CozumPlani, PaketYoneticisiHatasi> {
+        let tercihler = &secenekler.saglayici_tercihleri;
+        let paket_haritasi: BTreeMap<&str, &Paket> = paketler.iter().map(|p| (p.ad.as_str(), p)).collect();
+        let mut plan = CozumPlani::default();
+        let mut secilenler: BTreeSet<String> = BTreeSet::new();
+        let mut istenen_adlar: BTreeSet<String> = BTreeSet::new();
+        let mut kullanilan_ozellikler: BTreeSet<String> = BTreeSet::new();
+
+        // Derinlemesine ilk arama, post-order: (ad, bağımlılıkları yığına eklendi mi)
+        let mut yigin: Vec<(String, bool)> = Vec::new();
+        for istenen in istenenler.iter().rev() {
+            let ad = saglayici_sec(istenen, &paket_haritasi, &secilenler, kurulu, tercihler)
+                .map_err(|_| PaketYoneticisiHatasi::PaketBulunamadi((*istenen).to_owned()))?;
+            if ad != *istenen {
+                plan.secilen_saglayicilar.insert((*istenen).to_owned(), ad.clone());
+            }
+            istenen_adlar.insert(ad.clone());
+            yigin.push((ad, false));
+        }
 
-        // Ziyaret edilecek paketler listesi boşalana kadar döngü.
-        while let Some(paket_adi) = ziyaret_edilecekler.pop() { // Vec pop (alloc gerektirmez, ama içindeki String'i taşır)
-            // Eğer paket adı zaten ziyaret edilenler kümesindeyse (döngü veya tekrar ziyaret durumu), atla.
-            if ziyaret_edilenler.contains(&paket_adi) { // HashSet contains (&String, String için Hash ve Eq kullanır)
+        while let Some((ad, genisletildi)) = yigin.pop() {
+            if genisletildi {
+                if secilenler.insert(ad.clone()) {
+                    plan.kurulacaklar.push(ad);
+                }
+                continue;
+            }
+            if secilenler.contains(&ad) {
                 continue;
             }
+            let paket = paket_haritasi[ad.as_str()];
+            yigin.push((ad.clone(), true));
 
-            // Paket adını ziyaret edilenler kümesine ekle.
-            // ziyaret_edilenler.insert(paket_adi.clone()); // clone() ile eklemeden önce kullanıldıysa paket_adi'nin bir klonu alınmalıydı.
-            // Ancak pop ile ownership alındığı için doğrudan insert yapabiliriz.
-            // insert(String) String'i kümeye taşır (alloc gerektirmez ama rehash/resize alloc gerektirebilir).
-            ziyaret_edilenler.insert(paket_adi.clone()); // DFS'de visited kümesine paketi *işlemeye başlamadan önce* eklemek yaygındır.
-                                                      // Ancak burada visited kontrolü pop sonrası yapıldığı için, paketi işledikten
-                                                      // sonra visited'a eklemek daha uygun olabilir. Mevcut kod paketi işledikten
-                                                      // sonra ekliyor, bu da döngüleri tespit etmede biraz farklılık yaratabilir
-                                                      // ama temel amacı sağlar. Paketi *ziyaret edilecek* olarak işaretlemek
-                                                      // için buraya eklemek ve döngü kontrolünü başta yapmak daha standart DFS'tir.
-                                                      // Kodunuz pop sonrası visited kontrolü yapıyor, visited insert'i döngü sonuna yakın.
-                                                      // Bu durumda cycle tespit etme biraz farklı işler. Standart yaklaşım:
-                                                       let paket_adi = ziyaret_edilecekler.pop().unwrap(); // pop sonrası visited kontrolü için ownership alınır.
-                                                       if visited.contains(&paket_adi) { continue; }
-                                                       visited.insert(paket_adi.clone()); // Buraya eklenmeliydi
-                                                      // ... paket işleme ...
-                                                       cozulen_bagimliliklar.push(paket_adi); // Buraya ownership taşınır.
-
-            // Paket haritasında paket adını ara.
-            if let Some(paket) = paket_haritasi.get(&paket_adi) { // HashMap get (&String, String için Hash ve Eq kullanır)
-                // Paketin bağımlılıklarını işle (genellikle önce bağımlılıklar ziyaret edilir).
-                for bagimlilik_adi in &paket.bagimliliklar { // &Vec<String> üzerinde iterasyon
-                    // Bağımlılık paket haritasında (yani bilinen paketler arasında) yoksa
-                    if !paket_haritasi.contains_key(bagimlilik_adi) { // HashMap contains_key (&String)
-                         eprintln!("Bağımlılık bulunamadı: {}", bagimlilik_adi);
-                        // Hata: Bağımlılık bulunamadı. Hatayı PaketYoneticisiHatasi türünde döndür.
-                        // bagimlilik_adi.clone() String klonlama (alloc gerektirir).
-                        return Err(PaketYoneticisiHatasi::BagimlilikBulunamadi(bagimlilik_adi.clone()));
+            let secim = ozellik_secimi(paket, istenen_adlar.contains(&ad), kurulu, secenekler, &mut kullanilan_ozellikler);
+            let (etkin, ozellik_bagimliliklari) = paket.ozellikleri_coz(&secim)?;
+            if !etkin.is_empty() {
+                plan.etkin_ozellikler.insert(ad.clone(), etkin);
+            }
+
+            // (ad, zorunlu mu?) — öneri ve tavsiyeler zorunlu değildir.
+            let mut adaylar: Vec<(&String, bool)> = paket.bagimliliklar.iter().chain(ozellik_bagimliliklari.iter()).map(|b| (b, true)).collect();
+            for oneri in &paket.onerilenler {
+                adaylar.push((oneri, false));
+            }
+            for tavsiye in &paket.tavsiyeler {
+                adaylar.push((tavsiye, false));
+            }
+
+            // Yığına ters sırada eklenir ki ilk bağımlılık ilk işlensin.
+            let mut eklenecekler: Vec<String> = Vec::new();
+            let mut ek: Vec<String> = ozellik_bagimliliklari.clone();
+            for (bagimlilik, zorunlu) in adaylar {
+                // Bu planda seçilmiş bir paket veya kalacak bir kurulu paket bağımlılığı zaten karşılıyor mu?
+                let planda_var = secilenler.contains(bagimlilik)
+                    || plan.secilen_saglayicilar.contains_key(bagimlilik)
+                    || secilenler.iter().any(|s| paket_haritasi[s.as_str()].saglar.contains(bagimlilik));
+                if planda_var || kurulu_karsilar(bagimlilik, kurulu, haric) {
+                    // Zaten karşılanan öneriler de paketi ayakta tutar (yetim sayılmaz).
+                    if !zorunlu && !ek.contains(bagimlilik) {
+                        ek.push(bagimlilik.clone());
                     }
-                    // Bağımlılığı ziyaret edilecekler listesine ekle.
-                    // Ziyaret edilecekler yığınına eklenmeden önce visited kontrolü yapılmalı.
-                    if !ziyaret_edilenler.contains(bagimlilik_adi) { // HashSet contains (&String)
-                        ziyaret_edilecekler.push(bagimlilik_adi.clone()); // String klonlama (alloc gerektirir).
+                    continue;
+                }
+                if !zorunlu {
+                    let isteniyor = (secenekler.onerilenlerle && paket.onerilenler.contains(bagimlilik))
+                        || (secenekler.tavsiyelerle && paket.tavsiyeler.contains(bagimlilik));
+                    let secilen = if isteniyor {
+                        saglayici_sec(bagimlilik, &paket_haritasi, &secilenler, kurulu, tercihler).ok()
+                    } else {
+                        None
+                    };
+                    match secilen {
+                        Some(secilen) => {
+                            if secilen != *bagimlilik {
+                                plan.secilen_saglayicilar.insert(bagimlilik.clone(), secilen.clone());
+                            }
+                            if !ek.contains(bagimlilik) {
+                                ek.push(bagimlilik.clone());
+                            }
+                            eklenecekler.push(secilen);
+                        }
+                        None => {
+                            if !plan.kurulmayan_oneriler.contains(bagimlilik) {
+                                plan.kurulmayan_oneriler.push(bagimlilik.clone());
+                            }
+                        }
                     }
+                    continue;
                 }
+                let secilen = saglayici_sec(bagimlilik, &paket_haritasi, &secilenler, kurulu, tercihler)?;
+                if secilen != *bagimlilik {
+                    plan.secilen_saglayicilar.insert(bagimlilik.clone(), secilen.clone());
+                }
+                eklenecekler.push(secilen);
+            }
+            yigin.extend(eklenecekler.into_iter().rev().map(|ad| (ad, false)));
+            if !ek.is_empty() {
+                plan.ek_bagimliliklar.insert(ad.clone(), ek);
+            }
+        }
 
-                // Paketi çözülen bağımlılıklar listesine ekle.
-                // Bu liste kurulum sırasını temsil ediyorsa, önce bağımlılıklar gelmeli.
-                // DFS'de genellikle düğüm, bağımlılıkları işlendikten sonra son listeye eklenir (post-order traversal).
-                // Mevcut kodunuz pop sonrası visited kontrolü ve döngü sonunda push yapıyor.
-                // Bu aslında DFS'in biraz farklı bir implementasyonu.
-                // Standart post-order DFS için:
-                 while let Some(paket_adi) = ziyaret_edilecekler.pop() { ... logic ... cozulen_bagimliliklar.push(paket_adi); }
-                // Bu durumda çözülen_bagimliliklar listesi, bağımlılıkları önce gelecek şekilde sondan başa sıralanır.
-                // Listenin tersine çevrilmesi gerekebilir.
-
-                // Mevcut kodun mantığına göre paket_adi'nin kendisi cozulen_bagimliliklar'a ekleniyor.
-                // Bu da bir tür topolojik sıralamaya benzer, ancak tam olarak standart post-order DFS değil.
-                // Let's stick to the original logic's intent.
-                cozulen_bagimliliklar.push(paket_adi.clone()); // String klonlama (alloc gerektirir).
-
-                 // Paket adı ziyaret edilenler kümesine eklenmişti, burada tekrar gerek yok.
-                 // ziyaret_edilenler.insert(paket_adi); // Original code had this here, redundant if added at the start.
-                 // Let's remove the redundant insert.
+        for ad in &plan.kurulacaklar {
+            let neden = if istenen_adlar.contains(ad) {
+                KurulumNedeni::Acik
             } else {
-                 eprintln!("Başlangıç paketi veya bir bağımlılık bilinmiyor: {}", paket_adi);
-                // Hata: Paket bulunamadı (başlangıç paketi veya geçersiz bir bağımlılık adı).
-                // paket_adi String'i pop ile alınmıştı, ownership kullanılabilir.
-                return Err(PaketYoneticisiHatasi::PaketBulunamadi(paket_adi));
+                kurulu.paket(ad).map(|k| k.kurulum_nedeni).unwrap_or(KurulumNedeni::Bagimlilik)
+            };
+            plan.kurulum_nedenleri.insert(ad.clone(), neden);
+        }
+
+        // Hiçbir istenen pakette tanımlı olmayan düz özellik adları büyük olasılıkla yazım hatasıdır.
+        if let Some(ozellik) = secenekler.ozellikler.iter().find(|o| !o.contains('/') && !kullanilan_ozellikler.contains(*o)) {
+            return Err(PaketYoneticisiHatasi::InvalidParameter(format!("istenen paketlerin hiçbirinde '{}' özelliği yok", ozellik)));
+        }
+        // Sonradan zorunlu olarak plana girmiş olabilecek öneriler listeden çıkarılır.
+        plan.kurulmayan_oneriler.retain(|o| !secilenler.contains(o) && !plan.secilen_saglayicilar.contains_key(o));
+
+        // Çakışmalar ve değiştirmeler
+        for ad in &plan.kurulacaklar {
+            let paket = paket_haritasi[ad.as_str()];
+            for diger in &paket.cakisir {
+                // Kurulacak başka bir paket (veya onun sağladığı sanal ad) ile çakışma çözülemez.
+                if let Some(cakisan) = plan.kurulacaklar.iter().find(|k| {
+                    *k != ad && (*k == diger || paket_haritasi[k.as_str()].saglar.contains(diger))
+                }) {
+                    return Err(PaketYoneticisiHatasi::ConflictError(format!("'{}' ve '{}' birlikte kurulamaz", ad, cakisan)));
+                }
+            }
+            for hedef in paket.degistirir.iter().chain(paket.cakisir.iter()) {
+                // Aynı adlı paket güncelleniyorsa kaldırılmaz; sanal ad ise onu sağlayan kurulu paketler kaldırılır.
+                for kayit in kurulu.paketler.values() {
+                    let eslesiyor = kayit.ad == *hedef || kayit.saglar.contains(hedef);
+                    if eslesiyor && !secilenler.contains(&kayit.ad) && !plan.kaldirilacaklar.contains(&kayit.ad) {
+                        plan.kaldirilacaklar.push(kayit.ad.clone());
+                        plan.kaldiranlar.insert(kayit.ad.clone(), ad.clone());
+                    }
+                }
             }
         }
 
-        // Başarılı sonuç: Çözülen bağımlılıkların listesini döndür.
-        // Eğer cozulen_bagimliliklar listesi post-order DFS ile oluşturulduysa, kurulum sırası için tersine çevrilmesi gerekebilir.
-        // cozuldukten sonra reverse() yapilabilir: cozulen_bagimliliklar.reverse();
-        // Mevcut kodun mantığı tam bir post-order gibi durmuyor, paketi işledikten sonra ekliyor.
-        // Test senaryolarına göre listenin kurulum sırasını doğru temsil ettiğini varsayalım veya
-        /// burada reverse() ekleyelim. Tipik kurulum sırası için reverse() gerekir.
+        // Kurulu kalacak paketlerin kendi çakışma bildirimleri
+        for kayit in kurulu.paketler.values() {
+            if secilenler.contains(&kayit.ad) || plan.kaldirilacaklar.contains(&kayit.ad) {
+                continue;
+            }
+            for ad in &plan.kurulacaklar {
+                let paket = paket_haritasi[ad.as_str()];
+                if kayit.cakisir.iter().any(|c| *c == paket.ad || paket.saglar.contains(c)) {
+                    return Err(PaketYoneticisiHatasi::ConflictError(format!(
+                        "kurulu '{}' paketi '{}' ile çakışıyor", kayit.ad, paket.ad
+                    )));
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+// --- Kurulu paketler üzerinde ters bağımlılık sorguları ---
+
+// Kurulu paketler için ters bağımlılık indeksi: paket -> ona doğrudan bağımlı kurulu paketler.
+fn ters_indeks(kurulu: &KuruluVeritabani) -> BTreeMap<String, BTreeSet<String>> {
+    let mut indeks: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for ad in kurulu.paketler.keys() {
+        for bagimlilik in kurulu.dogrudan_bagimliliklar(ad) {
+            indeks.entry(bagimlilik).or_default().insert(ad.clone());
+        }
+    }
+    indeks
+}
+
+impl BagimlilikYoneticisi {
+    // 'ad' paketine doğrudan bağımlı olan kurulu paketler (sanal adlar ve ek bağımlılıklar dahil).
+    pub fn ters_bagimliliklar(kurulu: &KuruluVeritabani, ad: &str) -> BTreeSet<String> {
+        ters_indeks(kurulu).remove(ad).unwrap_or_default()
+    }
 
-        cozulen_bagimliliklar.reverse(); // Genellikle kurulum sırası için listeyi ters çevirmek gerekir.
+    // 'ad' paketine doğrudan veya dolaylı olarak bağımlı olan tüm kurulu paketler ('ad' hariç).
+    pub fn tum_ters_bagimliliklar(kurulu: &KuruluVeritabani, ad: &str) -> BTreeSet<String> {
+        let indeks = ters_indeks(kurulu);
+        let mut sonuc: BTreeSet<String> = BTreeSet::new();
+        let mut yigin: Vec<&str> = Vec::new();
+        yigin.push(ad);
+        while let Some(ad) = yigin.pop() {
+            for bagimli in indeks.get(ad).into_iter().flatten() {
+                if sonuc.insert(bagimli.clone()) {
+                    yigin.push(bagimli);
+                }
+            }
+        }
+        sonuc.remove(ad);
+        sonuc
+    }
+
+    // 'ad' paketinin neden kurulu olduğunu açıklayan en kısa zinciri döndürür:
+    // [açık kurulmuş paket, ..., ad]. Paket açık kurulmuşsa zincir sadece kendisidir.
+    // Paket kurulu değilse veya hiçbir açık paket onu gerektirmiyorsa (yetim) None döner.
+    pub fn neden_kurulu(kurulu: &KuruluVeritabani, ad: &str) -> Option<Vec<String>> {
+        kurulu.paket(ad)?;
+        let indeks = ters_indeks(kurulu);
+
+        // Ters kenarlar üzerinde genişlik öncelikli arama; önceki[x] = x'e bağımlı olarak ulaşılan paket.
+        let mut onceki: BTreeMap<String, String> = BTreeMap::new();
+        let mut kuyruk: VecDeque<String> = VecDeque::new();
+        kuyruk.push_back(ad.to_owned());
+        while let Some(simdiki) = kuyruk.pop_front() {
+            if kurulu.paket(&simdiki).map(|k| k.kurulum_nedeni) == Some(KurulumNedeni::Acik) {
+                // Zinciri açık paketten 'ad'a doğru kur.
+                let mut zincir = Vec::new();
+                zincir.push(simdiki.clone());
+                let mut adim = simdiki;
+                while adim != ad {
+                    // onceki, 'adim'in bağımlılığı olan (ad yönündeki) paketi tutar.
+                    adim = onceki[&adim].clone();
+                    zincir.push(adim.clone());
+                }
+                return Some(zincir);
+            }
+            for bagimli in indeks.get(&simdiki).into_iter().flatten() {
+                if bagimli != ad && !onceki.contains_key(bagimli) {
+                    onceki.insert(bagimli.clone(), simdiki.clone());
+                    kuyruk.push_back(bagimli.clone());
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srcdatabase::KuruluPaket;
+
+    fn paket(ad: &str, bagimliliklar: &[&str]) -> Paket {
+        Paket::yeni(ad.to_string(), "1.0".to_string(), bagimliliklar.iter().map(|b| b.to_string()).collect())
+    }
+
+    fn saglayan(ad: &str, saglar: &[&str]) -> Paket {
+        let mut p = paket(ad, &[]);
+        p.saglar = saglar.iter().map(|s| s.to_string()).collect();
+        p
+    }
+
+    #[test]
+    fn test_sirali_bagimliliklar() {
+        let paketler = vec![paket("uygulama", &["libx"]), paket("libx", &["libc"]), paket("libc", &[])];
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["uygulama"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.kurulacaklar, vec!["libc".to_string(), "libx".to_string(), "uygulama".to_string()]);
+        assert_eq!(plan.kurulum_nedenleri["uygulama"], KurulumNedeni::Acik);
+        assert_eq!(plan.kurulum_nedenleri["libc"], KurulumNedeni::Bagimlilik);
+    }
+
+    #[test]
+    fn test_dongu_grubu_ve_tani() {
+        let paketler = vec![paket("uygulama", &["a"]), paket("a", &["b"]), paket("b", &["a", "libc"]), paket("libc", &[])];
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["uygulama"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(
+            plan.kurulum_gruplari,
+            vec![vec!["libc".to_string()], vec!["a".to_string(), "b".to_string()], vec!["uygulama".to_string()]]
+        );
+        assert_eq!(plan.kurulacaklar, vec!["libc".to_string(), "a".to_string(), "b".to_string(), "uygulama".to_string()]);
+        assert_eq!(plan.donguler, vec![vec!["a".to_string(), "b".to_string(), "a".to_string()]]);
+        assert_eq!(donguleri_bicimlendir(&plan.donguler), "Bağımlılık döngüsü: a -> b -> a");
+    }
+
+    #[test]
+    fn test_sanal_paket_saglayici_secimi() {
+        let paketler = vec![
+            paket("mutt", &["mail-transport-agent"]),
+            saglayan("postfix", &["mail-transport-agent"]),
+            saglayan("exim", &["mail-transport-agent"]),
+        ];
+        let bos = KuruluVeritabani::default();
+
+        // Tercih yoksa ada göre ilk sağlayıcı
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["mutt"], &bos, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.secilen_saglayicilar.get("mail-transport-agent"), Some(&"exim".to_string()));
+
+        // Kullanıcı tercihi
+        let mut secenekler = KurulumSecenekleri::default();
+        secenekler.saglayici_tercihleri.insert("mail-transport-agent".to_string(), "postfix".to_string());
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["mutt"], &bos, &secenekler).unwrap();
+        assert!(plan.kurulacaklar.contains(&"postfix".to_string()));
+
+        // Kurulu sağlayıcı bağımlılığı karşılar
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(KuruluPaket::paketten(&paketler[1]));
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["mutt"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.kurulacaklar, vec!["mutt".to_string()]);
+    }
+
+    #[test]
+    fn test_degistirilen_paket_kaldirilir() {
+        let mut yeni = saglayan("exim", &["mail-transport-agent"]);
+        yeni.cakisir.push("mail-transport-agent".to_string());
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(KuruluPaket::paketten(&saglayan("postfix", &["mail-transport-agent"])));
+
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&[yeni], &["exim"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.kaldirilacaklar, vec!["postfix".to_string()]);
+        assert_eq!(plan.kaldiranlar.get("postfix"), Some(&"exim".to_string()));
+    }
+
+    #[test]
+    fn test_kaldirilan_saglayici_yerine_yenisi_secilir() {
+        // "yeni-mta" postfix'in yerini alıyor ama mail-transport-agent sağlamıyor; mutt için başka sağlayıcı gerekir.
+        let mut yeni_mta = paket("yeni-mta", &[]);
+        yeni_mta.degistirir.push("postfix".to_string());
+        let paketler = vec![paket("mutt", &["mail-transport-agent"]), yeni_mta, saglayan("exim", &["mail-transport-agent"])];
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(KuruluPaket::paketten(&saglayan("postfix", &["mail-transport-agent"])));
+
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["yeni-mta", "mutt"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.kaldirilacaklar, vec!["postfix".to_string()]);
+        assert!(plan.kurulacaklar.contains(&"exim".to_string()));
+    }
+
+    #[test]
+    fn test_islem_ici_cakisma_hatasi() {
+        let mut a = paket("a", &[]);
+        a.cakisir.push("b".to_string());
+        let sonuc = BagimlilikYoneticisi::cozum_plani_olustur(&[a, paket("b", &[])], &["a", "b"], &KuruluVeritabani::default(), &KurulumSecenekleri::default());
+        assert!(matches!(sonuc, Err(PaketYoneticisiHatasi::ConflictError(_))));
+    }
+
+    fn ozellikli_editor() -> Paket {
+        let mut editor = paket("editor", &[]);
+        editor.ozellikler.insert("yazim".to_string(), vec!["hunspell".to_string()]);
+        editor.ozellikler.insert("tam".to_string(), vec!["yazim".to_string(), "git".to_string()]);
+        editor.varsayilan_ozellikler.push("yazim".to_string());
+        editor.onerilenler.push("fontlar".to_string());
+        editor.tavsiyeler.push("eklenti-paketi".to_string());
+        editor
+    }
+
+    #[test]
+    fn test_ozellikleri_coz() {
+        let editor = ozellikli_editor();
+        let (etkin, bagimliliklar) = editor.ozellikleri_coz(&["tam".to_string()]).unwrap();
+        assert_eq!(etkin, vec!["tam".to_string(), "yazim".to_string()]);
+        assert_eq!(bagimliliklar, vec!["git".to_string(), "hunspell".to_string()]);
+        assert!(editor.ozellikleri_coz(&["yok".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_varsayilan_ve_secilen_ozellikler() {
+        let paketler = vec![ozellikli_editor(), paket("hunspell", &[]), paket("git", &[]), paket("fontlar", &[])];
+        let bos = KuruluVeritabani::default();
+
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["editor"], &bos, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(plan.kurulacaklar, vec!["hunspell".to_string(), "editor".to_string()]);
+        assert_eq!(plan.kurulmayan_oneriler, vec!["fontlar".to_string(), "eklenti-paketi".to_string()]);
+
+        let secenekler = KurulumSecenekleri {
+            varsayilan_ozelliksiz: true,
+            ozellikler: vec!["editor/tam".to_string()],
+            onerilenlerle: true,
+            ..Default::default()
+        };
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["editor"], &bos, &secenekler).unwrap();
+        assert!(plan.kurulacaklar.contains(&"git".to_string()));
+        assert!(plan.kurulacaklar.contains(&"fontlar".to_string()));
+        assert_eq!(plan.etkin_ozellikler["editor"], vec!["tam".to_string(), "yazim".to_string()]);
+        // Depoda olmayan tavsiye hata değildir, sadece bildirilir.
+        assert_eq!(plan.kurulmayan_oneriler, vec!["eklenti-paketi".to_string()]);
+    }
+
+    #[test]
+    fn test_kayitli_ozellikler_hatirlanir() {
+        let paketler = vec![ozellikli_editor(), paket("hunspell", &[]), paket("git", &[])];
+        let mut kurulu = KuruluVeritabani::default();
+        let mut kayit = KuruluPaket::paketten(&paketler[0]);
+        kayit.etkin_ozellikler = vec!["tam".to_string()];
+        kurulu.ekle(kayit);
+
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["editor"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+        assert!(plan.kurulacaklar.contains(&"git".to_string()));
+    }
+
+    fn kayit(ad: &str, bagimliliklar: &[&str], neden: KurulumNedeni) -> KuruluPaket {
+        let mut k = KuruluPaket::paketten(&paket(ad, bagimliliklar));
+        k.kurulum_nedeni = neden;
+        k
+    }
+
+    fn ornek_kurulu() -> KuruluVeritabani {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(kayit("editor", &["libyazi"], KurulumNedeni::Acik));
+        vt.ekle(kayit("libyazi", &["libc"], KurulumNedeni::Bagimlilik));
+        vt.ekle(kayit("kabuk", &["libc"], KurulumNedeni::Bagimlilik));
+        vt.ekle(kayit("libc", &[], KurulumNedeni::Bagimlilik));
+        vt
+    }
+
+    #[test]
+    fn test_ters_bagimliliklar() {
+        let vt = ornek_kurulu();
+        let dogrudan: Vec<String> = BagimlilikYoneticisi::ters_bagimliliklar(&vt, "libc").into_iter().collect();
+        assert_eq!(dogrudan, vec!["kabuk".to_string(), "libyazi".to_string()]);
+        let tumu: Vec<String> = BagimlilikYoneticisi::tum_ters_bagimliliklar(&vt, "libc").into_iter().collect();
+        assert_eq!(tumu, vec!["editor".to_string(), "kabuk".to_string(), "libyazi".to_string()]);
+    }
+
+    #[test]
+    fn test_neden_kurulu() {
+        let vt = ornek_kurulu();
+        assert_eq!(
+            BagimlilikYoneticisi::neden_kurulu(&vt, "libc"),
+            Some(vec!["editor".to_string(), "libyazi".to_string(), "libc".to_string()])
+        );
+        assert_eq!(BagimlilikYoneticisi::neden_kurulu(&vt, "editor"), Some(vec!["editor".to_string()]));
+        assert_eq!(BagimlilikYoneticisi::neden_kurulu(&vt, "kabuk"), None); // Yetim
+        assert_eq!(BagimlilikYoneticisi::neden_kurulu(&vt, "yok"), None);
+    }
 
-        Ok(cozulen_bagimliliklar) // Başarılı
+    #[test]
+    fn test_bilinmeyen_ozellik_hatasi() {
+        let secenekler = KurulumSecenekleri { ozellikler: vec!["yok".to_string()], ..Default::default() };
+        let sonuc = BagimlilikYoneticisi::cozum_plani_olustur(&[ozellikli_editor(), paket("hunspell", &[])], &["editor"], &KuruluVeritabani::default(), &secenekler);
+        assert!(matches!(sonuc, Err(PaketYoneticisiHatasi::InvalidParameter(_))));
     }
 }
 
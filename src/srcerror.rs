@@ -11,6 +11,9 @@ use postcard::Error as PostcardError; // postcard crate'inin no_std+alloc uyumlu
 
 // Sahne64 API'sının hata türü
 use crate::SahneError;
+
+// Güvenlik modülünün hata türü
+use crate::srcsecurity::SecurityError;
 // Paket Yöneticisi Hata Türleri (no_std uyumlu)
 // thiserror::Error yerine Debug ve Display manuel implementasyonları.
 #[derive(Debug)] // Debug derive'ı no_std'de çalışır
@@ -57,6 +60,30 @@ pub enum PaketYoneticisiHatasi {
     // Fonksiyona geçersiz parametre geçilmesi
     InvalidParameter(String), // Detay String (alloc gerektirir)
 
+    // Güvenlik işlemleri (imza doğrulama, sandbox vb.) sırasında oluşan hatalar
+    SecurityError(SecurityError), // srcsecurity::SecurityError'ı sarmalar
+
+    // Depo indeksi yayınlama sırasında oluşan hatalar (geçersiz arşiv, eksik meta veri vb.)
+    DepoYayinlamaHatasi(String), // Detay String (alloc gerektirir)
+
+    // Yapılandırma dosyası veya ortam değişkeni geçersiz ("kaynak:satır: mesaj" biçiminde)
+    YapilandirmaHatasi(String), // Detay String (alloc gerektirir)
+
+    // Kurulu dosyalar paket manifestosuyla uyuşmuyor (bkz. srcverify)
+    DogrulamaHatasi(String), // Detay String (alloc gerektirir)
+
+    // Arşiv çıkarılırken boyut, girdi sayısı veya sıkıştırma oranı sınırı aşıldı (bkz. srcarchive::CikarmaSinirlari)
+    ArsivSiniriAsildi(String), // Detay String (alloc gerektirir)
+
+    // tar arşivi veya sıkıştırma çözücüsü hatası (bkz. srctar)
+    TarHatasi(String), // Detay String (alloc gerektirir)
+
+    // Arşiv biçimi tanınmadı veya etkin özelliklerde kapalı (bkz. srcarchive::arsiv_bicimini_algila)
+    DesteklenmeyenArsivBicimi(String), // Detay String (alloc gerektirir)
+
+    // Paket betiği sıfırdan farklı kodla çıktı, zaman aşımına uğradı veya başlatılamadı (bkz. srcscript)
+    BetikCalistirmaHatasi(String), // Detay String (alloc gerektirir)
+
     // Beklenmedik veya eşlenmemiş hatalar
     UnknownError(String), // Detay String (alloc gerektirir)
 
@@ -82,6 +109,14 @@ impl core::fmt::Display for PaketYoneticisiHatasi {
             PaketYoneticisiHatasi::RemovalError(s) => write!(f, "Kaldırma hatası: {}", s),
             PaketYoneticisiHatasi::CacheError(s) => write!(f, "Önbellek hatası: {}", s),
             PaketYoneticisiHatasi::InvalidParameter(s) => write!(f, "Geçersiz parametre: {}", s),
+            PaketYoneticisiHatasi::SecurityError(e) => write!(f, "Güvenlik hatası: {}", e),
+            PaketYoneticisiHatasi::DepoYayinlamaHatasi(s) => write!(f, "Depo yayınlama hatası: {}", s),
+            PaketYoneticisiHatasi::YapilandirmaHatasi(s) => write!(f, "Yapılandırma hatası: {}", s),
+            PaketYoneticisiHatasi::DogrulamaHatasi(s) => write!(f, "Doğrulama hatası: {}", s),
+            PaketYoneticisiHatasi::ArsivSiniriAsildi(s) => write!(f, "Arşiv sınırı aşıldı: {}", s),
+            PaketYoneticisiHatasi::TarHatasi(s) => write!(f, "Tar arşivi hatası: {}", s),
+            PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(s) => write!(f, "Desteklenmeyen arşiv biçimi: {}", s),
+            PaketYoneticisiHatasi::BetikCalistirmaHatasi(s) => write!(f, "Betik çalıştırma hatası: {}", s),
             PaketYoneticisiHatasi::UnknownError(s) => write!(f, "Beklenmedik hata: {}", s),
         }
     }
@@ -123,5 +158,12 @@ impl From<ZipError> for PaketYoneticisiHatasi {
     }
 }
 
+// SecurityError'dan dönüşüm (srcsigning/srcsecurity çağrıları için)
+impl From<SecurityError> for PaketYoneticisiHatasi {
+    fn from(err: SecurityError) -> Self {
+        PaketYoneticisiHatasi::SecurityError(err)
+    }
+}
+
 // Eğer ağ Kaynakları için özel bir hata türü varsa, onun için de From implementasyonu eklenir.
 impl From<YourNoStdNetworkError> for PaketYoneticisiHatasi { ... }
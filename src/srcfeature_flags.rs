@@ -2,11 +2,14 @@
 extern crate alloc; // String için
 
 use alloc::string::String;
+use alloc::vec::Vec;
 // std::env yerine yapılandırma kaynağından okuyacağız
 // use std::env;
 
 // Yapılandırma struct'ını içe aktarın
 use crate::srcconfig::Yapilandirma;
+// Çalışma zamanında sorgulanan ayrıntılı özellikler
+use crate::srcfeatures::{ozellik_listesi, Feature, FeatureSet, LoggingFramework, SecurityFeature, TUM_OZELLIKLER};
 
 // Özel hata enum'ımızı içe aktar (no_std uyumlu hali) - aslında bu modül hata dönmeyebilir.
  use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
@@ -30,24 +33,12 @@ impl FeatureFlags {
     // Eğer yapılandırmada bir bayrak yoksa veya geçersizse varsayılan değeri kullanır.
     // config: Yüklenmiş Yapilandirma struct'ına referans.
     pub fn from_config(config: &Yapilandirma) -> Self {
-        // Yapılandırma struct'ında özellik bayrakları için alanlar olduğunu varsayıyoruz.
-        // Örn: Yapilandirma { ..., pub features: FeatureConfig { compression: bool, network: bool, ... }, ... }
-        // Eğer doğrudan Yapilandirma içinde bool alanları varsa:
+        // Bayraklar yapılandırmanın [ozellikler] bölümünden gelir; dosyada belirtilmeyenler
+        // Yapilandirma::default() değerlerini alır (bkz. srcconfig::KatmanliYapilandirma).
         FeatureFlags {
-            // Yapılandırma struct'ından değeri oku, yoksa varsayılanı kullan.
-            // Yapilandirma struct'ı Option<bool> veya bool tutabilir. Bool tutuyorsa default olmaz.
-            // Option<bool> tuttuğunu varsayalım veya doğrudan Yapilandirma'da alanlar olduğunu.
-            // Daha esnek bir Yapilandirma için ayrı bir features alanı olabilir.
-            // Örn: config.features.compression.unwrap_or(false) veya config.compression
-            // Şimdilik doğrudan Yapilandirma içinde bool alanları olduğunu varsayalım.
-            // Yapilandirma struct'ı güncellenmeli.
-
-            // Geçici olarak varsayılan değerler ile manuel initialize edelim,
-            // Yapilandirma'dan okuma kısmı, Yapilandirma struct'ı netleşince yapılır.
-            // Veya Yapilandirma içinde Option<bool> olduğunu varsayalım.
-            compression: config.compression, // Varsayım: config.compression bool veya Option<bool>
-            network: config.network, // Varsayım: config.network bool veya Option<bool>
-            security: config.security, // Varsayım: config.security bool veya Option<bool>
+            compression: config.compression,
+            network: config.network,
+            security: config.security,
             // ... diğer özellik bayrakları ...
         }
     }
@@ -62,6 +53,78 @@ impl FeatureFlags {
         }
     }
 
+    // Bir kategorinin ana bayrağı. Günlük hedeflerinin ayrı bir bayrağı yoktur (gunluk.seviye = "off" ile kapatılır).
+    pub fn kategori_etkin_mi(&self, kategori: &str) -> bool {
+        match kategori {
+            "sikistirma" => self.compression,
+            "ag" => self.network,
+            "guvenlik" => self.security,
+            _ => true,
+        }
+    }
+}
+
+// Yapılandırmadan kurulan çalışma zamanı yetenek kaydı. Davranışı özelliklere bağlı modüller
+// (arşiv çözücüler, depo imza doğrulaması, betik sandbox'ı, ağ protokolleri, günlük hedefleri) bunu sorgular.
+// Bir özellik, kategorisinin bayrağı açıksa ve yapılandırmadaki kategori listesinde yer alıyorsa etkindir.
+pub struct YetenekKaydi {
+    pub bayraklar: FeatureFlags,
+    ozellikler: FeatureSet,
+}
+
+impl YetenekKaydi {
+    pub fn from_config(config: &Yapilandirma) -> Self {
+        let bayraklar = FeatureFlags::from_config(config);
+        let mut ozellikler = FeatureSet::new();
+        let listeler = [
+            ("sikistirma", &config.sikistirma_algoritmalari),
+            ("ag", &config.ag_protokolleri),
+            ("guvenlik", &config.guvenlik_ozellikleri),
+            ("gunluk", &config.gunluk_hedefleri),
+        ];
+        for (kategori, liste) in listeler {
+            if !bayraklar.kategori_etkin_mi(kategori) {
+                continue;
+            }
+            // Dosyadan ve ortamdan gelen listeler yüklenirken doğrulanır; burada sadece elle kurulmuş
+            // yapılandırmalardaki geçersiz listeler atlanır.
+            match ozellik_listesi(liste, kategori) {
+                Ok(liste) => liste.into_iter().for_each(|ozellik| ozellikler.enable(ozellik)),
+                Err(e) => eprintln!("Uyarı: {} özellik listesi yok sayıldı: {}", kategori, e),
+            }
+        }
+        YetenekKaydi { bayraklar, ozellikler }
+    }
+
+    pub fn etkin_mi(&self, ozellik: Feature) -> bool {
+        self.ozellikler.is_enabled(&ozellik)
+    }
+
+    // Etkin özellik kümesi (örn. srcarchive::arsiv_ac ve srcnetwork::protokol_denetle için).
+    pub fn ozellikler(&self) -> &FeatureSet {
+        &self.ozellikler
+    }
+
+    // Uzak depo indeksinin imzası doğrulanmalı mı?
+    pub fn imza_dogrulama(&self) -> bool {
+        self.etkin_mi(Feature::Security(SecurityFeature::SignatureVerification))
+    }
+
+    // Paket betikleri sandbox içinde çalıştırılmalı mı?
+    pub fn sandbox(&self) -> bool {
+        self.etkin_mi(Feature::Security(SecurityFeature::Sandbox))
+    }
+
+    // Etkin günlük hedefleri, TUM_OZELLIKLER sırasıyla.
+    pub fn gunluk_hedefleri(&self) -> Vec<LoggingFramework> {
+        TUM_OZELLIKLER
+            .iter()
+            .filter_map(|ozellik| match ozellik {
+                Feature::Logging(hedef) if self.etkin_mi(*ozellik) => Some(*hedef),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 // main fonksiyonu bu dosyada olmamalıdır. srccli.rs veya lib.rs'de olmalı.
@@ -73,56 +136,31 @@ impl FeatureFlags {
      println!("Security: {}", features.security);
  }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srcfeatures::{CompressionAlgorithm, NetworkProtocol};
+
+    #[test]
+    fn test_varsayilan_yetenekler() {
+        let kayit = YetenekKaydi::from_config(&Yapilandirma::default());
+        assert!(kayit.etkin_mi(Feature::Compression(CompressionAlgorithm::Zstd)));
+        assert!(!kayit.etkin_mi(Feature::Compression(CompressionAlgorithm::Lz4)));
+        assert!(kayit.etkin_mi(Feature::Network(NetworkProtocol::Https)));
+        assert!(!kayit.etkin_mi(Feature::Network(NetworkProtocol::Http)));
+        assert!(kayit.imza_dogrulama() && kayit.sandbox());
+        assert_eq!(kayit.gunluk_hedefleri(), vec![LoggingFramework::Console]);
+    }
 
-// --- Yapilandirma struct tanımının güncellenmesi (srcconfig.rs) ---
-// Feature flag'leri için alanlar eklenmeli.
-
-// srcconfig.rs (Güncellenmiş - feature flag alanları eklenmiş)
-#![no_std]
-extern crate alloc;
-
-use alloc::string::String;
-use alloc::vec::Vec; // Postcard için
-
-use serde::{Deserialize, Serialize};
-use postcard::Error as PostcardError;
-
-use crate::resource;
-use crate::SahneError;
-use crate::Handle;
-use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
-
-// Yapılandırma verilerini tutan struct.
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Yapilandirma {
-    pub depo_url: String, // Paket deposu URL'i (Sahne64 Kaynak ID'si?)
-    pub yerel_depo_yolu: String, // Yerel cache/repo Kaynak ID'si
-    pub kurulum_dizini: String, // Kurulu paketlerin temel Kaynak ID'si
-    pub onbellek_dizini: String, // Önbellek temel Kaynak ID'si
-
-    // --- Yeni Alanlar: Özellik Bayrakları ---
-    // Bunlar Yapilandirma dosyasından okunacak. Option<bool> kullanmak esneklik sağlar.
-    pub compression: Option<bool>,
-    pub network: Option<bool>,
-    pub security: Option<bool>,
-    // ... diğer özellik bayrakları ...
-}
-
-impl Yapilandirma {
-    // ... yeni constructor, oku, yaz fonksiyonları (resource ve postcard kullanan) ...
-
-    // Örnek: Varsayılan Yapılandırma oluşturma
-    pub fn varsayilan() -> Self {
-        Yapilandirma {
-            depo_url: String::from("sahne://remotepkgrepo/"), // Varsayılan repo Kaynak ID'si
-            yerel_depo_yolu: String::from("sahne://cache/repo/"), // Varsayılan yerel repo Kaynak ID'si
-            kurulum_dizini: String::from("sahne://installed_packages/"), // Varsayılan kurulum Kaynak ID'si
-            onbellek_dizini: String::from("sahne://cache/packages/"), // Varsayılan önbellek Kaynak ID'si
-            // Varsayılan özellik bayrakları: None (yapılandırmada belirtilmemiş)
-            compression: None,
-            network: None,
-            security: None,
-            // ...
-        }
+    #[test]
+    fn test_kapali_bayrak_kategoriyi_kapatir() {
+        let mut config = Yapilandirma::default();
+        config.security = false;
+        config.compression = false;
+        config.guvenlik_ozellikleri = String::from("sandbox");
+        let kayit = YetenekKaydi::from_config(&config);
+        assert!(!kayit.imza_dogrulama() && !kayit.sandbox());
+        assert!(!kayit.etkin_mi(Feature::Compression(CompressionAlgorithm::Gzip)));
+        assert!(kayit.etkin_mi(Feature::Network(NetworkProtocol::Https)));
     }
 }
@@ -26,6 +26,7 @@ pub enum CompressionAlgorithm {
     Gzip,
     Bzip2,
     Zstd,
+    Xz,
     Lz4,
     Brotli,
 }
@@ -79,6 +80,7 @@ impl FromStr for Feature {
             "gzip" => Ok(Feature::Compression(CompressionAlgorithm::Gzip)),
             "bzip2" => Ok(Feature::Compression(CompressionAlgorithm::Bzip2)),
             "zstd" => Ok(Feature::Compression(CompressionAlgorithm::Zstd)),
+            "xz" => Ok(Feature::Compression(CompressionAlgorithm::Xz)),
             "lz4" => Ok(Feature::Compression(CompressionAlgorithm::Lz4)),
             "brotli" => Ok(Feature::Compression(CompressionAlgorithm::Brotli)),
 
@@ -114,7 +116,103 @@ impl FromStr for Feature {
     }
 }
 
+// Yapılandırmada ve `ozellikler` komutunda kullanılan tüm özellikler, kategori sırasıyla.
+pub const TUM_OZELLIKLER: &[Feature] = &[
+    Feature::Compression(CompressionAlgorithm::Gzip),
+    Feature::Compression(CompressionAlgorithm::Bzip2),
+    Feature::Compression(CompressionAlgorithm::Zstd),
+    Feature::Compression(CompressionAlgorithm::Xz),
+    Feature::Compression(CompressionAlgorithm::Lz4),
+    Feature::Compression(CompressionAlgorithm::Brotli),
+    Feature::Network(NetworkProtocol::Http),
+    Feature::Network(NetworkProtocol::Https),
+    Feature::Network(NetworkProtocol::Ftp),
+    Feature::Network(NetworkProtocol::Tcp),
+    Feature::Network(NetworkProtocol::Udp),
+    Feature::Network(NetworkProtocol::Websocket),
+    Feature::Network(NetworkProtocol::Smtp),
+    Feature::Network(NetworkProtocol::Pop3),
+    Feature::Network(NetworkProtocol::Imap),
+    Feature::Security(SecurityFeature::SignatureVerification),
+    Feature::Security(SecurityFeature::Sandbox),
+    Feature::Security(SecurityFeature::Firewall),
+    Feature::Security(SecurityFeature::Encryption),
+    Feature::Security(SecurityFeature::Authorization),
+    Feature::Security(SecurityFeature::Authentication),
+    Feature::Security(SecurityFeature::DataMasking),
+    Feature::Security(SecurityFeature::RateLimiting),
+    Feature::Logging(LoggingFramework::File),
+    Feature::Logging(LoggingFramework::Console),
+    Feature::Logging(LoggingFramework::Database),
+    Feature::Logging(LoggingFramework::Remote),
+    Feature::Logging(LoggingFramework::Syslog),
+    Feature::Logging(LoggingFramework::EventTracing),
+];
+
+impl Feature {
+    // Özelliğin from_str ile ayrıştırılan adı (örn. "gzip", "signature_verification", "file_logging").
+    pub fn ad(&self) -> &'static str {
+        match self {
+            Feature::Compression(CompressionAlgorithm::Gzip) => "gzip",
+            Feature::Compression(CompressionAlgorithm::Bzip2) => "bzip2",
+            Feature::Compression(CompressionAlgorithm::Zstd) => "zstd",
+            Feature::Compression(CompressionAlgorithm::Xz) => "xz",
+            Feature::Compression(CompressionAlgorithm::Lz4) => "lz4",
+            Feature::Compression(CompressionAlgorithm::Brotli) => "brotli",
+            Feature::Network(NetworkProtocol::Http) => "http",
+            Feature::Network(NetworkProtocol::Https) => "https",
+            Feature::Network(NetworkProtocol::Ftp) => "ftp",
+            Feature::Network(NetworkProtocol::Tcp) => "tcp",
+            Feature::Network(NetworkProtocol::Udp) => "udp",
+            Feature::Network(NetworkProtocol::Websocket) => "websocket",
+            Feature::Network(NetworkProtocol::Smtp) => "smtp",
+            Feature::Network(NetworkProtocol::Pop3) => "pop3",
+            Feature::Network(NetworkProtocol::Imap) => "imap",
+            Feature::Security(SecurityFeature::SignatureVerification) => "signature_verification",
+            Feature::Security(SecurityFeature::Sandbox) => "sandbox",
+            Feature::Security(SecurityFeature::Firewall) => "firewall",
+            Feature::Security(SecurityFeature::Encryption) => "encryption",
+            Feature::Security(SecurityFeature::Authorization) => "authorization",
+            Feature::Security(SecurityFeature::Authentication) => "authentication",
+            Feature::Security(SecurityFeature::DataMasking) => "data_masking",
+            Feature::Security(SecurityFeature::RateLimiting) => "rate_limiting",
+            Feature::Logging(LoggingFramework::File) => "file_logging",
+            Feature::Logging(LoggingFramework::Console) => "console_logging",
+            Feature::Logging(LoggingFramework::Database) => "database_logging",
+            Feature::Logging(LoggingFramework::Remote) => "remote_logging",
+            Feature::Logging(LoggingFramework::Syslog) => "syslog_logging",
+            Feature::Logging(LoggingFramework::EventTracing) => "event_tracing",
+        }
+    }
+
+    // Özelliğin kategorisi; yapılandırmadaki liste anahtarları ve `ozellikler` çıktısı bu adlarla gruplanır.
+    pub fn kategori(&self) -> &'static str {
+        match self {
+            Feature::Compression(_) => "sikistirma",
+            Feature::Network(_) => "ag",
+            Feature::Security(_) => "guvenlik",
+            Feature::Logging(_) => "gunluk",
+        }
+    }
+}
+
+// Virgülle ayrılmış özellik adlarını ayrıştırır (örn. "gzip, zstd"). Tüm özellikler verilen kategoride olmalıdır.
+pub fn ozellik_listesi(metin: &str, kategori: &str) -> Result<Vec<Feature>, String> {
+    let mut ozellikler = Vec::new();
+    for ad in metin.split(',').map(|ad| ad.trim()).filter(|ad| !ad.is_empty()) {
+        let ozellik = Feature::from_str(ad)?;
+        if ozellik.kategori() != kategori {
+            return Err(format!("'{}' bir {} özelliği değil", ad, kategori));
+        }
+        if !ozellikler.contains(&ozellik) {
+            ozellikler.push(ozellik);
+        }
+    }
+    Ok(ozellikler)
+}
+
 // Etkin özelliklerin kümesini yöneten yapı.
+#[derive(Debug, Clone)]
 pub struct FeatureSet {
     features: HashSet<Feature>, // alloc::collections::HashSet (alloc gerektirir)
 }
@@ -179,6 +277,7 @@ mod tests {
         assert_eq!(Feature::from_str("gzip").unwrap(), Feature::Compression(CompressionAlgorithm::Gzip));
         assert_eq!(Feature::from_str("Bzip2").unwrap(), Feature::Compression(CompressionAlgorithm::Bzip2));
         assert_eq!(Feature::from_str("lz4").unwrap(), Feature::Compression(CompressionAlgorithm::Lz4));
+        assert_eq!(Feature::from_str("xz").unwrap(), Feature::Compression(CompressionAlgorithm::Xz));
         assert_eq!(Feature::from_str("HTTP").unwrap(), Feature::Network(NetworkProtocol::Http));
         assert_eq!(Feature::from_str("https").unwrap(), Feature::Network(NetworkProtocol::Https));
         assert_eq!(Feature::from_str("websocket").unwrap(), Feature::Network(NetworkProtocol::Websocket));
@@ -233,4 +332,22 @@ mod tests {
         // Hata mesajını kontrol etmek isteyebiliriz:
          assert_eq!(result.unwrap_err(), "Bilinmeyen özellik: invalid_feature".to_string());
     }
+
+    #[test]
+    fn test_ad_from_str_ile_eslesir() {
+        for ozellik in TUM_OZELLIKLER {
+            assert_eq!(Feature::from_str(ozellik.ad()).unwrap(), *ozellik);
+        }
+    }
+
+    #[test]
+    fn test_ozellik_listesi() {
+        let liste = ozellik_listesi("gzip, zstd,,gzip", "sikistirma").unwrap();
+        assert_eq!(liste, vec![
+            Feature::Compression(CompressionAlgorithm::Gzip),
+            Feature::Compression(CompressionAlgorithm::Zstd),
+        ]);
+        assert_eq!(ozellik_listesi("gzip,https", "sikistirma").unwrap_err(), "'https' bir sikistirma özelliği değil");
+        assert!(ozellik_listesi("gzipp", "sikistirma").is_err());
+    }
 }
@@ -0,0 +1,468 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, BTreeMap, format! için
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use alloc::borrow::ToOwned; // &str -> String için
+use core::fmt::Write; // String'e write! ile yazmak için
+
+use crate::package::Paket;
+use crate::srcdatabase::KuruluVeritabani;
+
+// Bağımlılık grafının Graphviz DOT ve JSON olarak dışa aktarımı.
+// Graf; depodaki paket listesinden, kurulu paketler veritabanından veya srcresolver'ın
+// çözümlenmiş bağımlılık map'inden oluşturulabilir. Çıktılar deterministiktir (sıralı koleksiyonlar).
+
+// Graf düğümünün türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DugumTuru {
+    Paket, // Gerçek bir paket
+    Sanal, // Sadece Paket.saglar ile sağlanan sanal ad (örn. "mail-transport-agent")
+    Eksik, // Bağımlılık olarak adı geçen ama kaynakta bulunmayan ad
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dugum {
+    pub ad: String,
+    pub surum: Option<String>,
+    pub tur: DugumTuru,
+    pub derinlik: usize, // Köklerden en kısa uzaklık (kök yoksa 0)
+}
+
+// Kenar türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KenarTuru {
+    Bagimlilik, // kaynak -> hedef'e bağımlı
+    Saglayici, // Sanal ad (kaynak) -> onu sağlayan paket (hedef)
+    Cakisma, // kaynak, hedef ile çakıştığını bildiriyor (cakisir)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Kenar {
+    pub kaynak: String,
+    pub hedef: String,
+    pub tur: KenarTuru,
+}
+
+// Dışa aktarım seçenekleri.
+#[derive(Debug, Clone, Default)]
+pub struct GrafSecenekleri {
+    pub azami_derinlik: Option<usize>, // Köklerden en fazla kaç adım uzaklaşılacağı (None: sınırsız)
+    pub donguleri_vurgula: bool, // Döngüye katılan düğüm ve kenarları işaretle
+    pub cakismalari_goster: bool, // cakisir kenarlarını ekle (sadece iki ucu da graftaysa)
+}
+
+// Bağımlılık grafı.
+#[derive(Debug, Clone, Default)]
+pub struct BagimlilikGrafi {
+    pub dugumler: BTreeMap<String, Dugum>,
+    pub kenarlar: BTreeSet<Kenar>,
+}
+
+// Bir düğümün komşularını (graf dışında kalabilecek adlar dahil) veren soyutlama; depo ve kurulu
+// veritabanı için aynı genişlik öncelikli gezinmeyi kullanabilmek için.
+struct Kaynak<'a> {
+    paketler: BTreeMap<&'a str, (Option<&'a str>, &'a [String], &'a [String])>, // ad -> (sürüm, bağımlılıklar, çakışmalar)
+    saglayicilar: BTreeMap<&'a str, Vec<&'a str>>, // sanal ad -> sağlayıcılar
+}
+
+impl BagimlilikGrafi {
+    // Depo paket listesinden graf oluşturur.
+    // kokler: Başlangıç paketleri; boşsa tüm liste (depo dilimi yerine bütün depo).
+    pub fn depodan(paketler: &[Paket], kokler: &[&str], secenekler: &GrafSecenekleri) -> Self {
+        let mut kaynak = Kaynak { paketler: BTreeMap::new(), saglayicilar: BTreeMap::new() };
+        for paket in paketler {
+            kaynak.paketler.insert(&paket.ad, (Some(paket.surum.as_str()), &paket.bagimliliklar[..], &paket.cakisir[..]));
+            for sanal in &paket.saglar {
+                kaynak.saglayicilar.entry(sanal.as_str()).or_default().push(&paket.ad);
+            }
+        }
+        Self::olustur(&kaynak, kokler, secenekler)
+    }
+
+    // Kurulu paketler veritabanından graf oluşturur (ek bağımlılıklar da bağımlılık kenarı sayılır).
+    // kokler: Başlangıç paketleri; boşsa tüm kurulu küme.
+    pub fn kurulu_paketlerden(veritabani: &KuruluVeritabani, kokler: &[&str], secenekler: &GrafSecenekleri) -> Self {
+        // Ek bağımlılıklar kayıtta ayrı tutulduğu için birleştirilmiş listeler önceden hazırlanır.
+        let birlesik: BTreeMap<&str, Vec<String>> = veritabani
+            .paketler
+            .values()
+            .map(|k| (k.ad.as_str(), k.bagimliliklar.iter().chain(k.ek_bagimliliklar.iter()).cloned().collect()))
+            .collect();
+        let mut kaynak = Kaynak { paketler: BTreeMap::new(), saglayicilar: BTreeMap::new() };
+        for kayit in veritabani.paketler.values() {
+            kaynak.paketler.insert(&kayit.ad, (Some(kayit.surum.as_str()), &birlesik[kayit.ad.as_str()][..], &kayit.cakisir[..]));
+            for sanal in &kayit.saglar {
+                kaynak.saglayicilar.entry(sanal.as_str()).or_default().push(&kayit.ad);
+            }
+        }
+        Self::olustur(&kaynak, kokler, secenekler)
+    }
+
+    // Köklerden genişlik öncelikli gezinerek grafı kurar; derinlik sınırının ötesindeki düğümler eklenmez.
+    fn olustur(kaynak: &Kaynak, kokler: &[&str], secenekler: &GrafSecenekleri) -> Self {
+        let mut graf = BagimlilikGrafi::default();
+        let mut kuyruk: VecDeque<(String, usize)> = VecDeque::new();
+        if kokler.is_empty() {
+            kuyruk.extend(kaynak.paketler.keys().map(|ad| ((*ad).to_owned(), 0)));
+        } else {
+            kuyruk.extend(kokler.iter().map(|ad| ((*ad).to_owned(), 0)));
+        }
+
+        while let Some((ad, derinlik)) = kuyruk.pop_front() {
+            if graf.dugumler.contains_key(&ad) {
+                continue;
+            }
+            let (surum, komsular, tur) = match kaynak.paketler.get(ad.as_str()) {
+                Some((surum, bagimliliklar, _)) => (surum.map(|s| s.to_owned()), bagimliliklar.to_vec(), DugumTuru::Paket),
+                None => match kaynak.saglayicilar.get(ad.as_str()) {
+                    Some(saglayicilar) => (None, saglayicilar.iter().map(|s| (*s).to_owned()).collect(), DugumTuru::Sanal),
+                    None => (None, Vec::new(), DugumTuru::Eksik),
+                },
+            };
+            graf.dugumler.insert(ad.clone(), Dugum { ad: ad.clone(), surum, tur, derinlik });
+
+            if secenekler.azami_derinlik.map_or(false, |azami| derinlik >= azami) {
+                continue; // Sınırdaki düğümün kenarları çizilmez
+            }
+            let kenar_turu = if tur == DugumTuru::Sanal { KenarTuru::Saglayici } else { KenarTuru::Bagimlilik };
+            for komsu in komsular {
+                graf.kenarlar.insert(Kenar { kaynak: ad.clone(), hedef: komsu.clone(), tur: kenar_turu });
+                kuyruk.push_back((komsu, derinlik + 1));
+            }
+        }
+
+        // Derinlik sınırı yüzünden eklenmeyen uçlara giden kenarlar atılır.
+        let dugumler = &graf.dugumler;
+        graf.kenarlar.retain(|k| dugumler.contains_key(&k.hedef));
+
+        if secenekler.cakismalari_goster {
+            for (ad, (_, _, cakisir)) in &kaynak.paketler {
+                if !graf.dugumler.contains_key(*ad) {
+                    continue;
+                }
+                for diger in cakisir.iter() {
+                    if graf.dugumler.contains_key(diger) {
+                        graf.kenarlar.insert(Kenar { kaynak: (*ad).to_owned(), hedef: diger.clone(), tur: KenarTuru::Cakisma });
+                    }
+                }
+            }
+        }
+        graf
+    }
+
+    // Bir düğümün bağımlılık/sağlayıcı kenarlarıyla ulaştığı komşuları (çakışma kenarları hariç).
+    pub fn komsular(&self, ad: &str) -> Vec<&str> {
+        self.kenarlar
+            .iter()
+            .filter(|k| k.kaynak == ad && k.tur != KenarTuru::Cakisma)
+            .map(|k| k.hedef.as_str())
+            .collect()
+    }
+
+    // Güçlü bağlı bileşenleri (Tarjan) döndürür. Her bileşen sıralıdır; bileşenler, bağımlılıklar
+    // bağımlı olanlardan önce gelecek şekilde (ters topolojik) sıralanır.
+    pub fn guclu_bagli_bilesenler(&self) -> Vec<Vec<String>> {
+        // Yinelemeli Tarjan: derin graflarda yığın taşmasını önlemek için özyineleme kullanılmaz.
+        let adlar: Vec<&str> = self.dugumler.keys().map(|a| a.as_str()).collect();
+        let sira: BTreeMap<&str, usize> = adlar.iter().enumerate().map(|(i, a)| (*a, i)).collect();
+        let komsular: Vec<Vec<usize>> = adlar.iter().map(|a| self.komsular(a).iter().map(|k| sira[k]).collect()).collect();
+
+        let n = adlar.len();
+        let mut indeks = vec_dolu(n, usize::MAX);
+        let mut dusuk = vec_dolu(n, 0usize);
+        let mut yiginda = vec_dolu(n, false);
+        let mut yigin: Vec<usize> = Vec::new();
+        let mut sayac = 0usize;
+        let mut bilesenler: Vec<Vec<String>> = Vec::new();
+
+        for baslangic in 0..n {
+            if indeks[baslangic] != usize::MAX {
+                continue;
+            }
+            // (düğüm, sıradaki komşu konumu)
+            let mut cagri: Vec<(usize, usize)> = Vec::new();
+            cagri.push((baslangic, 0));
+            indeks[baslangic] = sayac;
+            dusuk[baslangic] = sayac;
+            sayac += 1;
+            yigin.push(baslangic);
+            yiginda[baslangic] = true;
+
+            while let Some(&(v, i)) = cagri.last() {
+                if i < komsular[v].len() {
+                    let w = komsular[v][i];
+                    if let Some(ust) = cagri.last_mut() {
+                        ust.1 += 1;
+                    }
+                    if indeks[w] == usize::MAX {
+                        indeks[w] = sayac;
+                        dusuk[w] = sayac;
+                        sayac += 1;
+                        yigin.push(w);
+                        yiginda[w] = true;
+                        cagri.push((w, 0));
+                    } else if yiginda[w] {
+                        dusuk[v] = dusuk[v].min(indeks[w]);
+                    }
+                    continue;
+                }
+                cagri.pop();
+                if let Some(&(ebeveyn, _)) = cagri.last() {
+                    dusuk[ebeveyn] = dusuk[ebeveyn].min(dusuk[v]);
+                }
+                if dusuk[v] == indeks[v] {
+                    let mut bilesen: Vec<String> = Vec::new();
+                    while let Some(w) = yigin.pop() {
+                        yiginda[w] = false;
+                        bilesen.push(adlar[w].to_owned());
+                        if w == v {
+                            break;
+                        }
+                    }
+                    bilesen.sort();
+                    bilesenler.push(bilesen);
+                }
+            }
+        }
+        bilesenler
+    }
+
+    // Döngüler: birden fazla düğümlü veya kendine kenarı olan güçlü bağlı bileşenler.
+    pub fn donguler(&self) -> Vec<Vec<String>> {
+        self.guclu_bagli_bilesenler()
+            .into_iter()
+            .filter(|b| b.len() > 1 || self.komsular(&b[0]).contains(&b[0].as_str()))
+            .collect()
+    }
+
+    // Bir döngüsel bileşen içinde, ilk düğümden başlayıp ona dönen en kısa yolu verir
+    // (örn. ["a", "b", "a"]); tanı mesajlarında döngüyü okunur göstermek için.
+    pub fn dongu_yolu(&self, bilesen: &[String]) -> Vec<String> {
+        let baslangic = match bilesen.first() {
+            Some(b) => b.as_str(),
+            None => return Vec::new(),
+        };
+        // Bileşen içinde genişlik öncelikli arama; onceki[x] = x'e gelinen düğüm.
+        let mut onceki: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut kuyruk: VecDeque<&str> = VecDeque::new();
+        kuyruk.push_back(baslangic);
+        while let Some(simdiki) = kuyruk.pop_front() {
+            for komsu in self.komsular(simdiki) {
+                if !bilesen.iter().any(|b| b == komsu) {
+                    continue;
+                }
+                if komsu == baslangic {
+                    let mut yol: Vec<String> = Vec::new();
+                    yol.push(baslangic.to_owned());
+                    let mut adim = simdiki;
+                    while adim != baslangic {
+                        yol.push(adim.to_owned());
+                        adim = onceki[adim];
+                    }
+                    yol.push(baslangic.to_owned());
+                    // Geri izleme ters sırada topladı; başlangıç iki uçta olduğundan sadece ortası ters çevrilir.
+                    let son = yol.len() - 1;
+                    yol[1..son].reverse();
+                    return yol;
+                }
+                if !onceki.contains_key(komsu) {
+                    onceki.insert(komsu, simdiki);
+                    kuyruk.push_back(komsu);
+                }
+            }
+        }
+        bilesen.to_vec() // Döngü yoksa (tek düğüm, kendine kenarsız) bileşenin kendisi
+    }
+
+    // Graphviz DOT çıktısı üretir.
+    pub fn dot_olustur(&self, secenekler: &GrafSecenekleri) -> String {
+        let donguler = if secenekler.donguleri_vurgula { self.donguler() } else { Vec::new() };
+        let dongu_bul = |ad: &str| donguler.iter().position(|d| d.iter().any(|x| x == ad));
+        let mut cikti = String::new();
+        let _ = writeln!(cikti, "digraph bagimliliklar {{");
+        let _ = writeln!(cikti, "  rankdir=LR;");
+        let _ = writeln!(cikti, "  node [shape=box];");
+        for dugum in self.dugumler.values() {
+            let etiket = match &dugum.surum {
+                Some(surum) => format!("{}\\n{}", dot_kacis(&dugum.ad), dot_kacis(surum)),
+                None => dot_kacis(&dugum.ad),
+            };
+            let mut nitelikler = format!("label=\"{}\"", etiket);
+            match dugum.tur {
+                DugumTuru::Paket => {}
+                DugumTuru::Sanal => nitelikler.push_str(", shape=ellipse, style=dashed"),
+                DugumTuru::Eksik => nitelikler.push_str(", style=dotted, fontcolor=gray"),
+            }
+            if dongu_bul(&dugum.ad).is_some() {
+                nitelikler.push_str(", color=red, penwidth=2");
+            }
+            let _ = writeln!(cikti, "  \"{}\" [{}];", dot_kacis(&dugum.ad), nitelikler);
+        }
+        for kenar in &self.kenarlar {
+            let mut nitelikler = String::new();
+            match kenar.tur {
+                KenarTuru::Bagimlilik => {}
+                KenarTuru::Saglayici => nitelikler.push_str("style=dashed, label=\"sağlar\""),
+                KenarTuru::Cakisma => nitelikler.push_str("style=dashed, color=orange, dir=none, label=\"çakışır\""),
+            }
+            // Sadece aynı döngüdeki iki düğüm arasındaki kenar döngünün parçasıdır.
+            if kenar.tur != KenarTuru::Cakisma && dongu_bul(&kenar.kaynak).is_some() && dongu_bul(&kenar.kaynak) == dongu_bul(&kenar.hedef) {
+                if !nitelikler.is_empty() {
+                    nitelikler.push_str(", ");
+                }
+                nitelikler.push_str("color=red, penwidth=2");
+            }
+            if nitelikler.is_empty() {
+                let _ = writeln!(cikti, "  \"{}\" -> \"{}\";", dot_kacis(&kenar.kaynak), dot_kacis(&kenar.hedef));
+            } else {
+                let _ = writeln!(cikti, "  \"{}\" -> \"{}\" [{}];", dot_kacis(&kenar.kaynak), dot_kacis(&kenar.hedef), nitelikler);
+            }
+        }
+        let _ = writeln!(cikti, "}}");
+        cikti
+    }
+
+    // JSON çıktısı üretir: {"dugumler": [...], "kenarlar": [...], "donguler": [[...]]}.
+    // serde_json std gerektirdiği için elle yazılır.
+    pub fn json_olustur(&self, secenekler: &GrafSecenekleri) -> String {
+        let donguler = if secenekler.donguleri_vurgula { self.donguler() } else { Vec::new() };
+        let mut cikti = String::from("{\"dugumler\":[");
+        for (i, dugum) in self.dugumler.values().enumerate() {
+            if i > 0 {
+                cikti.push(',');
+            }
+            let tur = match dugum.tur {
+                DugumTuru::Paket => "paket",
+                DugumTuru::Sanal => "sanal",
+                DugumTuru::Eksik => "eksik",
+            };
+            let surum = match &dugum.surum {
+                Some(s) => format!("\"{}\"", json_kacis(s)),
+                None => "null".to_string(),
+            };
+            let _ = write!(cikti, "{{\"ad\":\"{}\",\"surum\":{},\"tur\":\"{}\",\"derinlik\":{}}}", json_kacis(&dugum.ad), surum, tur, dugum.derinlik);
+        }
+        cikti.push_str("],\"kenarlar\":[");
+        for (i, kenar) in self.kenarlar.iter().enumerate() {
+            if i > 0 {
+                cikti.push(',');
+            }
+            let tur = match kenar.tur {
+                KenarTuru::Bagimlilik => "bagimlilik",
+                KenarTuru::Saglayici => "saglayici",
+                KenarTuru::Cakisma => "cakisma",
+            };
+            let _ = write!(cikti, "{{\"kaynak\":\"{}\",\"hedef\":\"{}\",\"tur\":\"{}\"}}", json_kacis(&kenar.kaynak), json_kacis(&kenar.hedef), tur);
+        }
+        cikti.push_str("],\"donguler\":[");
+        for (i, dongu) in donguler.iter().enumerate() {
+            if i > 0 {
+                cikti.push(',');
+            }
+            let adlar: Vec<String> = dongu.iter().map(|a| format!("\"{}\"", json_kacis(a))).collect();
+            let _ = write!(cikti, "[{}]", adlar.join(","));
+        }
+        cikti.push_str("]}");
+        cikti
+    }
+}
+
+// no_std'de vec![deger; n] yerine (Clone gerektirir).
+fn vec_dolu<T: Clone>(n: usize, deger: T) -> Vec<T> {
+    let mut v = Vec::with_capacity(n);
+    v.resize(n, deger);
+    v
+}
+
+// DOT tırnaklı kimlikleri için kaçış.
+fn dot_kacis(metin: &str) -> String {
+    metin.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// JSON dizgeleri için kaçış (kontrol karakterleri \u00XX olarak).
+fn json_kacis(metin: &str) -> String {
+    let mut cikti = String::with_capacity(metin.len());
+    for c in metin.chars() {
+        match c {
+            '"' => cikti.push_str("\\\""),
+            '\\' => cikti.push_str("\\\\"),
+            '\n' => cikti.push_str("\\n"),
+            '\r' => cikti.push_str("\\r"),
+            '\t' => cikti.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(cikti, "\\u{:04x}", c as u32);
+            }
+            c => cikti.push(c),
+        }
+    }
+    cikti
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paket(ad: &str, bagimliliklar: &[&str]) -> Paket {
+        Paket::yeni(ad.to_string(), "1.0".to_string(), bagimliliklar.iter().map(|b| b.to_string()).collect())
+    }
+
+    fn ornek() -> Vec<Paket> {
+        let mut postfix = paket("postfix", &["libc"]);
+        postfix.saglar.push("mta".to_string());
+        let mut exim = paket("exim", &[]);
+        exim.saglar.push("mta".to_string());
+        exim.cakisir.push("postfix".to_string());
+        vec![paket("mutt", &["mta", "libc"]), postfix, exim, paket("libc", &[]), paket("a", &["b"]), paket("b", &["a"])]
+    }
+
+    #[test]
+    fn test_derinlik_siniri() {
+        let sec = GrafSecenekleri { azami_derinlik: Some(1), ..Default::default() };
+        let graf = BagimlilikGrafi::depodan(&ornek(), &["mutt"], &sec);
+        let adlar: Vec<&str> = graf.dugumler.keys().map(|a| a.as_str()).collect();
+        assert_eq!(adlar, vec!["libc", "mta", "mutt"]);
+        assert_eq!(graf.dugumler["mta"].tur, DugumTuru::Sanal);
+        assert_eq!(graf.kenarlar.len(), 2); // mta'nın sağlayıcılarına kenar çizilmez
+    }
+
+    #[test]
+    fn test_donguler_ve_cakismalar() {
+        let sec = GrafSecenekleri { donguleri_vurgula: true, cakismalari_goster: true, ..Default::default() };
+        let graf = BagimlilikGrafi::depodan(&ornek(), &[], &sec);
+        assert_eq!(graf.donguler(), vec![vec!["a".to_string(), "b".to_string()]]);
+        assert!(graf.kenarlar.contains(&Kenar { kaynak: "exim".to_string(), hedef: "postfix".to_string(), tur: KenarTuru::Cakisma }));
+
+        let dot = graf.dot_olustur(&sec);
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("\"a\" -> \"b\" [color=red, penwidth=2];"));
+        let json = graf.json_olustur(&sec);
+        assert!(json.contains("\"donguler\":[[\"a\",\"b\"]]"));
+    }
+
+    #[test]
+    fn test_bilesenler_ters_topolojik() {
+        let graf = BagimlilikGrafi::depodan(&ornek(), &["mutt"], &GrafSecenekleri::default());
+        let bilesenler = graf.guclu_bagli_bilesenler();
+        let konum = |ad: &str| bilesenler.iter().position(|b| b.iter().any(|x| x == ad)).unwrap();
+        assert!(konum("libc") < konum("postfix"));
+        assert!(konum("postfix") < konum("mta"));
+        assert!(konum("mta") < konum("mutt"));
+    }
+
+    #[test]
+    fn test_dongu_yolu() {
+        let paketler = vec![paket("a", &["b"]), paket("b", &["c"]), paket("c", &["a"]), paket("d", &["d"])];
+        let graf = BagimlilikGrafi::depodan(&paketler, &[], &GrafSecenekleri::default());
+        let donguler = graf.donguler();
+        assert_eq!(graf.dongu_yolu(&donguler[0]), vec!["a", "b", "c", "a"]);
+        assert_eq!(graf.dongu_yolu(&donguler[1]), vec!["d", "d"]);
+    }
+
+    #[test]
+    fn test_json_kacis() {
+        assert_eq!(json_kacis("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+}
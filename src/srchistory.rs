@@ -0,0 +1,311 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+extern crate alloc; // String, Vec, BTreeSet için
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu serileştirme/deserileştirme
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::package::Paket;
+use crate::srcdatabase::{KuruluPaket, KuruluVeritabani};
+use crate::srcstaging::kaynagi_tasi;
+
+// no_std uyumlu print makroları
+use crate::print_macros::eprintln;
+
+// Tamamlanan ve geri alınan her işlemin kalıcı kaydı. İşlem günlüğü (srctransaction) sadece yürümekte olan
+// işlemi izler; geçmiş ise işlem bittikten sonra da saklanır ve `gecmis geri-al` ile bir işlemin tersi
+// uygulanabilir. Kayıt kimliği, işlem süresince günlük kayıtlarına eklenen işlem kimliğiyle aynıdır
+// (bkz. srclogging), böylece bir geçmiş kaydı günlüklerle eşleştirilebilir.
+
+// İşlem geçmişinin varsayılan Kaynak ID'si.
+pub const VARSAYILAN_GECMIS_KAYNAGI: &str = "sahne://config/islem_gecmisi.bin";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IslemSonucu {
+    Tamamlandi,
+    GeriAlindi, // İşlem başarısız oldu ve değişiklikleri geri alındı; kurulu durum değişmedi
+}
+
+impl IslemSonucu {
+    pub fn ad(self) -> &'static str {
+        match self {
+            IslemSonucu::Tamamlandi => "tamamlandı",
+            IslemSonucu::GeriAlindi => "başarısız",
+        }
+    }
+}
+
+// İşlemde sürümü veya varlığı değişen bir paket: işlem öncesi ve sonrası kayıtları.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PaketDegisikligi {
+    pub ad: String,
+    pub onceki: Option<KuruluPaket>,
+    pub sonraki: Option<KuruluPaket>,
+}
+
+impl PaketDegisikligi {
+    // "kuruldu 1.0", "kaldırıldı 1.0", "1.0 -> 2.0"
+    pub fn ozet(&self) -> String {
+        match (&self.onceki, &self.sonraki) {
+            (None, Some(sonraki)) => format!("kuruldu {}", sonraki.surum),
+            (Some(onceki), None) => format!("kaldırıldı {}", onceki.surum),
+            (Some(onceki), Some(sonraki)) => format!("{} -> {}", onceki.surum, sonraki.surum),
+            (None, None) => String::from("değişmedi"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GecmisKaydi {
+    pub kimlik: u64, // İşlem kimliği (srclogging::islem_kimligi_baslat)
+    pub baslangic_us: u64,
+    pub bitis_us: u64,
+    pub kullanici: String,
+    pub komut_satiri: String,
+    pub sonuc: IslemSonucu,
+    pub hata: Option<String>, // Geri alınan işlemin hatası
+    pub geri_alinan: Option<u64>, // Bu işlem `gecmis geri-al` ise tersi uygulanan işlemin kimliği
+    pub degisiklikler: Vec<PaketDegisikligi>,
+}
+
+// İki veritabanı arasında sürümü değişen, eklenen veya çıkarılan paketler (ada göre sıralı).
+pub fn degisiklikleri_bul(oncesi: &KuruluVeritabani, sonrasi: &KuruluVeritabani) -> Vec<PaketDegisikligi> {
+    let adlar: BTreeSet<&String> = oncesi.paketler.keys().chain(sonrasi.paketler.keys()).collect();
+    adlar
+        .into_iter()
+        .filter_map(|ad| {
+            let (onceki, sonraki) = (oncesi.paket(ad), sonrasi.paket(ad));
+            if onceki.map(|k| &k.surum) == sonraki.map(|k| &k.surum) {
+                return None;
+            }
+            Some(PaketDegisikligi { ad: ad.clone(), onceki: onceki.cloned(), sonraki: sonraki.cloned() })
+        })
+        .collect()
+}
+
+// Bir işlemin tersi: işlemin kaldırdığı/güncellediği paketlerin önceki kayıtları yeniden kurulur,
+// kurduğu paketler kaldırılır.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TersIslem {
+    pub kurulacaklar: Vec<KuruluPaket>,
+    pub kaldirilacaklar: Vec<String>,
+}
+
+// Kaydın ters işlemini hesaplar. İşlemin değiştirdiği paketler o zamandan beri yeniden değişmişse
+// (başka bir işlem onları güncellemiş/kaldırmışsa) ters işlem güvenli değildir ve hata döner.
+pub fn ters_islem(kayit: &GecmisKaydi, simdiki: &KuruluVeritabani) -> Result<TersIslem, String> {
+    if kayit.sonuc != IslemSonucu::Tamamlandi {
+        return Err(format!("{} işlemi tamamlanmamış; geri alınacak değişiklik yok", kimlik_metni(kayit.kimlik)));
+    }
+    if kayit.degisiklikler.is_empty() {
+        return Err(format!("{} işlemi hiçbir paketi değiştirmemiş", kimlik_metni(kayit.kimlik)));
+    }
+    let mut ters = TersIslem::default();
+    let mut degisenler = Vec::new();
+    for degisiklik in &kayit.degisiklikler {
+        let beklenen = degisiklik.sonraki.as_ref().map(|k| &k.surum);
+        let simdi = simdiki.paket(&degisiklik.ad).map(|k| &k.surum);
+        if beklenen != simdi {
+            degisenler.push(format!("{} (şu an: {})", degisiklik.ad, simdi.map_or("kurulu değil", |s| s.as_str())));
+            continue;
+        }
+        match &degisiklik.onceki {
+            Some(onceki) => ters.kurulacaklar.push(onceki.clone()),
+            None => ters.kaldirilacaklar.push(degisiklik.ad.clone()),
+        }
+    }
+    if !degisenler.is_empty() {
+        return Err(format!("bu işlemden sonra değişen paketler var: {}", degisenler.join(", ")));
+    }
+    // Kaldırılacaklar birbirine bağımlıysa bağımlı olan önce kaldırılır.
+    ters.kaldirilacaklar = simdiki.kaldirma_sirasi(&ters.kaldirilacaklar.iter().cloned().collect());
+    Ok(ters)
+}
+
+// Geri yüklenecek kaydın kurulum paketi: arşiv adı kayıtta varsa kayıttan, yoksa depoda aynı sürümden.
+pub fn kurulum_paketi(kayit: &KuruluPaket, depo: &[Paket]) -> Option<Paket> {
+    kayit.pakete().or_else(|| depo.iter().find(|p| p.ad == kayit.ad && p.surum == kayit.surum).cloned())
+}
+
+pub fn kimlik_metni(kimlik: u64) -> String {
+    crate::srclogging::kimlik_metni(kimlik)
+}
+
+// Kalıcı işlem geçmişi (eskiden yeniye). postcard ile serileştirilip tek bir Kaynakta saklanır.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct IslemGecmisi {
+    pub kayitlar: Vec<GecmisKaydi>,
+}
+
+impl IslemGecmisi {
+    // Geçmişi Kaynaktan okur. Kaynak yoksa (henüz hiç işlem yapılmamış) boş geçmiş döner.
+    pub fn oku(resource_id: &str) -> Result<Self, PaketYoneticisiHatasi> {
+        let handle = match resource::acquire(resource_id, resource::MODE_READ) {
+            Ok(h) => h,
+            Err(SahneError::ResourceNotFound) => return Ok(IslemGecmisi::default()),
+            Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+        };
+        let mut buffer = Vec::new();
+        let mut temp_buffer = [0u8; 512];
+        loop {
+            match resource::read(handle, &mut temp_buffer) {
+                Ok(0) => break,
+                Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+                Err(e) => {
+                    let _ = resource::release(handle);
+                    return Err(PaketYoneticisiHatasi::from(e));
+                }
+            }
+        }
+        let _ = resource::release(handle);
+
+        if buffer.is_empty() {
+            return Ok(IslemGecmisi::default());
+        }
+        postcard::from_bytes_copy::<IslemGecmisi>(&buffer).map_err(|e| {
+            eprintln!("İşlem geçmişi deserialize hatası ({}): {:?}", resource_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })
+    }
+
+    // Geçmişi geçici bir Kaynağa yazıp taşıyarak yerine koyar (bkz. KuruluVeritabani::yaz).
+    pub fn yaz(&self, resource_id: &str) -> Result<(), PaketYoneticisiHatasi> {
+        let veri = postcard::to_postcard(self).map_err(PaketYoneticisiHatasi::from)?;
+        let gecici_id = format!("{}.yeni", resource_id);
+        let handle = resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
+            .map_err(PaketYoneticisiHatasi::from)?;
+        let mut written = 0;
+        while written < veri.len() {
+            match resource::write(handle, &veri[written..]) {
+                Ok(0) => {
+                    let _ = resource::release(handle);
+                    return Err(PaketYoneticisiHatasi::from(SahneError::InvalidOperation));
+                }
+                Ok(n) => written += n,
+                Err(e) => {
+                    let _ = resource::release(handle);
+                    return Err(PaketYoneticisiHatasi::from(e));
+                }
+            }
+        }
+        resource::release(handle).map_err(PaketYoneticisiHatasi::from)?;
+        kaynagi_tasi(&gecici_id, resource_id).map_err(PaketYoneticisiHatasi::from)
+    }
+
+    // Bir kaydı geçmişin sonuna ekler ve geçmişi yazar. Kurulum kilidi alınmışken çağrılmalıdır.
+    pub fn kaydet(resource_id: &str, kayit: GecmisKaydi) -> Result<(), PaketYoneticisiHatasi> {
+        let mut gecmis = IslemGecmisi::oku(resource_id)?;
+        gecmis.kayitlar.push(kayit);
+        gecmis.yaz(resource_id)
+    }
+
+    // Kaydı tam kimlik veya kimliğin başıyla (en az 4 hane) bulur.
+    pub fn bul(&self, kimlik: &str) -> Result<&GecmisKaydi, PaketYoneticisiHatasi> {
+        let kimlik = kimlik.trim().to_ascii_lowercase();
+        if kimlik.len() < 4 || !kimlik.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(PaketYoneticisiHatasi::GecersizParametre(format!("'{}' geçerli bir işlem kimliği değil", kimlik)));
+        }
+        let mut eslesenler = self.kayitlar.iter().filter(|k| kimlik_metni(k.kimlik).starts_with(&kimlik));
+        match (eslesenler.next(), eslesenler.next()) {
+            (Some(kayit), None) => Ok(kayit),
+            (None, _) => Err(PaketYoneticisiHatasi::GecersizParametre(format!("'{}' kimlikli işlem geçmişte yok", kimlik))),
+            (Some(_), Some(_)) => Err(PaketYoneticisiHatasi::GecersizParametre(format!("'{}' birden fazla işlemle eşleşiyor", kimlik))),
+        }
+    }
+
+    // Verilen işlemi geri alan (tersini uygulayan ve tamamlanan) işlem varsa onun kimliği.
+    pub fn geri_alan(&self, kimlik: u64) -> Option<u64> {
+        self.kayitlar
+            .iter()
+            .find(|k| k.geri_alinan == Some(kimlik) && k.sonuc == IslemSonucu::Tamamlandi)
+            .map(|k| k.kimlik)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kurulu(paketler: &[(&str, &str)]) -> KuruluVeritabani {
+        let mut vt = KuruluVeritabani::default();
+        for (ad, surum) in paketler {
+            let mut paket = Paket::yeni(ad.to_string(), surum.to_string(), Vec::new());
+            paket.dosya_adi = Some(format!("{}-{}.tar.gz", ad, surum));
+            vt.ekle(KuruluPaket::paketten(&paket));
+        }
+        vt
+    }
+
+    fn kayit(kimlik: u64, oncesi: &KuruluVeritabani, sonrasi: &KuruluVeritabani) -> GecmisKaydi {
+        GecmisKaydi {
+            kimlik,
+            baslangic_us: 0,
+            bitis_us: 0,
+            kullanici: "ali".to_string(),
+            komut_satiri: "kur editor".to_string(),
+            sonuc: IslemSonucu::Tamamlandi,
+            hata: None,
+            geri_alinan: None,
+            degisiklikler: degisiklikleri_bul(oncesi, sonrasi),
+        }
+    }
+
+    #[test]
+    fn test_degisiklikler_ve_ters_islem() {
+        let oncesi = kurulu(&[("libc", "1.0"), ("eski", "1.0"), ("kabuk", "1.0")]);
+        let sonrasi = kurulu(&[("libc", "2.0"), ("editor", "1.0"), ("kabuk", "1.0")]);
+        let kayit = kayit(0xabcd, &oncesi, &sonrasi);
+        let ozetler: Vec<String> = kayit.degisiklikler.iter().map(|d| format!("{} {}", d.ad, d.ozet())).collect();
+        assert_eq!(ozetler, ["editor kuruldu 1.0", "eski kaldırıldı 1.0", "libc 1.0 -> 2.0"]);
+
+        let ters = ters_islem(&kayit, &sonrasi).unwrap();
+        assert_eq!(ters.kaldirilacaklar, ["editor"]);
+        let geri: Vec<(&str, &str)> = ters.kurulacaklar.iter().map(|k| (k.ad.as_str(), k.surum.as_str())).collect();
+        assert_eq!(geri, [("eski", "1.0"), ("libc", "1.0")]);
+        assert_eq!(kurulum_paketi(&ters.kurulacaklar[1], &[]).unwrap().dosya_adi.as_deref(), Some("libc-1.0.tar.gz"));
+    }
+
+    #[test]
+    fn test_kurulum_gecmisi() {
+        // kur komutu (plani_uygula) boş sisteme bağımlılığıyla birlikte bir paket kurar.
+        let oncesi = KuruluVeritabani::default();
+        let sonrasi = kurulu(&[("libx", "1.0"), ("uygulama", "1.0")]);
+        let kayit = kayit(2, &oncesi, &sonrasi);
+        let ozetler: Vec<String> = kayit.degisiklikler.iter().map(|d| format!("{} {}", d.ad, d.ozet())).collect();
+        assert_eq!(ozetler, ["libx kuruldu 1.0", "uygulama kuruldu 1.0"]);
+
+        let mut ters = ters_islem(&kayit, &sonrasi).unwrap();
+        ters.kaldirilacaklar.sort();
+        assert_eq!(ters.kaldirilacaklar, ["libx", "uygulama"]);
+        assert!(ters.kurulacaklar.is_empty());
+    }
+
+    #[test]
+    fn test_sonradan_degisen_paket_ters_islemi_engeller() {
+        let oncesi = kurulu(&[("libc", "1.0")]);
+        let sonrasi = kurulu(&[("libc", "2.0")]);
+        let kayit = kayit(1, &oncesi, &sonrasi);
+        let hata = ters_islem(&kayit, &kurulu(&[("libc", "3.0")])).unwrap_err();
+        assert_eq!(hata, "bu işlemden sonra değişen paketler var: libc (şu an: 3.0)");
+    }
+
+    #[test]
+    fn test_kimlik_onekiyle_bul() {
+        let bos = KuruluVeritabani::default();
+        let gecmis = IslemGecmisi { kayitlar: vec![kayit(0x1234_0000_0000_0001, &bos, &bos), kayit(0x1234_0000_0000_0002, &bos, &bos)] };
+        assert_eq!(gecmis.bul("1234000000000002").unwrap().kimlik, 0x1234_0000_0000_0002);
+        assert!(gecmis.bul("1234").is_err()); // İki kayıtla eşleşir
+        assert!(gecmis.bul("12").is_err()); // Çok kısa
+    }
+}
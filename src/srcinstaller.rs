@@ -5,6 +5,7 @@ use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::format; // Hata mesajları için
 use alloc::borrow::ToOwned; // &str -> String için
+use alloc::collections::BTreeMap; // Plan uygulamada ad -> paket eşlemesi ve betik tanımları için
 
 // Paket struct tanımını içeren modül
 use crate::package::Paket;
@@ -17,13 +18,38 @@ use crate::Handle; // Kaynak Handle'ları
 use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
 // SahneError'dan dönüşüm From implementasyonu ile sağlanacak
 
-// ZIP arşiv işlemleri modülü
+// Arşiv (zip/tar.*) işlemleri modülü
 use crate::srcarchive;
+// Hangi arşiv sıkıştırmalarının kabul edildiğini belirleyen özellik kümesi
+use crate::srcfeatures::FeatureSet;
+// İndirirken doğrulayan akışlı kurulum
+use crate::srcpipeline;
+// Hazırlık alanı ve atomik etkinleştirme
+use crate::srcstaging::{self, HazirlikAlani};
+// Dosya çakışması denetimi ve kurulu dosyalar veritabanı
+use crate::srcconflict;
+use crate::srcdatabase::{self, KuruluPaket, KuruluVeritabani};
+// Bağımlılık çözüm planı (özellik seçimlerinin kaydı için)
+use crate::srcdependency::{BagimlilikYoneticisi, CozumPlani};
+
+// Kaldırılan paketlerin yedek adları için zaman damgası
+use crate::task;
+// Kurulum/kaldırma betikleri (hook) ve işlem günlüğü
+use crate::srcscript::{self, BetikAsamasi, BetikOrtami};
+use crate::srctransaction::{self, IslemYoneticisi};
+// Biten her işlem kalıcı geçmişe yazılır
+use crate::srchistory::{self, GecmisKaydi, IslemGecmisi, IslemSonucu};
+// Kurulu dosyaların manifestosu ve onarımı
+use crate::srcverify::{self, ManifestGirdisi};
+// Yapılandırma dosyalarındaki kullanıcı değişikliklerinin korunması
+use crate::srcconffiles::{self, KurulumKararlari};
+// Betik sandbox profilleri paketin güven seviyesine göre seçilir
+use crate::srcsecurity::SandboxAyarlari;
+use crate::srctrust::{self, TrustManager};
 
 // no_std uyumlu print makroları
 use crate::print_macros::{println, eprintln};
 
-
 // Paket kurulum ve indirme işlemlerini yöneten yapı.
 pub struct KurulumYoneticisi {
     // Paket deposunun temel Kaynak ID'si (örn. "sahne://remotepkgrepo/packages/")
@@ -32,6 +58,186 @@ pub struct KurulumYoneticisi {
     pub kurulum_base_resource_id: String,
     // Önbellek temel Kaynak ID'si (örn. "sahne://cache/packages/") - İndirilen paketler buraya kaydedilecek
     pub onbellek_base_resource_id: String,
+    // Kabul edilen arşiv biçimleri (varsayılan: çözücüsü olan tüm tar sıkıştırmaları)
+    pub arsiv_ozellikleri: FeatureSet,
+    // Kurulu paketler ve dosyaları veritabanının Kaynak ID'si
+    pub veritabani_resource_id: String,
+    // Betik çıktılarının ve işlem adımlarının yazıldığı işlem günlüğünün Kaynak ID'si
+    pub islem_gunlugu_resource_id: String,
+    // Tek bir paket betiğinin azami çalışma süresi (mikrosaniye)
+    pub betik_zaman_asimi_us: u64,
+    // Güvenilen ve güvenilmeyen paketlerin betiklerine uygulanacak sandbox profilleri
+    pub sandbox: SandboxAyarlari,
+    // İşlem geçmişinin Kaynak ID'si ve geçmiş kayıtlarına yazılan, işlemi başlatan kullanıcı ve komut satırı
+    pub gecmis_resource_id: String,
+    pub kullanici: String,
+    pub komut_satiri: String,
+    // Yapılandırma dosyalarının varsayılan kopyaları ve bekleyen birleştirmelerinin dizin Kaynak ID'si
+    pub yapilandirma_dosyalari_dizini: String,
+    // Paket betiklerinin dizin Kaynak ID'si; betikler "<dizin>/<paket adı>/" altında aranır
+    pub betik_base_resource_id: String,
+    // Betiklerin güven seviyesini belirleyen güvenilen yayıncı ve paket listelerinin Kaynak ID'leri
+    pub guvenilen_yayincilar_resource_id: String,
+    pub guvenilen_paketler_resource_id: String,
+}
+
+// Yürütülen bir kurulum/kaldırma işleminin geri alınabilmesi için gereken durum.
+// İşlem başında veritabanının kopyası alınır; etkinleştirilen/kaldırılan her paketin kurulum dizini ve
+// kenara alınan önceki içeriği (yedek) kaydedilir.
+struct IslemDurumu {
+    islem: IslemYoneticisi,
+    kimlik: u64, // İşlem kimliği (geçmiş kaydının kimliği)
+    baslangic_us: u64,
+    geri_alinan: Option<u64>, // Bu işlem bir geçmiş kaydının tersiyse o kaydın kimliği
+    oncesi: KuruluVeritabani,
+    dizinler: Vec<(String, Option<String>)>, // (kurulum dizini, yedek)
+    guven: TrustManager, // Betiklerin güven seviyesi işlem boyunca aynı listeden belirlenir
+}
+
+impl IslemDurumu {
+    // İşlemi günlükte tamamlandı olarak işaretler, yedekleri siler ve geçmişe yazar. Yedekler ancak işlem
+    // günlükte tamamlandıktan sonra silinir; silinemeyenler bir sonraki temizlikte silinir (bkz.
+    // srcstaging::artik_hazirliklari_temizle).
+    fn tamamla(self, kurulum: &KurulumYoneticisi) -> Result<(), PaketYoneticisiHatasi> {
+        let sonuc = self.islem.tamamla_islem();
+        if sonuc.is_ok() {
+            for yedek in self.dizinler.iter().filter_map(|(_, yedek)| yedek.as_ref()) {
+                if let Err(e) = srcstaging::kaynagi_sil(yedek, true) {
+                    eprintln!("Yedek silinemedi ({}): {:?}", yedek, e);
+                }
+            }
+        }
+        kurulum.gecmise_yaz(&self, IslemSonucu::Tamamlandi, None);
+        sonuc
+    }
+
+    // Kurulum dizinlerini ters sırayla işlem öncesi hallerine döndürür, veritabanını geri yazar ve işlemi
+    // geçmişe başarısız olarak yazar. Geri yükleme hataları sadece loglanır; geri yüklenemeyen yedekler
+    // hazırlık dizininde ".kurtarma" olarak ayrılır (bkz. srcstaging::kurtarmaya_ayir).
+    fn geri_al(self, kurulum: &KurulumYoneticisi, hata: &PaketYoneticisiHatasi) {
+        let veritabani_resource_id = kurulum.veritabani_resource_id.as_str();
+        eprintln!("İşlem geri alınıyor...");
+        for (dizin, yedek) in self.dizinler.iter().rev() {
+            if let Err(e) = srcstaging::yedegi_geri_yukle(dizin, yedek.as_deref()) {
+                eprintln!("Kurulum dizini geri yüklenemedi ({}): {:?}", dizin, e);
+                if let Some(yedek) = yedek {
+                    srcstaging::kurtarmaya_ayir(yedek);
+                }
+            }
+        }
+        if let Err(e) = self.oncesi.yaz(veritabani_resource_id) {
+            eprintln!("Veritabanı geri yüklenemedi ({}): {:?}", veritabani_resource_id, e);
+        }
+        if let Err(e) = self.islem.geri_al_islem() {
+            eprintln!("İşlem günlüğü geri alınamadı: {:?}", e);
+        }
+        kurulum.gecmise_yaz(&self, IslemSonucu::GeriAlindi, Some(format!("{:?}", hata)));
+        crate::srclogging::islem_kimligi_bitir();
+    }
+}
+
+// Bir kurulum işleminin sırayla uygulanan adımları (bkz. islem_adimlari).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IslemAdimi {
+    Kaldir(String),       // Paketi kaldırma betikleriyle birlikte kaldır
+    BetikOncesi(String),  // Kurulum/güncelleme öncesi betiği
+    Kur(String),          // Arşivi hazırlık alanına çıkar ve etkinleştir
+    BetikSonrasi(String), // Kurulum/güncelleme sonrası betiği
+}
+
+// Çözüm planını sıralı işlem adımlarına çevirir. Gruplar topolojik sırayla işlenir: bir paketin
+// bağımlılıkları (sonrası betikleri dahil) kendisinden önce kurulur. Her grubun öncesi betikleri grup
+// kurulmadan önce, sonrası betikleri grubun tüm paketleri kurulduktan sonra çalışır; böylece döngüsel
+// bağımlı paketler (çok paketli gruplar) betikleri çalışırken birbirlerinin dosyalarını bulabilir.
+// degistirir/cakisir nedeniyle kaldırılacak paketler, yerlerini alan paketin grubundan hemen önce
+// kaldırma betikleriyle birlikte kaldırılır (kaldıranı planda olmayanlar en başta).
+pub fn islem_adimlari(plan: &CozumPlani) -> Vec<IslemAdimi> {
+    let kaldiran = |ad: &String| plan.kaldiranlar.get(ad).filter(|k| plan.kurulacaklar.contains(k));
+    let mut adimlar: Vec<IslemAdimi> = plan.kaldirilacaklar.iter()
+        .filter(|ad| kaldiran(ad).is_none())
+        .map(|ad| IslemAdimi::Kaldir(ad.clone()))
+        .collect();
+    for grup in &plan.kurulum_gruplari {
+        adimlar.extend(plan.kaldirilacaklar.iter()
+            .filter(|ad| kaldiran(ad).map_or(false, |k| grup.contains(k)))
+            .map(|ad| IslemAdimi::Kaldir(ad.clone())));
+        adimlar.extend(grup.iter().map(|ad| IslemAdimi::BetikOncesi(ad.clone())));
+        adimlar.extend(grup.iter().map(|ad| IslemAdimi::Kur(ad.clone())));
+        adimlar.extend(grup.iter().map(|ad| IslemAdimi::BetikSonrasi(ad.clone())));
+    }
+    adimlar
+}
+
+// İşlem adımlarını uygulayan taraf. Adımların sırası ve hata durumunda geri alma (islemi_yurut)
+// adımların nasıl uygulandığından bağımsızdır.
+trait IslemYurutucu {
+    fn adimi_uygula(&mut self, adim: &IslemAdimi) -> Result<(), PaketYoneticisiHatasi>;
+    // Tüm adımlardan sonra, işlem tamamlanmadan önce çalışır (seçimlerin kaydı, tetikleyiciler).
+    fn son_adimlar(&mut self) -> Result<(), PaketYoneticisiHatasi>;
+    fn tamamla(self) -> Result<(), PaketYoneticisiHatasi>;
+    fn geri_al(self, hata: &PaketYoneticisiHatasi);
+}
+
+// Adımları sırayla uygular. İlk hatada kalan adımlar atlanır ve o ana kadar yapılanlar geri alınır.
+fn islemi_yurut<Y: IslemYurutucu>(adimlar: &[IslemAdimi], mut yurutucu: Y) -> Result<(), PaketYoneticisiHatasi> {
+    let sonuc = adimlar.iter()
+        .try_for_each(|adim| yurutucu.adimi_uygula(adim))
+        .and_then(|_| yurutucu.son_adimlar());
+    match sonuc {
+        Ok(()) => yurutucu.tamamla(),
+        Err(e) => {
+            yurutucu.geri_al(&e);
+            Err(e)
+        }
+    }
+}
+
+// Adımları kurulum yöneticisi üzerinde, tek bir geri alınabilir işlem olarak uygular.
+struct PlanYurutucu<'a> {
+    kurulum: &'a KurulumYoneticisi,
+    harita: &'a BTreeMap<&'a str, &'a Paket>,
+    durum: IslemDurumu,
+    secimler: Option<&'a CozumPlani>, // Özellik seçimleri ve kurulum nedenleri kaydedilecek plan
+    geri_yazilacaklar: Vec<KuruluPaket>, // Kurulumdan sonra aynen geri yazılacak kayıtlar (bkz. durumu_geri_yukle)
+}
+
+impl<'a> PlanYurutucu<'a> {
+    fn paket(&self, ad: &str) -> Result<&'a Paket, PaketYoneticisiHatasi> {
+        self.harita.get(ad).copied().ok_or_else(|| PaketYoneticisiHatasi::PaketBulunamadi(ad.to_owned()))
+    }
+}
+
+impl IslemYurutucu for PlanYurutucu<'_> {
+    fn adimi_uygula(&mut self, adim: &IslemAdimi) -> Result<(), PaketYoneticisiHatasi> {
+        match adim {
+            IslemAdimi::Kaldir(ad) => self.kurulum.tek_paketi_kaldir(ad, &mut self.durum),
+            IslemAdimi::BetikOncesi(ad) => self.kurulum.kurulum_betigini_calistir(self.paket(ad)?, false, &self.durum),
+            IslemAdimi::Kur(ad) => self.kurulum.islemde_kur(self.paket(ad)?, &mut self.durum),
+            IslemAdimi::BetikSonrasi(ad) => self.kurulum.kurulum_betigini_calistir(self.paket(ad)?, true, &self.durum),
+        }
+    }
+
+    fn son_adimlar(&mut self) -> Result<(), PaketYoneticisiHatasi> {
+        if let Some(plan) = self.secimler {
+            self.kurulum.secimleri_kaydet(plan)?;
+        }
+        if !self.geri_yazilacaklar.is_empty() {
+            let mut veritabani = KuruluVeritabani::oku(&self.kurulum.veritabani_resource_id)?;
+            for kayit in &self.geri_yazilacaklar {
+                veritabani.ekle(kayit.clone());
+            }
+            veritabani.yaz(&self.kurulum.veritabani_resource_id)?;
+        }
+        self.kurulum.tetikleyicileri_calistir(&self.durum)
+    }
+
+    fn tamamla(self) -> Result<(), PaketYoneticisiHatasi> {
+        self.durum.tamamla(self.kurulum)
+    }
+
+    fn geri_al(self, hata: &PaketYoneticisiHatasi) {
+        self.durum.geri_al(self.kurulum, hata)
+    }
 }
 
 impl KurulumYoneticisi {
@@ -48,6 +254,18 @@ impl KurulumYoneticisi {
             paket_deposu_base_resource_id,
             kurulum_base_resource_id,
             onbellek_base_resource_id,
+            arsiv_ozellikleri: srcarchive::varsayilan_arsiv_ozellikleri(),
+            veritabani_resource_id: String::from(srcdatabase::VARSAYILAN_VERITABANI_KAYNAGI),
+            islem_gunlugu_resource_id: String::from(srctransaction::VARSAYILAN_ISLEM_GUNLUGU_KAYNAGI),
+            betik_zaman_asimi_us: srcscript::VARSAYILAN_BETIK_ZAMAN_ASIMI_US,
+            sandbox: SandboxAyarlari::default(),
+            gecmis_resource_id: String::from(srchistory::VARSAYILAN_GECMIS_KAYNAGI),
+            kullanici: String::new(),
+            komut_satiri: String::new(),
+            yapilandirma_dosyalari_dizini: String::from(srcconffiles::VARSAYILAN_YAPILANDIRMA_DOSYALARI_DIZINI),
+            betik_base_resource_id: String::from(srcscript::VARSAYILAN_BETIK_DIZINI),
+            guvenilen_yayincilar_resource_id: String::from(srctrust::VARSAYILAN_YAYINCILAR_KAYNAGI),
+            guvenilen_paketler_resource_id: String::from(srctrust::VARSAYILAN_PAKETLER_KAYNAGI),
         }
     }
 
@@ -137,38 +355,401 @@ impl KurulumYoneticisi {
         }
     }
 
-    // Paketi önbellekten kurulum dizinine kurar (çıkarma ve kopyalama).
+    // İşlemdeki paketlerin dosyalarını kurulu dosyalar veritabanı ve birbirleriyle karşılaştırır.
+    // Paketler arasında izin verilmemiş (degistirir/cakisir ile bildirilmemiş) ortak yol varsa,
+    // çakışan yolları ve sahiplerini listeleyen ConflictError döner; hiçbir şey kurulmamış olur.
+    // paketler: Bu işlemde kurulacak/güncellenecek paketler.
+    // Dönüş değeri: Uygulanacak devralmalar ve kaldırılacak paketler.
+    pub fn kurulum_planini_dogrula(&self, paketler: &[Paket]) -> Result<srcconflict::DosyaPlani, PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        srcconflict::dosya_cakismalarini_bul(paketler, &veritabani).map_err(|cakismalar| {
+            let metin = srcconflict::dosya_cakismalarini_bicimlendir(&cakismalar);
+            eprintln!("{}", metin);
+            PaketYoneticisiHatasi::ConflictError(metin)
+        })
+    }
+
+    // Etkinleştirilen paketi, çıkarmada üretilen manifestosuyla kurulu paketler veritabanına kaydeder ve
+    // planlanan devralmaları uygular. degistirir/cakisir nedeniyle kaldırılan paketler bu noktada zaten
+    // kaldırılmıştır (bkz. islem_adimlari).
+    fn veritabanina_kaydet(&self, paket: &Paket, plan: &srcconflict::DosyaPlani, manifesto: Vec<ManifestGirdisi>) -> Result<(), PaketYoneticisiHatasi> {
+        let mut veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        for devralma in plan.devralmalar.iter().filter(|d| d.yeni_sahip == paket.ad) {
+            veritabani.sahipligi_devret(&devralma.yol, &devralma.eski_sahip);
+        }
+        let mut kayit = KuruluPaket::paketten(paket);
+        kayit.manifesto = manifesto;
+        // Güncellemede önceki kurulumda seçilmiş (ve yeni sürümde hâlâ tanımlı) özellikler ve kurulum nedeni korunur.
+        if let Some(onceki) = veritabani.paket(&paket.ad) {
+            kayit.etkin_ozellikler = onceki.etkin_ozellikler.iter().filter(|o| paket.ozellikler.contains_key(*o)).cloned().collect();
+            kayit.ek_bagimliliklar = onceki.ek_bagimliliklar.clone();
+            kayit.kurulum_nedeni = onceki.kurulum_nedeni;
+        }
+        veritabani.ekle(kayit);
+        veritabani.yaz(&self.veritabani_resource_id)
+    }
+
+    // Çözüm planındaki özellik seçimlerini ve kurulum nedenlerini kurulu paket kayıtlarına yazar; sonraki
+    // güncellemelerde aynı özellikler (ve çektikleri bağımlılıklar) yeniden etkinleşir.
+    // Planın tüm paketleri kurulduktan sonra bir kez çağrılmalıdır.
+    pub fn secimleri_kaydet(&self, plan: &CozumPlani) -> Result<(), PaketYoneticisiHatasi> {
+        let mut veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        for ad in &plan.kurulacaklar {
+            if let Some(kayit) = veritabani.paketler.get_mut(ad) {
+                kayit.etkin_ozellikler = plan.etkin_ozellikler.get(ad).cloned().unwrap_or_default();
+                kayit.ek_bagimliliklar = plan.ek_bagimliliklar.get(ad).cloned().unwrap_or_default();
+                if let Some(neden) = plan.kurulum_nedenleri.get(ad) {
+                    kayit.kurulum_nedeni = *neden;
+                }
+            }
+        }
+        veritabani.yaz(&self.veritabani_resource_id)
+    }
+
+    // Bir çözüm planını tek bir işlem olarak uygular: gruplar topolojik sırayla kurulur; her grubun
+    // pre-install/pre-upgrade betikleri grup kurulmadan önce, post-install/post-upgrade betikleri grubun
+    // tüm paketleri kurulduktan sonra çalıştırılır. Böylece döngüsel bağımlı paketler (çok paketli gruplar)
+    // betikleri çalışırken birbirlerinin dosyalarını bulabilir. Tetikleyiciler en sonda bir kez çalışır.
+    // Bir betik sıfırdan farklı kodla çıkar veya zaman aşımına uğrarsa işlem geri alınır: bu işlemde
+    // kurulan paketler silinir, güncellenenlerin önceki sürümleri ve veritabanı geri yüklenir.
+    // plan: cozum_plani_olustur ile üretilmiş plan.
+    // paketler: Plan oluşturulurken kullanılan paket listesi (meta veriler için).
+    pub fn plani_uygula(&self, plan: &CozumPlani, paketler: &[Paket]) -> Result<(), PaketYoneticisiHatasi> {
+        let harita: BTreeMap<&str, &Paket> = paketler.iter().map(|p| (p.ad.as_str(), p)).collect();
+        let mut islemdekiler: Vec<Paket> = Vec::new();
+        for ad in &plan.kurulacaklar {
+            let paket = harita.get(ad.as_str()).ok_or_else(|| PaketYoneticisiHatasi::PaketBulunamadi(ad.clone()))?;
+            islemdekiler.push((*paket).clone());
+        }
+        // Dosya çakışmaları tüm işlem için meta verideki listelerle önceden denetlenir; bir şey kurulmadan hata
+        // verilir. Her paket çıkarıldıktan sonra gerçek dosyalarıyla yeniden denetlenir.
+        self.kurulum_planini_dogrula(&islemdekiler)?;
+        for grup in plan.kurulum_gruplari.iter().filter(|g| g.len() > 1) {
+            println!("Döngüsel bağımlı paketler birlikte kuruluyor: {}", grup.join(", "));
+        }
+
+        let yurutucu = PlanYurutucu {
+            kurulum: self,
+            harita: &harita,
+            durum: self.islem_baslat()?,
+            secimler: Some(plan),
+            geri_yazilacaklar: Vec::new(),
+        };
+        islemi_yurut(&islem_adimlari(plan), yurutucu)
+    }
+
+    // Yeni bir işlem başlatır ve geri alma için veritabanının o anki halini saklar.
+    fn islem_baslat(&self) -> Result<IslemDurumu, PaketYoneticisiHatasi> {
+        let islem = IslemYoneticisi::yeni(&self.islem_gunlugu_resource_id);
+        let oncesi = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        let baslangic_us = task::current_time_us().map_err(PaketYoneticisiHatasi::from)?;
+        let kimlik = islem.baslat_islem()?;
+        let guven = TrustManager::new(&self.guvenilen_yayincilar_resource_id, &self.guvenilen_paketler_resource_id);
+        Ok(IslemDurumu { islem, kimlik, baslangic_us, geri_alinan: None, oncesi, dizinler: Vec::new(), guven })
+    }
+
+    // Biten işlemi, işlem öncesi ve şimdiki veritabanı arasındaki farkla geçmişe ekler. İşlemin kendisi
+    // bittiği için geçmiş yazılamazsa sadece uyarı verilir.
+    fn gecmise_yaz(&self, durum: &IslemDurumu, sonuc: IslemSonucu, hata: Option<String>) {
+        let degisiklikler = match KuruluVeritabani::oku(&self.veritabani_resource_id) {
+            Ok(sonrasi) => srchistory::degisiklikleri_bul(&durum.oncesi, &sonrasi),
+            Err(e) => {
+                eprintln!("Uyarı: İşlem geçmişi için veritabanı okunamadı: {:?}", e);
+                Vec::new()
+            }
+        };
+        let kayit = GecmisKaydi {
+            kimlik: durum.kimlik,
+            baslangic_us: durum.baslangic_us,
+            bitis_us: task::current_time_us().unwrap_or(durum.baslangic_us),
+            kullanici: self.kullanici.clone(),
+            komut_satiri: self.komut_satiri.clone(),
+            sonuc,
+            hata,
+            geri_alinan: durum.geri_alinan,
+            degisiklikler,
+        };
+        if let Err(e) = IslemGecmisi::kaydet(&self.gecmis_resource_id, kayit) {
+            eprintln!("Uyarı: İşlem geçmişe yazılamadı ({}): {:?}", self.gecmis_resource_id, e);
+        }
+    }
+
+    // Önceki bir paket durumunu tek bir işlem olarak geri yükler (geçmiş kaydının tersi, bkz.
+    // srchistory::ters_islem, veya bir anlık görüntüye dönüş, bkz. srcsnapshot::donus_plani): önce verilen
+    // paketler kaldırılır, sonra önceki sürümler önbellekteki arşivlerinden kurulur ve kayıtları (özellikler,
+    // kurulum nedeni) aynen geri yazılır. Betikler ve tetikleyiciler normal kurulum/kaldırmadaki gibi
+    // çalışır; hata olursa bu işlem de geri alınır.
+    // geri_alinan: Tersi uygulanan işlemin kimliği, varsa (yeni geçmiş kaydında saklanır).
+    // kurulacaklar: Yeniden kurulacak paket meta verisi ve geri yazılacak kayıt çiftleri.
+    pub fn durumu_geri_yukle(&self, geri_alinan: Option<u64>, kurulacaklar: &[(Paket, KuruluPaket)], kaldirilacaklar: &[String]) -> Result<(), PaketYoneticisiHatasi> {
+        // Eski sürümlerin arşivleri önbellekte olmalı; hiçbir şey değiştirilmeden önce denetlenir.
+        for (paket, _) in kurulacaklar {
+            let arsiv_id = format!("{}/{}", self.onbellek_base_resource_id, paket.dosya_adi.as_deref().unwrap_or_default());
+            match resource::acquire(&arsiv_id, resource::MODE_READ) {
+                Ok(handle) => {
+                    let _ = resource::release(handle);
+                }
+                Err(SahneError::ResourceNotFound) => {
+                    return Err(PaketYoneticisiHatasi::InstallationError(format!(
+                        "'{}' {} arşivi önbellekte yok: {}", paket.ad, paket.surum, arsiv_id
+                    )));
+                }
+                Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+            }
+        }
+        // Ortaya çıkacak durumda, şu an karşılanan bir bağımlılık karşılanmaz kalmamalı (örn. geri alınan
+        // işlemden sonra kurulan bir paket, kaldırılacak bir pakete bağımlıysa).
+        let simdiki = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        let mut hedef = simdiki.clone();
+        for ad in kaldirilacaklar {
+            hedef.kaldir(ad);
+        }
+        for (_, kayit) in kurulacaklar {
+            hedef.ekle(kayit.clone());
+        }
+        let kirilanlar: Vec<String> = hedef.karsilanmayan_bagimliliklar()
+            .into_iter()
+            .filter(|(_, bagimlilik)| simdiki.karsilayanlar(bagimlilik).next().is_some())
+            .map(|(ad, bagimlilik)| format!("{} -> {}", ad, bagimlilik))
+            .collect();
+        if !kirilanlar.is_empty() {
+            return Err(PaketYoneticisiHatasi::ConflictError(format!("geri yükleme bağımlılıkları bozar: {}", kirilanlar.join(", "))));
+        }
+
+        let harita: BTreeMap<&str, &Paket> = kurulacaklar.iter().map(|(p, _)| (p.ad.as_str(), p)).collect();
+        let plan = CozumPlani {
+            kurulacaklar: harita.keys().map(|ad| ad.to_string()).collect(),
+            kurulum_gruplari: harita.keys().map(|ad| alloc::vec![ad.to_string()]).collect(),
+            kaldirilacaklar: kaldirilacaklar.to_vec(), // Kaldıranı olmadığından önce kaldırılırlar
+            ..CozumPlani::default()
+        };
+        let mut durum = self.islem_baslat()?;
+        durum.geri_alinan = geri_alinan;
+        let yurutucu = PlanYurutucu {
+            kurulum: self,
+            harita: &harita,
+            durum,
+            secimler: None,
+            geri_yazilacaklar: kurulacaklar.iter().map(|(_, kayit)| kayit.clone()).collect(),
+        };
+        islemi_yurut(&islem_adimlari(&plan), yurutucu)
+    }
+
+    // Paketin kurulum/güncelleme öncesi veya sonrası betiğini çalıştırır. Güncelleme betikleri kurulu sürümden
+    // değil, kurulacak sürümden alınır (geçişi yeni sürüm bilir).
+    fn kurulum_betigini_calistir(&self, paket: &Paket, sonrasi: bool, durum: &IslemDurumu) -> Result<(), PaketYoneticisiHatasi> {
+        let eski_surum = durum.oncesi.paket(&paket.ad).map(|k| k.surum.clone());
+        let asama = match (sonrasi, eski_surum.is_some()) {
+            (false, false) => BetikAsamasi::KurulumOncesi,
+            (false, true) => BetikAsamasi::GuncellemeOncesi,
+            (true, false) => BetikAsamasi::KurulumSonrasi,
+            (true, true) => BetikAsamasi::GuncellemeSonrasi,
+        };
+        let ortam = self.betik_ortami(&paket.ad, asama, eski_surum, Some(paket.surum.clone()));
+        let eski_bicim = if sonrasi { paket.kurulum_scripti.as_deref() } else { None };
+        self.paket_betigini_calistir(durum, ortam, &paket.betikler, eski_bicim, paket.yazar.as_deref())
+    }
+
+    // Paketi işlem içinde kurar; geri alma için kurulum dizinini ve önceki içeriğin yedeğini, tetikleyiciler
+    // için değişen yolları kaydeder.
+    fn islemde_kur(&self, paket: &Paket, durum: &mut IslemDurumu) -> Result<(), PaketYoneticisiHatasi> {
+        let (yedek, dosyalar) = self.paketi_cikar_ve_etkinlestir(paket)?;
+        durum.dizinler.push((format!("{}/{}/", self.kurulum_base_resource_id, paket.ad), yedek.clone()));
+        if let Some(yedek) = &yedek {
+            durum.islem.yedegi_kaydet(yedek)?;
+        }
+        // Güncellemede eski sürümün (artık bulunmayan) dosyaları da değişmiş sayılır.
+        if let Some(onceki) = durum.oncesi.paket(&paket.ad) {
+            durum.islem.yollari_kaydet(&onceki.dosyalar);
+        }
+        durum.islem.yollari_kaydet(&dosyalar);
+        durum.islem.islem_adimi(&format!("KURULDU {} {}", paket.ad, paket.surum))
+    }
+
+    // Paketin verilen aşamadaki betiğini bulur. Sırasıyla: meta verideki betikler tablosu, eski biçim betik
+    // alanı (kurulum_scripti), betik dizinindeki "<aşama>.sh" ve eski adlandırma (kurulum/güncelleme sonrası
+    // "install.sh", kaldırma öncesi "uninstall.sh"). Değerler tam Kaynak ID'si değilse paketin betik
+    // dizinine göre yorumlanır.
+    // Dönüş değeri: Var olan ilk adayın Kaynak ID'si; betik yoksa None.
+    fn betik_kaynagini_bul(
+        &self,
+        paket_adi: &str,
+        asama: BetikAsamasi,
+        betikler: &BTreeMap<BetikAsamasi, String>,
+        eski_bicim: Option<&str>,
+    ) -> Result<Option<String>, PaketYoneticisiHatasi> {
+        let mut adaylar: Vec<String> = Vec::new();
+        if let Some(betik) = betikler.get(&asama) {
+            adaylar.push(betik.clone());
+        }
+        if let Some(betik) = eski_bicim {
+            adaylar.push(betik.to_owned());
+        }
+        adaylar.push(asama.varsayilan_dosya_adi());
+        match asama {
+            BetikAsamasi::KurulumSonrasi | BetikAsamasi::GuncellemeSonrasi => adaylar.push(String::from("install.sh")),
+            BetikAsamasi::KaldirmaOncesi => adaylar.push(String::from("uninstall.sh")),
+            _ => {}
+        }
+
+        for aday in adaylar {
+            let betik_id = if aday.contains("://") { aday } else { format!("{}/{}/{}", self.betik_base_resource_id.trim_end_matches('/'), paket_adi, aday) };
+            match resource::acquire(&betik_id, resource::MODE_READ) {
+                Ok(handle) => {
+                    let _ = resource::release(handle);
+                    return Ok(Some(betik_id));
+                }
+                Err(SahneError::ResourceNotFound) => continue,
+                Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+            }
+        }
+        Ok(None)
+    }
+
+    fn betik_ortami(&self, paket_adi: &str, asama: BetikAsamasi, eski_surum: Option<String>, yeni_surum: Option<String>) -> BetikOrtami {
+        BetikOrtami {
+            asama,
+            paket_adi: paket_adi.to_owned(),
+            eski_surum,
+            yeni_surum,
+            kurulum_koku: self.kurulum_base_resource_id.clone(),
+            tetikleyici: None,
+        }
+    }
+
+    // İşlemde değişen yollara uyan kurulu paket tetikleyicilerini, her birini bir kez çalıştırır.
+    // İşlemin son adımıdır; başarısız bir tetikleyici diğer betikler gibi işlemi geri aldırır.
+    fn tetikleyicileri_calistir(&self, durum: &IslemDurumu) -> Result<(), PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        for is in durum.islem.tetiklenenler(&veritabani) {
+            println!("Tetikleyici çalıştırılıyor: {} ({}, {} yol)", is.tetikleyici.ad, is.sahip, is.yollar.len());
+            durum.islem.islem_adimi(&format!("TETIKLENDI {} {} ({} yol)", is.sahip, is.tetikleyici.ad, is.yollar.len()))?;
+            let surum = veritabani.paket(&is.sahip).map(|k| k.surum.clone());
+            let mut ortam = self.betik_ortami(&is.sahip, BetikAsamasi::Tetikleyici, surum.clone(), surum);
+            ortam.tetikleyici = Some(is.tetikleyici.ad.clone());
+            let mut betikler = BTreeMap::new();
+            betikler.insert(BetikAsamasi::Tetikleyici, is.tetikleyici.betik);
+            let yayinci = veritabani.paket(&is.sahip).and_then(|k| k.yayinci.clone());
+            self.paket_betigini_calistir(durum, ortam, &betikler, None, yayinci.as_deref())?;
+        }
+        Ok(())
+    }
+
+    // Paketin ortamdaki aşamaya ait betiğini (varsa) bekleyerek çalıştırır ve sonucunu işlem günlüğüne yazar.
+    // Betik, paketin (ve yayıncısının) güven seviyesine göre seçilen sandbox profiliyle çalışır.
+    // Betik sıfırdan farklı kodla çıkar veya zaman aşımına uğrarsa BetikCalistirmaHatasi döner.
+    fn paket_betigini_calistir(
+        &self,
+        durum: &IslemDurumu,
+        ortam: BetikOrtami,
+        betikler: &BTreeMap<BetikAsamasi, String>,
+        eski_bicim: Option<&str>,
+        yayinci: Option<&str>,
+    ) -> Result<(), PaketYoneticisiHatasi> {
+        let (paket_adi, asama) = (ortam.paket_adi.as_str(), ortam.asama);
+        let betik_id = match self.betik_kaynagini_bul(paket_adi, asama, betikler, eski_bicim)? {
+            Some(id) => id,
+            None => return Ok(()), // Bu aşamada betiği olmayan paketler için bir şey yapılmaz
+        };
+        let seviye = durum.guven.guven_seviyesi(paket_adi, yayinci);
+        let profil = self.sandbox.profil(seviye, &self.kurulum_base_resource_id);
+        let sonuc = srcscript::betik_calistir_bekle(&betik_id, &ortam, &profil, self.betik_zaman_asimi_us)?;
+        durum.islem.betik_sonucunu_kaydet(paket_adi, asama, &sonuc)?;
+        if sonuc.basarili() {
+            return Ok(());
+        }
+
+        if !sonuc.stderr.is_empty() {
+            eprintln!("{}", String::from_utf8_lossy(&sonuc.stderr));
+        }
+        let neden = match sonuc.cikis_kodu {
+            Some(kod) => format!("çıkış kodu {}", kod),
+            None => format!("{} us içinde tamamlanmadı", self.betik_zaman_asimi_us),
+        };
+        Err(PaketYoneticisiHatasi::BetikCalistirmaHatasi(format!(
+            "'{}' paketinin {} betiği başarısız oldu ({}): {}", paket_adi, asama.ad(), neden, betik_id
+        )))
+    }
+
+    // Önceki çalıştırmalarda yarıda kalmış kurulumların hazırlık alanlarını temizler.
+    // Kurulumlara başlamadan önce, kurulum kilidi alınmışken bir kez çağrılmalıdır.
+    pub fn hazirlik_artiklarini_temizle(&self) -> Result<usize, PaketYoneticisiHatasi> {
+        srcstaging::artik_hazirliklari_temizle(&self.kurulum_base_resource_id, &self.islem_gunlugu_resource_id)
+    }
+
+    // Paketi önbellekten (önbellekte yoksa depodan doğrulayarak indirip) kurulum dizinine kurar.
+    // Arşiv önce hazırlık alanına çıkarılır, dosya listesi doğrulanır ve taşıma ile etkinleştirilir;
+    // hata durumunda kurulum dizinine hiçbir şey yansımaz. Paket betikleri çalıştırılmaz; betikli ve
+    // geri alınabilir kurulum için plani_uygula kullanılmalıdır.
     // paket: Kurulacak paketin meta verisi.
     // Dönüş değeri: Başarı veya PaketYoneticisiHatasi.
     pub fn paketi_kur(&self, paket: &Paket) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+        if let (Some(yedek), _) = self.paketi_cikar_ve_etkinlestir(paket)? {
+            if let Err(e) = srcstaging::kaynagi_sil(&yedek, true) {
+                // Kurulum başarılı; yedek bir işleme kayıtlı olmadığından bir sonraki temizlikte silinmez,
+                // kurtarmaya ayrılır (bkz. artik_hazirliklari_temizle).
+                eprintln!("Eski kurulum silinemedi ({}): {:?}", yedek, e);
+            }
+        }
+        Ok(())
+    }
+
+    // paketi_kur'un gövdesi. Güncellemede önceki sürüm silinmez, kenara alınır ve yedeğin Kaynak ID'si döner
+    // (bkz. HazirlikAlani::etkinlestir_yedekli); yeni kurulumda None döner. Yedekle birlikte arşivden
+    // gerçekten çıkarılan (dizin olmayan) yollar da döner.
+    fn paketi_cikar_ve_etkinlestir(&self, paket: &Paket) -> Result<(Option<String>, Vec<String>), PaketYoneticisiHatasi> {
         // Paketin dosya adını al
         if let Some(dosya_adi) = &paket.dosya_adi { // dosya_adi Option<String>
             // Önbellekteki paket dosyasının kaynak ID'sini oluştur (örn. "sahne://cache/packages/my_package.zip")
             let onbellek_paket_id = format!("{}/{}", self.onbellek_base_resource_id, dosya_adi); // format! alloc gerektirir
 
-            // Kurulum dizini hedef kaynak ID'sini oluştur (örn. "sahne://installed_packages/my_package/")
-            // Zip dosyasının içeriği bu ana dizin altına çıkarılacak.
-            // Paket ismi, kurulum_base_resource_id altına bir alt dizin oluşturmak için kullanılabilir.
-            let kurulum_hedef_base_id = format!("{}/{}/", self.kurulum_base_resource_id, paket.ad); // format! alloc gerektirir. Sonuna '/' eklemek dizin anlamı katabilir.
-
+            // Arşiv önce hazırlık alanına (örn. "sahne://installed_packages/.hazirlik/my_package-<zaman>/") çıkarılır,
+            // etkinleştirmede "sahne://installed_packages/my_package/" konumuna taşınır.
+            let hazirlik = HazirlikAlani::yeni(&self.kurulum_base_resource_id, &paket.ad)?;
 
             println!("Paket kurulumuna başlanıyor: {:?}", paket.ad);
             println!("Paket önbellek yolu: {}", onbellek_paket_id);
-            println!("Kurulum hedef yolu: {}", kurulum_hedef_base_id);
-
-            // Zip arşivini önbellek konumundan kurulum hedef dizinine çıkar (srcarchive modülü kullanılarak)
-             srcarchive::zip_ac(arsiv_resource_id: &str, cikartma_base_resource_id: &str)
-            match srcarchive::zip_ac(&onbellek_paket_id, &kurulum_hedef_base_id) {
-                Ok(_) => {
-                    println!("Paket içeriği çıkarıldı ve kuruldu: {:?}", paket.ad);
-                    Ok(())
-                }
+            println!("Kurulum hedef yolu: {}", hazirlik.hedef_yolu());
+
+            // Arşivi (zip veya tar.*) hazırlık alanına çıkar; önbellekte yoksa indirilirken doğrulanır.
+            let rapor = match self.hazirliga_cikar(paket, dosya_adi, &onbellek_paket_id, &hazirlik) {
+                Ok(rapor) => rapor,
                 Err(e) => {
                     eprintln!("Paket içeriği çıkarma/kurulum hatası (Kaynak: {}): {:?}", onbellek_paket_id, e);
+                    hazirlik.iptal(); // Yarım çıkarılmış içerik kurulum dizinine hiç yansımaz
                     // srcarchive'dan gelen hata zaten PaketYoneticisiHatasi türünde.
-                    Err(e)
+                    return Err(e);
                 }
+            };
+
+            // Güvensiz oldukları için atlanan girdileri kullanıcıya bildir.
+            for reddedilen in &rapor.reddedilen {
+                eprintln!("Uyarı: '{}' paketindeki '{}' girdisi atlandı: {}", paket.ad, reddedilen.ad, reddedilen.neden);
             }
+
+            // Dosya çakışmaları meta verideki listeyle değil, arşivden gerçekten çıkan yollarla denetlenir; böylece
+            // dosya listesi eksik veya boş bir paket başka paketlerin dosyalarının üzerine yazamaz. Kayıt da bu
+            // yollarla yapılır.
+            let mut kurulan = paket.clone();
+            kurulan.dosyalar = rapor.cikarilan.iter().filter(|yol| !yol.ends_with('/')).cloned().collect();
+
+            // Değiştirilmiş yapılandırma dosyaları etkinleştirmeden önce hazırlık alanına aktarılır.
+            let sonuc = hazirlik.dogrula(&rapor, paket)
+                .and_then(|_| self.kurulum_planini_dogrula(core::slice::from_ref(&kurulan)))
+                .and_then(|plan| self.yapilandirma_dosyalarini_hazirla(paket, &hazirlik, &rapor.manifesto).map(|kararlar| (plan, kararlar)));
+            let (plan, kararlar) = match sonuc {
+                Ok(sonuc) => sonuc,
+                Err(e) => {
+                    hazirlik.iptal();
+                    return Err(e);
+                }
+            };
+            let yedek = hazirlik.etkinlestir_yedekli()?;
+            self.veritabanina_kaydet(&kurulan, &plan, rapor.manifesto.clone())?;
+            self.yapilandirma_kararlarini_kaydet(&kararlar);
+
+            println!("Paket içeriği çıkarıldı ve kuruldu: {:?} ({} girdi, {} bayt)", paket.ad, rapor.cikarilan.len(), rapor.toplam_boyut);
+            Ok((yedek, kurulan.dosyalar))
         } else {
             eprintln!("Paket meta verisinde dosya adı belirtilmemiş: {:?}", paket.ad);
              // Dosya adı belirtilmemişse hata dönelim.
@@ -176,40 +757,281 @@ impl KurulumYoneticisi {
         }
     }
 
-     // Paketi kaldırma fonksiyonu (Eksik fonksiyonellik: resource silme)
-     // package_name: Kaldırılacak paketin adı.
-     // Dönüş değeri: Başarı veya PaketYoneticisiHatasi.
-      pub fn paketi_kaldir(&self, paket_adi: &str) -> Result<(), PaketYoneticisiHatasi> {
-          println!("Paket kaldırma başlatılıyor: {}", paket_adi);
-     //     // Kurulu paketlerin dosyalarının bulunduğu temel Kaynak ID'sini oluştur (örn. "sahne://installed_packages/my_package/")
-          let kurulum_paket_base_id = format!("{}/{}/", self.kurulum_base_resource_id, paket_adi); // format! alloc gerektirir
-     //
-     //     // Kurulu dosyaları silme mantığı burada olacak.
-     //     // Bu, resource::delete veya resource::control kullanımı gerektirir (Sahne64 API'sında eksik).
-     //     // Ayrıca, silinecek dosyaların listesi bilinmelidir (paket veritabanından veya paket meta verilerinden).
-     //
-          eprintln!("UYARI: Sahne64 API'sında resource silme mekanizması bulunmamaktadır. Paket kaldırma tamamlanmamıştır.");
-          eprintln!("Silinmek istenen temel Kaynak ID: {}", kurulum_paket_base_id);
-     //
-     //     // Placeholder: resource::delete(kurulum_paket_base_id)? gibi bir çağrı yapılacak.
-     //
-     //     // Eğer kaldırma scripti varsa, onu çalıştır (task::spawn).
-           const PACKAGE_SCRIPT_BASE_RESOURCE: &str = "sahne://system/package_scripts/"; // srccli.rs'den hatırlatma
-           let uninstall_script_resource_id = format!("{}{}/uninstall.sh", PACKAGE_SCRIPT_BASE_RESOURCE, paket_adi);
-           match resource::acquire(&uninstall_script_resource_id, resource::MODE_READ) {
-                Ok(script_handle) => {
-                    let _ = task::spawn(script_handle, b"");
-                    let _ = resource::release(script_handle);
+    // Paket arşivini hazırlık alanına çıkarır. Arşiv önbellekte varsa checksum'ı doğrulanıp oradan açılır (biçim,
+    // dosya uzantısından değil arşivin ilk baytlarından algılanır); doğrulanamayan önbellek dosyası silinir ve
+    // arşiv yeniden indirilir. Önbellekte yoksa uzak depodan akışlı olarak indirilir: indirme, checksum
+    // doğrulaması ve (tar.* için) çıkarma tek geçişte yapılır ve checksum eşleşmezse hiçbir şey kullanılmaz
+    // (bkz. srcpipeline::akisli_indir_ve_ac).
+    fn hazirliga_cikar(&self, paket: &Paket, dosya_adi: &str, onbellek_paket_id: &str, hazirlik: &HazirlikAlani) -> Result<srcarchive::CikarmaRaporu, PaketYoneticisiHatasi> {
+        match resource::acquire(onbellek_paket_id, resource::MODE_READ) {
+            Ok(handle) => {
+                let _ = resource::release(handle);
+                match srcpipeline::onbellek_arsivini_dogrula(onbellek_paket_id, &paket.checksums) {
+                    Ok(()) => return srcarchive::arsiv_ac(onbellek_paket_id, hazirlik.yol(), &self.arsiv_ozellikleri),
+                    Err(PaketYoneticisiHatasi::ChecksumVerificationError) => {
+                        eprintln!("Uyarı: Önbellekteki arşiv doğrulanamadı, yeniden indirilecek: {}", onbellek_paket_id);
+                        srcstaging::kaynagi_sil(onbellek_paket_id, false).map_err(PaketYoneticisiHatasi::from)?;
+                    }
+                    Err(e) => return Err(e),
                 }
-                Err(SahneError::ResourceNotFound) => { /* Script yoksa devam et */ }
-                Err(e) => { /* Hata yönetimi */ }
-           }
-     //
-     //     // Paket veritabanı kaydını silme (paketi kurulu listesinden çıkarma).
-     //     // Bu da paket veritabanını yöneten modül tarafından yapılmalıdır.
-     
-          Err(PaketYoneticisiHatasi::SahneApiHatasi(SahneError::NotSupported)) // Silme desteği yok hatası
-      }
+            }
+            Err(SahneError::ResourceNotFound) => {}
+            Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+        }
+        let paket_kaynak_id = format!("{}/{}", self.paket_deposu_base_resource_id, dosya_adi);
+        let sonuc = srcpipeline::akisli_indir_ve_ac(
+            &paket_kaynak_id,
+            onbellek_paket_id,
+            hazirlik.yol(),
+            &paket.checksums,
+            &self.arsiv_ozellikleri,
+            &srcarchive::CikarmaSinirlari::default(),
+        )?;
+        println!("Paket indirildi: {:?} ({} bayt)", paket.ad, sonuc.indirilen_bayt);
+        Ok(sonuc.rapor)
+    }
+
+    // Paketin arşivini çıkarmadan uzak depodan önbelleğe indirir; checksum eşleşmezse önbelleğe hiçbir şey
+    // yazılmaz ve ChecksumVerificationError döner (bkz. srcpipeline::dogrulayarak_indir).
+    pub fn paketi_dogrulayarak_indir(&self, paket: &Paket) -> Result<(), PaketYoneticisiHatasi> {
+        let dosya_adi = paket.dosya_adi.as_deref().ok_or_else(|| {
+            PaketYoneticisiHatasi::InvalidParameter(format!("Paket '{}' için dosya adı belirtilmemiş.", paket.ad))
+        })?;
+        let paket_kaynak_id = format!("{}/{}", self.paket_deposu_base_resource_id, dosya_adi);
+        let onbellek_paket_id = format!("{}/{}", self.onbellek_base_resource_id, dosya_adi);
+        srcpipeline::dogrulayarak_indir(&paket_kaynak_id, &onbellek_paket_id, &paket.checksums).map(|_| ())
+    }
+
+    // Hazırlık alanındaki paketin yapılandırma dosyalarını kurulu hallerine göre düzenler
+    // (bkz. srcconffiles::kuruluma_hazirla).
+    fn yapilandirma_dosyalarini_hazirla(&self, paket: &Paket, hazirlik: &HazirlikAlani, manifesto: &[ManifestGirdisi]) -> Result<KurulumKararlari, PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        srcconffiles::kuruluma_hazirla(
+            &self.yapilandirma_dosyalari_dizini,
+            veritabani.paket(&paket.ad),
+            paket,
+            hazirlik.yol(),
+            &hazirlik.hedef_yolu(),
+            manifesto,
+        )
+    }
+
+    // Etkinleştirilen kurulumun yapılandırma dosyası kayıtlarını günceller. Kurulum zaten yerinde olduğundan
+    // hatalar sadece uyarı olarak bildirilir.
+    fn yapilandirma_kararlarini_kaydet(&self, kararlar: &KurulumKararlari) {
+        let hedef_kok = format!("{}/{}/", self.kurulum_base_resource_id, kararlar.paket);
+        if let Err(e) = kararlar.kaydet(&self.yapilandirma_dosyalari_dizini, &hedef_kok) {
+            eprintln!("Uyarı: '{}' yapılandırma dosyası kayıtları güncellenemedi: {:?}", kararlar.paket, e);
+        }
+    }
+
+    // Paketi kaldırır: kaldırma betiğini (varsa) çalıştırır, kurulum dizinini siler ve veritabanı kaydını çıkarır.
+    // Pakete bağımlı kurulu paketler varsa hiçbir şey kaldırılmaz ve bunları listeleyen RemovalError döner;
+    // bağımlılıklarla birlikte kaldırma için bagimliliklariyla_kaldir kullanılmalıdır.
+    // paket_adi: Kaldırılacak paketin adı.
+    // Dönüş değeri: Başarı veya PaketYoneticisiHatasi.
+    pub fn paketi_kaldir(&self, paket_adi: &str) -> Result<(), PaketYoneticisiHatasi> {
+        self.paketleri_kaldir(core::slice::from_ref(&paket_adi.to_owned()))
+    }
+
+    // Kaldırılacak paketlere, kendisi kaldırılmayacak kurulu paketlerin bağımlı olup olmadığını denetler.
+    // Bağımlı paketler varsa hepsini (kaldırılacak paket -> bağımlılar) listeleyen RemovalError döner.
+    fn bagimlilari_denetle(&self, adlar: &[String]) -> Result<(), PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        let mut engeller: Vec<String> = Vec::new();
+        // Bağımlılık adını bu pakete ek olarak kalacak başka bir kurulu paket (örn. aynı sanal adın başka
+        // bir sağlayıcısı) da karşılıyorsa, bağımlı paket kaldırmadan etkilenmez.
+        let karsilanmaz_kalir = |gereken: &str, ad: &str| {
+            let mut karsilayanlar = veritabani.karsilayanlar(gereken).map(|k| k.ad.as_str());
+            karsilayanlar.clone().any(|k| k == ad) && karsilayanlar.all(|k| adlar.iter().any(|a| a == k))
+        };
+        for ad in adlar {
+            let bagimlilar: Vec<String> = BagimlilikYoneticisi::ters_bagimliliklar(&veritabani, ad)
+                .into_iter()
+                .filter(|b| !adlar.contains(b))
+                .filter(|b| {
+                    let kayit = &veritabani.paketler[b];
+                    kayit.bagimliliklar.iter().chain(kayit.ek_bagimliliklar.iter()).any(|g| karsilanmaz_kalir(g, ad))
+                })
+                .collect();
+            if !bagimlilar.is_empty() {
+                engeller.push(format!("'{}' paketine bağımlı olanlar: {}", ad, bagimlilar.join(", ")));
+            }
+        }
+        if engeller.is_empty() {
+            Ok(())
+        } else {
+            let metin = engeller.join("; ");
+            eprintln!("Kaldırma reddedildi: {}", metin);
+            Err(PaketYoneticisiHatasi::RemovalError(metin))
+        }
+    }
+
+    // Tek bir paketi, bağımlılık denetimi yapmadan kaldırır (denetim paketleri_kaldir'da yapılır).
+    // pre-remove betiği dosyalar kaldırılmadan önce (betik paket dosyalarına ihtiyaç duyabilir), post-remove
+    // betiği sonra çalıştırılır. Kurulum dizini silinmez, işlem geri alınabilsin diye hazırlık dizinine
+    // kenara alınır; yedek işlem tamamlanınca silinir.
+    fn tek_paketi_kaldir(&self, paket_adi: &str, durum: &mut IslemDurumu) -> Result<(), PaketYoneticisiHatasi> {
+        println!("Paket kaldırma başlatılıyor: {}", paket_adi);
+        let kayit = durum.oncesi.paket(paket_adi).cloned().ok_or_else(|| PaketYoneticisiHatasi::PaketBulunamadi(paket_adi.to_owned()))?;
+
+        let ortam = self.betik_ortami(paket_adi, BetikAsamasi::KaldirmaOncesi, Some(kayit.surum.clone()), None);
+        self.paket_betigini_calistir(durum, ortam, &kayit.betikler, None, kayit.yayinci.as_deref())?;
+
+        // Kurulu dosyaların bulunduğu dizini (örn. "sahne://installed_packages/my_package/") kenara al.
+        let kurulum_paket_id = format!("{}/{}/", self.kurulum_base_resource_id, paket_adi); // format! alloc gerektirir
+        // Değiştirilmiş yapılandırma dosyaları dizin kenara alınmadan saklanır (yeniden kurulumda kullanılır).
+        srcconffiles::kaldirirken_sakla(&self.yapilandirma_dosyalari_dizini, &kayit, &kurulum_paket_id)?;
+        let zaman = task::current_time_us().map_err(PaketYoneticisiHatasi::from)?;
+        let yedek_id = format!("{}/{}/{}-{}.eski/", self.kurulum_base_resource_id, srcstaging::HAZIRLIK_DIZINI, paket_adi, zaman);
+        let yedek = match srcstaging::kaynagi_tasi(&kurulum_paket_id, &yedek_id) {
+            Ok(()) => Some(yedek_id),
+            Err(SahneError::ResourceNotFound) => None, // Dizini olmayan (örn. sadece meta paket) kayıt
+            Err(e) => {
+                eprintln!("Paket dizini kaldırılamadı ({}): {:?}", kurulum_paket_id, e);
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        };
+        durum.dizinler.push((kurulum_paket_id, yedek.clone()));
+        if let Some(yedek) = &yedek {
+            durum.islem.yedegi_kaydet(yedek)?;
+        }
+        durum.islem.yollari_kaydet(&kayit.dosyalar);
+
+        let mut veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        veritabani.kaldir(paket_adi);
+        veritabani.yaz(&self.veritabani_resource_id)?;
+        durum.islem.islem_adimi(&format!("KALDIRILDI {} {}", paket_adi, kayit.surum))?;
+
+        let ortam = self.betik_ortami(paket_adi, BetikAsamasi::KaldirmaSonrasi, Some(kayit.surum.clone()), None);
+        self.paket_betigini_calistir(durum, ortam, &kayit.betikler, None, kayit.yayinci.as_deref())?;
+
+        println!("Paket kaldırıldı: {}", paket_adi);
+        Ok(())
+    }
+
+    // Paketleri verilen sırayla tek bir işlem olarak kaldırır. Listede olmayan kurulu paketler bunlardan
+    // birine bağımlıysa hiçbir şey kaldırılmaz. Bir betik başarısız olursa veya kaldırma hata verirse
+    // işlem geri alınır: o ana kadar kaldırılan paketler dizinleri ve kayıtlarıyla geri yüklenir.
+    // Kaldırılan dosyalara uyan tetikleyiciler en sonda bir kez çalışır.
+    pub fn paketleri_kaldir(&self, adlar: &[String]) -> Result<(), PaketYoneticisiHatasi> {
+        self.bagimlilari_denetle(adlar)?;
+        let mut durum = self.islem_baslat()?;
+        let sonuc = adlar.iter()
+            .try_for_each(|ad| self.tek_paketi_kaldir(ad, &mut durum))
+            .and_then(|_| self.tetikleyicileri_calistir(&durum));
+        match sonuc {
+            Ok(()) => durum.tamamla(self),
+            Err(e) => {
+                durum.geri_al(self, &e);
+                Err(e)
+            }
+        }
+    }
+
+    // Paketi, onunla birlikte gereksiz kalacak otomatik kurulmuş bağımlılıklarıyla birlikte kaldırır.
+    // Dönüş değeri: Kaldırılan paketlerin adları (kaldırma sırasıyla).
+    pub fn bagimliliklariyla_kaldir(&self, paket_adi: &str) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        if !veritabani.kurulu_mu(paket_adi) {
+            return Err(PaketYoneticisiHatasi::PaketBulunamadi(paket_adi.to_owned()));
+        }
+        let kume = veritabani.bagimliliklariyla_kaldirma_kumesi(paket_adi);
+        self.paketleri_kaldir(&kume)?;
+        Ok(kume)
+    }
+
+    // Otomatik kurulmuş ve artık hiçbir açık kurulmuş paketin gerektirmediği paketleri listeler.
+    pub fn yetimleri_listele(&self) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+        Ok(KuruluVeritabani::oku(&self.veritabani_resource_id)?.yetimler())
+    }
+
+    // Tüm yetim paketleri kaldırır.
+    // Dönüş değeri: Kaldırılan paketlerin adları (kaldırma sırasıyla).
+    pub fn yetimleri_kaldir(&self) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+        let veritabani = KuruluVeritabani::oku(&self.veritabani_resource_id)?;
+        let yetimler = veritabani.yetimler().into_iter().collect();
+        let sira = veritabani.kaldirma_sirasi(&yetimler);
+        self.paketleri_kaldir(&sira)?;
+        Ok(sira)
+    }
+
+    // Kurulu bir paketin verilen yollarını (bkz. srcverify::DogrulamaRaporu::onarilacaklar) önbellekteki
+    // arşivinden yeniden çıkarır. Arşiv bir hazırlık alanına çıkarılır ve sadece istenen yollar kurulu
+    // halleriyle değiştirilir; var olan dizinlerin ve yapılandırma dosyalarının sadece izinleri düzeltilir. Arşivdeki hali kayıtlı
+    // manifestoyla uyuşmayan bir yol varsa (arşiv değişmişse) hiçbir dosyaya dokunulmaz.
+    // Kurulum kilidi alınmışken çağrılmalıdır.
+    // Dönüş değeri: Onarılan yol sayısı.
+    pub fn dosyalari_onar(&self, kayit: &KuruluPaket, yollar: &[String]) -> Result<usize, PaketYoneticisiHatasi> {
+        let arsiv = kayit.arsiv.as_deref().ok_or_else(|| {
+            PaketYoneticisiHatasi::InstallationError(format!("'{}' kaydında arşiv adı yok", kayit.ad))
+        })?;
+        let arsiv_id = format!("{}/{}", self.onbellek_base_resource_id, arsiv);
+        let hazirlik = HazirlikAlani::yeni(&self.kurulum_base_resource_id, &kayit.ad)?;
+        let rapor = match srcarchive::arsiv_ac(&arsiv_id, hazirlik.yol(), &self.arsiv_ozellikleri) {
+            Ok(rapor) => rapor,
+            Err(e) => {
+                hazirlik.iptal();
+                return Err(e);
+            }
+        };
+
+        // Manifestosu olmayan eski kayıtlarda sadece yolun arşivde bulunması aranır.
+        let arsivdeki = |yol: &str| rapor.manifesto.iter().find(|g| g.yol == yol);
+        let uyusmayanlar: Vec<&str> = yollar
+            .iter()
+            .filter(|yol| match arsivdeki(yol) {
+                None => true,
+                Some(girdi) => !kayit.manifesto.is_empty() && kayit.manifesto.iter().find(|g| &g.yol == *yol) != Some(girdi),
+            })
+            .map(|yol| yol.as_str())
+            .collect();
+        if !uyusmayanlar.is_empty() {
+            hazirlik.iptal();
+            return Err(PaketYoneticisiHatasi::DogrulamaHatasi(format!(
+                "'{}' arşivi ({}) kayıtlı manifestoyla uyuşmuyor: {}", kayit.ad, arsiv_id, uyusmayanlar.join(", ")
+            )));
+        }
+
+        // Üst dizinler alt yollarından önce gelir; eksik bir dizin içeriğiyle birlikte taşınır.
+        let mut sirali: Vec<&String> = yollar.iter().collect();
+        sirali.sort();
+        let hedef_kok = hazirlik.hedef_yolu();
+        let mut tasinanlar: Vec<&str> = Vec::new();
+        let mut onarilan = 0;
+        for yol in sirali {
+            if tasinanlar.iter().any(|ust| yol.strip_prefix(ust).map_or(false, |kalan| kalan.starts_with('/'))) {
+                onarilan += 1;
+                continue;
+            }
+            let kaynak_id = format!("{}{}", hazirlik.yol(), yol);
+            let hedef_id = format!("{}{}", hedef_kok, yol);
+            let girdi = arsivdeki(yol).expect("manifestoyla eşleştiği denetlendi");
+            let yapilandirma = kayit.yapilandirma_dosyalari.iter().any(|y| y.trim_matches('/') == yol.as_str());
+            if girdi.tur == srcverify::GirdiTuru::Dizin || yapilandirma {
+                // Var olan dizin taşınırsa içindeki (sağlam veya paket dışı) dosyalar da değişirdi; yapılandırma
+                // dosyalarındaki kullanıcı değişiklikleri de korunur.
+                if let Ok(handle) = resource::acquire(&hedef_id, resource::MODE_WRITE) {
+                    if let Some(izinler) = girdi.izinler {
+                        srcarchive::izinleri_uygula(handle, izinler, &hedef_id);
+                    }
+                    let _ = resource::release(handle);
+                    onarilan += 1;
+                    continue;
+                }
+            }
+            if let Err(e) = srcstaging::kaynagi_sil(&hedef_id, false).and_then(|_| srcstaging::kaynagi_tasi(&kaynak_id, &hedef_id)) {
+                eprintln!("Dosya onarılamadı ({}): {:?}", hedef_id, e);
+                hazirlik.iptal();
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+            println!("Onarıldı: {}/{}", kayit.ad, yol);
+            tasinanlar.push(yol);
+            onarilan += 1;
+        }
+        hazirlik.iptal(); // Kullanılmayan çıkarılmış dosyalar silinir
+        Ok(onarilan)
+    }
 }
 
 // --- Paket struct tanımı (crate::package modülünde olmalı) ---
@@ -232,3 +1054,182 @@ pub struct Paket {
     pub dosya_adi: Option<String>, // Paketin arşiv dosyasının adı (örn. "my_package-1.0.0.zip")
     // Diğer meta veriler...
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+    use alloc::collections::BTreeSet;
+    use crate::srcdependency::KurulumSecenekleri;
+
+    fn paket(ad: &str, bagimliliklar: &[&str]) -> Paket {
+        Paket::yeni(ad.to_string(), "1.0".to_string(), bagimliliklar.iter().map(|b| b.to_string()).collect())
+    }
+
+    fn kur(ad: &str) -> IslemAdimi {
+        IslemAdimi::Kur(ad.to_string())
+    }
+
+    #[test]
+    fn test_bagimlilik_bagimlidan_once_kurulur() {
+        let paketler = vec![paket("uygulama", &["libx"]), paket("libx", &["libc"]), paket("libc", &[])];
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["uygulama"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        let adimlar = islem_adimlari(&plan);
+        let sira = |adim: &IslemAdimi| adimlar.iter().position(|a| a == adim).unwrap();
+
+        assert!(sira(&kur("libc")) < sira(&kur("libx")));
+        assert!(sira(&kur("libx")) < sira(&kur("uygulama")));
+        // Bağımlılığın sonrası betiği, bağımlı paketin öncesi betiğinden önce çalışır.
+        assert!(sira(&IslemAdimi::BetikSonrasi("libc".to_string())) < sira(&IslemAdimi::BetikOncesi("libx".to_string())));
+    }
+
+    // Adımları uygulamak yerine günlüğe yazan yürütücü; hatali adımda betik hatası döner.
+    struct KayitYurutucu<'a> {
+        gunluk: &'a RefCell<Vec<String>>,
+        hatali: IslemAdimi,
+    }
+
+    impl IslemYurutucu for KayitYurutucu<'_> {
+        fn adimi_uygula(&mut self, adim: &IslemAdimi) -> Result<(), PaketYoneticisiHatasi> {
+            self.gunluk.borrow_mut().push(format!("{:?}", adim));
+            if *adim == self.hatali {
+                return Err(PaketYoneticisiHatasi::BetikCalistirmaHatasi("çıkış kodu 1".to_string()));
+            }
+            Ok(())
+        }
+
+        fn son_adimlar(&mut self) -> Result<(), PaketYoneticisiHatasi> {
+            self.gunluk.borrow_mut().push("son".to_string());
+            Ok(())
+        }
+
+        fn tamamla(self) -> Result<(), PaketYoneticisiHatasi> {
+            self.gunluk.borrow_mut().push("tamamla".to_string());
+            Ok(())
+        }
+
+        fn geri_al(self, _hata: &PaketYoneticisiHatasi) {
+            self.gunluk.borrow_mut().push("geri_al".to_string());
+        }
+    }
+
+    #[test]
+    fn test_sonrasi_betigi_hatasi_islemi_geri_alir() {
+        // kur komutunun yolu: cozum_plani_olustur -> plani_uygula (islem_adimlari + islemi_yurut)
+        let paketler = vec![paket("uygulama", &["libx"]), paket("libx", &[])];
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["uygulama"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        let gunluk = RefCell::new(Vec::new());
+        let yurutucu = KayitYurutucu { gunluk: &gunluk, hatali: IslemAdimi::BetikSonrasi("libx".to_string()) };
+
+        let sonuc = islemi_yurut(&islem_adimlari(&plan), yurutucu);
+
+        assert!(matches!(sonuc, Err(PaketYoneticisiHatasi::BetikCalistirmaHatasi(_))));
+        // Hatadan sonraki adımlar uygulanmaz, işlem tamamlanmaz, geri alınır.
+        assert_eq!(
+            gunluk.into_inner(),
+            vec![
+                format!("{:?}", IslemAdimi::BetikOncesi("libx".to_string())),
+                format!("{:?}", kur("libx")),
+                format!("{:?}", IslemAdimi::BetikSonrasi("libx".to_string())),
+                "geri_al".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_basarili_islem_tamamlanir() {
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&[paket("libx", &[])], &["libx"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        let gunluk = RefCell::new(Vec::new());
+        let yurutucu = KayitYurutucu { gunluk: &gunluk, hatali: IslemAdimi::Kaldir("yok".to_string()) };
+
+        assert!(islemi_yurut(&islem_adimlari(&plan), yurutucu).is_ok());
+        let gunluk = gunluk.into_inner();
+        assert_eq!(&gunluk[gunluk.len() - 2..], &["son".to_string(), "tamamla".to_string()]);
+    }
+
+    #[test]
+    fn test_degistirilen_paket_yerini_alandan_once_kaldirilir() {
+        let mut yeni = paket("yeni", &["libc"]);
+        yeni.degistirir.push("eski".to_string());
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(KuruluPaket::paketten(&paket("eski", &[])));
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&[yeni, paket("libc", &[])], &["yeni"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(
+            islem_adimlari(&plan),
+            vec![
+                IslemAdimi::BetikOncesi("libc".to_string()),
+                kur("libc"),
+                IslemAdimi::BetikSonrasi("libc".to_string()),
+                IslemAdimi::Kaldir("eski".to_string()),
+                IslemAdimi::BetikOncesi("yeni".to_string()),
+                kur("yeni"),
+                IslemAdimi::BetikSonrasi("yeni".to_string()),
+            ]
+        );
+    }
+
+    // Kurulum kökünü paket dizinleri kümesi olarak taklit eden yürütücü.
+    struct DizinYurutucu<'a> {
+        dizinler: &'a RefCell<BTreeSet<String>>,
+    }
+
+    impl IslemYurutucu for DizinYurutucu<'_> {
+        fn adimi_uygula(&mut self, adim: &IslemAdimi) -> Result<(), PaketYoneticisiHatasi> {
+            match adim {
+                IslemAdimi::Kaldir(ad) => {
+                    assert!(self.dizinler.borrow_mut().remove(ad), "kurulu olmayan paket kaldırıldı: {}", ad);
+                }
+                IslemAdimi::Kur(ad) => {
+                    self.dizinler.borrow_mut().insert(ad.clone());
+                }
+                IslemAdimi::BetikOncesi(_) | IslemAdimi::BetikSonrasi(_) => {}
+            }
+            Ok(())
+        }
+
+        fn son_adimlar(&mut self) -> Result<(), PaketYoneticisiHatasi> {
+            Ok(())
+        }
+
+        fn tamamla(self) -> Result<(), PaketYoneticisiHatasi> {
+            Ok(())
+        }
+
+        fn geri_al(self, _hata: &PaketYoneticisiHatasi) {}
+    }
+
+    #[test]
+    fn test_degistirilen_paketin_dizini_kaldirilir() {
+        let mut yeni_mta = paket("yeni-mta", &[]);
+        yeni_mta.degistirir.push("postfix".to_string());
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(KuruluPaket::paketten(&paket("postfix", &[])));
+        kurulu.ekle(KuruluPaket::paketten(&paket("kabuk", &[])));
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&[yeni_mta], &["yeni-mta"], &kurulu, &KurulumSecenekleri::default()).unwrap();
+
+        let dizinler = RefCell::new(kurulu.paketler.keys().cloned().collect::<BTreeSet<String>>());
+        islemi_yurut(&islem_adimlari(&plan), DizinYurutucu { dizinler: &dizinler }).unwrap();
+
+        let dizinler = dizinler.into_inner();
+        assert!(!dizinler.contains("postfix"));
+        assert!(dizinler.contains("yeni-mta"));
+        assert!(dizinler.contains("kabuk"));
+    }
+
+    #[test]
+    fn test_dongu_grubu_betikleri() {
+        let paketler = vec![paket("a", &["b"]), paket("b", &["a"])];
+        let plan = BagimlilikYoneticisi::cozum_plani_olustur(&paketler, &["a"], &KuruluVeritabani::default(), &KurulumSecenekleri::default()).unwrap();
+        assert_eq!(
+            islem_adimlari(&plan),
+            vec![
+                IslemAdimi::BetikOncesi("a".to_string()),
+                IslemAdimi::BetikOncesi("b".to_string()),
+                kur("a"),
+                kur("b"),
+                IslemAdimi::BetikSonrasi("a".to_string()),
+                IslemAdimi::BetikSonrasi("b".to_string()),
+            ]
+        );
+    }
+}
@@ -22,6 +22,9 @@ use crate::Handle; // Kaynak Handle'ları
 const RESOURCE_CONTROL_CMD_LOCK_EXCLUSIVE: u33 = 1; // Exclusive kilit al
 const RESOURCE_CONTROL_CMD_UNLOCK: u33 = 2;       // Kilidi serbest bırak
 
+// Kurulum/kaldırma işlemlerini birbirinden koruyan kilidin varsayılan Kaynak ID'si.
+pub const VARSAYILAN_KILIT_KAYNAGI: &str = "sahne://system/pkgmgr.lock";
+
 
 // Dosya veya Kaynak tabanlı kilit yönetimini sağlar.
 // Sahne64 Kaynak kontrol mekanizmasını kullanır.
@@ -1,168 +1,460 @@
 #![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
-extern crate alloc; // String, format! için
+extern crate alloc; // String, Vec, format! için
 
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use alloc::format; // format! makrosu için
-use alloc::borrow::ToOwned; // &str -> String için
-use alloc::boxed::Box; // set_logger için Box<dyn Log> gerekebilir
+use alloc::boxed::Box; // Logger Box::leak ile 'static yapılır
+use core::fmt::Write as _; // String'e write! ile yazmak için
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 // log crate'i ve gerekli traitler
-use log::{self, Level, LevelFilter, Log, Metadata, Record};
+use log::{self, info, warn, Level, LevelFilter, Log, Metadata, Record};
 
-// Sahne64 resource modülü (çıktı için)
-use crate::resource;
+// Sahne64 API modülleri
+use crate::resource; // Çıktı Kaynakları (konsol, dosyalar, syslog soketi)
+use crate::task; // Zaman damgaları için task::current_time_us
 use crate::SahneError; // Hata yönetimi için
-use crate::Handle; // Kaynak Handle'ları
 
-// Yapılandırma struct'ı (log seviyesini okumak için varsayım)
-use crate::srcconfig::Yapilandirma; // Varsayım: Yapilandirma srcconfig.rs'de tanımlı
+// Yapılandırma (gunluk.* anahtarları) ve etkin günlük hedefleri (gunluk.hedefler, yetenek kaydından)
+use crate::srcconfig::{self, Yapilandirma};
+use crate::srcfeature_flags::YetenekKaydi;
+use crate::srcfeatures::LoggingFramework;
+// Dosya döndürme için Kaynak taşıma/silme
+use crate::srcstaging::{kaynagi_sil, kaynagi_tasi};
+
+// no_std uyumlu print makroları (logger'ın kendi hatalarını bildirmek için; log makroları değil, özyineleme olmaz)
+use crate::print_macros::eprintln;
+
+// Günlük hedefleri (gunluk.hedefler, bkz. srcfeatures::LoggingFramework):
+// - console_logging: Konsola "[SEVİYE] modül: mesaj" satırları; gunluk.renkli ise seviyeye göre renkli.
+// - file_logging: gunluk.dosya Kaynağına zaman damgalı düz metin satırları.
+// - event_tracing: gunluk.json_dosyasi Kaynağına satır başına bir JSON nesnesi (JSON lines).
+// - syslog_logging: gunluk.syslog_soketi yerel soketine RFC 5424 biçiminde mesajlar.
+// Dosyalar gunluk.dosya_azami_boyut aşılınca döndürülür (dosya -> dosya.1 -> ... -> dosya.N,
+// N = gunluk.dosya_sayisi; en eskisi silinir).
+// Seviye gunluk.seviye ile, modül bazında gunluk.modul_seviyeleri ile belirlenir. Bir işlem (srctransaction)
+// süresince tüm kayıtlar aynı işlem kimliğini taşır; böylece bir kurulum tüm modüller boyunca izlenebilir.
+
+const KONSOL_KAYNAGI: &str = "sahne://dev/console";
+const UYGULAMA_ADI: &str = "paket_yoneticisi";
+const SYSLOG_TESISI: u8 = 1; // "user" tesisi
+
+// Etkin işlemin kimliği (0: işlem yok).
+static ISLEM_KIMLIGI: AtomicU64 = AtomicU64::new(0);
+// Aynı mikrosaniyede başlayan işlemlerin kimliklerini ayırmak için
+static ISLEM_SAYACI: AtomicU64 = AtomicU64::new(0);
+
+// Yeni bir işlem kimliği üretir ve bundan sonraki tüm kayıtlara eklenmesini sağlar.
+pub fn islem_kimligi_baslat() -> u64 {
+    let zaman = task::current_time_us().unwrap_or(0);
+    let sayac = ISLEM_SAYACI.fetch_add(1, Ordering::Relaxed);
+    let kimlik = (zaman ^ sayac.rotate_right(16)).max(1);
+    ISLEM_KIMLIGI.store(kimlik, Ordering::Relaxed);
+    kimlik
+}
 
-// no_std uyumlu print makroları (logger backend olarak kullanılabilir)
- use crate::print_macros::{println, eprintln}; // Logger doğrudan resource'a yazacak
+// İşlem bittiğinde (tamamlandı veya geri alındı) kimliği kayıtlardan kaldırır.
+pub fn islem_kimligi_bitir() {
+    ISLEM_KIMLIGI.store(0, Ordering::Relaxed);
+}
 
+pub fn islem_kimligi() -> Option<u64> {
+    match ISLEM_KIMLIGI.load(Ordering::Relaxed) {
+        0 => None,
+        kimlik => Some(kimlik),
+    }
+}
 
-// Sahne64 ortamına özel loglama backend implementasyonu.
-// log crate'inin `Log` trait'ini implement eder.
-struct Sahne64Logger {
-    // Log çıktısının yazılacağı Sahne64 Kaynağının Handle'ı.
-    // Logger statik olduğu için Handle'ın 'static lifetime'ı olmalı veya güvenli erişim sağlanmalı.
-    // Statik Handle yönetimi no_std'de zordur. Geçici olarak Handle'ı field olarak tutmayalım,
-    // Loglama sırasında konsol Kaynağını isimle bulmayı veya sabit bir Handle kullanmayı varsayalım.
-    // Daha temiz bir çözüm, logger'ın başlangıçta console output handle'ını almasıdır.
-    // Ancak set_logger &dyn Log ister.
+// İşlem kimliğinin günlüklerde ve işlem günlüğünde kullanılan biçimi (16 onaltılık hane).
+pub fn kimlik_metni(kimlik: u64) -> String {
+    format!("{:016x}", kimlik)
+}
+
+// Modül bazlı seviye filtresi; bir kayda en uzun eşleşen modül adının seviyesi uygulanır.
+#[derive(Debug, Clone, PartialEq)]
+struct SeviyeFiltresi {
+    varsayilan: LevelFilter,
+    moduller: Vec<(String, LevelFilter)>,
+}
 
-    // Basitlik adına, logger'ın çıktı için standart bir Sahne64 Kaynağına (örn. "sahne://dev/console")
-    // isimle eriştiğini varsayalım. Bu, Kaynak acquire/release overhead'i getirir.
-    // Alternatif: Console output handle'ı global static mut olarak tutulur (unsafe gerektirir).
+impl SeviyeFiltresi {
+    // Seviyeler yapılandırma yüklenirken doğrulanır; yine de ayrıştırılamayanlar atlanır.
+    fn yapilandirmadan(config: &Yapilandirma) -> Self {
+        let moduller = srcconfig::modul_seviyeleri(&config.gunluk_modul_seviyeleri)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(modul, seviye)| seviye.parse::<LevelFilter>().ok().map(|s| (modul, s)))
+            .collect();
+        SeviyeFiltresi {
+            varsayilan: config.log_level.parse::<LevelFilter>().unwrap_or(LevelFilter::Info),
+            moduller,
+        }
+    }
 
-    // Logger struct'ının field'a ihtiyacı yoksa boş bırakabiliriz.
-     output_resource_id: String, // Çıktı Kaynağı ID'si
+    fn seviye(&self, modul: &str) -> LevelFilter {
+        self.moduller.iter()
+            .filter(|(ad, _)| modul_eslesir(modul, ad))
+            .max_by_key(|(ad, _)| ad.len())
+            .map_or(self.varsayilan, |(_, seviye)| *seviye)
+    }
+
+    // log::set_max_level için: herhangi bir modülün izin verdiği en ayrıntılı seviye.
+    fn en_ayrintili(&self) -> LevelFilter {
+        self.moduller.iter().map(|(_, seviye)| *seviye).fold(self.varsayilan, core::cmp::max)
+    }
 }
 
-impl Log for Sahne64Logger {
-    // Log mesajının etkin olup olmadığını kontrol eder.
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        // log::set_max_level tarafından kontrol edilir, genellikle burada ekstra filtreleme yapılmaz.
-        metadata.level() <= log::max_level()
+// "paket_yoneticisi::srcnetwork::alt" yolu "srcnetwork", "srcnetwork::alt" ve tam yol filtrelerine uyar,
+// "srcnet" filtresine uymaz.
+fn modul_eslesir(yol: &str, filtre: &str) -> bool {
+    let kisa = yol.split_once("::").map_or(yol, |(_, kalan)| kalan);
+    [yol, kisa].iter().any(|y| y.strip_prefix(filtre).map_or(false, |kalan| kalan.is_empty() || kalan.starts_with("::")))
+}
+
+// Bir günlük kaydının hedeflere yazılan alanları.
+struct Kayit<'a> {
+    zaman_us: u64, // UNIX zamanı (mikrosaniye)
+    seviye: Level,
+    modul: &'a str,
+    islem: Option<u64>,
+    mesaj: String,
+}
+
+// UNIX zamanını (mikrosaniye) RFC 3339 UTC zaman damgasına çevirir: "2026-10-19T08:30:00.000000Z".
+pub fn zaman_damgasi(zaman_us: u64) -> String {
+    const GUN_US: u64 = 86_400_000_000;
+    let (gun, gun_ici) = ((zaman_us / GUN_US) as i64, zaman_us % GUN_US);
+    // Gün sayısından takvim tarihine (proleptik Gregoryen, 1 Mart'ta başlayan 400 yıllık döngüler)
+    let z = gun + 719_468;
+    let donem = z.div_euclid(146_097);
+    let donem_gunu = z.rem_euclid(146_097);
+    let donem_yili = (donem_gunu - donem_gunu / 1460 + donem_gunu / 36_524 - donem_gunu / 146_096) / 365;
+    let yil_gunu = donem_gunu - (365 * donem_yili + donem_yili / 4 - donem_yili / 100);
+    let ay_indeksi = (5 * yil_gunu + 2) / 153;
+    let ay_gunu = yil_gunu - (153 * ay_indeksi + 2) / 5 + 1;
+    let ay = if ay_indeksi < 10 { ay_indeksi + 3 } else { ay_indeksi - 9 };
+    let yil = donem_yili + donem * 400 + if ay <= 2 { 1 } else { 0 };
+    let saniye = gun_ici / 1_000_000;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        yil, ay, ay_gunu, saniye / 3600, saniye / 60 % 60, saniye % 60, gun_ici % 1_000_000
+    )
+}
+
+fn islem_alani(kayit: &Kayit) -> String {
+    kayit.islem.map_or_else(|| String::from("-"), kimlik_metni)
+}
+
+// Konsol satırı: "[INFO] modül: mesaj"; renkli ise seviye ANSI renk koduyla yazılır.
+fn konsol_satiri(kayit: &Kayit, renkli: bool) -> String {
+    if !renkli {
+        return format!("[{}] {}: {}\n", kayit.seviye, kayit.modul, kayit.mesaj);
     }
+    let renk = match kayit.seviye {
+        Level::Error => "31", // kırmızı
+        Level::Warn => "33", // sarı
+        Level::Info => "32", // yeşil
+        Level::Debug => "34", // mavi
+        Level::Trace => "90", // gri
+    };
+    format!("\x1b[{}m[{}]\x1b[0m {}: {}\n", renk, kayit.seviye, kayit.modul, kayit.mesaj)
+}
 
-    // Log mesajını formatlar ve çıktı Kaynağına yazar.
-    fn log(&self, record: &Record) {
-        // Sadece etkin olan mesajları işle
-        if self.enabled(record.metadata()) {
-            // Log mesajını formatla (timestamp, seviye, modül yolu, mesaj)
-            // format! makrosu alloc gerektirir.
-            let formatted_message = format!(
-                "[{}] {}: {}",
-                record.level(), // Level enum'ı Display implement eder
-                record.module_path().unwrap_or(""), // Modül yolu (opsiyonel)
-                record.args() // Format argümanları
-            );
-
-            // Sahne64 çıktı Kaynağını acquire et (örn. "sahne://dev/console")
-            // Her log mesajı için acquire/release overhead'i yüksektir.
-            // Daha iyi bir backend, handle'ı cache'ler.
-            let console_output_resource_id = "sahne://dev/console"; // Varsayımsal konsol Kaynak ID'si
-            match resource::acquire(console_output_resource_id, resource::MODE_WRITE) { // Yazma izni
-                Ok(handle) => {
-                    // Formatlanmış mesajı Kaynağa yaz (byte dilimi olarak)
-                    let write_result = resource::write(handle, formatted_message.as_bytes()); // String -> &[u8]
-
-                    // Handle'ı serbest bırak
-                    let release_result = resource::release(handle);
-
-                    // Hata durumunda loglama yapamayız (zaten log fonksiyonundayız), hata akışını yönetmek zor.
-                    // Basitçe hataları görmezden gelebiliriz veya çekirdeğe doğrudan bir debug çıktısı göndermeye çalışabiliriz.
-                    if let Err(_e) = write_result {
-                        // Log yazma hatası (örn. Kaynak kapalı, izin yok).
-                         eprintln!("Logger yazma hatası: {:?}", e); // Bu recursive çağrıya neden olabilir!
-                        // Güvenli bir şekilde çekirdeğe temel bir hata bildirme syscall'u olabilir.
-                    }
-                     if let Err(_e) = release_result {
-                         // Handle release hatası.
-                     }
-                }
-                Err(_e) => {
-                    // Kaynak acquire hatası (örn. Kaynak bulunamadı).
-                     eprintln!("Logger Kaynak acquire hatası: {:?}", e); // Recursive olabilir!
-                }
-            }
+// Düz metin dosya satırı: "<zaman> <SEVİYE> [<işlem>] modül: mesaj".
+fn metin_satiri(kayit: &Kayit) -> String {
+    format!("{} {:<5} [{}] {}: {}\n", zaman_damgasi(kayit.zaman_us), kayit.seviye, islem_alani(kayit), kayit.modul, kayit.mesaj)
+}
+
+fn json_metni(cikti: &mut String, metin: &str) {
+    cikti.push('"');
+    for c in metin.chars() {
+        match c {
+            '"' => cikti.push_str("\\\""),
+            '\\' => cikti.push_str("\\\\"),
+            '\n' => cikti.push_str("\\n"),
+            '\r' => cikti.push_str("\\r"),
+            '\t' => cikti.push_str("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write!(cikti, "\\u{:04x}", c as u32); }
+            c => cikti.push(c),
         }
     }
+    cikti.push('"');
+}
 
-    // Log mesajını temizler (genellikle bir şey yapmaz)
-    fn flush(&self) {
-        // resource::write muhtemelen veriyi hemen gönderir veya buffer'lar.
-        // Eğer Kaynak buffering yapıyorsa ve flush komutu destekliyorsa, resource::control ile flush yapılabilir.
-        // Şimdilik bir şey yapmaya gerek yok.
+// JSON lines kaydı: {"zaman":..., "seviye":..., "modul":..., "islem":... veya null, "mesaj":...}
+fn json_satiri(kayit: &Kayit) -> String {
+    let mut satir = String::from("{\"zaman\":");
+    json_metni(&mut satir, &zaman_damgasi(kayit.zaman_us));
+    satir.push_str(",\"seviye\":");
+    json_metni(&mut satir, kayit.seviye.as_str());
+    satir.push_str(",\"modul\":");
+    json_metni(&mut satir, kayit.modul);
+    satir.push_str(",\"islem\":");
+    match kayit.islem {
+        Some(kimlik) => json_metni(&mut satir, &kimlik_metni(kimlik)),
+        None => satir.push_str("null"),
     }
+    satir.push_str(",\"mesaj\":");
+    json_metni(&mut satir, &kayit.mesaj);
+    satir.push_str("}\n");
+    satir
 }
 
+// RFC 5424 syslog mesajı; işlem kimliği MSGID alanında taşınır:
+// "<PRI>1 ZAMAN - paket_yoneticisi - <işlem> - modül: mesaj"
+fn syslog_mesaji(kayit: &Kayit) -> String {
+    let onem = match kayit.seviye {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    };
+    format!(
+        "<{}>1 {} - {} - {} - {}: {}",
+        SYSLOG_TESISI * 8 + onem, zaman_damgasi(kayit.zaman_us), UYGULAMA_ADI, islem_alani(kayit), kayit.modul, kayit.mesaj
+    )
+}
 
-// Loglama sistemini başlatır.
-// config: Yapılandırma struct'ına referans (log seviyesini okumak için).
-// Dönüş değeri: Başarı veya hata (Logger kurulumu hata verebilir).
-pub fn baslat_gunlukleme(config: &Yapilandirma) -> Result<(), Box<dyn log::SetLoggerError>> { // log::SetLoggerError standart bir hata türüdür, Box içinde dönebilir
-    // Log seviyesini yapılandırmadan oku.
-    // Yapilandirma struct'ında log_level: Option<String> alanı olduğunu varsayalım.
-    let log_seviyesi_str = config.log_level.as_deref().unwrap_or("info"); // Default "info"
-
-    // String'i LevelFilter'a dönüştür.
-    let log_seviyesi_filtresi = log_seviyesi_str.parse::<LevelFilter>()
-        .unwrap_or_else(|_| {
-            // Ayrıştırma hatası durumunda uyarı logla (ama logger henüz tam kurulmamış olabilir!)
-            // veya sadece varsayılan değeri kullan.
-            // Logger kurulmadan loglamak zor. Sadece varsayılanı kullanalım ve kurulumdan sonra uyarı loglayalım.
-            LevelFilter::Info
-        });
+// Veriyi verilen modda açılan Kaynağa tamamen yazar.
+fn kaynaga_yaz(resource_id: &str, mod_: u32, veri: &[u8]) -> Result<(), SahneError> {
+    let handle = resource::acquire(resource_id, mod_)?;
+    let mut yazilan = 0;
+    let mut sonuc = Ok(());
+    while yazilan < veri.len() {
+        match resource::write(handle, &veri[yazilan..]) {
+            Ok(0) => {
+                sonuc = Err(SahneError::InvalidOperation);
+                break;
+            }
+            Ok(n) => yazilan += n,
+            Err(e) => {
+                sonuc = Err(e);
+                break;
+            }
+        }
+    }
+    let _ = resource::release(handle);
+    sonuc
+}
 
-    // Logger implementasyonunu ve maksimum log seviyesini ayarla.
-    // Sahne64Logger'ın 'static olması gerekir. Bir static mut kullanmak veya Box'ı leak etmek gerekebilir.
-    // Statik mut kullanımı unsafe gerektirir ve dikkatli senkronizasyon ister.
-    // Box::leak en basit no_std çözümüdür ama bellek sızıntısına neden olur (tek seferlik kurulum için kabul edilebilir).
-    static LOGGER: Sahne64Logger = Sahne64Logger { /* fields if any */ };
+// Bir Kaynağın boyutu (okunarak bulunur; günlük dosyaları azami boyutla sınırlı olduğundan ucuzdur).
+fn kaynak_boyutu(resource_id: &str) -> u64 {
+    let Ok(handle) = resource::acquire(resource_id, resource::MODE_READ) else { return 0 };
+    let mut tampon = [0u8; 4096];
+    let mut boyut = 0u64;
+    while let Ok(n) = resource::read(handle, &mut tampon) {
+        if n == 0 {
+            break;
+        }
+        boyut += n as u64;
+    }
+    let _ = resource::release(handle);
+    boyut
+}
 
-    // set_logger sadece bir kez çağrılmalıdır.
-    log::set_logger(&LOGGER)?; // LOGGER'ın static reference'ı alınır
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DosyaBicimi {
+    Metin,
+    Json,
+}
 
-    // Maksimum log seviyesini ayarla
-    log::set_max_level(log_seviyesi_filtresi);
+// Boyutu aşınca döndürülen günlük dosyası.
+struct DosyaHedefi {
+    kaynak: String,
+    bicim: DosyaBicimi,
+    azami_boyut: u64,
+    dosya_sayisi: u32,
+    boyut: AtomicU64, // Başlangıçta okunur, her yazmada artırılır
+    donduruluyor: AtomicBool, // Aynı anda tek döndürme
+    hata_bildirildi: AtomicBool, // Yazma hataları her kayıtta değil, bir kez bildirilir
+}
 
-    // Logger kurulduktan sonra uyarı loglayabiliriz.
-     if log_seviyesi_str.parse::<LevelFilter>().is_err() {
-         warn!("Geçersiz günlük seviyesi belirtildi: '{}', varsayılan '{}' seviyesi kullanılıyor.",
-               log_seviyesi_str,
-               log_seviyesi_filtresi);
-     }
+impl DosyaHedefi {
+    fn yeni(kaynak: &str, bicim: DosyaBicimi, config: &Yapilandirma) -> Self {
+        DosyaHedefi {
+            kaynak: kaynak.to_string(),
+            bicim,
+            azami_boyut: config.gunluk_dosya_azami_boyut,
+            dosya_sayisi: config.gunluk_dosya_sayisi,
+            boyut: AtomicU64::new(kaynak_boyutu(kaynak)),
+            donduruluyor: AtomicBool::new(false),
+            hata_bildirildi: AtomicBool::new(false),
+        }
+    }
 
+    fn yaz(&self, kayit: &Kayit) {
+        let satir = match self.bicim {
+            DosyaBicimi::Metin => metin_satiri(kayit),
+            DosyaBicimi::Json => json_satiri(kayit),
+        };
+        let boyut = self.boyut.load(Ordering::Relaxed);
+        if boyut > 0 && boyut + satir.len() as u64 > self.azami_boyut {
+            self.dondur();
+        }
+        match kaynaga_yaz(&self.kaynak, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_APPEND, satir.as_bytes()) {
+            Ok(()) => {
+                self.boyut.fetch_add(satir.len() as u64, Ordering::Relaxed);
+            }
+            Err(e) => self.hata("yazılamadı", e),
+        }
+    }
 
-    info!("Günlükleme sistemi başlatıldı. Seviye: {}", log_seviyesi_filtresi); // no_std log
+    // En eski dosya (dosya.N) silinir, dosya.i -> dosya.i+1 kaydırılır ve dosya -> dosya.1 taşınır.
+    // dosya_sayisi 0 ise dosya sadece silinir.
+    fn dondur(&self) {
+        if self.donduruluyor.swap(true, Ordering::Acquire) {
+            return; // Başka bir iş parçacığı döndürüyor
+        }
+        let eski = |i: u32| format!("{}.{}", self.kaynak, i);
+        let sonuc = if self.dosya_sayisi == 0 {
+            kaynagi_sil(&self.kaynak, false)
+        } else {
+            kaynagi_sil(&eski(self.dosya_sayisi), false)
+                .and_then(|_| {
+                    (1..self.dosya_sayisi).rev().try_for_each(|i| match kaynagi_tasi(&eski(i), &eski(i + 1)) {
+                        Err(SahneError::ResourceNotFound) => Ok(()),
+                        sonuc => sonuc,
+                    })
+                })
+                .and_then(|_| kaynagi_tasi(&self.kaynak, &eski(1)))
+        };
+        if let Err(e) = sonuc {
+            self.hata("döndürülemedi", e);
+        }
+        // Döndürme başarısız olsa da her yazmada yeniden denenmemesi için sayaç sıfırlanır.
+        self.boyut.store(0, Ordering::Relaxed);
+        self.donduruluyor.store(false, Ordering::Release);
+    }
 
-    Ok(()) // Başarı
+    fn hata(&self, ne: &str, e: SahneError) {
+        if !self.hata_bildirildi.swap(true, Ordering::Relaxed) {
+            eprintln!("Günlük dosyası {} ({}): {:?}", ne, self.kaynak, e);
+        }
+    }
 }
 
+// Etkin bir günlük hedefi.
+enum Hedef {
+    Konsol { renkli: bool },
+    Dosya(DosyaHedefi),
+    Syslog { soket: String },
+}
 
-// --- Yapilandirma struct tanımının güncellenmesi (srcconfig.rs) ---
-// Log seviyesi için alan eklenmeli.
+impl Hedef {
+    // Hedef hataları kaydı düşürür; günlükleme hatası işlemi durdurmaz.
+    fn yaz(&self, kayit: &Kayit) {
+        match self {
+            Hedef::Konsol { renkli } => {
+                let _ = kaynaga_yaz(KONSOL_KAYNAGI, resource::MODE_WRITE, konsol_satiri(kayit, *renkli).as_bytes());
+            }
+            Hedef::Dosya(dosya) => dosya.yaz(kayit),
+            Hedef::Syslog { soket } => {
+                let _ = kaynaga_yaz(soket, resource::MODE_SEND, syslog_mesaji(kayit).as_bytes());
+            }
+        }
+    }
+}
 
-// srcconfig.rs (Güncellenmiş - log_level alanı eklenmiş)
-#![no_std]
-extern crate alloc;
+// Sahne64 ortamına özel loglama backend implementasyonu.
+// log crate'inin `Log` trait'ini implement eder; her kaydı tüm etkin hedeflere yazar.
+struct Sahne64Logger {
+    filtre: SeviyeFiltresi,
+    hedefler: Vec<Hedef>,
+}
 
-// ... diğer importlar ...
+impl Log for Sahne64Logger {
+    // Kaydın modülü için geçerli seviyeye göre karar verir.
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filtre.seviye(metadata.target())
+    }
 
-use serde::{Deserialize, Serialize};
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let kayit = Kayit {
+            zaman_us: task::current_time_us().unwrap_or(0),
+            seviye: record.level(),
+            modul: record.module_path().unwrap_or_else(|| record.target()),
+            islem: islem_kimligi(),
+            mesaj: record.args().to_string(),
+        };
+        for hedef in &self.hedefler {
+            hedef.yaz(&kayit);
+        }
+    }
 
-// ... diğer struct'lar ...
+    // Hedefler her kaydı hemen yazar; tamponlanan bir şey yok.
+    fn flush(&self) {}
+}
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Yapilandirma {
-    // ... diğer alanlar ...
+// Loglama sistemini başlatır. Sadece bir kez çağrılabilir (log::set_logger kısıtı).
+// config: Seviyeler, hedefler ve hedeflerin Kaynakları buradan okunur.
+pub fn baslat_gunlukleme(config: &Yapilandirma) -> Result<(), log::SetLoggerError> {
+    let filtre = SeviyeFiltresi::yapilandirmadan(config);
+    let mut hedefler = Vec::new();
+    let mut desteklenmeyenler = Vec::new();
+    for hedef in YetenekKaydi::from_config(config).gunluk_hedefleri() {
+        match hedef {
+            LoggingFramework::Console => hedefler.push(Hedef::Konsol { renkli: config.gunluk_renkli }),
+            LoggingFramework::File => hedefler.push(Hedef::Dosya(DosyaHedefi::yeni(&config.gunluk_dosyasi, DosyaBicimi::Metin, config))),
+            LoggingFramework::EventTracing => hedefler.push(Hedef::Dosya(DosyaHedefi::yeni(&config.gunluk_json_dosyasi, DosyaBicimi::Json, config))),
+            LoggingFramework::Syslog => hedefler.push(Hedef::Syslog { soket: config.syslog_soketi.clone() }),
+            LoggingFramework::Database | LoggingFramework::Remote => desteklenmeyenler.push(hedef),
+        }
+    }
+    // Hiç hedef yoksa kayıtlar hiç oluşturulmaz.
+    let azami_seviye = if hedefler.is_empty() { LevelFilter::Off } else { filtre.en_ayrintili() };
 
-    // Loglama seviyesi (örn. "info", "debug", "trace")
-    pub log_level: Option<String>, // Option<String> olarak saklamak ayrıştırmayı başlatma sırasında yapar.
+    // Logger program boyunca yaşar; Box::leak tek seferlik kurulum için kabul edilebilir bir sızıntıdır.
+    log::set_logger(Box::leak(Box::new(Sahne64Logger { filtre, hedefler })))?;
+    log::set_max_level(azami_seviye);
 
-    // ... diğer özellik bayrakları ...
+    for hedef in desteklenmeyenler {
+        warn!("{:?} günlük hedefi desteklenmiyor, yok sayılıyor.", hedef);
+    }
+    info!("Günlükleme sistemi başlatıldı. Seviye: {}", config.log_level);
+    Ok(())
 }
 
-// ... impl Yapilandirma { ... }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kayit(islem: Option<u64>, mesaj: &str) -> Kayit<'static> {
+        Kayit { zaman_us: 1_700_000_000_123_456, seviye: Level::Warn, modul: "paket_yoneticisi::srcnetwork", islem, mesaj: mesaj.to_string() }
+    }
+
+    #[test]
+    fn test_zaman_damgasi() {
+        assert_eq!(zaman_damgasi(0), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(zaman_damgasi(1_700_000_000_123_456), "2023-11-14T22:13:20.123456Z");
+        assert_eq!(zaman_damgasi(951_782_400_000_000), "2000-02-29T00:00:00.000000Z");
+    }
+
+    #[test]
+    fn test_modul_filtresi_en_uzun_eslesme() {
+        let filtre = SeviyeFiltresi {
+            varsayilan: LevelFilter::Info,
+            moduller: vec![("srcnetwork".to_string(), LevelFilter::Debug), ("srcnetwork::tls".to_string(), LevelFilter::Error)],
+        };
+        assert_eq!(filtre.seviye("paket_yoneticisi::srcnetwork"), LevelFilter::Debug);
+        assert_eq!(filtre.seviye("paket_yoneticisi::srcnetwork::tls"), LevelFilter::Error);
+        assert_eq!(filtre.seviye("paket_yoneticisi::srcnetworkx"), LevelFilter::Info);
+        assert_eq!(filtre.en_ayrintili(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_kayit_bicimleri() {
+        let k = kayit(Some(0xabc), "\"zip\" indirilemedi\n");
+        assert_eq!(metin_satiri(&k), "2023-11-14T22:13:20.123456Z WARN  [0000000000000abc] paket_yoneticisi::srcnetwork: \"zip\" indirilemedi\n\n");
+        assert_eq!(
+            json_satiri(&k),
+            "{\"zaman\":\"2023-11-14T22:13:20.123456Z\",\"seviye\":\"WARN\",\"modul\":\"paket_yoneticisi::srcnetwork\",\"islem\":\"0000000000000abc\",\"mesaj\":\"\\\"zip\\\" indirilemedi\\n\"}\n"
+        );
+        let k = kayit(None, "a");
+        assert_eq!(syslog_mesaji(&k), "<12>1 2023-11-14T22:13:20.123456Z - paket_yoneticisi - - - paket_yoneticisi::srcnetwork: a");
+        assert_eq!(konsol_satiri(&k, true), "\x1b[33m[WARN]\x1b[0m paket_yoneticisi::srcnetwork: a\n");
+    }
+}
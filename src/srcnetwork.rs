@@ -16,17 +16,54 @@ use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
 // no_std uyumlu print makroları
 use crate::print_macros::{println, eprintln};
 
+// Hangi protokollerin kullanılabileceği yetenek kaydındaki özellik kümesinden gelir
+use crate::srcfeatures::{Feature, FeatureSet, NetworkProtocol};
+
 
 // Sahne64'e özgü ağ (network) işlemleri için Kaynak tabanlı arayüz kullanılıyor.
 // Bir URL, belirli bir şemaya sahip bir Kaynak ID'si olarak kabul edilir
 // (örn. "http://example.com/file.zip").
 // resource::acquire(url, MODE_READ) isteği başlatır, resource::read cevabı okur.
 
+// URL şemasının ağ protokolü. "sahne://" Kaynakları ve şemasız yollar yerel sayılır (None).
+// Tanınmayan şemalar için Err(şema) döner.
+pub fn url_protokolu(url: &str) -> Result<Option<NetworkProtocol>, &str> {
+    let sema = match url.split_once("://") {
+        Some((sema, _)) => sema,
+        None => return Ok(None),
+    };
+    let protokol = match sema.to_ascii_lowercase().as_str() {
+        "sahne" => return Ok(None),
+        "http" => NetworkProtocol::Http,
+        "https" => NetworkProtocol::Https,
+        "ftp" => NetworkProtocol::Ftp,
+        "tcp" => NetworkProtocol::Tcp,
+        "udp" => NetworkProtocol::Udp,
+        "ws" | "wss" => NetworkProtocol::Websocket,
+        _ => return Err(sema),
+    };
+    Ok(Some(protokol))
+}
+
+// URL'nin protokolü etkin özelliklerde (ozellikler.ag ve ozellikler.ag_protokolleri) izinli değilse NetworkError döner.
+pub fn protokol_denetle(url: &str, ozellikler: &FeatureSet) -> Result<(), PaketYoneticisiHatasi> {
+    match url_protokolu(url) {
+        Ok(None) => Ok(()),
+        Ok(Some(protokol)) if ozellikler.is_enabled(&Feature::Network(protokol)) => Ok(()),
+        Ok(Some(protokol)) => Err(PaketYoneticisiHatasi::NetworkError(format!(
+            "{}: {} protokolü etkin özelliklerde kapalı", url, Feature::Network(protokol).ad()
+        ))),
+        Err(sema) => Err(PaketYoneticisiHatasi::NetworkError(format!("{}: bilinmeyen protokol '{}'", url, sema))),
+    }
+}
+
 // URL'den hedef Kaynağa dosya indirir.
 // url: İndirilecek dosyanın Kaynak ID'si (URL formatında olabilir).
 // hedef_kaynak_id: Dosyanın kaydedileceği yerel Kaynağın ID'si.
+// ozellikler: Etkin özellikler; URL'nin protokolü izinli değilse indirme başlatılmaz.
 // Dönüş değeri: Başarı veya PaketYoneticisiHatasi.
-pub fn dosya_indir(url: &str, hedef_kaynak_id: &str) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+pub fn dosya_indir(url: &str, hedef_kaynak_id: &str, ozellikler: &FeatureSet) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+    protokol_denetle(url, ozellikler)?;
     println!("Dosya indirme başlatılıyor: {} -> {}", url, hedef_kaynak_id); // no_std print
 
     // Uzak (ağ) Kaynağı okuma izniyle acquire et. Bu isteği başlatır.
@@ -92,7 +129,7 @@ pub fn dosya_indir(url: &str, hedef_kaynak_id: &str) -> Result<(), PaketYonetici
 // URL'den hedef Kaynağa dosyayı indirir ve ilerleme raporlar (Placeholder).
 // Sahne64 API'sında indirme ilerlemesini almak için özel bir mekanizma gereklidir
 // (örn. resource::control komutu veya read syscall'undan dönen özel durumlar).
-pub fn dosya_indir_ilerleme(url: &str, hedef_kaynak_id: &str) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
+pub fn dosya_indir_ilerleme(url: &str, hedef_kaynak_id: &str, ozellikler: &FeatureSet) -> Result<(), PaketYoneticisiHatasi> { // Result türü PaketYoneticisiHatasi olmalı
     println!("İlerlemeli dosya indirme başlatılıyor (ilerleme raporlama implemente edilmedi): {} -> {}", url, hedef_kaynak_id); // no_std print
 
     // Temel indirme mantığı 'dosya_indir' fonksiyonu ile aynı olacaktır.
@@ -129,5 +166,21 @@ pub fn dosya_indir_ilerleme(url: &str, hedef_kaynak_id: &str) -> Result<(), Pake
      }
 
     // Şu anki API eksikliği nedeniyle, sadece temel indirme fonksiyonunu çağırıyoruz.
-    dosya_indir(url, hedef_kaynak_id) // Temel indirme işlevini kullan
+    dosya_indir(url, hedef_kaynak_id, ozellikler) // Temel indirme işlevini kullan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protokol_denetle() {
+        let mut ozellikler = FeatureSet::new();
+        ozellikler.enable(Feature::Network(NetworkProtocol::Https));
+        assert!(protokol_denetle("sahne://remoterepo/packages", &ozellikler).is_ok());
+        assert!(protokol_denetle("HTTPS://ayna.ornek/paketler", &ozellikler).is_ok());
+        assert!(matches!(protokol_denetle("http://ayna.ornek/paketler", &ozellikler), Err(PaketYoneticisiHatasi::NetworkError(_))));
+        assert!(matches!(protokol_denetle("gopher://ayna.ornek", &ozellikler), Err(PaketYoneticisiHatasi::NetworkError(_))));
+        assert_eq!(url_protokolu("wss://ayna.ornek"), Ok(Some(NetworkProtocol::Websocket)));
+    }
 }
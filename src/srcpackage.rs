@@ -4,6 +4,16 @@ extern crate alloc; // String, Vec, HashMap için
 use alloc::string::String; // std::string::String yerine
 use alloc::vec::Vec; // std::vec::Vec yerine
 use alloc::collections::HashMap; // Checksums gibi ek alanlar için gerekebilir
+use alloc::collections::{BTreeMap, BTreeSet}; // Özellik tanımları (deterministik serileştirme ve Hash için)
+use alloc::format;
+use alloc::borrow::ToOwned;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+// Betik (hook) aşamaları
+use crate::srcscript::BetikAsamasi;
+// Sistem genelinde tetikleyiciler
+use crate::srctrigger::Tetikleyici;
 
 // serde derive'lar (no_std uyumlu backend ile çalışır)
 use serde::{Deserialize, Serialize};
@@ -37,6 +47,48 @@ pub struct Paket {
     // resource::acquire ile çalıştırılabilir dosyalar veya betikler olabilir.
     pub kurulum_scripti: Option<String>, // Kurulum betiği Kaynak ID'si veya içeriği (String alloc gerektirir)
     pub kaldirma_scripti: Option<String>, // Kaldırma betiği Kaynak ID'si veya içeriği (String alloc gerektirir)
+    // Aşama -> betik Kaynağı (paketin betik dizinine göre yol veya tam Kaynak ID'si), örn. "pre-install": "hazirla.sh".
+    // Tanımlanmayan aşamalar için betik dizinindeki "<aşama>.sh" aranır; kurulum_scripti post-install,
+    // kaldirma_scripti pre-remove için eski biçim olarak kullanılmaya devam eder.
+    #[serde(default)]
+    pub betikler: BTreeMap<BetikAsamasi, String>,
+    // Bu paketin bildirdiği tetikleyiciler: herhangi bir paketin desenlere uyan dosyaları kurulduğunda veya
+    // kaldırıldığında, işlem sonunda bir kez çalıştırılacak betikler (örn. yazı tipi önbelleğini yenileme).
+    #[serde(default)]
+    pub tetikleyiciler: Vec<Tetikleyici>,
+
+    // Bu paketin sağladığı sanal yetenek adları (örn. "mail-transport-agent").
+    // Bu adlara bağımlı paketler, sağlayıcılardan biri kurularak karşılanır.
+    #[serde(default)]
+    pub saglar: Vec<String>,
+    // Bu paketin yerini aldığı paketlerin adları (Debian "Replaces" benzeri).
+    // Bu paketler kurulu ise kurulumda otomatik kaldırılır ve dosyaları devralınabilir.
+    #[serde(default)]
+    pub degistirir: Vec<String>,
+    // Bu paketle aynı anda kurulu olamayan paketlerin adları (Debian "Conflicts" benzeri).
+    // Kurulumda bu paketler kaldırılır; dosyaları da devralınabilir.
+    #[serde(default)]
+    pub cakisir: Vec<String>,
+
+    // Önerilen paketler (Debian "Recommends" benzeri). Kurulumda istenirse bağımlılık gibi kurulur,
+    // istenmezse sadece kullanıcıya bildirilir.
+    #[serde(default)]
+    pub onerilenler: Vec<String>,
+    // Tavsiye edilen paketler (Debian "Suggests" benzeri). Varsayılan olarak sadece bildirilir.
+    #[serde(default)]
+    pub tavsiyeler: Vec<String>,
+    // Adlandırılmış paket özellikleri (cargo "features" benzeri): özellik adı -> etkinleştirildiğinde
+    // çekilecek isteğe bağlı bağımlılıklar. Listede başka bir özelliğin adı geçerse o özellik de etkinleşir.
+    #[serde(default)]
+    pub ozellikler: BTreeMap<String, Vec<String>>,
+    // Kullanıcı aksini belirtmedikçe etkin olan özellikler.
+    #[serde(default)]
+    pub varsayilan_ozellikler: Vec<String>,
+
+    // Kullanıcının düzenlemesi beklenen yapılandırma dosyaları (Debian "conffiles" benzeri; `dosyalar`
+    // içindeki yollar). Güncellemede değiştirilmiş olanların üzerine yazılmaz (bkz. srcconffiles).
+    #[serde(default)]
+    pub yapilandirma_dosyalari: Vec<String>,
 
     // Lisans bilgisi, yazar vb. diğer meta veriler eklenebilir.
      pub lisans: Option<String>,
@@ -57,10 +109,48 @@ impl Paket {
             dosyalar: Vec::new(), // Vec::new() alloc gerektirir.
             kurulum_scripti: None, // Option alloc gerektirmez
             kaldirma_scripti: None, // Option alloc gerektirmez
+            betikler: BTreeMap::new(),
+            tetikleyiciler: Vec::new(),
+            saglar: Vec::new(),
+            degistirir: Vec::new(),
+            cakisir: Vec::new(),
+            onerilenler: Vec::new(),
+            tavsiyeler: Vec::new(),
+            ozellikler: BTreeMap::new(),
+            varsayilan_ozellikler: Vec::new(),
+            yapilandirma_dosyalari: Vec::new(),
             // ... diğer alanlar default/None ...
         }
     }
 
+    // Seçilen özellikleri, başka özelliklere verdikleri referanslarla birlikte açar.
+    // secilenler: Etkinleştirilmek istenen özellik adları.
+    // Dönüş değeri: (etkin özellikler, bu özelliklerin çektiği bağımlılıklar), ikisi de sıralı ve tekrarsız.
+    // Pakette tanımlı olmayan bir özellik adı InvalidParameter hatası verir.
+    pub fn ozellikleri_coz(&self, secilenler: &[String]) -> Result<(Vec<String>, Vec<String>), PaketYoneticisiHatasi> {
+        let mut etkin: BTreeSet<String> = BTreeSet::new();
+        let mut bagimliliklar: BTreeSet<String> = BTreeSet::new();
+        let mut yigin: Vec<String> = secilenler.to_vec();
+
+        while let Some(ozellik) = yigin.pop() {
+            let girdiler = self.ozellikler.get(&ozellik).ok_or_else(|| {
+                PaketYoneticisiHatasi::InvalidParameter(format!("'{}' paketinde '{}' özelliği yok", self.ad, ozellik))
+            })?;
+            if !etkin.insert(ozellik) {
+                continue; // Zaten açıldı (özellikler arası döngülere karşı da koruma)
+            }
+            for girdi in girdiler {
+                if self.ozellikler.contains_key(girdi) {
+                    yigin.push(girdi.clone());
+                } else {
+                    bagimliliklar.insert(girdi.to_owned());
+                }
+            }
+        }
+
+        Ok((etkin.into_iter().collect(), bagimliliklar.into_iter().collect()))
+    }
+
     // Paket struct'ının kimliğini (ad ve sürüm) temsil eden bir method (Hash ve Eq derive'ları ile aynı bilgiyi verir).
     // Kullanım örneği: Başka bir struct içinde Paket'e referans yerine PaketId tutmak.
      #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -0,0 +1,369 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, format! için
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use alloc::collections::HashMap; // Paket.checksums türü (srcpackage.rs)
+
+use md5::Md5;
+use sha2::{Sha256, Digest};
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+use crate::Handle;
+
+// no_std okuma arayüzü (srcarchive.rs'deki SahneResourceReader notlarındaki varsayımla aynı:
+// zip/tar/sıkıştırma crate'lerinin beklediği Read trait'i ve hata türü).
+use crate::io::{Read, Error as IoError, ErrorKind};
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::srcarchive::{self, ArsivBicimi, CikarmaRaporu, CikarmaSinirlari};
+use crate::srcfeatures::FeatureSet;
+use crate::srctar;
+use crate::srcstaging::{kaynagi_sil, kaynagi_tasi};
+
+// no_std uyumlu print makroları
+use crate::print_macros::{println, eprintln};
+
+// Biçim algılama için akışın başından tamponlanan bayt sayısı (srcarchive ile aynı).
+const BICIM_ALGILAMA_BOYUTU: usize = 512;
+
+// İndirme sırasında önbellek dosyasının yazıldığı geçici Kaynağın son eki. Arşiv, özeti doğrulandıktan
+// sonra asıl adına taşınır; böylece yarıda kesilen bir indirme önbellekte geçerli bir arşiv gibi görünmez.
+const GECICI_SON_EKI: &str = ".gecici";
+
+// Ağ Kaynağından okuyan, okunan her baytı özetleyen (SHA256 + MD5) ve aynı anda önbellek Kaynağına
+// yazan okuyucu. tar çözücüsü bu okuyucudan çektikçe indirme, özetleme, önbelleğe yazma ve çıkarma
+// tek geçişte ilerler; paket hiçbir zaman tamamen belleğe alınmaz veya iki kez okunmaz.
+struct OzetleyenAkis {
+    kaynak_handle: Handle,
+    onbellek_handle: Handle,
+    sha256: Sha256,
+    md5: Md5,
+    okunan: u64,
+    // Read trait'inin hata türü SahneError taşıyamadığı için asıl hata burada saklanır.
+    sahne_hatasi: Option<SahneError>,
+}
+
+impl OzetleyenAkis {
+    // Akışın kalanını (tar sonundaki dolgu blokları, arşiv sonrası veriler) okuyup özete ve önbelleğe ekler.
+    // Özetin tüm dosyayı kapsaması için çıkarmadan sonra mutlaka çağrılmalıdır.
+    fn kalani_tuket(&mut self) -> Result<(), IoError> {
+        let mut buffer = [0u8; 4096];
+        while self.read(&mut buffer)? != 0 {}
+        Ok(())
+    }
+
+    // Sahne hatası varsa onu, yoksa okuma hatasını PaketYoneticisiHatasi'na çevirir.
+    fn hatayi_cevir(&mut self, e: IoError) -> PaketYoneticisiHatasi {
+        match self.sahne_hatasi.take() {
+            Some(sahne) => PaketYoneticisiHatasi::from(sahne),
+            None => PaketYoneticisiHatasi::NetworkError(format!("{:?}", e)),
+        }
+    }
+}
+
+impl Read for OzetleyenAkis {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+        let n = match resource::read(self.kaynak_handle, buf) {
+            Ok(n) => n,
+            Err(e) => {
+                self.sahne_hatasi = Some(e);
+                return Err(IoError::new(ErrorKind::Other, "ağ Kaynağı okuma hatası"));
+            }
+        };
+
+        self.sha256.update(&buf[..n]);
+        self.md5.update(&buf[..n]);
+        self.okunan += n as u64;
+
+        // Okunan veriyi önbelleğe yaz (kısmi yazmaları tamamla)
+        let mut written = 0;
+        while written < n {
+            match resource::write(self.onbellek_handle, &buf[written..n]) {
+                Ok(0) => {
+                    self.sahne_hatasi = Some(SahneError::InvalidOperation);
+                    return Err(IoError::new(ErrorKind::WriteZero, "önbellek Kaynağına yazılamadı"));
+                }
+                Ok(m) => written += m,
+                Err(e) => {
+                    self.sahne_hatasi = Some(e);
+                    return Err(IoError::new(ErrorKind::Other, "önbellek Kaynağı yazma hatası"));
+                }
+            }
+        }
+        Ok(n)
+    }
+}
+
+// Akışlı kurulumun sonucu.
+#[derive(Debug)]
+pub struct AkisSonucu {
+    pub rapor: CikarmaRaporu, // Hazırlık alanına çıkarılan/reddedilen girdiler
+    pub indirilen_bayt: u64,
+    pub sha256: String, // Hesaplanan özetler (hex)
+    pub md5: String,
+}
+
+// Paketi indirirken özetler, önbelleğe yazar ve (tar.* için) aynı anda hazırlık alanına çıkarır.
+// Hazırlık alanı yalnızca son özet paket meta verisindeki checksum ile eşleşirse geçerli kabul edilir;
+// eşleşmezse hazırlık alanı ve geçici önbellek dosyası silinir ve ChecksumVerificationError döner.
+// İndirilen arşiv "<onbellek_id>.gecici" Kaynağına yazılır ve ancak özet doğrulandıktan sonra onbellek_id
+// adına taşınır.
+// zip akış halinde açılamadığı (merkezi dizin dosyanın sonundadır) için zip paketleri önce önbelleğe
+// indirilip doğrulanır, sonra açılır.
+// url: Paketin uzak Kaynak ID'si.
+// onbellek_id: İndirilen arşivin yazılacağı önbellek Kaynak ID'si.
+// hazirlik_id: Çıkarılacak içeriğin geçici hedefi (etkinleştirme çağırana aittir).
+// beklenen: Paket meta verisindeki checksum'lar ("sha256" ve/veya "md5").
+pub fn akisli_indir_ve_ac(
+    url: &str,
+    onbellek_id: &str,
+    hazirlik_id: &str,
+    beklenen: &HashMap<String, String>,
+    ozellikler: &FeatureSet,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<AkisSonucu, PaketYoneticisiHatasi> {
+    // Doğrulanamayacak bir paketi indirmeye hiç başlama.
+    if !beklenen.contains_key("sha256") && !beklenen.contains_key("md5") {
+        return Err(PaketYoneticisiHatasi::InvalidParameter(format!("{}: meta veride checksum yok, akışlı kurulum doğrulanamaz", url)));
+    }
+
+    println!("Akışlı indirme başlatılıyor: {} -> {} (hazırlık: {})", url, onbellek_id, hazirlik_id);
+    let gecici_id = format!("{}{}", onbellek_id, GECICI_SON_EKI); // alloc
+
+    let kaynak_handle = resource::acquire(url, resource::MODE_READ).map_err(|e| {
+        eprintln!("Uzak Kaynak acquire hatası ({}): {:?}", url, e);
+        PaketYoneticisiHatasi::from(e)
+    })?;
+    let onbellek_handle = match resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE) {
+        Ok(h) => h,
+        Err(e) => {
+            eprintln!("Önbellek Kaynağı acquire hatası ({}): {:?}", gecici_id, e);
+            let _ = resource::release(kaynak_handle);
+            return Err(PaketYoneticisiHatasi::from(e));
+        }
+    };
+
+    let mut akis = OzetleyenAkis {
+        kaynak_handle,
+        onbellek_handle,
+        sha256: Sha256::new(),
+        md5: Md5::new(),
+        okunan: 0,
+        sahne_hatasi: None,
+    };
+
+    let dogrulanan = akisi_isle(&mut akis, onbellek_id, hazirlik_id, beklenen, ozellikler, sinirlar);
+
+    let _ = resource::release(kaynak_handle);
+    if let Err(e) = resource::release(onbellek_handle) {
+        eprintln!("Önbellek Kaynağı release hatası ({}): {:?}", gecici_id, e);
+    }
+
+    let sonuc = dogrulanan.and_then(|(tar_raporu, sha256, md5)| {
+        // Doğrulanmış arşiv asıl adına taşınır. Taşıma atomik olmasa da (önce eski kopya silinir) önbellekte
+        // hiçbir zaman doğrulanmamış bir arşiv bulunmaz.
+        kaynagi_sil(onbellek_id, false).map_err(PaketYoneticisiHatasi::from)?;
+        kaynagi_tasi(&gecici_id, onbellek_id).map_err(PaketYoneticisiHatasi::from)?;
+
+        // zip için çıkarma artık doğrulanmış önbellek dosyasından yapılabilir.
+        let rapor = match tar_raporu {
+            Some(rapor) => rapor,
+            None => srcarchive::zip_ac_sinirli(onbellek_id, hazirlik_id, sinirlar)?,
+        };
+        println!("Paket indirildi ve doğrulandı: {} ({} bayt, sha256 {})", onbellek_id, akis.okunan, sha256);
+        Ok(AkisSonucu { rapor, indirilen_bayt: akis.okunan, sha256, md5 })
+    });
+
+    if sonuc.is_err() {
+        // Yarım kalan hazırlık alanı ve doğrulanmamış geçici önbellek dosyası kullanılmamalı.
+        if let Err(e) = kaynagi_sil(hazirlik_id, true) {
+            eprintln!("Hazırlık alanı temizlenemedi ({}): {:?}", hazirlik_id, e);
+        }
+        if let Err(e) = kaynagi_sil(&gecici_id, false) {
+            eprintln!("Geçici önbellek dosyası temizlenemedi ({}): {:?}", gecici_id, e);
+        }
+    }
+
+    sonuc
+}
+
+// Paketi çıkarmadan önbelleğe indirir ve doğrular (akisli_indir_ve_ac'ın çıkarmasız hali; örn. onarım için).
+// Arşiv "<onbellek_id>.gecici" Kaynağına yazılır ve ancak özeti meta verideki checksum ile eşleşirse
+// onbellek_id adına taşınır; eşleşmezse geçici dosya silinir ve ChecksumVerificationError döner.
+// Dönüş değeri: İndirilen bayt sayısı.
+pub fn dogrulayarak_indir(url: &str, onbellek_id: &str, beklenen: &HashMap<String, String>) -> Result<u64, PaketYoneticisiHatasi> {
+    if !beklenen.contains_key("sha256") && !beklenen.contains_key("md5") {
+        return Err(PaketYoneticisiHatasi::InvalidParameter(format!("{}: meta veride checksum yok, indirme doğrulanamaz", url)));
+    }
+    let gecici_id = format!("{}{}", onbellek_id, GECICI_SON_EKI); // alloc
+
+    let kaynak_handle = resource::acquire(url, resource::MODE_READ).map_err(|e| {
+        eprintln!("Uzak Kaynak acquire hatası ({}): {:?}", url, e);
+        PaketYoneticisiHatasi::from(e)
+    })?;
+    let onbellek_handle = match resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE) {
+        Ok(h) => h,
+        Err(e) => {
+            eprintln!("Önbellek Kaynağı acquire hatası ({}): {:?}", gecici_id, e);
+            let _ = resource::release(kaynak_handle);
+            return Err(PaketYoneticisiHatasi::from(e));
+        }
+    };
+    let mut akis = OzetleyenAkis {
+        kaynak_handle,
+        onbellek_handle,
+        sha256: Sha256::new(),
+        md5: Md5::new(),
+        okunan: 0,
+        sahne_hatasi: None,
+    };
+
+    let indirme = match akis.kalani_tuket() {
+        Ok(()) => {
+            let sha256 = hex::encode(akis.sha256.finalize_reset());
+            let md5 = hex::encode(akis.md5.finalize_reset());
+            ozetleri_dogrula(beklenen, &sha256, &md5)
+        }
+        Err(e) => Err(akis.hatayi_cevir(e)),
+    };
+    let _ = resource::release(kaynak_handle);
+    if let Err(e) = resource::release(onbellek_handle) {
+        eprintln!("Önbellek Kaynağı release hatası ({}): {:?}", gecici_id, e);
+    }
+
+    let sonuc = indirme.and_then(|()| {
+        kaynagi_sil(onbellek_id, false).map_err(PaketYoneticisiHatasi::from)?;
+        kaynagi_tasi(&gecici_id, onbellek_id).map_err(PaketYoneticisiHatasi::from)
+    });
+    if sonuc.is_err() {
+        if let Err(e) = kaynagi_sil(&gecici_id, false) {
+            eprintln!("Geçici önbellek dosyası temizlenemedi ({}): {:?}", gecici_id, e);
+        }
+    }
+    sonuc?;
+    println!("Paket indirildi ve doğrulandı: {} ({} bayt)", onbellek_id, akis.okunan);
+    Ok(akis.okunan)
+}
+
+// Önbellekteki bir arşivin özetlerini hesaplayıp paket meta verisindeki checksum'larla karşılaştırır
+// (bkz. ozetleri_dogrula). Önbellekteki arşivler açılmadan önce bununla doğrulanır.
+pub fn onbellek_arsivini_dogrula(onbellek_id: &str, beklenen: &HashMap<String, String>) -> Result<(), PaketYoneticisiHatasi> {
+    let handle = resource::acquire(onbellek_id, resource::MODE_READ).map_err(PaketYoneticisiHatasi::from)?;
+    let mut sha256 = Sha256::new();
+    let mut md5 = Md5::new();
+    let mut buffer = [0u8; 4096];
+    let okuma = loop {
+        match resource::read(handle, &mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(n) => {
+                sha256.update(&buffer[..n]);
+                md5.update(&buffer[..n]);
+            }
+            Err(e) => break Err(PaketYoneticisiHatasi::from(e)),
+        }
+    };
+    let _ = resource::release(handle);
+    okuma?;
+    ozetleri_dogrula(beklenen, &hex::encode(sha256.finalize()), &hex::encode(md5.finalize()))
+}
+
+// akisli_indir_ve_ac'ın asıl gövdesi: indirir, (tar.* için) çıkarır ve özetleri doğrular.
+// Handle'ların serbest bırakılması, arşivin yerine taşınması ve hata durumunda temizlik çağırana aittir.
+// Dönüş değeri: tar.* için çıkarma raporu (zip için None, henüz çıkarılmamıştır), sha256 ve md5 (hex).
+fn akisi_isle(
+    akis: &mut OzetleyenAkis,
+    onbellek_id: &str,
+    hazirlik_id: &str,
+    beklenen: &HashMap<String, String>,
+    ozellikler: &FeatureSet,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<(Option<CikarmaRaporu>, String, String), PaketYoneticisiHatasi> {
+    // 1. Biçimi algılamak için akışın başını tamponla (bu baytlar da özetlenip önbelleğe yazılır).
+    let mut on_ek = Vec::with_capacity(BICIM_ALGILAMA_BOYUTU); // alloc
+    if let Err(e) = (&mut *akis).take(BICIM_ALGILAMA_BOYUTU as u64).read_to_end(&mut on_ek) {
+        return Err(akis.hatayi_cevir(e));
+    }
+
+    let bicim = srcarchive::arsiv_bicimini_algila(&on_ek)
+        .ok_or_else(|| PaketYoneticisiHatasi::InstallationError(format!("{}: tanınmayan arşiv biçimi", onbellek_id)))?;
+    if !srcarchive::bicim_etkin_mi(bicim, ozellikler) {
+        return Err(PaketYoneticisiHatasi::InstallationError(format!("{}: {:?} biçimi etkin özelliklerde kapalı", onbellek_id, bicim)));
+    }
+
+    // 2. tar.* : indirme ile eş zamanlı çıkar. zip: sadece indir (çıkarma doğrulamadan sonra).
+    let tar_raporu = match bicim {
+        ArsivBicimi::Tar(sikistirma) => {
+            let cikarma = {
+                let zincir = (&on_ek[..]).chain(&mut *akis);
+                srctar::cozucu_olustur(zincir, sikistirma)
+                    .and_then(|okuyucu| srctar::tar_girdilerini_cikar(okuyucu, hazirlik_id, sinirlar))
+            };
+            // Çözücü hatası ağ/önbellek Kaynağındaki bir hatadan kaynaklandıysa asıl Sahne hatası döndürülür.
+            match cikarma {
+                Ok(rapor) => Some(rapor),
+                Err(e) => return Err(akis.sahne_hatasi.take().map(PaketYoneticisiHatasi::from).unwrap_or(e)),
+            }
+        }
+        ArsivBicimi::Zip => None,
+    };
+
+    // 3. Akışın kalanını tüket, özetin tüm dosyayı kapsamasını sağla.
+    if let Err(e) = akis.kalani_tuket() {
+        return Err(akis.hatayi_cevir(e));
+    }
+
+    let sha256 = hex::encode(akis.sha256.finalize_reset());
+    let md5 = hex::encode(akis.md5.finalize_reset());
+
+    // 4. Doğrula. Eşleşmezse hiçbir şey kullanılmaz (çağıran temizler).
+    ozetleri_dogrula(beklenen, &sha256, &md5)?;
+    Ok((tar_raporu, sha256, md5))
+}
+
+// Hesaplanan özetleri meta verideki değerlerle karşılaştırır (büyük/küçük harf duyarsız).
+// Meta veride bulunan her algoritma eşleşmelidir; en az biri bulunmalıdır.
+pub fn ozetleri_dogrula(beklenen: &HashMap<String, String>, sha256: &str, md5: &str) -> Result<(), PaketYoneticisiHatasi> {
+    let mut dogrulanan = 0;
+    for (algoritma, hesaplanan) in [("sha256", sha256), ("md5", md5)] {
+        if let Some(deger) = beklenen.get(algoritma) {
+            if !deger.eq_ignore_ascii_case(hesaplanan) {
+                eprintln!("Checksum uyuşmazlığı ({}): beklenen {}, hesaplanan {}", algoritma, deger, hesaplanan);
+                return Err(PaketYoneticisiHatasi::ChecksumVerificationError);
+            }
+            dogrulanan += 1;
+        }
+    }
+    if dogrulanan == 0 {
+        return Err(PaketYoneticisiHatasi::ChecksumVerificationError);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beklenen(ciftler: &[(&str, &str)]) -> HashMap<String, String> {
+        ciftler.iter().map(|(a, d)| (a.to_string(), d.to_string())).collect()
+    }
+
+    #[test]
+    fn test_ozetleri_dogrula_eslesme() {
+        let b = beklenen(&[("sha256", "ABCD"), ("md5", "1234")]);
+        assert!(ozetleri_dogrula(&b, "abcd", "1234").is_ok());
+        assert!(ozetleri_dogrula(&beklenen(&[("md5", "1234")]), "ffff", "1234").is_ok());
+    }
+
+    #[test]
+    fn test_ozetleri_dogrula_uyusmazlik() {
+        let b = beklenen(&[("sha256", "abcd"), ("md5", "1234")]);
+        assert!(matches!(ozetleri_dogrula(&b, "abcd", "9999"), Err(PaketYoneticisiHatasi::ChecksumVerificationError)));
+        assert!(matches!(ozetleri_dogrula(&beklenen(&[]), "abcd", "1234"), Err(PaketYoneticisiHatasi::ChecksumVerificationError)));
+    }
+}
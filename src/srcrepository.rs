@@ -27,6 +27,15 @@ use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
 // no_std uyumlu print makroları
 use crate::print_macros::{println, eprintln};
 
+// Uzak indeksin imzası yayınlanırken üretilen ayrık imza dosyasıyla doğrulanır
+use crate::srcrepositorypublish::{
+    GUVENLIK_BILDIRILERI_DOSYASI, GUVENLIK_BILDIRILERI_IMZA_DOSYASI, PAKET_LISTESI_DOSYASI, PAKET_LISTESI_IMZA_DOSYASI,
+};
+use crate::srcadvisory::BildiriAkisi;
+use crate::srcsecurity::SecurityError;
+use crate::srcsigning;
+use crate::task; // Bildiri akışının geçerlilik denetimi için task::current_time_us
+
 // Helper function to read resource content into a Vec<u8> (reused from previous refactoring)
 fn read_resource_to_vec(resource_id: &str) -> Result<Vec<u8>, PaketYoneticisiHatasi> {
     let handle = resource::acquire(resource_id, resource::MODE_READ)
@@ -60,6 +69,20 @@ fn read_resource_to_vec(resource_id: &str) -> Result<Vec<u8>, PaketYoneticisiHat
     Ok(buffer)
 }
 
+// Helper: Doğrulanmış uzak verinin yerel kopyasını yazar. Hatalar yalnızca raporlanır; yerel kopya
+// yazılamazsa bir sonraki çalışmada uzak depo yeniden denenir.
+fn yerel_kopyaya_yaz(resource_id: &str, veri: &[u8]) {
+    match resource::acquire(resource_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE) {
+        Ok(handle) => {
+            let write_result = resource::write(handle, veri);
+            let release_result = resource::release(handle);
+            if let Err(e) = write_result { eprintln!("Yerel kopya yazma hatası ({}): {:?}", resource_id, e); }
+            if let Err(e) = release_result { eprintln!("Yerel kopya release hatası ({}): {:?}", resource_id, e); }
+        }
+        Err(e) => eprintln!("Yerel kopya acquire hatası ({}): {:?}", resource_id, e),
+    }
+}
+
 // Depo Yöneticisi Yapısı (Paket Deposunu Yönetir)
 pub struct DepoYoneticisi {
     // Paket deposunun temel Kaynak ID'si (örn. "sahne://remotepkgrepo/packages/")
@@ -68,6 +91,12 @@ pub struct DepoYoneticisi {
     pub yerel_depo_base_resource_id: String,
     // Paket listesi önbelleği (bellek içi)
     paket_listesi_cache: Option<Vec<Paket>>,
+    // true ise uzak depodan indirilen indeks, yanındaki imza dosyasıyla doğrulanmadan kullanılmaz
+    // (yetenek kaydındaki signature_verification özelliği).
+    pub imza_dogrulama: bool,
+    // Ayrık imzaların doğrulandığı Ed25519 açık anahtarları (bkz. srctrust::guvenilen_anahtarlari_oku).
+    // Boşsa imza doğrulaması açıkken hiçbir uzak veri kabul edilmez.
+    pub guvenilen_anahtarlar: Vec<[u8; srcsigning::ANAHTAR_UZUNLUGU]>,
 }
 
 impl DepoYoneticisi {
@@ -76,6 +105,8 @@ impl DepoYoneticisi {
             depo_base_resource_id,
             yerel_depo_base_resource_id,
             paket_listesi_cache: None, // Başlangıçta önbellek boş
+            imza_dogrulama: true,
+            guvenilen_anahtarlar: Vec::new(),
         }
     }
 
@@ -116,9 +147,12 @@ impl DepoYoneticisi {
 
         // 3. Yerel önbellekte yoksa, uzak depodan indir
         println!("Uzak depodan paket listesi indiriliyor: {}", self.depo_base_resource_id);
-        let uzak_paket_listesi_id = format!("{}/paketler.bin", self.depo_base_resource_id);
+        let uzak_paket_listesi_id = format!("{}/{}", self.depo_base_resource_id, PAKET_LISTESI_DOSYASI);
 
         let buffer = read_resource_to_vec(&uzak_paket_listesi_id)?;
+        if self.imza_dogrulama {
+            self.indeks_imzasini_dogrula(&buffer)?;
+        }
 
         match postcard::from_bytes_copy::<Vec<Paket>>(&buffer) {
             Ok(paketler) => {
@@ -159,6 +193,78 @@ impl DepoYoneticisi {
         }
     }
 
+    // İndirilen indeksi depodaki ayrık imza dosyasıyla doğrular. İmza dosyası yoksa veya güvenilen
+    // anahtarların hiçbiriyle doğrulanmazsa indeks kullanılmaz (ve yerel depoya kaydedilmez).
+    fn indeks_imzasini_dogrula(&self, indeks: &[u8]) -> Result<(), PaketYoneticisiHatasi> {
+        self.ayrik_imzayi_dogrula(indeks, PAKET_LISTESI_IMZA_DOSYASI).map(|_| ())
+    }
+
+    // Depodaki bir dosyanın verisini, depo kökündeki imza_dosyasi'ndaki Ed25519 imzasıyla doğrular.
+    fn ayrik_imzayi_dogrula(&self, veri: &[u8], imza_dosyasi: &str) -> Result<Vec<u8>, PaketYoneticisiHatasi> {
+        let imza_id = format!("{}/{}", self.depo_base_resource_id, imza_dosyasi);
+        self.imzayi_dogrula(veri, &imza_id)
+    }
+
+    // Veriyi imza_id Kaynağındaki ayrık imzayla doğrular; doğrulanan imzayı (yerel kopyayla saklanmak üzere) döndürür.
+    fn imzayi_dogrula(&self, veri: &[u8], imza_id: &str) -> Result<Vec<u8>, PaketYoneticisiHatasi> {
+        let imza = read_resource_to_vec(imza_id).map_err(|e| {
+            eprintln!("Depo imzası okunamadı ({}): {:?}", imza_id, e);
+            PaketYoneticisiHatasi::SecurityError(SecurityError::InvalidSignatureFile(format!("{} okunamadı", imza_id)))
+        })?;
+        let imza_metni = core::str::from_utf8(&imza).unwrap_or("");
+        srcsigning::imzayi_dogrula(veri, imza_metni, &self.guvenilen_anahtarlar).map_err(|e| {
+            eprintln!("Depo imzası doğrulanamadı ({}): {}", imza_id, e);
+            PaketYoneticisiHatasi::SecurityError(e)
+        })?;
+        Ok(imza)
+    }
+
+    // Güvenlik bildirisi akışını alır (bkz. srcadvisory). Bildiriler güncel olmalı, bu yüzden paket
+    // listesinin aksine önce uzak depo denenir; doğrulanan akış imzasıyla birlikte yerel depoya kaydedilir.
+    // Uzak akış okunamazsa (ağ yok, depo akış yayınlamıyor) yerel kopya kullanılır; yerel kopyanın da imzası
+    // ve geçerlilik süresi denetlenir, süresi dolmuşsa kullanılmaz. Uzak akışta imza, çözümleme veya süre
+    // hatasında yerel kopyaya düşülmez: kurcalanmış ya da eski bir akış hiçbir zaman sessizce kabul edilmemeli.
+    pub fn guvenlik_bildirilerini_al(&self) -> Result<BildiriAkisi, PaketYoneticisiHatasi> {
+        let uzak_id = format!("{}/{}", self.depo_base_resource_id, GUVENLIK_BILDIRILERI_DOSYASI);
+        let yerel_id = format!("{}/{}", self.yerel_depo_base_resource_id, GUVENLIK_BILDIRILERI_DOSYASI);
+        let yerel_imza_id = format!("{}/{}", self.yerel_depo_base_resource_id, GUVENLIK_BILDIRILERI_IMZA_DOSYASI);
+        let simdi_us = task::current_time_us().map_err(PaketYoneticisiHatasi::from)?;
+
+        let veri = match read_resource_to_vec(&uzak_id) {
+            Ok(veri) => veri,
+            Err(e) => {
+                eprintln!("Uzak güvenlik bildirileri okunamadı ({}): {:?}; yerel kopya deneniyor.", uzak_id, e);
+                let yerel = read_resource_to_vec(&yerel_id)?;
+                if self.imza_dogrulama {
+                    self.imzayi_dogrula(&yerel, &yerel_imza_id)?;
+                }
+                let akis = BildiriAkisi::cozumle(&yerel)?;
+                // Süresi dolmuş yerel kopya "akış yok" sayılır; kurulum politikası buna göre uyarır veya engeller.
+                akis.tazelik_denetle(simdi_us).map_err(|e| PaketYoneticisiHatasi::DogrulamaHatasi(format!(
+                    "yerel güvenlik bildirileri kullanılamaz ({}): {}", yerel_id, e
+                )))?;
+                return Ok(akis);
+            }
+        };
+        let imza = if self.imza_dogrulama {
+            Some(self.ayrik_imzayi_dogrula(&veri, GUVENLIK_BILDIRILERI_IMZA_DOSYASI)?)
+        } else {
+            None
+        };
+        let akis = BildiriAkisi::cozumle(&veri)?;
+        akis.tazelik_denetle(simdi_us).map_err(|e| {
+            eprintln!("Uzak güvenlik bildirileri reddedildi ({}): {}", uzak_id, e);
+            PaketYoneticisiHatasi::SecurityError(SecurityError::SuresiDolmusVeri(format!("{}: {}", uzak_id, e)))
+        })?;
+
+        yerel_kopyaya_yaz(&yerel_id, &veri);
+        if let Some(imza) = imza {
+            yerel_kopyaya_yaz(&yerel_imza_id, &imza);
+        }
+
+        Ok(akis)
+    }
+
     // Yerel Depoyu Güncelleme (Paket listesini indirip yerel depoya kaydeder).
     pub fn yerel_depoyu_guncelle(&mut self) -> Result<(), PaketYoneticisiHatasi> {
         println!("Yerel depo güncelleniyor: {}", self.yerel_depo_base_resource_id);
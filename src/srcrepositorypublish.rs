@@ -0,0 +1,495 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, HashMap, format! için
+
+use alloc::collections::HashMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format; // format! makrosu için
+use alloc::borrow::ToOwned; // &str -> String için
+
+// serde ve no_std uyumlu serileştirme/deserileştirme kütüphanesi
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu binary serileştirme
+
+// Paket struct tanımını içeren modül
+use crate::package::Paket;
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+use crate::Handle;
+
+// Özel hata enum'ımızı içe aktar (no_std uyumlu hali)
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+// Arşivden meta veri okuma, checksum ve imza modülleri
+use crate::srcarchive;
+use crate::srcchecksum;
+use crate::srcsigning;
+use crate::srcadvisory::{surum_karsilastir, BildiriAkisi, VARSAYILAN_GECERLILIK_SN};
+use core::cmp::Ordering;
+use crate::task; // Bildiri akışının üretim zamanı için task::current_time_us
+
+// log kütüphanesini içe aktar (no_std uyumlu backend varsayımıyla)
+use log::{info, warn, debug};
+
+// no_std uyumlu print makroları
+use crate::print_macros::{println, eprintln};
+
+// Paket arşivlerine gömülü meta verinin arşiv içindeki adı.
+// Paket oluşturma aracı, Paket struct'ını postcard ile serileştirip bu isimle arşive koymalıdır.
+pub const PAKET_META_GIRDISI: &str = ".PAKET/paket.bin";
+
+// Depo çıktısındaki dosya adları.
+// DepoYoneticisi::paket_listesini_al "{depo}/paketler.bin" Kaynağını okur.
+pub const PAKET_LISTESI_DOSYASI: &str = "paketler.bin";
+pub const PAKET_LISTESI_IMZA_DOSYASI: &str = "paketler.bin.imza";
+pub const PAKET_LISTESI_DELTA_DOSYASI: &str = "paketler.delta.bin";
+// Güvenlik bildirisi akışı (bkz. srcadvisory). Paket dizininde bu adla bir akış varsa doğrulanıp
+// çıktıya kopyalanır ve indeks gibi ayrık imzayla imzalanır.
+// İmza dosyaları, yayıncının Ed25519 gizli anahtarıyla üretilen imzanın hex halidir (bkz. srcsigning::veriyi_imzala);
+// istemciler bunları srctrust'taki güvenilen açık anahtarlarla doğrular.
+pub const GUVENLIK_BILDIRILERI_DOSYASI: &str = "guvenlik_bildirileri.bin";
+pub const GUVENLIK_BILDIRILERI_IMZA_DOSYASI: &str = "guvenlik_bildirileri.bin.imza";
+
+// Bir arşivin yanındaki ayrık imza dosyasının uzantısı (örn. "coreutils-8.32.zip.imza"). Paketi derleyen
+// tarafından imzalanır ve yayınlanırken güvenilen anahtarlarla doğrulanır.
+const ARSIV_IMZA_UZANTISI: &str = ".imza";
+
+// Paket dizininde arşiv olarak ele alınan dosya uzantıları. Biçim uzantıdan değil, arşivin ilk
+// baytlarından algılanır (bkz. srcarchive::arsiv_bicimini_algila); uzantı yalnızca adayları seçer.
+const ARSIV_UZANTILARI: [&str; 7] = [".zip", ".tar", ".tar.gz", ".tgz", ".tar.zst", ".tar.xz", ".tar.bz2"];
+
+// Dizin içeriğini listelemek için Sahne64 Kaynak kontrol komutu (Varsayımsal).
+// Gerçek Sahne64 API'sında tanımlanmalıdır. Cevap, satır sonu ile ayrılmış girdi adlarıdır.
+const RESOURCE_CONTROL_CMD_LIST: u64 = 3;
+
+
+// İki paket listesi arasındaki farkı temsil eder.
+// İstemciler tam listeyi indirmek yerine sadece deltayı uygulayabilir.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DepoDelta {
+    pub eklenen: Vec<Paket>, // Önceki listede olmayan paketler
+    pub guncellenen: Vec<Paket>, // Sürümü veya checksum'ı değişen paketler (yeni halleri)
+    pub kaldirilan: Vec<String>, // Yeni listede artık bulunmayan paket adları
+}
+
+impl DepoDelta {
+    pub fn bos_mu(&self) -> bool {
+        self.eklenen.is_empty() && self.guncellenen.is_empty() && self.kaldirilan.is_empty()
+    }
+}
+
+// Yayınlama sırasında reddedilen bir arşiv ve nedeni.
+#[derive(Debug, Clone)]
+pub struct ReddedilenArsiv {
+    pub dosya_adi: String,
+    pub neden: String,
+}
+
+// Yayınlama işleminin özeti.
+#[derive(Debug)]
+pub struct YayinRaporu {
+    pub yayinlanan: Vec<Paket>,
+    pub reddedilen: Vec<ReddedilenArsiv>,
+    // Aynı paketin daha yeni sürümü yayınlandığı için indekse alınmayan arşivler (hata sayılmaz)
+    pub atlanan: Vec<ReddedilenArsiv>,
+    pub delta: DepoDelta,
+    pub bildiri_sayisi: Option<usize>, // Yayınlanan güvenlik bildirisi sayısı (akış yoksa None)
+}
+
+
+// Bir dizindeki paket arşivlerinden depo indeksini üreten yapı.
+// Çıktı, herhangi bir statik dosya sunucusu tarafından depo olarak sunulabilir.
+pub struct DepoYayinlayici {
+    // Paket arşivlerinin bulunduğu dizin Kaynağı (örn. "sahne://build/paketler/")
+    pub paket_dizini_id: String,
+    // İndeks, imza ve delta dosyalarının yazılacağı dizin Kaynağı (örn. "sahne://srv/depo/")
+    pub cikti_dizini_id: String,
+    // İndeksi ve güvenlik bildirisi akışını imzalayan Ed25519 gizli anahtarı
+    imza_anahtari: [u8; srcsigning::ANAHTAR_UZUNLUGU],
+    // Arşivlerin yanındaki ayrık imzaların kabul edildiği açık anahtarlar (varsayılan: yalnızca yayıncının kendisi)
+    pub guvenilen_anahtarlar: Vec<[u8; srcsigning::ANAHTAR_UZUNLUGU]>,
+    // Yayınlanan güvenlik bildirisi akışının geçerlilik süresi (saniye); depo bu süre dolmadan yeniden yayınlanmalıdır.
+    pub bildiri_gecerlilik_sn: u64,
+}
+
+impl DepoYayinlayici {
+    pub fn yeni(paket_dizini_id: &str, cikti_dizini_id: &str, imza_anahtari: [u8; srcsigning::ANAHTAR_UZUNLUGU]) -> Self {
+        DepoYayinlayici {
+            paket_dizini_id: paket_dizini_id.trim_end_matches('/').to_owned(), // alloc
+            cikti_dizini_id: cikti_dizini_id.trim_end_matches('/').to_owned(), // alloc
+            imza_anahtari,
+            guvenilen_anahtarlar: alloc::vec![srcsigning::acik_anahtar(&imza_anahtari)], // alloc
+            bildiri_gecerlilik_sn: VARSAYILAN_GECERLILIK_SN,
+        }
+    }
+
+    // Paket dizinini tarar, her arşivi doğrular ve depo indeksini yazar.
+    // Doğrulanamayan arşivler indekse alınmaz, rapordaki reddedilenler listesine eklenir.
+    pub fn yayinla(&self) -> Result<YayinRaporu, PaketYoneticisiHatasi> {
+        info!("Depo yayınlanıyor: {} -> {}", self.paket_dizini_id, self.cikti_dizini_id); // no_std log
+
+        let arsivler = self.arsivleri_listele()?;
+        let mut dogrulanan = Vec::new(); // alloc
+        let mut reddedilen = Vec::new(); // alloc
+
+        for dosya_adi in arsivler {
+            match self.arsivi_isle(&dosya_adi) {
+                Ok(paket) => {
+                    debug!("Arşiv doğrulandı: {} ({}@{})", dosya_adi, paket.ad, paket.surum); // no_std log
+                    dogrulanan.push(paket);
+                }
+                Err(e) => {
+                    warn!("Arşiv reddedildi: {}: {}", dosya_adi, e); // no_std log
+                    reddedilen.push(ReddedilenArsiv { dosya_adi, neden: format!("{}", e) }); // format! alloc
+                }
+            }
+        }
+
+        // İndekste her paketin tek sürümü olabilir (bkz. surumleri_ayikla).
+        let (mut yayinlanan, atlanan, yinelenen) = surumleri_ayikla(dogrulanan);
+        for arsiv in &yinelenen {
+            warn!("Arşiv reddedildi: {}: {}", arsiv.dosya_adi, arsiv.neden); // no_std log
+        }
+        reddedilen.extend(yinelenen);
+
+        // Sıralı çıktı, aynı girdiden aynı indeksin üretilmesini sağlar.
+        yayinlanan.sort_by(|a, b| a.ad.cmp(&b.ad));
+
+        // Önceki indeks varsa deltayı hesapla (yoksa her şey "eklenen" sayılır).
+        let liste_id = format!("{}/{}", self.cikti_dizini_id, PAKET_LISTESI_DOSYASI); // format! alloc
+        let eski_liste = match read_resource_to_vec(&liste_id) {
+            Ok(veri) => postcard::from_bytes_copy::<Vec<Paket>>(&veri).unwrap_or_else(|e| {
+                warn!("Önceki paket listesi okunamadı, delta tam liste olarak üretilecek: {:?}", e); // no_std log
+                Vec::new()
+            }),
+            Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let delta = delta_hesapla(&eski_liste, &yayinlanan);
+
+        // İndeksi, deltayı ve indeks imzasını yaz.
+        let liste_verisi = postcard::to_postcard(&yayinlanan).map_err(PaketYoneticisiHatasi::from)?; // alloc
+        write_vec_to_resource(&liste_id, &liste_verisi)?;
+
+        let delta_id = format!("{}/{}", self.cikti_dizini_id, PAKET_LISTESI_DELTA_DOSYASI); // format! alloc
+        let delta_verisi = postcard::to_postcard(&delta).map_err(PaketYoneticisiHatasi::from)?; // alloc
+        write_vec_to_resource(&delta_id, &delta_verisi)?;
+
+        // İmza, yazılan indeks verisinin üzerindedir; DepoYoneticisi indirdiği veriyi aynı haliyle doğrular.
+        let imza = srcsigning::veriyi_imzala(&liste_verisi, &self.imza_anahtari);
+        let imza_id = format!("{}/{}", self.cikti_dizini_id, PAKET_LISTESI_IMZA_DOSYASI); // format! alloc
+        write_vec_to_resource(&imza_id, imza.as_bytes())?;
+
+        let bildiri_sayisi = self.bildirileri_yayinla()?;
+
+        println!(
+            "Depo yayınlandı: {} paket, {} reddedilen, {} atlanan, delta: +{} ~{} -{}",
+            yayinlanan.len(), reddedilen.len(), atlanan.len(),
+            delta.eklenen.len(), delta.guncellenen.len(), delta.kaldirilan.len()
+        ); // no_std print
+
+        Ok(YayinRaporu { yayinlanan, reddedilen, atlanan, delta, bildiri_sayisi })
+    }
+
+    // Paket dizinindeki güvenlik bildirisi akışını doğrular, üretim ve geçerlilik zamanıyla damgalayıp
+    // çıktıya yazar ve imzalar. Paket dizininde akış yoksa çıktıdaki önceki akış yeniden damgalanır; akışın
+    // süresi dolmaması için depo düzenli olarak yeniden yayınlanmalıdır. Geçersiz akış yayınlamayı durdurur.
+    fn bildirileri_yayinla(&self) -> Result<Option<usize>, PaketYoneticisiHatasi> {
+        let kaynak_id = format!("{}/{}", self.paket_dizini_id, GUVENLIK_BILDIRILERI_DOSYASI); // format! alloc
+        let akis_id = format!("{}/{}", self.cikti_dizini_id, GUVENLIK_BILDIRILERI_DOSYASI); // format! alloc
+        let kaynak_verisi = match read_resource_to_vec(&kaynak_id) {
+            Ok(veri) => veri,
+            Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => match read_resource_to_vec(&akis_id) {
+                Ok(veri) => {
+                    debug!("Paket dizininde bildiri akışı yok, önceki akış yeniden damgalanıyor: {}", akis_id); // no_std log
+                    veri
+                }
+                Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => return Ok(None),
+                Err(e) => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+        let mut akis = BildiriAkisi::cozumle(&kaynak_verisi)?;
+        akis.dogrula().map_err(|e| PaketYoneticisiHatasi::DepoYayinlamaHatasi(format!(
+            "{} geçersiz: {}", GUVENLIK_BILDIRILERI_DOSYASI, e
+        )))?;
+        akis.damgala(task::current_time_us().map_err(PaketYoneticisiHatasi::from)?, self.bildiri_gecerlilik_sn);
+
+        let veri = postcard::to_postcard(&akis).map_err(PaketYoneticisiHatasi::from)?; // alloc
+        write_vec_to_resource(&akis_id, &veri)?;
+        let imza_id = format!("{}/{}", self.cikti_dizini_id, GUVENLIK_BILDIRILERI_IMZA_DOSYASI); // format! alloc
+        write_vec_to_resource(&imza_id, srcsigning::veriyi_imzala(&veri, &self.imza_anahtari).as_bytes())?;
+
+        info!("Güvenlik bildirileri yayınlandı: {} bildiri", akis.bildiriler.len()); // no_std log
+        Ok(Some(akis.bildiriler.len()))
+    }
+
+    // Paket dizinindeki arşivlerin (bkz. ARSIV_UZANTILARI) adlarını döndürür.
+    fn arsivleri_listele(&self) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+        let handle = resource::acquire(&self.paket_dizini_id, resource::MODE_READ)
+            .map_err(|e| {
+                eprintln!("Paket dizini acquire hatası ({}): {:?}", self.paket_dizini_id, e); // no_std print
+                PaketYoneticisiHatasi::from(e)
+            })?;
+
+        // resource::control(handle, command, args: &[u8]) -> Result<Vec<u8>, SahneError>
+        let liste_sonucu = resource::control(handle, RESOURCE_CONTROL_CMD_LIST, &[]);
+        let _ = resource::release(handle);
+        let ham_liste = liste_sonucu.map_err(|e| {
+            eprintln!("Paket dizini listelenemedi ({}): {:?}", self.paket_dizini_id, e); // no_std print
+            PaketYoneticisiHatasi::from(e)
+        })?;
+
+        let metin = core::str::from_utf8(&ham_liste)
+            .map_err(|_| PaketYoneticisiHatasi::ParsingError(String::from("Dizin listesi UTF-8 değil")))?;
+
+        let mut arsivler: Vec<String> = metin
+            .lines()
+            .map(|satir| satir.trim())
+            .filter(|ad| arsiv_adi_mi(ad))
+            .map(|ad| ad.to_owned()) // alloc
+            .collect();
+        arsivler.sort();
+        Ok(arsivler)
+    }
+
+    // Tek bir arşivin gömülü meta verisini okur, checksum ve (varsa) imzasını doğrular.
+    // Dönüş değeri: İndekse yazılacak, checksum'ları ve dosya adı doldurulmuş Paket.
+    fn arsivi_isle(&self, dosya_adi: &str) -> Result<Paket, PaketYoneticisiHatasi> {
+        let arsiv_id = format!("{}/{}", self.paket_dizini_id, dosya_adi); // format! alloc
+
+        // 1. Gömülü meta veriyi oku (zip veya tar.*, biçim arşivin başından algılanır)
+        let meta_verisi = srcarchive::arsiv_girdisini_oku(&arsiv_id, PAKET_META_GIRDISI, &srcarchive::varsayilan_arsiv_ozellikleri())?
+            .ok_or_else(|| PaketYoneticisiHatasi::DepoYayinlamaHatasi(format!(
+                "arşivde {} bulunamadı", PAKET_META_GIRDISI
+            )))?;
+        let mut paket = postcard::from_bytes_copy::<Paket>(&meta_verisi).map_err(PaketYoneticisiHatasi::from)?;
+
+        if paket.ad.is_empty() || paket.surum.is_empty() {
+            return Err(PaketYoneticisiHatasi::DepoYayinlamaHatasi(String::from("meta veride ad veya sürüm boş")));
+        }
+
+        // 2. Checksum'ları hesapla. Meta veride beklenen değer varsa karşılaştır, yoksa doldur.
+        let arsiv_verisi = read_resource_to_vec(&arsiv_id)?;
+        let md5 = srcchecksum::hesapla_md5(&arsiv_id)?;
+        let sha256 = srcsigning::veri_ozeti(&arsiv_verisi);
+        for (algoritma, hesaplanan) in [("md5", &md5), ("sha256", &sha256)] {
+            if let Some(beklenen) = paket.checksums.get(algoritma) {
+                if !beklenen.eq_ignore_ascii_case(hesaplanan) {
+                    eprintln!("{} {} uyuşmazlığı: beklenen {}, hesaplanan {}", dosya_adi, algoritma, beklenen, hesaplanan); // no_std print
+                    return Err(PaketYoneticisiHatasi::ChecksumVerificationError);
+                }
+            }
+        }
+        paket.checksums.insert(String::from("md5"), md5); // alloc
+        paket.checksums.insert(String::from("sha256"), sha256.clone()); // alloc
+
+        // 3. Arşivin yanında ayrık imza dosyası varsa güvenilen anahtarlarla doğrula.
+        let imza_id = format!("{}{}", arsiv_id, ARSIV_IMZA_UZANTISI); // format! alloc
+        match read_resource_to_vec(&imza_id) {
+            Ok(imza_verisi) => {
+                let imza = core::str::from_utf8(&imza_verisi)
+                    .map_err(|_| PaketYoneticisiHatasi::ParsingError(format!("İmza dosyası UTF-8 değil: {}", imza_id)))?;
+                srcsigning::imzayi_dogrula(&arsiv_verisi, imza, &self.guvenilen_anahtarlar).map_err(|e| {
+                    PaketYoneticisiHatasi::DepoYayinlamaHatasi(format!("imza doğrulanamadı: {}: {}", imza_id, e))
+                })?;
+            }
+            Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => {
+                debug!("Arşiv için ayrık imza yok: {}", dosya_adi); // no_std log
+            }
+            Err(e) => return Err(e),
+        }
+
+        // 4. İndeksteki dosya adı, arşivin depodaki adıdır (KurulumYoneticisi::paketi_indir bunu kullanır).
+        paket.dosya_adi = Some(dosya_adi.to_owned()); // alloc
+        Ok(paket)
+    }
+}
+
+
+// Dosya adının paket arşivi adayı olup olmadığı (gizli dosyalar ve ayrık imzalar hariç).
+fn arsiv_adi_mi(ad: &str) -> bool {
+    !ad.starts_with('.') && ARSIV_UZANTILARI.iter().any(|uzanti| ad.ends_with(uzanti))
+}
+
+// Doğrulanan arşivlerden her paketin en yüksek sürümünü seçer. Dönüş değeri: (yayınlanacaklar, atlananlar,
+// reddedilenler). Daha düşük sürümler atlanır; aynı sürümün ikinci arşivi belirsiz olduğu için reddedilir
+// (dosya adı sırasıyla ilki yayınlanır).
+pub fn surumleri_ayikla(paketler: Vec<Paket>) -> (Vec<Paket>, Vec<ReddedilenArsiv>, Vec<ReddedilenArsiv>) {
+    let mut secilen: Vec<Paket> = Vec::new(); // alloc
+    let mut atlanan = Vec::new(); // alloc
+    let mut reddedilen = Vec::new(); // alloc
+
+    for paket in paketler {
+        let i = match secilen.iter().position(|p| p.ad == paket.ad) {
+            Some(i) => i,
+            None => {
+                secilen.push(paket);
+                continue;
+            }
+        };
+        let karsilastirma = surum_karsilastir(&paket.surum, &secilen[i].surum);
+        let disarida = if karsilastirma == Ordering::Greater {
+            core::mem::replace(&mut secilen[i], paket)
+        } else {
+            paket
+        };
+        let kalan = &secilen[i];
+        let arsiv = ReddedilenArsiv {
+            dosya_adi: disarida.dosya_adi.clone().unwrap_or_default(),
+            neden: if karsilastirma == Ordering::Equal {
+                format!("'{}' {} sürümünün başka bir arşivi var ({})", kalan.ad, kalan.surum, kalan.dosya_adi.as_deref().unwrap_or("?"))
+            } else {
+                format!("'{}' için daha yeni {} sürümü yayınlandı", kalan.ad, kalan.surum)
+            },
+        };
+        if karsilastirma == Ordering::Equal { reddedilen.push(arsiv) } else { atlanan.push(arsiv) }
+    }
+
+    (secilen, atlanan, reddedilen)
+}
+
+// Eski ve yeni paket listeleri arasındaki deltayı hesaplar.
+// Bir paketin sürümü veya checksum'ları değiştiyse "güncellenen" sayılır.
+pub fn delta_hesapla(eski: &[Paket], yeni: &[Paket]) -> DepoDelta {
+    let eski_harita: HashMap<&str, &Paket> = eski.iter().map(|p| (p.ad.as_str(), p)).collect(); // alloc
+    let yeni_harita: HashMap<&str, &Paket> = yeni.iter().map(|p| (p.ad.as_str(), p)).collect(); // alloc
+
+    let mut delta = DepoDelta { eklenen: Vec::new(), guncellenen: Vec::new(), kaldirilan: Vec::new() };
+
+    for paket in yeni {
+        match eski_harita.get(paket.ad.as_str()) {
+            None => delta.eklenen.push(paket.clone()),
+            Some(onceki) if onceki.surum != paket.surum || onceki.checksums != paket.checksums => {
+                delta.guncellenen.push(paket.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    for paket in eski {
+        if !yeni_harita.contains_key(paket.ad.as_str()) {
+            delta.kaldirilan.push(paket.ad.clone());
+        }
+    }
+    delta.kaldirilan.sort();
+    delta
+}
+
+
+// Helper fonksiyon: Kaynaktan tüm içeriği Vec<u8> olarak oku.
+// Note: Bu helper, utils gibi ortak bir modülde olmalıdır.
+fn read_resource_to_vec(resource_id: &str) -> Result<Vec<u8>, PaketYoneticisiHatasi> {
+    let handle = resource::acquire(resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from(e))?;
+
+    let mut buffer = Vec::new();
+    let mut temp_buffer = [0u8; 512]; // Stack buffer
+
+    loop {
+        match resource::read(handle, &mut temp_buffer) {
+            Ok(0) => break,
+            Ok(bytes_read) => buffer.extend_from_slice(&temp_buffer[..bytes_read]),
+            Err(e) => {
+                let _ = resource::release(handle);
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        }
+    }
+
+    let _ = resource::release(handle);
+    Ok(buffer)
+}
+
+// Helper fonksiyon: Veriyi Kaynağa yazar (varsa içeriği silinir).
+fn write_vec_to_resource(resource_id: &str, veri: &[u8]) -> Result<(), PaketYoneticisiHatasi> {
+    let handle = resource::acquire(
+        resource_id,
+        resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE
+    ).map_err(|e| {
+        eprintln!("Depo çıktı Kaynağı acquire hatası ({}): {:?}", resource_id, e); // no_std print
+        PaketYoneticisiHatasi::from(e)
+    })?;
+
+    let mut written = 0;
+    while written < veri.len() {
+        match resource::write(handle, &veri[written..]) {
+            Ok(0) => {
+                let _ = resource::release(handle);
+                return Err(PaketYoneticisiHatasi::DepoYayinlamaHatasi(format!("Kaynak yazmayı durdurdu: {}", resource_id)));
+            }
+            Ok(n) => written += n,
+            Err(e) => {
+                let _ = resource::release(handle);
+                eprintln!("Depo çıktı Kaynağı yazma hatası ({}): {:?}", resource_id, e); // no_std print
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        }
+    }
+
+    let _ = resource::release(handle);
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    // delta_hesapla saf bir fonksiyondur, Sahne64 mock'u gerektirmez.
+    use super::*;
+
+    fn paket(ad: &str, surum: &str) -> Paket {
+        Paket::yeni(ad.to_string(), surum.to_string(), Vec::new())
+    }
+
+    #[test]
+    fn test_delta_hesapla() {
+        let eski = vec![paket("a", "1.0"), paket("b", "1.0"), paket("c", "1.0")];
+        let yeni = vec![paket("a", "1.0"), paket("b", "2.0"), paket("d", "1.0")];
+
+        let delta = delta_hesapla(&eski, &yeni);
+        assert_eq!(delta.eklenen.len(), 1);
+        assert_eq!(delta.eklenen[0].ad, "d");
+        assert_eq!(delta.guncellenen.len(), 1);
+        assert_eq!(delta.guncellenen[0].surum, "2.0");
+        assert_eq!(delta.kaldirilan, vec!["c".to_string()]);
+    }
+
+    fn arsiv(ad: &str, surum: &str, dosya_adi: &str) -> Paket {
+        let mut p = paket(ad, surum);
+        p.dosya_adi = Some(dosya_adi.to_string());
+        p
+    }
+
+    #[test]
+    fn test_surumleri_ayikla() {
+        let (secilen, atlanan, reddedilen) = surumleri_ayikla(vec![
+            arsiv("a", "1.0", "a-1.0.zip"),
+            arsiv("a", "1.10", "a-1.10.tar.zst"),
+            arsiv("a", "1.2", "a-1.2.zip"),
+            arsiv("b", "2.0", "b-2.0.tar.gz"),
+            arsiv("b", "2.0", "b-2.0.zip"),
+        ]);
+        let secilen: Vec<(&str, &str)> = secilen.iter().map(|p| (p.ad.as_str(), p.surum.as_str())).collect();
+        assert_eq!(secilen, [("a", "1.10"), ("b", "2.0")]);
+        let atlanan: Vec<&str> = atlanan.iter().map(|r| r.dosya_adi.as_str()).collect();
+        assert_eq!(atlanan, ["a-1.0.zip", "a-1.2.zip"]);
+        assert_eq!(reddedilen.len(), 1);
+        assert_eq!(reddedilen[0].dosya_adi, "b-2.0.zip");
+    }
+
+    #[test]
+    fn test_arsiv_adi_mi() {
+        assert!(arsiv_adi_mi("coreutils-8.32.zip"));
+        assert!(arsiv_adi_mi("coreutils-8.32.tar.zst"));
+        assert!(!arsiv_adi_mi("coreutils-8.32.zip.imza"));
+        assert!(!arsiv_adi_mi("guvenlik_bildirileri.bin"));
+        assert!(!arsiv_adi_mi(".gizli.zip"));
+    }
+
+    #[test]
+    fn test_delta_bos() {
+        let liste = vec![paket("a", "1.0")];
+        assert!(delta_hesapla(&liste, &liste).bos_mu());
+    }
+}
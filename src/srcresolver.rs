@@ -2,6 +2,7 @@
 extern crate alloc; // HashMap, HashSet, String, Vec, format! için
 
 use alloc::collections::{HashMap, HashSet}; // std::collections::* yerine
+use alloc::collections::BTreeMap; // Paket.ozellikler alanı ve graf düğüm eşlemesi için
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::format; // format! makrosu için
@@ -27,6 +28,9 @@ use crate::paket_yoneticisi_hatasi::PaketYoneticisiHatasi;
 // no_std uyumlu print makroları
 use crate::print_macros::{println, eprintln};
 
+// Çözümlenmiş grafın DOT/JSON olarak dışa aktarımı için
+use crate::srcgraph::{BagimlilikGrafi, Dugum, DugumTuru, Kenar, KenarTuru};
+
 // Bağımlılıkları temsil eden bir yapı (Paket -> Bağlı Olduğu Paketler)
 // Bağımlılıklar genellikle sadece isim içerir Paket struct'ında.
 // Buradaki Dependencies map'i çözümlenmiş (isim + versiyon) bağımlılıkları tutar.
@@ -155,6 +159,16 @@ fn parse_package_id(package_id_str: &str) -> Result<Paket, PaketYoneticisiHatasi
             dosyalar: Vec::new(), // alloc
             kurulum_scripti: None,
             kaldirma_scripti: None,
+            betikler: BTreeMap::new(),
+            tetikleyiciler: Vec::new(), // alloc
+            saglar: Vec::new(), // alloc
+            degistirir: Vec::new(), // alloc
+            cakisir: Vec::new(), // alloc
+            onerilenler: Vec::new(), // alloc
+            tavsiyeler: Vec::new(), // alloc
+            ozellikler: BTreeMap::new(),
+            varsayilan_ozellikler: Vec::new(), // alloc
+            yapilandirma_dosyalari: Vec::new(), // alloc
         })
     } else {
          eprintln!("Geçersiz paket ID formatı: {}", package_id_str); // no_std print
@@ -163,38 +177,18 @@ fn parse_package_id(package_id_str: &str) -> Result<Paket, PaketYoneticisiHatasi
 }
 
 
-// Döngü içeren bağımlılıkları elde eder (read_dependencies_from_resource üzerine inşa edilir)
+// Bağımlılıkları okur ve içindeki döngüleri tanı olarak bildirir (read_dependencies_from_resource üzerine inşa edilir).
+// Döngüler hata değildir; resolve_dependencies döngüdeki paketleri tek bir kurulum grubu olarak sıralar.
 // resource_id: Bağımlılık verisini içeren Kaynağın ID'si.
-// Dönüş değeri: Ayrıştırılmış bağımlılık map'i (döngü eklenmiş hali) veya PaketYoneticisiHatasi.
-fn get_dependencies_with_cycle(resource_id: &str) -> Result<Dependencies, PaketYoneticisiHatasi> { // SahneError yerine PaketYoneticisiHatasi
-    let mut deps = read_dependencies_from_resource(resource_id)?; // Bağımlılıkları oku
-
-    // Döngüyü manuel olarak ekleyelim (örnek amaçlı)
-    let package_c = Paket {
-        ad: "C".to_owned(), // alloc
-        surum: "3.0.0".to_owned(), // alloc
-        bagimliliklar: Vec::new(), aciklama: None, dosya_adi: None,
-        checksums: HashMap::new(), dosyalar: Vec::new(),
-        kurulum_scripti: None, kaldirma_scripti: None,
-    };
-    let package_a = Paket {
-        ad: "A".to_owned(), // alloc
-        surum: "1.0.0".to_owned(), // alloc
-        bagimliliklar: Vec::new(), aciklama: None, dosya_adi: None,
-        checksums: HashMap::new(), dosyalar: Vec::new(),
-        kurulum_scripti: None, kaldirma_scripti: None,
-    };
+// Dönüş değeri: Ayrıştırılmış bağımlılık map'i veya PaketYoneticisiHatasi.
+fn get_dependencies(resource_id: &str) -> Result<Dependencies, PaketYoneticisiHatasi> { // SahneError yerine PaketYoneticisiHatasi
+    let deps = read_dependencies_from_resource(resource_id)?; // Bağımlılıkları oku
 
-    // Eğer "C@3.0.0" varsa, ona "A@1.0.0" bağımlılığını ekleyelim.
-    if deps.contains_key(&package_c) {
-        if let Some(deps_for_c) = deps.get_mut(&package_c) {
-            deps_for_c.push(package_a); // push alloc
-        }
-    } else {
-        // "C@3.0.0" yoksa, onu oluşturup bağımlılığını ekle
-        deps.insert(package_c, vec![package_a]); // insert, vec! alloc
+    let graf = grafa_donustur(&deps);
+    for dongu in graf.donguler() {
+        eprintln!("Uyarı: Bağımlılık döngüsü ({}): {}", resource_id, graf.dongu_yolu(&dongu).join(" -> ")); // no_std print
     }
-    Ok(deps) // Güncellenmiş map'i döndür
+    Ok(deps)
 }
 
 // Özel hata türü (no_std uyumlu)
@@ -215,67 +209,82 @@ impl core::fmt::Display for DependencyResolverError {
     }
 }
 
-// Basit bir bağımlılık çözücü (döngü tespiti ile)
+// Kök paketten ulaşılabilen paketleri kurulum gruplarına ayırır.
+// Gruplar topolojik sıradadır (bağımlılıklar önce); her grup bir güçlü bağlı bileşendir. Döngüdeki paketler
+// aynı grupta birlikte döner ve kurulum betikleri grubun tamamı kurulduktan sonra çalıştırılmalıdır.
 // dependencies: Çözümlenmiş bağımlılık grafı (HashMap<Paket, Vec<Paket>>).
 // root_package: Çözümlemeye başlanacak kök paket.
-// Dönüş değeri: Kurulması gereken paketlerin kümesi (HashSet<Paket>) veya DependencyResolverError.
+// Dönüş değeri: Kurulum grupları veya DependencyResolverError.
 fn resolve_dependencies(
     dependencies: &Dependencies,
     root_package: &Paket, // Kök paket de Paket struct'ı olmalı
-) -> Result<HashSet<Paket>, DependencyResolverError> { // Result türü DependencyResolverError olmalı
-    let mut resolved = HashSet::new(); // alloc gerektirir
-    let mut to_resolve = vec![root_package.clone()]; // Kök paketi stack'e ekle (clone, vec! alloc)
-    let mut resolving_stack: HashSet<Paket> = HashSet::new(); // Döngü tespiti için yığın (alloc gerektirir)
-
-    // root_package'ın bağımlılık grafında olup olmadığını kontrol etmeliyiz.
-    // Eğer yoksa, resolve edilmeye çalışılamaz (veya kendi kendine bağımlılığı yoksa boş döner).
-    // Kök paket grafın bir parçası değilse hata dönmek mantıklı olabilir.
+) -> Result<Vec<Vec<Paket>>, DependencyResolverError> { // Result türü DependencyResolverError olmalı
+    // Kök paket grafın bir parçası değilse çözümlenemez.
      if !dependencies.contains_key(root_package) {
          return Err(DependencyResolverError::PackageNotFound(format!("Kök paket bağımlılık grafında bulunamadı: {}@{}", root_package.ad, root_package.surum))); // format! alloc
      }
 
-
-    while let Some(package) = to_resolve.pop() { // pop() Option<Paket>
-        // Eğer paket zaten çözümlenmişse veya şu an çözülmekte olan yığındaysa atla.
-        if resolved.contains(&package) { // contains (&Paket)
-            continue;
-        }
-
-        if resolving_stack.contains(&package) { // contains (&Paket)
-             // Döngü tespit edildi. Hata olarak dön.
-             eprintln!("Bağımlılık döngüsü tespit edildi: {}@{}", package.ad, package.surum); // no_std print
-            return Err(DependencyResolverError::CycleDetected(format!("{}@{}", package.ad, package.surum))); // format! alloc
-        }
-
-        // Paketi işleme yığınına ekle.
-        resolving_stack.insert(package.clone()); // insert, clone alloc
-
-        // Paketin bağımlılıklarını al ve çözülecekler listesine ekle.
-        if let Some(deps) = dependencies.get(&package) { // get (&Paket)
-            for dep in deps {
-                 // Bağımlılık grafında olmayan bir bağımlılık varsa hata dön (bulunamayan paket).
-                 // Her bağımlılığın kendisi de grafın bir anahtarı olmalıdır (veya yaprak düğümse bağımlılığı yoktur).
-                 // Basitlik adına, burada sadece bağımlılığı to_resolve'a ekliyoruz.
-                 // Gerçek bir çözümleyicide, burada paketin depoda/veritabanında olup olmadığını kontrol etmek gerekir.
-                  if !dependencies.contains_key(dep) && !dep.bagimliliklar.is_empty() { // Eğer grafın bir parçası değilse ve kendisinin bağımlılığı varsa... karmaşık.
-                 //     // Basit hata: Eğer bağımlılık grafın anahtarlarında yoksa ve zaten çözülmüş/çözülüyor değilse, bulunamadı hatası verebiliriz.
-                  }
-
-                 to_resolve.push(dep.clone()); // push, clone alloc
+    // Kökten ulaşılabilen alt grafı çıkar.
+    let mut ulasilan: HashSet<Paket> = HashSet::new(); // alloc gerektirir
+    let mut yigin: Vec<&Paket> = Vec::new();
+    yigin.push(root_package);
+    while let Some(paket) = yigin.pop() {
+        if ulasilan.insert(paket.clone()) {
+            if let Some(deps) = dependencies.get(paket) {
+                yigin.extend(deps.iter());
             }
-        } else {
-             // Eğer paketin bağımlılığı yoksa veya graf içinde yoksa, bu bir yaprak düğümdür.
-             // Bu normal olabilir.
         }
+    }
+    let alt_graf: Dependencies = dependencies
+        .iter()
+        .filter(|(paket, _)| ulasilan.contains(*paket))
+        .map(|(paket, deps)| (paket.clone(), deps.clone()))
+        .collect();
+
+    let (graf, paketler) = grafa_donustur_eslemeli(&alt_graf);
+    for dongu in graf.donguler() {
+        eprintln!("Bağımlılık döngüsü tespit edildi, paketler birlikte kurulacak: {}", graf.dongu_yolu(&dongu).join(" -> ")); // no_std print
+    }
 
-        // Paket çözümlendi olarak işaretle.
-        resolved.insert(package.clone()); // insert, clone alloc
+    // Sadece bağımlılık olarak geçen (kendi satırı olmayan) yaprak paketler de grafta düğümdür.
+    Ok(graf
+        .guclu_bagli_bilesenler()
+        .into_iter()
+        .map(|grup| grup.iter().map(|anahtar| paketler[anahtar].clone()).collect())
+        .collect())
+}
+
+// Çözümlenmiş bağımlılık map'ini dışa aktarılabilir grafa çevirir (hata ayıklama için).
+// Düğümler paket adıyla anahtarlanır; aynı adın birden fazla sürümü varsa "ad@sürüm" kullanılır.
+pub(crate) fn grafa_donustur(dependencies: &Dependencies) -> BagimlilikGrafi {
+    grafa_donustur_eslemeli(dependencies).0
+}
 
-        // Paketi işleme yığınından çıkar.
-        resolving_stack.remove(&package); // remove (&Paket)
+// grafa_donustur ile aynı; ayrıca düğüm anahtarından Paket'e eşlemeyi de döndürür.
+fn grafa_donustur_eslemeli(dependencies: &Dependencies) -> (BagimlilikGrafi, BTreeMap<String, Paket>) {
+    let mut surumler: BTreeMap<&str, HashSet<&str>> = BTreeMap::new();
+    for (paket, deps) in dependencies {
+        for p in core::iter::once(paket).chain(deps.iter()) {
+            surumler.entry(p.ad.as_str()).or_default().insert(p.surum.as_str());
+        }
     }
+    let anahtar = |p: &Paket| {
+        if surumler[p.ad.as_str()].len() > 1 { format!("{}@{}", p.ad, p.surum) } else { p.ad.clone() }
+    };
 
-    Ok(resolved) // Çözümlenmiş paketlerin kümesini döndür
+    let mut graf = BagimlilikGrafi::default();
+    let mut eslesme: BTreeMap<String, Paket> = BTreeMap::new();
+    for (paket, deps) in dependencies {
+        for p in core::iter::once(paket).chain(deps.iter()) {
+            let ad = anahtar(p);
+            eslesme.entry(ad.clone()).or_insert_with(|| p.clone());
+            graf.dugumler.entry(ad.clone()).or_insert(Dugum { ad, surum: Some(p.surum.clone()), tur: DugumTuru::Paket, derinlik: 0 });
+        }
+        for dep in deps {
+            graf.kenarlar.insert(Kenar { kaynak: anahtar(paket), hedef: anahtar(dep), tur: KenarTuru::Bagimlilik });
+        }
+    }
+    (graf, eslesme)
 }
 
 
@@ -286,6 +295,25 @@ fn resolve_dependencies(
 mod tests {
     // std::collections, std::io, std::path, std::fs, tempfile kullandığı için no_std'de doğrudan çalışmaz.
     // Bağımlılık dosyasını okuma helper'ları ve test senaryoları mock resource veya Sahne64 simülasyonu gerektirir.
+    // Bellek içi graf üzerinde çalışan çözümleme testleri aşağıdadır.
+    use super::*;
+
+    fn paket(ad: &str) -> Paket {
+        Paket::yeni(ad.to_string(), "1.0".to_string(), Vec::new())
+    }
+
+    #[test]
+    fn test_dongu_tek_grup_olarak_siralanir() {
+        let mut deps: Dependencies = HashMap::new();
+        deps.insert(paket("uygulama"), vec![paket("a")]);
+        deps.insert(paket("a"), vec![paket("b")]);
+        deps.insert(paket("b"), vec![paket("a"), paket("libc")]);
+        deps.insert(paket("kullanilmayan"), vec![paket("libc")]);
+
+        let gruplar = resolve_dependencies(&deps, &paket("uygulama")).unwrap();
+        let adlar: Vec<Vec<&str>> = gruplar.iter().map(|g| g.iter().map(|p| p.ad.as_str()).collect()).collect();
+        assert_eq!(adlar, vec![vec!["libc"], vec!["a", "b"], vec!["uygulama"]]);
+    }
 }
 
 // --- Paket Struct Tanımı ---
@@ -0,0 +1,685 @@
+#![cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))] // Sadece Linux ana makine arka ucunda derlenir
+
+// Paket betikleri için Linux sandbox'ı (SecurityManager::run_in_sandbox'ın ana makine uygulaması).
+// Ayrıcalık gerektirmeden (kullanıcı ad alanı ile) çalışır; kullanıcı ad alanları kapalıysa ve süreç
+// root ise onsuz devam eder. Betik şu ortamda çalışır:
+// - Yeni mount, PID, IPC, UTS ad alanları; profil ağa izin vermiyorsa boş bir ağ ad alanı.
+// - tmpfs üzerinde kurulan yeni bir kök: profildeki sistem yolları salt okunur, kurulum kökü yazılabilir
+//   bağlanır; /tmp boş bir tmpfs, /dev sadece null/zero/random/urandom içerir. Kökün kendisi salt okunurdur.
+// - rlimit ile bellek, CPU, süreç, dosya boyutu ve açık dosya sınırları.
+// - no_new_privs ve profilin seccomp düzeyine göre sistem çağrısı filtresi.
+// Çocuk süreçte fork sonrası bellek ayırmamak için tüm hazırlık (yollar, ortam, filtre) önceden yapılır.
+// Profil yalıtımsızsa (SandboxProfili::yalitimsiz) betik ana makinede doğrudan çalıştırılır.
+
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use libc::{c_char, c_int, c_long, c_ulong};
+
+use crate::srcscript::{BetikSonucu, BETIK_CIKTI_SINIRI};
+use crate::srcsecurity::{SandboxProfili, SeccompDuzeyi, SecurityError};
+
+// "sahne://" Kaynak ID'lerinin eşlendiği ana makine dizinini belirleyen ortam değişkeni.
+pub const HOST_KOK_DEGISKENI: &str = "SAHNE_HOST_KOK";
+const VARSAYILAN_HOST_KOK: &str = "/var/lib/sahne";
+
+// Betiğin sandbox içindeki yolu.
+const SANDBOX_BETIK_YOLU: &str = "/.sahne-betik";
+
+// Sandbox kurulumu başarısız olursa çocuk sürecin çıkış kodu (betiğin kendi kodlarıyla karışmaması için).
+const KURULUM_HATASI_KODU: c_int = 125;
+
+// Linux başlıklarındaki BPF ve seccomp sabitleri (linux/filter.h, linux/seccomp.h, linux/audit.h).
+const BPF_LD_W_ABS: u16 = 0x20; // BPF_LD | BPF_W | BPF_ABS
+const BPF_JMP_JEQ_K: u16 = 0x15; // BPF_JMP | BPF_JEQ | BPF_K
+const BPF_JMP_JGE_K: u16 = 0x35; // BPF_JMP | BPF_JGE | BPF_K
+const BPF_RET_K: u16 = 0x06; // BPF_RET | BPF_K
+const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_VERI_NR: u32 = 0; // offsetof(struct seccomp_data, nr)
+const SECCOMP_VERI_ARCH: u32 = 4; // offsetof(struct seccomp_data, arch)
+// x86_64'te x32 ABI çağrıları aynı AUDIT_ARCH ile, numaranın bu biti işaretli olarak gelir (__X32_SYSCALL_BIT).
+// Diğer mimarilerde bu aralıkta sistem çağrısı yoktur.
+const X32_CAGRI_BITI: u32 = 0x4000_0000;
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e; // AUDIT_ARCH_X86_64
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7; // AUDIT_ARCH_AARCH64
+
+// Kaynak ID'sini ana makine yoluna çevirir: "sahne://a/b" -> "<host_kok>/a/b"; diğerleri olduğu gibi yol sayılır.
+pub fn kaynak_yolu(resource_id: &str, host_kok: &Path) -> PathBuf {
+    match resource_id.strip_prefix("sahne://") {
+        Some(yol) => host_kok.join(yol.trim_start_matches('/')),
+        None => PathBuf::from(resource_id),
+    }
+}
+
+fn host_koku() -> PathBuf {
+    std::env::var_os(HOST_KOK_DEGISKENI).map(PathBuf::from).unwrap_or_else(|| PathBuf::from(VARSAYILAN_HOST_KOK))
+}
+
+// "ANAHTAR=değer\0" dizisini (bkz. srcscript::BetikOrtami::argumanlar) execve ortamına çevirir.
+// "sahne://" ile başlayan değerler ana makine yoluna çevrilir (örn. SAHNE_KURULUM_KOKU); '=' içermeyen
+// ve anahtarı boş girdiler atlanır.
+pub fn ortam_degiskenleri(args: &[u8], host_kok: &Path) -> Vec<CString> {
+    args.split(|b| *b == 0)
+        .filter_map(|girdi| {
+            let esittir = girdi.iter().position(|b| *b == b'=').filter(|i| *i > 0)?;
+            let (anahtar, deger) = (&girdi[..esittir], &girdi[esittir + 1..]);
+            let mut degisken = anahtar.to_vec();
+            degisken.push(b'=');
+            match std::str::from_utf8(deger) {
+                Ok(metin) if metin.starts_with("sahne://") => degisken.extend_from_slice(kaynak_yolu(metin, host_kok).as_os_str().as_bytes()),
+                _ => degisken.extend_from_slice(deger),
+            }
+            CString::new(degisken).ok()
+        })
+        .collect()
+}
+
+// Düzeye göre engellenen sistem çağrıları.
+pub fn yasak_cagrilar(duzey: SeccompDuzeyi) -> Vec<c_long> {
+    let temel = [
+        libc::SYS_mount, libc::SYS_umount2, libc::SYS_pivot_root, libc::SYS_swapon, libc::SYS_swapoff,
+        libc::SYS_reboot, libc::SYS_kexec_load, libc::SYS_init_module, libc::SYS_finit_module,
+        libc::SYS_delete_module, libc::SYS_ptrace, libc::SYS_process_vm_readv, libc::SYS_process_vm_writev,
+        libc::SYS_acct, libc::SYS_settimeofday, libc::SYS_clock_settime, libc::SYS_sethostname,
+        libc::SYS_setdomainname, libc::SYS_quotactl,
+    ];
+    let kati = [
+        libc::SYS_unshare, libc::SYS_setns, libc::SYS_chroot, libc::SYS_bpf, libc::SYS_perf_event_open,
+        libc::SYS_keyctl, libc::SYS_add_key, libc::SYS_request_key, libc::SYS_userfaultfd,
+        libc::SYS_io_uring_setup, libc::SYS_io_uring_enter, libc::SYS_io_uring_register,
+        libc::SYS_open_by_handle_at, libc::SYS_name_to_handle_at, libc::SYS_personality,
+    ];
+    match duzey {
+        SeccompDuzeyi::Kapali => Vec::new(),
+        SeccompDuzeyi::Temel => temel.to_vec(),
+        SeccompDuzeyi::Kati => temel.iter().chain(kati.iter()).copied().collect(),
+    }
+}
+
+fn bpf(code: u16, jt: u8, jf: u8, k: u32) -> libc::sock_filter {
+    libc::sock_filter { code, jt, jf, k }
+}
+
+// Yasak çağrıları EPERM ile reddeden, diğerlerine izin veren seccomp programı. Farklı bir mimariden
+// (örn. i386 uyumluluk katmanı) gelen çağrılar ve x32 ABI çağrıları (aynı mimari, X32_CAGRI_BITI işaretli
+// numara; yasak numaralarla hiç eşleşmezdi) süreci sonlandırır.
+pub fn seccomp_programi(yasaklar: &[c_long]) -> Vec<libc::sock_filter> {
+    let mut program = vec![
+        bpf(BPF_LD_W_ABS, 0, 0, SECCOMP_VERI_ARCH),
+        bpf(BPF_JMP_JEQ_K, 1, 0, AUDIT_ARCH),
+        bpf(BPF_RET_K, 0, 0, SECCOMP_RET_KILL_PROCESS),
+        bpf(BPF_LD_W_ABS, 0, 0, SECCOMP_VERI_NR),
+        bpf(BPF_JMP_JGE_K, 0, 1, X32_CAGRI_BITI),
+        bpf(BPF_RET_K, 0, 0, SECCOMP_RET_KILL_PROCESS),
+    ];
+    for (i, nr) in yasaklar.iter().enumerate() {
+        // Eşleşirse kalan karşılaştırmaları ve ALLOW'u atlayıp en sondaki ERRNO dönüşüne git.
+        let atlama = (yasaklar.len() - i) as u8;
+        program.push(bpf(BPF_JMP_JEQ_K, atlama, 0, *nr as u32));
+    }
+    program.push(bpf(BPF_RET_K, 0, 0, SECCOMP_RET_ALLOW));
+    program.push(bpf(BPF_RET_K, 0, 0, SECCOMP_RET_ERRNO | libc::EPERM as u32));
+    program
+}
+
+// Yeni kök altında yapılacak bir bağlama.
+enum Baglama {
+    Dizin { kaynak: CString, hedef: CString, yazilabilir: bool, kilitli: c_ulong },
+    Dosya { kaynak: CString, hedef: CString, salt_okunur: bool, kilitli: c_ulong }, // Hedefte boş dosya oluşturulup üzerine bağlanır
+    Sembolik { baglanti: CString, hedef: CString }, // Birleşik /usr sistemlerindeki /bin -> usr/bin gibi
+}
+
+// Çocuk süreçte kullanılacak, önceden hazırlanmış her şey.
+struct CocukPlani {
+    yalitim: bool, // false ise sadece program/argv/envp kullanılır
+    yeni_kok: CString,
+    dizinler: Vec<CString>, // Yeni kök altında sırayla oluşturulacak dizinler
+    baglamalar: Vec<Baglama>,
+    tmp: CString,
+    proc: CString,
+    setgroups: CString,
+    uid_haritasi: CString,
+    gid_haritasi: CString,
+    ag_ad_alani: bool,
+    profil: SandboxProfili,
+    seccomp: Vec<libc::sock_filter>,
+    program: CString,
+    argv: Vec<CString>,
+    envp: Vec<CString>,
+    // argv/envp için NULL ile biten işaretçi dizileri (fork sonrası ayırma yapmamak için önceden kurulur)
+    argv_isaretcileri: Vec<*const c_char>,
+    envp_isaretcileri: Vec<*const c_char>,
+}
+
+fn cstr(yol: &Path) -> Result<CString, SecurityError> {
+    CString::new(yol.as_os_str().as_bytes())
+        .map_err(|_| SecurityError::SandboxError(format!("Yol NUL karakteri içeriyor: {}", yol.display())))
+}
+
+fn sandbox_hatasi(mesaj: &str, e: std::io::Error) -> SecurityError {
+    SecurityError::SandboxError(format!("{}: {}", mesaj, e))
+}
+
+// Kullanıcı ad alanında yeniden bağlama yaparken korunması gereken (kilitli) bağlama bayrakları.
+fn kilitli_bayraklar(yol: &Path) -> c_ulong {
+    let Ok(c) = cstr(yol) else { return 0 };
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c.as_ptr(), &mut st) } != 0 {
+        return 0;
+    }
+    let mut bayraklar = 0;
+    for (st_bayrak, ms_bayrak) in [(libc::ST_NOSUID, libc::MS_NOSUID), (libc::ST_NODEV, libc::MS_NODEV), (libc::ST_NOEXEC, libc::MS_NOEXEC)] {
+        if st.f_flag & st_bayrak != 0 {
+            bayraklar |= ms_bayrak;
+        }
+    }
+    bayraklar
+}
+
+// Yeni kök altında bir yolun tüm üst dizinlerini (ve istenirse kendisini) dizin listesine ekler.
+fn dizinleri_ekle(dizinler: &mut Vec<PathBuf>, yeni_kok: &Path, yol: &Path, kendisi: bool) {
+    let goreli = yol.strip_prefix("/").unwrap_or(yol);
+    let mut gecerli = yeni_kok.to_path_buf();
+    let bilesenler: Vec<_> = goreli.components().collect();
+    let son = if kendisi { bilesenler.len() } else { bilesenler.len().saturating_sub(1) };
+    for bilesen in &bilesenler[..son] {
+        gecerli.push(bilesen);
+        if !dizinler.contains(&gecerli) {
+            dizinler.push(gecerli.clone());
+        }
+    }
+}
+
+fn plan_olustur(betik: &Path, args: &[u8], profil: &SandboxProfili, yeni_kok: &Path) -> Result<CocukPlani, SecurityError> {
+    let host_kok = host_koku();
+    let mut dizinler: Vec<PathBuf> = Vec::new();
+    let mut baglamalar = Vec::new();
+    let hedef = |yol: &Path| yeni_kok.join(yol.strip_prefix("/").unwrap_or(yol));
+
+    let yollar = profil.salt_okunur.iter().map(|y| (y, false)).chain(profil.yazilabilir.iter().map(|y| (y, true)));
+    for (kaynak_id, yazilabilir) in yollar {
+        let kaynak = kaynak_yolu(kaynak_id, &host_kok);
+        if yazilabilir {
+            fs::create_dir_all(&kaynak).map_err(|e| sandbox_hatasi(&format!("Yazılabilir dizin oluşturulamadı ({})", kaynak.display()), e))?;
+        }
+        let meta = match fs::symlink_metadata(&kaynak) {
+            Ok(meta) => meta,
+            Err(_) => continue, // Bu sistemde olmayan yollar (örn. /lib64) atlanır
+        };
+        if meta.file_type().is_symlink() {
+            let baglanti = fs::read_link(&kaynak).map_err(|e| sandbox_hatasi("Sembolik bağlantı okunamadı", e))?;
+            dizinleri_ekle(&mut dizinler, yeni_kok, &kaynak, false);
+            baglamalar.push(Baglama::Sembolik { baglanti: cstr(&baglanti)?, hedef: cstr(&hedef(&kaynak))? });
+        } else {
+            dizinleri_ekle(&mut dizinler, yeni_kok, &kaynak, true);
+            baglamalar.push(Baglama::Dizin {
+                kaynak: cstr(&kaynak)?,
+                hedef: cstr(&hedef(&kaynak))?,
+                yazilabilir,
+                kilitli: kilitli_bayraklar(&kaynak),
+            });
+        }
+    }
+
+    dizinleri_ekle(&mut dizinler, yeni_kok, Path::new("/dev"), true);
+    for aygit in ["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"] {
+        let aygit = Path::new(aygit);
+        baglamalar.push(Baglama::Dosya { kaynak: cstr(aygit)?, hedef: cstr(&hedef(aygit))?, salt_okunur: false, kilitli: 0 });
+    }
+    baglamalar.push(Baglama::Dosya {
+        kaynak: cstr(betik)?,
+        hedef: cstr(&hedef(Path::new(SANDBOX_BETIK_YOLU)))?,
+        salt_okunur: true,
+        kilitli: kilitli_bayraklar(betik),
+    });
+    dizinleri_ekle(&mut dizinler, yeni_kok, Path::new("/tmp"), true);
+    dizinleri_ekle(&mut dizinler, yeni_kok, Path::new("/proc"), true);
+
+    let (program, argv) = betik_komutu(betik, &CString::new(SANDBOX_BETIK_YOLU).unwrap());
+
+    let mut envp = ortam_degiskenleri(args, &host_kok);
+    envp.push(CString::new("PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin").unwrap());
+    envp.push(CString::new("HOME=/tmp").unwrap());
+
+    let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    let mut plan = CocukPlani {
+        yalitim: true,
+        yeni_kok: cstr(yeni_kok)?,
+        dizinler: dizinler.iter().map(|d| cstr(d)).collect::<Result<_, _>>()?,
+        baglamalar,
+        tmp: cstr(&yeni_kok.join("tmp"))?,
+        proc: cstr(&yeni_kok.join("proc"))?,
+        setgroups: CString::new("deny").unwrap(),
+        uid_haritasi: CString::new(format!("0 {} 1\n", uid)).unwrap(),
+        gid_haritasi: CString::new(format!("0 {} 1\n", gid)).unwrap(),
+        ag_ad_alani: !profil.ag_erisimi,
+        profil: profil.clone(),
+        seccomp: seccomp_programi(&yasak_cagrilar(profil.seccomp)),
+        program,
+        argv,
+        envp,
+        argv_isaretcileri: Vec::new(),
+        envp_isaretcileri: Vec::new(),
+    };
+    plan.argv_isaretcileri = plan.argv.iter().map(|a| a.as_ptr()).chain(std::iter::once(std::ptr::null())).collect();
+    plan.envp_isaretcileri = plan.envp.iter().map(|e| e.as_ptr()).chain(std::iter::once(std::ptr::null())).collect();
+    Ok(plan)
+}
+
+// ELF ve çalıştırılabilir dosyalar doğrudan, diğerleri (izinsiz kabuk betikleri) /bin/sh ile çalıştırılır.
+// betik: Ana makinedeki betik dosyası; yol: execve'ye verilecek yolu (sandbox içindeki veya ana makinedeki).
+fn betik_komutu(betik: &Path, yol: &CString) -> (CString, Vec<CString>) {
+    let mut baslik = [0u8; 4];
+    let elf = fs::File::open(betik)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut baslik))
+        .map(|_| &baslik == b"\x7fELF")
+        .unwrap_or(false);
+    let calistirilabilir = fs::metadata(betik).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false);
+    if elf || calistirilabilir {
+        (yol.clone(), vec![yol.clone()])
+    } else {
+        (CString::new("/bin/sh").unwrap(), vec![CString::new("/bin/sh").unwrap(), yol.clone()])
+    }
+}
+
+// Sandbox özelliği kapalıyken kullanılan plan: betik ana makine yolu ve ortamıyla doğrudan çalıştırılır.
+fn yalitimsiz_plan(betik: &Path, args: &[u8]) -> Result<CocukPlani, SecurityError> {
+    let (program, argv) = betik_komutu(betik, &cstr(betik)?);
+    let mut envp = ortam_degiskenleri(args, &host_koku());
+    for degisken in ["PATH", "HOME"] {
+        if let Some(deger) = std::env::var_os(degisken) {
+            let mut girdi = format!("{}=", degisken).into_bytes();
+            girdi.extend_from_slice(deger.as_bytes());
+            envp.extend(CString::new(girdi).ok());
+        }
+    }
+    let bos = CString::default;
+    let mut plan = CocukPlani {
+        yalitim: false,
+        yeni_kok: bos(),
+        dizinler: Vec::new(),
+        baglamalar: Vec::new(),
+        tmp: bos(),
+        proc: bos(),
+        setgroups: bos(),
+        uid_haritasi: bos(),
+        gid_haritasi: bos(),
+        ag_ad_alani: false,
+        profil: SandboxProfili::yalitimsiz(),
+        seccomp: Vec::new(),
+        program,
+        argv,
+        envp,
+        argv_isaretcileri: Vec::new(),
+        envp_isaretcileri: Vec::new(),
+    };
+    plan.argv_isaretcileri = plan.argv.iter().map(|a| a.as_ptr()).chain(std::iter::once(std::ptr::null())).collect();
+    plan.envp_isaretcileri = plan.envp.iter().map(|e| e.as_ptr()).chain(std::iter::once(std::ptr::null())).collect();
+    Ok(plan)
+}
+
+// Çocuk süreçte bir adımın sonucunu denetler; başarısızsa mesajı stderr'e yazıp çıkar.
+// Sadece async-signal-safe çağrılar kullanır.
+unsafe fn dene(sonuc: c_int, mesaj: &str) {
+    if sonuc < 0 {
+        libc::write(2, mesaj.as_ptr().cast(), mesaj.len());
+        libc::write(2, b"\n".as_ptr().cast(), 1);
+        libc::_exit(KURULUM_HATASI_KODU);
+    }
+}
+
+unsafe fn dosyaya_yaz(yol: &[u8], icerik: &CString) -> c_int {
+    let fd = libc::open(yol.as_ptr().cast(), libc::O_WRONLY);
+    if fd < 0 {
+        return -1;
+    }
+    let bytes = icerik.as_bytes();
+    let yazilan = libc::write(fd, bytes.as_ptr().cast(), bytes.len());
+    libc::close(fd);
+    if yazilan == bytes.len() as isize { 0 } else { -1 }
+}
+
+// İlk çocuk: ad alanlarını oluşturur, ikinci çocuğu (yeni PID ad alanının 1 numaralı süreci) başlatır ve
+// onun çıkış durumunu aynen yansıtır.
+unsafe fn ilk_cocuk(plan: &CocukPlani) -> ! {
+    let mut bayraklar = libc::CLONE_NEWNS | libc::CLONE_NEWPID | libc::CLONE_NEWIPC | libc::CLONE_NEWUTS;
+    if plan.ag_ad_alani {
+        bayraklar |= libc::CLONE_NEWNET;
+    }
+    if libc::unshare(bayraklar | libc::CLONE_NEWUSER) == 0 {
+        dene(dosyaya_yaz(b"/proc/self/setgroups\0", &plan.setgroups), "setgroups yazılamadı");
+        dene(dosyaya_yaz(b"/proc/self/uid_map\0", &plan.uid_haritasi), "uid_map yazılamadı");
+        dene(dosyaya_yaz(b"/proc/self/gid_map\0", &plan.gid_haritasi), "gid_map yazılamadı");
+    } else {
+        // Kullanıcı ad alanları kapalı: sadece root olarak ad alanları oluşturulabilir.
+        dene(libc::unshare(bayraklar), "Sandbox ad alanları oluşturulamadı (kullanıcı ad alanları kapalı ve root değil)");
+    }
+
+    let pid = libc::fork();
+    dene(pid, "Sandbox süreci başlatılamadı");
+    if pid == 0 {
+        ikinci_cocuk(plan);
+    }
+    let mut durum: c_int = 0;
+    while libc::waitpid(pid, &mut durum, 0) < 0 {}
+    if libc::WIFEXITED(durum) {
+        libc::_exit(libc::WEXITSTATUS(durum));
+    }
+    libc::_exit(128 + libc::WTERMSIG(durum));
+}
+
+macro_rules! sinir_uygula {
+    ($kaynak:expr, $deger:expr) => {
+        if let Some(deger) = $deger {
+            let rl = libc::rlimit { rlim_cur: deger as libc::rlim_t, rlim_max: deger as libc::rlim_t };
+            dene(libc::setrlimit($kaynak, &rl), "Kaynak sınırı uygulanamadı");
+        }
+    };
+}
+
+// İkinci çocuk: yeni kökü kurar, kısıtları uygular ve betiği çalıştırır.
+unsafe fn ikinci_cocuk(plan: &CocukPlani) -> ! {
+    let bos = std::ptr::null::<c_char>();
+    // İlk çocuk (ve onunla ana süreç bağlantısı) ölürse sandbox'taki her şey sonlandırılır.
+    libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+
+    dene(libc::mount(bos, b"/\0".as_ptr().cast(), bos, libc::MS_REC | libc::MS_PRIVATE, std::ptr::null()), "Bağlama yayılımı kapatılamadı");
+    dene(libc::mount(b"tmpfs\0".as_ptr().cast(), plan.yeni_kok.as_ptr(), b"tmpfs\0".as_ptr().cast(), 0, b"mode=0755\0".as_ptr().cast()), "Sandbox kökü oluşturulamadı");
+    for dizin in &plan.dizinler {
+        libc::mkdir(dizin.as_ptr(), 0o755);
+    }
+
+    for baglama in &plan.baglamalar {
+        match baglama {
+            Baglama::Sembolik { baglanti, hedef } => {
+                dene(libc::symlink(baglanti.as_ptr(), hedef.as_ptr()), "Sembolik bağlantı oluşturulamadı");
+            }
+            Baglama::Dizin { kaynak, hedef, yazilabilir, kilitli } => {
+                dene(libc::mount(kaynak.as_ptr(), hedef.as_ptr(), bos, libc::MS_BIND | libc::MS_REC, std::ptr::null()), "Dizin bağlanamadı");
+                if !*yazilabilir {
+                    let bayraklar = libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | kilitli;
+                    dene(libc::mount(bos, hedef.as_ptr(), bos, bayraklar, std::ptr::null()), "Dizin salt okunur yapılamadı");
+                }
+            }
+            Baglama::Dosya { kaynak, hedef, salt_okunur, kilitli } => {
+                let fd = libc::open(hedef.as_ptr(), libc::O_WRONLY | libc::O_CREAT, 0o644);
+                if fd >= 0 {
+                    libc::close(fd);
+                }
+                dene(libc::mount(kaynak.as_ptr(), hedef.as_ptr(), bos, libc::MS_BIND, std::ptr::null()), "Dosya bağlanamadı");
+                if *salt_okunur {
+                    let bayraklar = libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | kilitli;
+                    dene(libc::mount(bos, hedef.as_ptr(), bos, bayraklar, std::ptr::null()), "Dosya salt okunur yapılamadı");
+                }
+            }
+        }
+    }
+    dene(libc::mount(b"tmpfs\0".as_ptr().cast(), plan.tmp.as_ptr(), b"tmpfs\0".as_ptr().cast(), libc::MS_NOSUID | libc::MS_NODEV, b"mode=1777\0".as_ptr().cast()), "/tmp oluşturulamadı");
+    dene(libc::mount(b"proc\0".as_ptr().cast(), plan.proc.as_ptr(), b"proc\0".as_ptr().cast(), libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC, std::ptr::null()), "/proc bağlanamadı");
+
+    // Kök değiştir: eski kök yeni kökün üzerine alınıp ayrılır.
+    dene(libc::chdir(plan.yeni_kok.as_ptr()), "Sandbox köküne geçilemedi");
+    dene(libc::syscall(libc::SYS_pivot_root, b".\0".as_ptr(), b".\0".as_ptr()) as c_int, "pivot_root başarısız");
+    dene(libc::umount2(b".\0".as_ptr().cast(), libc::MNT_DETACH), "Eski kök ayrılamadı");
+    dene(libc::chdir(b"/\0".as_ptr().cast()), "Kök dizine geçilemedi");
+    dene(libc::mount(bos, b"/\0".as_ptr().cast(), bos, libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY, std::ptr::null()), "Sandbox kökü salt okunur yapılamadı");
+
+    let profil = &plan.profil;
+    sinir_uygula!(libc::RLIMIT_AS, profil.bellek_siniri);
+    sinir_uygula!(libc::RLIMIT_CPU, profil.cpu_siniri_sn);
+    // Linux 5.14'ten beri RLIMIT_NPROC kullanıcı ad alanı başına sayılır; daha eski çekirdeklerde kullanıcının
+    // sandbox dışındaki süreçleri de sayıma girer.
+    sinir_uygula!(libc::RLIMIT_NPROC, profil.surec_siniri);
+    sinir_uygula!(libc::RLIMIT_FSIZE, profil.dosya_boyutu_siniri);
+    sinir_uygula!(libc::RLIMIT_NOFILE, profil.acik_dosya_siniri);
+
+    dene(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0), "no_new_privs ayarlanamadı");
+    if !plan.seccomp.is_empty() && profil.seccomp != SeccompDuzeyi::Kapali {
+        let program = libc::sock_fprog { len: plan.seccomp.len() as u16, filter: plan.seccomp.as_ptr() as *mut libc::sock_filter };
+        dene(libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &program as *const libc::sock_fprog), "seccomp filtresi yüklenemedi");
+    }
+
+    libc::execve(plan.program.as_ptr(), plan.argv_isaretcileri.as_ptr(), plan.envp_isaretcileri.as_ptr());
+    dene(-1, "Betik çalıştırılamadı");
+    libc::_exit(KURULUM_HATASI_KODU);
+}
+
+// Çıktı borusundan okunabilecek her şeyi okur; BETIK_CIKTI_SINIRI'nı aşan kısım atılır (boru yine de
+// boşaltılır ki betik yazarken tıkanmasın). Dönüş değeri: boru kapandıysa true.
+fn borudan_oku(fd: c_int, cikti: &mut Vec<u8>) -> bool {
+    let mut tampon = [0u8; 4096];
+    let n = unsafe { libc::read(fd, tampon.as_mut_ptr().cast(), tampon.len()) };
+    if n <= 0 {
+        return n == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::EINTR);
+    }
+    let kalan = BETIK_CIKTI_SINIRI.saturating_sub(cikti.len());
+    cikti.extend_from_slice(&tampon[..(n as usize).min(kalan)]);
+    false
+}
+
+// Betiği profile göre sandbox içinde çalıştırır ve bekler (bkz. SecurityManager::run_in_sandbox).
+pub fn calistir(betik_kaynagi_id: &str, args: &[u8], profil: &SandboxProfili, zaman_asimi_us: u64) -> Result<BetikSonucu, SecurityError> {
+    let betik = kaynak_yolu(betik_kaynagi_id, &host_koku());
+    if !betik.is_file() {
+        return Err(SecurityError::SandboxError(format!("Betik bulunamadı: {}", betik.display())));
+    }
+    if !profil.yalitim {
+        return yalitimsiz_plan(&betik, args).and_then(|plan| calistir_ve_bekle(&plan, zaman_asimi_us));
+    }
+    let zaman = SystemTime::now().duration_since(UNIX_EPOCH).map(|s| s.as_nanos()).unwrap_or(0);
+    let yeni_kok = std::env::temp_dir().join(format!("sahne-sandbox-{}-{}", std::process::id(), zaman));
+    fs::create_dir(&yeni_kok).map_err(|e| sandbox_hatasi("Sandbox kök dizini oluşturulamadı", e))?;
+    let sonuc = plan_olustur(&betik, args, profil, &yeni_kok).and_then(|plan| calistir_ve_bekle(&plan, zaman_asimi_us));
+    let _ = fs::remove_dir(&yeni_kok); // tmpfs sadece çocuğun mount ad alanındaydı; burada boş dizin kalır
+    sonuc
+}
+
+fn calistir_ve_bekle(plan: &CocukPlani, zaman_asimi_us: u64) -> Result<BetikSonucu, SecurityError> {
+    let mut stdout_boru = [0 as c_int; 2];
+    let mut stderr_boru = [0 as c_int; 2];
+    let null = CString::new("/dev/null").unwrap();
+    unsafe {
+        if libc::pipe2(stdout_boru.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err(sandbox_hatasi("Çıktı borusu oluşturulamadı", std::io::Error::last_os_error()));
+        }
+        if libc::pipe2(stderr_boru.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            libc::close(stdout_boru[0]);
+            libc::close(stdout_boru[1]);
+            return Err(sandbox_hatasi("Çıktı borusu oluşturulamadı", std::io::Error::last_os_error()));
+        }
+    }
+    let stdin_fd = unsafe { libc::open(null.as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC) };
+
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+        unsafe {
+            if stdin_fd >= 0 {
+                libc::dup2(stdin_fd, 0);
+            }
+            libc::dup2(stdout_boru[1], 1);
+            libc::dup2(stderr_boru[1], 2);
+            if plan.yalitim {
+                ilk_cocuk(plan);
+            }
+            libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+            libc::execve(plan.program.as_ptr(), plan.argv_isaretcileri.as_ptr(), plan.envp_isaretcileri.as_ptr());
+            dene(-1, "Betik çalıştırılamadı");
+        }
+    }
+    unsafe {
+        libc::close(stdin_fd);
+        libc::close(stdout_boru[1]);
+        libc::close(stderr_boru[1]);
+    }
+    if pid < 0 {
+        unsafe {
+            libc::close(stdout_boru[0]);
+            libc::close(stderr_boru[0]);
+        }
+        return Err(sandbox_hatasi("Sandbox süreci başlatılamadı", std::io::Error::last_os_error()));
+    }
+
+    let son_an = Instant::now() + Duration::from_micros(zaman_asimi_us);
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut acik = [true, true];
+    let mut zaman_asimi = false;
+    while acik[0] || acik[1] {
+        let kalan = son_an.saturating_duration_since(Instant::now());
+        if kalan.is_zero() {
+            zaman_asimi = true;
+            break;
+        }
+        let mut fdler = [
+            libc::pollfd { fd: if acik[0] { stdout_boru[0] } else { -1 }, events: libc::POLLIN, revents: 0 },
+            libc::pollfd { fd: if acik[1] { stderr_boru[0] } else { -1 }, events: libc::POLLIN, revents: 0 },
+        ];
+        let hazir = unsafe { libc::poll(fdler.as_mut_ptr(), 2, kalan.as_millis().min(c_int::MAX as u128) as c_int) };
+        if hazir == 0 {
+            continue; // Zaman aşımı döngü başında ele alınır
+        }
+        if hazir < 0 {
+            let hata = std::io::Error::last_os_error();
+            if hata.raw_os_error() == Some(libc::EINTR) {
+                continue;
+            }
+            unsafe {
+                libc::kill(pid, libc::SIGKILL);
+                libc::waitpid(pid, std::ptr::null_mut(), 0);
+                libc::close(stdout_boru[0]);
+                libc::close(stderr_boru[0]);
+            }
+            return Err(sandbox_hatasi("Betik çıktısı beklenemedi", hata));
+        }
+        if fdler[0].revents != 0 && borudan_oku(stdout_boru[0], &mut stdout) {
+            acik[0] = false;
+        }
+        if fdler[1].revents != 0 && borudan_oku(stderr_boru[0], &mut stderr) {
+            acik[1] = false;
+        }
+    }
+
+    // Borular kapandıktan sonra da betik (çıktısını kapatıp) çalışmaya devam edebilir; süre dolana kadar bekle.
+    let mut durum: c_int = 0;
+    loop {
+        if zaman_asimi {
+            unsafe { libc::kill(pid, libc::SIGKILL) };
+            unsafe { libc::waitpid(pid, &mut durum, 0) };
+            break;
+        }
+        let bitti = unsafe { libc::waitpid(pid, &mut durum, libc::WNOHANG) };
+        if bitti == pid {
+            break;
+        }
+        if Instant::now() >= son_an {
+            zaman_asimi = true;
+            continue;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    unsafe {
+        libc::close(stdout_boru[0]);
+        libc::close(stderr_boru[0]);
+    }
+
+    let cikis_kodu = if zaman_asimi {
+        None
+    } else if libc::WIFEXITED(durum) {
+        Some(libc::WEXITSTATUS(durum))
+    } else {
+        Some(128 + libc::WTERMSIG(durum))
+    };
+    Ok(BetikSonucu { cikis_kodu, stdout, stderr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_kaynak_yolu() {
+        let kok = Path::new("/var/lib/sahne");
+        assert_eq!(kaynak_yolu("sahne://installed_packages/editor", kok), PathBuf::from("/var/lib/sahne/installed_packages/editor"));
+        assert_eq!(kaynak_yolu("/usr", kok), PathBuf::from("/usr"));
+    }
+
+    #[test]
+    fn test_ortam_degiskenleri() {
+        let args = b"SAHNE_PAKET=editor\0SAHNE_ESKI_SURUM=\0SAHNE_KURULUM_KOKU=sahne://installed_packages\0bozuk\0=deger\0";
+        let envp = ortam_degiskenleri(args, Path::new("/var/lib/sahne"));
+        let metinler: Vec<&OsStr> = envp.iter().map(|e| OsStr::from_bytes(e.as_bytes())).collect();
+        assert_eq!(metinler, vec![
+            OsStr::new("SAHNE_PAKET=editor"),
+            OsStr::new("SAHNE_ESKI_SURUM="),
+            OsStr::new("SAHNE_KURULUM_KOKU=/var/lib/sahne/installed_packages"),
+        ]);
+    }
+
+    #[test]
+    fn test_seccomp_duzeyleri() {
+        assert!(yasak_cagrilar(SeccompDuzeyi::Kapali).is_empty());
+        let temel = yasak_cagrilar(SeccompDuzeyi::Temel);
+        let kati = yasak_cagrilar(SeccompDuzeyi::Kati);
+        assert!(temel.iter().all(|c| kati.contains(c)));
+        assert!(kati.contains(&libc::SYS_unshare) && !temel.contains(&libc::SYS_unshare));
+    }
+
+    #[test]
+    fn test_seccomp_programi_atlamalari() {
+        let yasaklar = yasak_cagrilar(SeccompDuzeyi::Temel);
+        let program = seccomp_programi(&yasaklar);
+        assert_eq!(program.len(), 6 + yasaklar.len() + 2);
+        let errno = program.len() - 1;
+        assert_eq!(program[errno].k, SECCOMP_RET_ERRNO | libc::EPERM as u32);
+        assert_eq!(program[errno - 1].k, SECCOMP_RET_ALLOW);
+        // Her karşılaştırma eşleştiğinde ERRNO dönüşüne atlamalı.
+        for (i, komut) in program.iter().enumerate().skip(6).take(yasaklar.len()) {
+            assert_eq!(i + 1 + komut.jt as usize, errno);
+        }
+    }
+
+    // Programı (sadece seccomp_programi'nin kullandığı komutlarla) verilen mimari ve çağrı numarası için yürütür.
+    fn programi_calistir(program: &[libc::sock_filter], arch: u32, nr: u32) -> u32 {
+        let mut a = 0;
+        let mut pc = 0;
+        loop {
+            let komut = &program[pc];
+            pc += 1;
+            match komut.code {
+                BPF_LD_W_ABS => a = if komut.k == SECCOMP_VERI_ARCH { arch } else { nr },
+                BPF_JMP_JEQ_K => pc += usize::from(if a == komut.k { komut.jt } else { komut.jf }),
+                BPF_JMP_JGE_K => pc += usize::from(if a >= komut.k { komut.jt } else { komut.jf }),
+                BPF_RET_K => return komut.k,
+                kod => panic!("beklenmeyen BPF komutu {:#x}", kod),
+            }
+        }
+    }
+
+    #[test]
+    fn test_seccomp_programi_x32() {
+        let program = seccomp_programi(&yasak_cagrilar(SeccompDuzeyi::Temel));
+        let mount = libc::SYS_mount as u32;
+        assert_eq!(programi_calistir(&program, AUDIT_ARCH, mount), SECCOMP_RET_ERRNO | libc::EPERM as u32);
+        assert_eq!(programi_calistir(&program, AUDIT_ARCH, mount | X32_CAGRI_BITI), SECCOMP_RET_KILL_PROCESS);
+        assert_eq!(programi_calistir(&program, AUDIT_ARCH, libc::SYS_read as u32), SECCOMP_RET_ALLOW);
+        assert_eq!(programi_calistir(&program, 0x4000_0003, mount), SECCOMP_RET_KILL_PROCESS); // AUDIT_ARCH_I386
+    }
+
+    #[test]
+    fn test_dizinleri_ekle() {
+        let mut dizinler = Vec::new();
+        let kok = Path::new("/tmp/kok");
+        dizinleri_ekle(&mut dizinler, kok, Path::new("/usr/lib"), true);
+        dizinleri_ekle(&mut dizinler, kok, Path::new("/usr/bin"), false);
+        assert_eq!(dizinler, vec![PathBuf::from("/tmp/kok/usr"), PathBuf::from("/tmp/kok/usr/lib")]);
+    }
+}
@@ -18,6 +18,16 @@ use crate::resource; // Betik Kaynağını acquire etmek için
 use crate::SahneError; // Sahne64 hata türü
 use crate::Handle; // Kaynak Handle'ları
 
+// Yakalanan çıktı Kaynaklarını silmek için
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+use crate::srcstaging;
+// Betikler güven seviyesine göre seçilen sandbox profiliyle çalıştırılır
+use crate::srcsecurity::SandboxProfili;
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+use crate::srcsecurity::SecurityManager;
+
+use serde::{Deserialize, Serialize};
+
 // Verilen betik Kaynağını (executable code resource) yeni bir Sahne64 görevi olarak çalıştırır.
 // Not: Sahne64 API'sında task::wait ve task çıktısını (stdout/stderr) yakalama mekanizmaları
 // henüz tanımlanmamıştır. Bu nedenle bu fonksiyon sadece betik görevini başlatır,
@@ -73,6 +83,239 @@ pub fn betik_calistir(betik_kaynagi_id: &str, args: &[u8]) -> Result<(), PaketYo
     }
 }
 
+// Paket betiklerinin (hook) çalıştırıldığı işlem aşamaları.
+// Meta verideki adları (serde) paket biçimiyle uyumlu olması için İngilizce tutulur.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BetikAsamasi {
+    #[serde(rename = "pre-install")]
+    KurulumOncesi,
+    #[serde(rename = "post-install")]
+    KurulumSonrasi,
+    #[serde(rename = "pre-remove")]
+    KaldirmaOncesi,
+    #[serde(rename = "post-remove")]
+    KaldirmaSonrasi,
+    #[serde(rename = "pre-upgrade")]
+    GuncellemeOncesi,
+    #[serde(rename = "post-upgrade")]
+    GuncellemeSonrasi,
+    // İşlem sonunda çalışan tetikleyici betikleri (bkz. srctrigger); paketin betikler tablosunda kullanılmaz.
+    #[serde(rename = "trigger")]
+    Tetikleyici,
+}
+
+impl BetikAsamasi {
+    pub const TUMU: [BetikAsamasi; 7] = [
+        BetikAsamasi::KurulumOncesi,
+        BetikAsamasi::KurulumSonrasi,
+        BetikAsamasi::KaldirmaOncesi,
+        BetikAsamasi::KaldirmaSonrasi,
+        BetikAsamasi::GuncellemeOncesi,
+        BetikAsamasi::GuncellemeSonrasi,
+        BetikAsamasi::Tetikleyici,
+    ];
+
+    // Meta verideki ve betik ortamındaki aşama adı (örn. "pre-install").
+    pub fn ad(self) -> &'static str {
+        match self {
+            BetikAsamasi::KurulumOncesi => "pre-install",
+            BetikAsamasi::KurulumSonrasi => "post-install",
+            BetikAsamasi::KaldirmaOncesi => "pre-remove",
+            BetikAsamasi::KaldirmaSonrasi => "post-remove",
+            BetikAsamasi::GuncellemeOncesi => "pre-upgrade",
+            BetikAsamasi::GuncellemeSonrasi => "post-upgrade",
+            BetikAsamasi::Tetikleyici => "trigger",
+        }
+    }
+
+    pub fn ayristir(ad: &str) -> Option<Self> {
+        BetikAsamasi::TUMU.iter().copied().find(|a| a.ad() == ad)
+    }
+
+    // Meta veride betik tanımlanmamışsa paketin betik dizininde aranacak dosya adı (örn. "pre-install.sh").
+    pub fn varsayilan_dosya_adi(self) -> String {
+        format!("{}.sh", self.ad())
+    }
+}
+
+// Betiğe çalıştığı işlemi anlatan ortam. Görev argümanı olarak "ANAHTAR=değer\0" dizisi biçiminde geçilir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BetikOrtami {
+    pub asama: BetikAsamasi,
+    pub paket_adi: String,
+    pub eski_surum: Option<String>, // Kaldırma ve güncellemede kurulu olan sürüm
+    pub yeni_surum: Option<String>, // Kurulum ve güncellemede kurulacak sürüm
+    pub kurulum_koku: String, // Paketlerin kurulduğu temel Kaynak ID'si
+    pub tetikleyici: Option<String>, // Tetikleyici betiklerinde tetikleyicinin adı
+}
+
+impl BetikOrtami {
+    // Ortamı görev argümanlarına çevirir. Bilinmeyen sürümler boş değerle geçilir.
+    pub fn argumanlar(&self) -> Vec<u8> {
+        let mut cikti = Vec::new();
+        let mut ekle = |anahtar: &str, deger: &str| {
+            cikti.extend_from_slice(anahtar.as_bytes());
+            cikti.push(b'=');
+            cikti.extend_from_slice(deger.as_bytes());
+            cikti.push(0);
+        };
+        ekle("SAHNE_BETIK_ASAMASI", self.asama.ad());
+        ekle("SAHNE_PAKET", &self.paket_adi);
+        ekle("SAHNE_ESKI_SURUM", self.eski_surum.as_deref().unwrap_or(""));
+        ekle("SAHNE_YENI_SURUM", self.yeni_surum.as_deref().unwrap_or(""));
+        ekle("SAHNE_KURULUM_KOKU", &self.kurulum_koku);
+        if let Some(tetikleyici) = &self.tetikleyici {
+            ekle("SAHNE_TETIKLEYICI", tetikleyici);
+        }
+        cikti
+    }
+}
+
+// Beklenerek çalıştırılan bir betiğin sonucu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BetikSonucu {
+    pub cikis_kodu: Option<i32>, // None: betik zaman aşımına uğradı ve sonlandırıldı
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl BetikSonucu {
+    pub fn basarili(&self) -> bool {
+        self.cikis_kodu == Some(0)
+    }
+}
+
+// Bir betiğin varsayılan azami çalışma süresi (mikrosaniye).
+pub const VARSAYILAN_BETIK_ZAMAN_ASIMI_US: u64 = 300_000_000; // 5 dakika
+
+// Paket betiklerinin varsayılan dizini; her paketin betikleri "<dizin>/<paket adı>/" altında aranır.
+pub const VARSAYILAN_BETIK_DIZINI: &str = "sahne://system/package_scripts";
+
+// Her çıktı akışından tutulacak azami bayt sayısı; fazlası işlem günlüğünü şişirmemek için atılır.
+pub const BETIK_CIKTI_SINIRI: usize = 64 * 1024;
+
+// Betik çıktılarının geçici olarak yazıldığı Kaynak dizini.
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+const BETIK_CIKTI_DIZINI: &str = "sahne://tmp/package_scripts";
+
+// Betiği verilen sandbox profiliyle çalıştırır, tamamlanmasını (en fazla zaman_asimi_us kadar) bekler ve
+// stdout/stderr çıktısını yakalar. Süre dolarsa betik sonlandırılır ve cikis_kodu None döner.
+// Linux ana makine arka ucunda SecurityManager::run_in_sandbox kullanılır; Sahne64'te betik yeni bir
+// görev olarak başlatılır.
+// betik_kaynagi_id: Çalıştırılacak betik Kaynağının ID'si.
+// ortam: Betiğe argüman olarak geçilecek işlem bilgisi.
+// profil: Betiğe uygulanacak sandbox kısıtları.
+// Dönüş değeri: Betik sonucu (başarısız çıkış kodu da Ok ile döner) veya betik başlatılamazsa hata.
+pub fn betik_calistir_bekle(
+    betik_kaynagi_id: &str,
+    ortam: &BetikOrtami,
+    profil: &SandboxProfili,
+    zaman_asimi_us: u64,
+) -> Result<BetikSonucu, PaketYoneticisiHatasi> {
+    info!("Betik çalıştırılıyor ({}, {}): {}", ortam.paket_adi, ortam.asama.ad(), betik_kaynagi_id); // no_std log
+
+    #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        SecurityManager::new()
+            .run_in_sandbox(betik_kaynagi_id, &ortam.argumanlar(), profil, zaman_asimi_us)
+            .map_err(|e| {
+                let mesaj = format!("Betik sandbox içinde çalıştırılamadı (Kaynak: {}): {:?}", betik_kaynagi_id, e);
+                error!("{}", mesaj); // no_std log
+                PaketYoneticisiHatasi::BetikCalistirmaHatasi(mesaj)
+            })
+    }
+
+    #[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+    {
+        // Sahne64 çekirdeğinde henüz sandbox API'si yok; profil çekirdek tarafından uygulanamaz.
+        let _ = profil;
+        gorev_olarak_calistir(betik_kaynagi_id, ortam, zaman_asimi_us)
+    }
+}
+
+// Betiği yeni bir Sahne64 görevi olarak çalıştırır.
+// Varsayım: Sahne64 task modülü, standart çıktıları verilen Handle'lara yönlendirerek başlatma
+// (task::spawn_with_output), zaman aşımlı bekleme (task::wait, süre dolarsa Ok(None)) ve
+// sonlandırma (task::kill) sağlar.
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+fn gorev_olarak_calistir(betik_kaynagi_id: &str, ortam: &BetikOrtami, zaman_asimi_us: u64) -> Result<BetikSonucu, PaketYoneticisiHatasi> {
+    let betik_hatasi = |mesaj: String| {
+        error!("{}", mesaj); // no_std log
+        PaketYoneticisiHatasi::BetikCalistirmaHatasi(mesaj)
+    };
+
+    let zaman = task::current_time_us().map_err(PaketYoneticisiHatasi::from)?;
+    let cikti_oneki = format!("{}/{}-{}-{}", BETIK_CIKTI_DIZINI, ortam.paket_adi, ortam.asama.ad(), zaman);
+    let stdout_id = format!("{}.stdout", cikti_oneki);
+    let stderr_id = format!("{}.stderr", cikti_oneki);
+
+    let script_handle = resource::acquire(betik_kaynagi_id, resource::MODE_READ)
+        .map_err(|e| betik_hatasi(format!("Betik Kaynağı acquire hatası (Kaynak: {}): {:?}", betik_kaynagi_id, e)))?;
+    let cikti_modu = resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE;
+    let stdout_handle = match resource::acquire(&stdout_id, cikti_modu) {
+        Ok(h) => h,
+        Err(e) => {
+            let _ = resource::release(script_handle);
+            return Err(betik_hatasi(format!("Betik çıktı Kaynağı oluşturulamadı ({}): {:?}", stdout_id, e)));
+        }
+    };
+    let stderr_handle = match resource::acquire(&stderr_id, cikti_modu) {
+        Ok(h) => h,
+        Err(e) => {
+            let _ = resource::release(script_handle);
+            let _ = resource::release(stdout_handle);
+            let _ = srcstaging::kaynagi_sil(&stdout_id, false);
+            return Err(betik_hatasi(format!("Betik çıktı Kaynağı oluşturulamadı ({}): {:?}", stderr_id, e)));
+        }
+    };
+
+    let baslatma = task::spawn_with_output(script_handle, &ortam.argumanlar(), stdout_handle, stderr_handle);
+    let _ = resource::release(script_handle);
+
+    let bekleme = baslatma.and_then(|tid| {
+        info!("Betik görevi başlatıldı, TaskId: {:?}", tid); // no_std log
+        match task::wait(tid, zaman_asimi_us) {
+            Ok(None) => {
+                error!("Betik zaman aşımına uğradı ({} us), görev sonlandırılıyor: {}", zaman_asimi_us, betik_kaynagi_id); // no_std log
+                let _ = task::kill(tid);
+                Ok(None)
+            }
+            sonuc => sonuc,
+        }
+    });
+    let _ = resource::release(stdout_handle);
+    let _ = resource::release(stderr_handle);
+
+    let stdout = cikti_oku(&stdout_id);
+    let stderr = cikti_oku(&stderr_id);
+    let _ = srcstaging::kaynagi_sil(&stdout_id, false);
+    let _ = srcstaging::kaynagi_sil(&stderr_id, false);
+
+    let cikis_kodu = bekleme.map_err(|e| betik_hatasi(format!("Betik görevi çalıştırılamadı (Kaynak: {}): {:?}", betik_kaynagi_id, e)))?;
+    Ok(BetikSonucu { cikis_kodu, stdout, stderr })
+}
+
+// Yakalanan çıktı Kaynağını BETIK_CIKTI_SINIRI kadar okur. Okunamayan çıktı boş sayılır (betik sonucu
+// çıkış koduna göre değerlendirilir).
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+fn cikti_oku(resource_id: &str) -> Vec<u8> {
+    let handle = match resource::acquire(resource_id, resource::MODE_READ) {
+        Ok(h) => h,
+        Err(_) => return Vec::new(),
+    };
+    let mut buffer = Vec::new();
+    let mut temp_buffer = [0u8; 512];
+    while buffer.len() < BETIK_CIKTI_SINIRI {
+        match resource::read(handle, &mut temp_buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+        }
+    }
+    buffer.truncate(BETIK_CIKTI_SINIRI);
+    let _ = resource::release(handle);
+    buffer
+}
+
 // #[cfg(test)] bloğu std test runner'ı ve Sahne64 task/resource mock'ları gerektirir.
 // Bu blok no_std ortamında derlenmeyecektir eğer std feature aktif değilse ve test ortamı yoksa.
 
@@ -80,6 +323,40 @@ pub fn betik_calistir(betik_kaynagi_id: &str, args: &[u8]) -> Result<(), PaketYo
 mod tests {
     // std::io, std::process kullandığı için no_std'de doğrudan çalışmaz.
     // Mock task::spawn, resource::acquire/release ve çıktı yakalama/kontrol mekanizması gerektirir.
+    use super::*;
+
+    #[test]
+    fn test_asama_adlari() {
+        for asama in BetikAsamasi::TUMU {
+            assert_eq!(BetikAsamasi::ayristir(asama.ad()), Some(asama));
+        }
+        assert_eq!(BetikAsamasi::KurulumOncesi.varsayilan_dosya_adi(), "pre-install.sh");
+        assert_eq!(BetikAsamasi::ayristir("install"), None);
+    }
+
+    #[test]
+    fn test_ortam_argumanlari() {
+        let ortam = BetikOrtami {
+            asama: BetikAsamasi::GuncellemeSonrasi,
+            paket_adi: "editor".to_string(),
+            eski_surum: Some("1.0".to_string()),
+            yeni_surum: Some("1.1".to_string()),
+            kurulum_koku: "sahne://installed_packages".to_string(),
+            tetikleyici: None,
+        };
+        let argumanlar = ortam.argumanlar();
+        let alanlar: Vec<&[u8]> = argumanlar.split(|b| *b == 0).filter(|a| !a.is_empty()).collect();
+        assert_eq!(alanlar, vec![
+            &b"SAHNE_BETIK_ASAMASI=post-upgrade"[..],
+            &b"SAHNE_PAKET=editor"[..],
+            &b"SAHNE_ESKI_SURUM=1.0"[..],
+            &b"SAHNE_YENI_SURUM=1.1"[..],
+            &b"SAHNE_KURULUM_KOKU=sahne://installed_packages"[..],
+        ]);
+
+        let kurulum = BetikOrtami { asama: BetikAsamasi::KurulumOncesi, eski_surum: None, ..ortam };
+        assert!(kurulum.argumanlar().windows(18).any(|w| w == b"SAHNE_ESKI_SURUM=\0"));
+    }
 }
 
 // --- PaketYoneticisiHatasi enum tanımının no_std uyumlu hale getirilmesi ---
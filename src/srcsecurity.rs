@@ -8,7 +8,6 @@ use hex::FromHexError; // hex hata türü
 
 // Sahne64 API modülleri
 use crate::resource; // Kaynak işlemleri (okuma)
-use crate::task; // Görev işlemleri (sandbox çalıştırma için)
 use crate::SahneError; // Sahne64 hata türü
 use crate::Handle; // Kaynak Handle'ları
 
@@ -27,6 +26,15 @@ use alloc::format; // format! makrosu için
 // no_std uyumlu print makroları (örnek çıktılar için)
 use crate::print_macros::{println, eprintln};
 
+// Sandbox profilleri güven seviyesine göre seçilir; sonuç betik sonucu biçimindedir
+use crate::srctrust::GuvenSeviyesi;
+use crate::srcscript::BetikSonucu;
+// Güvenlik açığı taraması depo tarafından yayınlanan bildiri akışına dayanır
+use crate::srcadvisory::{BildiriAkisi, Bulgu, Onem};
+// Linux ana makine arka ucundaki sandbox uygulaması
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+use crate::srcsandbox;
+
 
 // Güvenlik hatalarını temsil eden enum (no_std uyumlu)
 // thiserror::Error yerine Debug ve Display manuel implementasyonları.
@@ -44,6 +52,9 @@ pub enum SecurityError {
     // İmza doğrulama başarısız oldu (özetler eşleşmiyor)
     SignatureVerificationFailed,
 
+    // İmzalı verinin geçerlilik süresi dolmuş veya zaman damgası tutarsız (eski bir kopyanın yeniden sunulması)
+    SuresiDolmusVeri(String), // String alloc gerektirir.
+
     // Güvenlik açığı taraması sırasında oluşan hata (tarama motoru hatası vb.)
     VulnerabilityScanError(String), // Hata detayını string olarak tutmak alloc gerektirir.
 
@@ -62,6 +73,7 @@ impl core::fmt::Display for SecurityError {
             SecurityError::HexDecodeError(e) => write!(f, "Hex çözme hatası: {:?}", e),
             SecurityError::InvalidSignatureFile(s) => write!(f, "Geçersiz imza dosyası: {}", s),
             SecurityError::SignatureVerificationFailed => write!(f, "İmza doğrulanamadı: Paket özeti imza özetiyle eşleşmiyor."),
+            SecurityError::SuresiDolmusVeri(s) => write!(f, "Süresi dolmuş veri: {}", s),
             SecurityError::VulnerabilityScanError(s) => write!(f, "Güvenlik açığı taraması başarısız oldu: {}", s),
             SecurityError::SandboxError(s) => write!(f, "Sandbox ortamında çalıştırma başarısız oldu: {}", s),
             SecurityError::OperationNotSupported(s) => write!(f, "İşlem desteklenmiyor: {}", s),
@@ -83,6 +95,117 @@ impl From<FromHexError> for SecurityError {
 }
 
 
+// Sandbox içinde seccomp ile engellenecek sistem çağrısı kümesi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeccompDuzeyi {
+    Kapali, // Filtre uygulanmaz
+    Temel, // Çekirdek modülü, mount, reboot, ptrace, kexec gibi sistem yönetimi çağrıları engellenir
+    Kati, // Temel'e ek olarak ad alanı değiştirme, bpf, perf, keyctl, io_uring gibi saldırı yüzeyi geniş çağrılar
+}
+
+// Paket betiklerinin çalıştırıldığı sandbox'ın kısıtları.
+// Yollar ana makine yolu veya Sahne Kaynak ID'si olabilir (ana makine arka ucu "sahne://" ID'lerini
+// kendi kök dizinine eşler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxProfili {
+    pub yalitim: bool, // false ise betik hiçbir kısıt olmadan çalışır (sandbox özelliği kapalı; bkz. yalitimsiz)
+    pub salt_okunur: Vec<String>, // Sandbox içinde salt okunur görünen sistem yolları
+    pub yazilabilir: Vec<String>, // Yazılabilir bağlanan yollar (kurulum kökü SandboxAyarlari::profil ile eklenir)
+    pub ag_erisimi: bool, // false ise betik ayrı ve boş bir ağ ad alanında çalışır
+    pub seccomp: SeccompDuzeyi,
+    pub bellek_siniri: Option<u64>, // Adres alanı sınırı (bayt)
+    pub cpu_siniri_sn: Option<u64>, // CPU süresi sınırı (saniye)
+    pub surec_siniri: Option<u64>, // Azami süreç/iş parçacığı sayısı
+    pub dosya_boyutu_siniri: Option<u64>, // Yazılabilecek en büyük dosya (bayt)
+    pub acik_dosya_siniri: Option<u64>, // Azami açık dosya tanımlayıcısı
+}
+
+// Sistem dizinlerinin betiklere salt okunur gösterilen varsayılan listesi.
+const VARSAYILAN_SALT_OKUNUR: [&str; 6] = ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"];
+
+impl SandboxProfili {
+    // Güvenilir paketlerin betikleri için profil: ağ kapalı, temel seccomp filtresi, geniş sınırlar.
+    pub fn guvenilir() -> Self {
+        SandboxProfili {
+            yalitim: true,
+            salt_okunur: VARSAYILAN_SALT_OKUNUR.iter().map(|y| y.to_string()).collect(),
+            yazilabilir: Vec::new(),
+            ag_erisimi: false,
+            seccomp: SeccompDuzeyi::Temel,
+            bellek_siniri: Some(2 * 1024 * 1024 * 1024),
+            cpu_siniri_sn: Some(600),
+            surec_siniri: Some(256),
+            dosya_boyutu_siniri: Some(4 * 1024 * 1024 * 1024),
+            acik_dosya_siniri: Some(1024),
+        }
+    }
+
+    // Güvenilmeyen yayıncıların betikleri için en sıkı profil.
+    pub fn guvenilmeyen() -> Self {
+        SandboxProfili {
+            seccomp: SeccompDuzeyi::Kati,
+            bellek_siniri: Some(512 * 1024 * 1024),
+            cpu_siniri_sn: Some(120),
+            surec_siniri: Some(32),
+            dosya_boyutu_siniri: Some(1024 * 1024 * 1024),
+            acik_dosya_siniri: Some(256),
+            ..SandboxProfili::guvenilir()
+        }
+    }
+
+    // Yetenek kaydında sandbox özelliği kapalıyken kullanılan profil: ad alanı, bağlama, sınır ve seccomp
+    // uygulanmaz; sadece zaman aşımı ve çıktı yakalama geçerlidir.
+    pub fn yalitimsiz() -> Self {
+        SandboxProfili {
+            yalitim: false,
+            salt_okunur: Vec::new(),
+            yazilabilir: Vec::new(),
+            ag_erisimi: true,
+            seccomp: SeccompDuzeyi::Kapali,
+            bellek_siniri: None,
+            cpu_siniri_sn: None,
+            surec_siniri: None,
+            dosya_boyutu_siniri: None,
+            acik_dosya_siniri: None,
+        }
+    }
+}
+
+// Güven seviyesi başına sandbox profilleri. Alanlar yapılandırma ile değiştirilebilir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxAyarlari {
+    pub etkin: bool, // false ise güven seviyesinden bağımsız olarak SandboxProfili::yalitimsiz kullanılır
+    pub guvenilir: SandboxProfili,
+    pub guvenilmeyen: SandboxProfili,
+}
+
+impl Default for SandboxAyarlari {
+    fn default() -> Self {
+        SandboxAyarlari {
+            etkin: true,
+            guvenilir: SandboxProfili::guvenilir(),
+            guvenilmeyen: SandboxProfili::guvenilmeyen(),
+        }
+    }
+}
+
+impl SandboxAyarlari {
+    // Seviyeye ait profili, kurulum kökü yazılabilir olacak şekilde döner.
+    pub fn profil(&self, seviye: GuvenSeviyesi, kurulum_koku: &str) -> SandboxProfili {
+        if !self.etkin {
+            return SandboxProfili::yalitimsiz();
+        }
+        let mut profil = match seviye {
+            GuvenSeviyesi::Guvenilir => self.guvenilir.clone(),
+            GuvenSeviyesi::Guvenilmeyen => self.guvenilmeyen.clone(),
+        };
+        if !profil.yazilabilir.iter().any(|y| y == kurulum_koku) {
+            profil.yazilabilir.push(kurulum_koku.to_string());
+        }
+        profil
+    }
+}
+
 // Güvenlik yönetimi işlevlerini sağlar.
 // İmza doğrulama, güvenlik açığı taraması ve sandbox çalıştırma (Sahne64 API'sine bağlı).
 pub struct SecurityManager {
@@ -197,94 +320,64 @@ impl SecurityManager {
     }
 
 
-    // Belirtilen paketin Kaynağını güvenlik açıkları için tarar (Placeholder).
-    // package_resource_id: Taranacak paketin Kaynak ID'si.
-    // Dönüş değeri: Bulunan güvenlik açığı ID'lerinin listesi (Vec<String>) veya SecurityError.
-    // Not: Gerçek bir güvenlik açığı tarama motoru ve Sahne64 API desteği gerektirir.
-    pub fn scan_for_vulnerabilities(&self, package_resource_id: &str) -> Result<Vec<String>, SecurityError> { // Path yerine &str Kaynak ID
-        println!("Güvenlik açığı taraması başlatılıyor (implemente edilmedi): {}", package_resource_id); // no_std print
-
-        // --- Sahne64 API Eksikliği / Gerçek Tarama İhtiyacı ---
-        // Bu işlevsellik, ya bir harici güvenlik açığı tarama görevini başlatmayı ve
-        // çıktısını/sonuçlarını işlemeyi, ya da doğrudan Sahne64 çekirdeğinde bir
-        // tarama mekanizması olmasını gerektirir.
-        // Şu anda bu mekanizmalar Sahne64 API'sında tanımlanmamıştır.
-
-        // Şimdilik sadece bir placeholder implementasyonu sunulmaktadır.
-        // Gerçek tarama motoru, package_resource_id'yi okuyarak veya Kaynak üzerinde çalışarak
-        // güvenlik açıklarını bulmalıdır.
-
-        // Örnek olarak, tarama sırasında bir hata oluştuğunu simüle edelim.
-        let scan_engine_result = Err(SecurityError::VulnerabilityScanError("Simüle edilmiş tarama motoru hatası".to_string()));
-
-        // Veya örnek güvenlik açığı bulguları döndürelim.
-        let vulnerabilities = vec!["CVE-2023-1234".to_owned(), "CVE-2023-5678".to_owned()]; // alloc gerektirir
-
-        // Tarama sonuçlarını veya hata durumunu döndürme
-        // Eğer güvenlik açığı bulunursa Vec<String> döner, bulunmazsa boş Vec.
-        // Tarama motoru hatası durumunda Err(SecurityError::VulnerabilityScanError) döner.
-        if vulnerabilities.is_empty() {
-             info!("Güvenlik açığı bulunamadı: {}", package_resource_id); // no_std log
-             Ok(Vec::new()) // Eğer güvenlik açığı bulunmazsa boş vektör döndür (alloc)
+    // Belirtilen paket sürümünü depo tarafından yayınlanan güvenlik bildirisi akışına göre tarar (bkz. srcadvisory).
+    // akis: DepoYoneticisi::guvenlik_bildirilerini_al ile alınmış, imzası doğrulanmış akış.
+    // asgari_onem: Bu önem derecesinin altındaki bildiriler raporlanmaz.
+    // Dönüş değeri: Eşleşen bildiriler (önem derecesine göre azalan sırada); açık bulunmazsa boş Vec.
+    pub fn scan_for_vulnerabilities(
+        &self,
+        akis: &BildiriAkisi,
+        package_name: &str,
+        version: &str,
+        asgari_onem: Onem,
+    ) -> Vec<Bulgu> {
+        debug!("Güvenlik açığı taraması: {}@{} ({} bildiri)", package_name, version, akis.bildiriler.len()); // no_std log
+
+        let bulgular = akis.denetle([(package_name, version)], asgari_onem);
+        if bulgular.is_empty() {
+            info!("Güvenlik açığı bulunamadı: {}@{}", package_name, version); // no_std log
         } else {
-             warn!("Paket için güvenlik açıkları bulundu ({} adet): {}", vulnerabilities.len(), package_resource_id); // no_std log
-             // Bulguları loglayabiliriz
-             for vul in &vulnerabilities {
-                 warn!("  - {}", vul); // no_std log
-             }
-            Ok(vulnerabilities) // Güvenlik açığı bulgularını vektör olarak döndür (alloc)
+            warn!("Paket için güvenlik açıkları bulundu ({} adet): {}@{}", bulgular.len(), package_name, version); // no_std log
+            for bulgu in &bulgular {
+                warn!("  - {} ({})", bulgu.kimlik, bulgu.onem); // no_std log
+            }
         }
+        bulgular
     }
 
-    // Belirtilen yürütülebilir Kaynağı sandbox ortamında çalıştırır (Placeholder).
+    // Belirtilen yürütülebilir Kaynağı sandbox ortamında çalıştırır, tamamlanmasını (en fazla zaman_asimi_us
+    // kadar) bekler ve stdout/stderr çıktısını yakalar.
+    // Linux ana makine arka ucunda ad alanları (kullanıcı, mount, PID, IPC, UTS ve ağ kapalıysa ağ), salt
+    // okunur sistem görünümü, rlimit sınırları ve seccomp filtresi ile uygulanır (bkz. srcsandbox.rs).
+    // profil.yalitim false ise (sandbox özelliği kapalı) betik bu kısıtlar olmadan çalıştırılır.
     // executable_resource_id: Sandbox ortamında çalıştırılacak yürütülebilir Kaynağın ID'si.
-    // Dönüş değeri: Başarı (sandbox görevini başlatma) veya SecurityError.
-    // Not: Sahne64 API'sında sandbox ortamının detayları ve task::spawn'ın bu ortamı destekleyip desteklemediği
-    // henüz tanımlanmamıştır. Task::wait ve çıktı yakalama da eksiktir.
-    pub fn run_in_sandbox(&self, executable_resource_id: &str) -> Result<(), SecurityError> { // Path yerine &str Kaynak ID
-        println!("Sandbox ortamında çalıştırma başlatılıyor (implemente edilmedi): {}", executable_resource_id); // no_std print
+    // args: "ANAHTAR=değer\0" dizisi; ana makinede ortam değişkenleri olarak geçilir (bkz. srcscript::BetikOrtami).
+    // profil: Uygulanacak kısıtlar.
+    // Dönüş değeri: Çalıştırma sonucu (sıfırdan farklı çıkış kodu da Ok ile döner) veya SecurityError.
+    pub fn run_in_sandbox(
+        &self,
+        executable_resource_id: &str,
+        args: &[u8],
+        profil: &SandboxProfili,
+        zaman_asimi_us: u64,
+    ) -> Result<BetikSonucu, SecurityError> {
+        debug!("Sandbox ortamında çalıştırma başlatılıyor: {} ({:?})", executable_resource_id, profil.seccomp); // no_std log
+
+        #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            srcsandbox::calistir(executable_resource_id, args, profil, zaman_asimi_us)
+        }
 
         // --- Sahne64 API Eksikliği / Sandbox Mekanizması İhtiyacı ---
-        // Sandbox ortamı, ya resource access control listeleriyle (ACL), ya
-        // da task::spawn fonksiyonuna özel parametreler geçirilerek Sahne64
-        // çekirdeği tarafından sağlanmalıdır.
-        // Şu anda bu mekanizmalar Sahne64 API'sında tanımlanmamıştır.
-        // Ayrıca, betik çalıştırma gibi, sandbox görevinin tamamlanmasını beklemek
-        // ve davranışını izlemek (örn. izin ihlalleri) API'da eksiktir.
-
-        // Şimdilik, sadece yürütülebilir Kaynağı normal bir görev olarak başlatmayı simüle edelim.
-        // task::spawn bir handle ve argümanlar alır. executable_resource_id'den handle almalıyız.
-        match resource::acquire(executable_resource_id, resource::MODE_READ) { // Yürütme izni MODE_READ olabilir
-            Ok(executable_handle) => {
-                // Varsayımsal olarak sandbox kısıtlamalarını task::spawn'a argüman olarak geçebiliriz.
-                // task::spawn(executable_handle, args: &[u8]) -> Result<TaskId, SahneError>
-                // Sandbox argümanları nasıl temsil edilir? Özel bir yapı? Byte dilimi içinde?
-                // Şu anki task::spawn sadece &[u8] alıyor.
-                // Gerçek API'da sandbox parametreleri için özel bir yol olmalıdır.
-
-                // Basitlik adına, sadece argümansız başlatalım ve sandbox kısıtlamalarının çekirdek tarafından
-                // Kaynak tipine veya handle'ın özelliklerine göre uygulandığını varsayalım (bu çok varsayımsal).
-                let task_args: &[u8] = b""; // Sandbox görevine argüman geçilebilir.
-
-                match task::spawn(executable_handle, task_args) {
-                    Ok(_new_tid) => {
-                        info!("Yürütülebilir kaynak sandbox (simüle edilmiş) ortamında başlatıldı: {}", executable_resource_id); // no_std log
-                        let _ = resource::release(executable_handle); // Handle'ı serbest bırak
-                        Ok(()) // Görev başlatıldı olarak başarı dön
-                    }
-                    Err(e) => {
-                        let _ = resource::release(executable_handle);
-                        let hata_mesaji = format!("Sandbox görevi başlatılamadı (Kaynak: {}): {:?}", executable_resource_id, e); // format! alloc
-                        error!("{}", hata_mesaji); // no_std log
-                        Err(SecurityError::SandboxError(hata_mesaji)) // SahneError -> SecurityError
-                    }
-                }
-            }
-            Err(e) => {
-                let hata_mesaji = format!("Sandbox çalıştırma Kaynağı acquire hatası ({}): {:?}", executable_resource_id, e); // format! alloc
-                error!("{}", hata_mesaji); // no_std log
-                Err(SecurityError::SandboxError(hata_mesaji)) // SahneError -> SecurityError
-            }
+        // Sahne64 çekirdeğinde sandbox ortamı, ya resource access control listeleriyle (ACL), ya da
+        // task::spawn fonksiyonuna özel parametreler geçirilerek sağlanmalıdır. Bu mekanizmalar henüz
+        // tanımlanmadığından, kısıtlanmamış çalıştırma yerine hata dönülür.
+        #[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+        {
+            let _ = (args, zaman_asimi_us);
+            let hata_mesaji = format!("Sahne64 çekirdeğinde sandbox API'si yok, çalıştırılamadı: {}", executable_resource_id); // format! alloc
+            warn!("{}", hata_mesaji); // no_std log
+            Err(SecurityError::OperationNotSupported(hata_mesaji))
         }
     }
 }
@@ -296,6 +389,37 @@ impl SecurityManager {
 mod tests {
     // std::path, std::io, std::fs, tempfile, sha2, hex kullandığı için no_std'de doğrudan çalışmaz.
     // Mock resource::acquire/read/release, task::spawn ve test dosyası oluşturma/okuma helper'ları gerektirir.
+    use super::*;
+
+    #[test]
+    fn test_guvenilmeyen_profil_daha_siki() {
+        let ayarlar = SandboxAyarlari::default();
+        let guvenilir = ayarlar.profil(GuvenSeviyesi::Guvenilir, "sahne://installed_packages");
+        let guvenilmeyen = ayarlar.profil(GuvenSeviyesi::Guvenilmeyen, "sahne://installed_packages");
+
+        assert!(!guvenilir.ag_erisimi && !guvenilmeyen.ag_erisimi);
+        assert_eq!(guvenilmeyen.seccomp, SeccompDuzeyi::Kati);
+        assert!(guvenilmeyen.bellek_siniri < guvenilir.bellek_siniri);
+        assert!(guvenilmeyen.surec_siniri < guvenilir.surec_siniri);
+        assert_eq!(guvenilmeyen.yazilabilir, vec!["sahne://installed_packages".to_string()]);
+    }
+
+    #[test]
+    fn test_kurulum_koku_bir_kez_eklenir() {
+        let mut ayarlar = SandboxAyarlari::default();
+        ayarlar.guvenilir.yazilabilir.push("sahne://installed_packages".to_string());
+        let profil = ayarlar.profil(GuvenSeviyesi::Guvenilir, "sahne://installed_packages");
+        assert_eq!(profil.yazilabilir.len(), 1);
+    }
+
+    #[test]
+    fn test_kapali_sandbox_yalitimsiz_profil_verir() {
+        let mut ayarlar = SandboxAyarlari::default();
+        ayarlar.etkin = false;
+        let profil = ayarlar.profil(GuvenSeviyesi::Guvenilmeyen, "sahne://installed_packages");
+        assert_eq!(profil, SandboxProfili::yalitimsiz());
+        assert!(!profil.yalitim);
+    }
 }
 
 // --- PaketYoneticisiHatasi enum tanımı ---
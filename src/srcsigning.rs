@@ -4,6 +4,8 @@ extern crate alloc; // String, Vec, format! için
 // no_std ve alloc uyumlu kripto ve hex crate'leri
 use sha2::{Sha256, Digest};
 use hex;
+// Depo indeksi ve güvenlik bildirisi akışı gibi ayrık imzalar için (no_std uyumlu)
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 // hex::EncodeError için From implementasyonu gerekebilir.
 
 // Sahne64 API modülleri
@@ -18,7 +20,7 @@ use crate::paket_yoneticisi_hatasi::PaketYoneticisiHatasi;
 use crate::srcsecurity::SecurityError; // İmza/Hash/Hex hatalarını SecurityError ile handle edelim
 
 // log kütüphanesini içe aktar (no_std uyumlu backend varsayımıyla)
-use log::{info, error, debug};
+use log::{info, warn, error, debug, trace};
 
 // String ve Vec from alloc
 use alloc::string::String;
@@ -64,28 +66,30 @@ fn read_resource_to_vec(resource_id: &str) -> Result<Vec<u8>, SecurityError> { /
 // Basit bir örnek için, imzaları dosya içeriklerinin SHA256 karmalarının hex kodlanmış hali olarak hesaplar.
 // package_resource_id: İmzalanacak paketin Kaynak ID'si.
 // Dönüş değeri: Hesaplanmış imza (hex string) veya SecurityError.
-// Not: Gerçek bir uygulamada, daha güvenli bir dijital imzalama yöntemi (asimetrik kriptografi) kullanılmalıdır.
+// Not: Bu yalnızca bir checksum'dır, anahtarsız olduğu için kaynağı kanıtlamaz. Depodan gelen verinin
+// kimin tarafından yayınlandığı veriyi_imzala / imzayi_dogrula ile denetlenir.
 pub fn sign_package(package_resource_id: &str) -> Result<String, SecurityError> { // Path yerine &str Kaynak ID, Result<String, SecurityError> olmalı
     debug!("Paket imzalanıyor: {}", package_resource_id); // no_std log
 
     // Paket Kaynağının içeriğini oku (Vec<u8> olarak)
     let package_data = read_resource_to_vec(package_resource_id)?; // Kendi helper'ımızı kullan (SecurityError döner)
 
-    // Dosya içeriğinin SHA256 karmasını hesapla.
-    let mut hasher = Sha256::new(); // Sha256::new() alloc gerektirir mi? Genellikle değil.
-    hasher.update(&package_data); // update &[] alır no_std
-    let digest = hasher.finalize(); // finalize GenericArray<u8, 32> no_std
-
-    trace!("Paket özeti (SHA256) hesaplandı."); // no_std log
-
-    // Karmayı hex string'e dönüştür.
-    // hex::encode(&digest) -> String. alloc gerektirir, no_std.
-    let signature = hex::encode(&digest); // hex::encode Result dönmüyor, hata fırlatabilir mi? Hayır, hep başarılı olmalı.
+    let signature = veri_ozeti(&package_data);
 
     debug!("Paket imzası (hex SHA256) hesaplandı: {}", signature); // no_std log
     Ok(signature) // İmza hex stringini döndür (alloc)
 }
 
+// Bellekteki verinin SHA256 özetini (hex) hesaplar. Zaten okunmuş veri için Kaynağı yeniden okumadan
+// checksum üretmeye yarar; imza değildir.
+pub fn veri_ozeti(veri: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(veri);
+    let digest = hasher.finalize(); // finalize GenericArray<u8, 32> no_std
+    trace!("Veri özeti (SHA256) hesaplandı."); // no_std log
+    hex::encode(&digest) // alloc
+}
+
 // Belirtilen paketin imzasını hesaplar ve beklenen imza (hex string) ile karşılaştırır.
 // package_resource_id: Doğrulanacak paketin Kaynak ID'si.
 // expected_signature: Beklenen imza (hex string).
@@ -111,11 +115,66 @@ pub fn verify_package(package_resource_id: &str, expected_signature: &str) -> Re
     Ok(is_valid) // Eşleşirse true, eşleşmezse false dön
 }
 
-// #[cfg(test)] bloğu std test runner'ı ve std bağımlılıkları gerektirir.
-// Testler için mock resource veya Sahne64 simülasyonu gereklidir.
+// Ed25519 gizli anahtarının (32 baytlık tohum) ve açık anahtarın bayt uzunluğu.
+pub const ANAHTAR_UZUNLUGU: usize = 32;
+
+// Veriyi yayıncının gizli anahtarıyla imzalar. Dönüş değeri: 64 baytlık Ed25519 imzasının hex hali;
+// depo bunu verinin yanına ayrık imza dosyası olarak koyar.
+pub fn veriyi_imzala(veri: &[u8], gizli_anahtar: &[u8; ANAHTAR_UZUNLUGU]) -> String {
+    let imza = SigningKey::from_bytes(gizli_anahtar).sign(veri);
+    hex::encode(imza.to_bytes()) // alloc
+}
+
+// Gizli anahtara karşılık gelen açık anahtar (güvenilen anahtarlar listesine eklenecek değer).
+pub fn acik_anahtar(gizli_anahtar: &[u8; ANAHTAR_UZUNLUGU]) -> [u8; ANAHTAR_UZUNLUGU] {
+    SigningKey::from_bytes(gizli_anahtar).verifying_key().to_bytes()
+}
+
+// Ayrık imzayı (hex) verilen güvenilen açık anahtarlardan herhangi biriyle doğrular.
+// Güvenilen anahtar yoksa hiçbir imza kabul edilmez.
+pub fn imzayi_dogrula(veri: &[u8], imza_hex: &str, guvenilen_anahtarlar: &[[u8; ANAHTAR_UZUNLUGU]]) -> Result<(), SecurityError> {
+    let imza_baytlari = hex::decode(imza_hex.trim()).map_err(SecurityError::HexDecodeError)?; // alloc
+    let imza_baytlari: [u8; 64] = imza_baytlari.as_slice().try_into()
+        .map_err(|_| SecurityError::InvalidSignatureFile(format!("imza 64 bayt olmalı, {} bayt", imza_baytlari.len())))?;
+    let imza = Signature::from_bytes(&imza_baytlari);
+
+    let dogrulandi = guvenilen_anahtarlar.iter().any(|anahtar| {
+        VerifyingKey::from_bytes(anahtar).map_or(false, |acik| acik.verify_strict(veri, &imza).is_ok())
+    });
+    if dogrulandi {
+        Ok(())
+    } else {
+        warn!("İmza güvenilen {} anahtarın hiçbiriyle doğrulanamadı.", guvenilen_anahtarlar.len()); // no_std log
+        Err(SecurityError::SignatureVerificationFailed)
+    }
+}
+
+// Yayıncının gizli anahtarını (32 baytlık tohumun hex hali, tek satır) Kaynaktan okur.
+pub fn gizli_anahtari_oku(resource_id: &str) -> Result<[u8; ANAHTAR_UZUNLUGU], SecurityError> {
+    let veri = read_resource_to_vec(resource_id)?;
+    let metin = core::str::from_utf8(&veri)
+        .map_err(|_| SecurityError::InvalidSignatureFile(format!("{}: anahtar UTF-8 değil", resource_id)))?;
+    let baytlar = hex::decode(metin.trim()).map_err(SecurityError::HexDecodeError)?; // alloc
+    baytlar.as_slice().try_into()
+        .map_err(|_| SecurityError::InvalidSignatureFile(format!("{}: anahtar {} bayt olmalı", resource_id, ANAHTAR_UZUNLUGU)))
+}
 
 #[cfg(test)]
 mod tests {
-    // std::path, std::io, std::fs, tempfile, sha2, hex kullandığı için no_std'de doğrudan çalışmaz.
-    // Mock resource::acquire/read/release ve test dosyası oluşturma/okuma helper'ları gerektirir.
+    // Kaynak okuyan fonksiyonlar mock resource gerektirir; imzalama ve doğrulama saf fonksiyonlardır.
+    use super::*;
+
+    #[test]
+    fn test_imza_dogrulama() {
+        let gizli = [7u8; ANAHTAR_UZUNLUGU];
+        let baska = [9u8; ANAHTAR_UZUNLUGU];
+        let imza = veriyi_imzala(b"paketler", &gizli);
+
+        assert!(imzayi_dogrula(b"paketler", &imza, &[acik_anahtar(&baska), acik_anahtar(&gizli)]).is_ok());
+        assert!(imzayi_dogrula(b"paketler!", &imza, &[acik_anahtar(&gizli)]).is_err());
+        assert!(imzayi_dogrula(b"paketler", &imza, &[acik_anahtar(&baska)]).is_err());
+        assert!(imzayi_dogrula(b"paketler", &imza, &[]).is_err());
+        // Anahtarsız özet imza yerine geçmez
+        assert!(imzayi_dogrula(b"paketler", &veri_ozeti(b"paketler"), &[acik_anahtar(&gizli)]).is_err());
+    }
 }
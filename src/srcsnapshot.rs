@@ -0,0 +1,299 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc ve postcard kullanacağız)
+extern crate alloc; // String, Vec, BTreeSet için
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use serde::{Deserialize, Serialize};
+use postcard; // no_std uyumlu serileştirme/deserileştirme
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::srcdatabase::{KuruluPaket, KuruluVeritabani};
+use crate::srchistory::TersIslem;
+use crate::srcstaging::{kaynagi_sil, kaynagi_tasi};
+
+// no_std uyumlu print makroları
+use crate::print_macros::eprintln;
+
+// Kurulu paket kümesinin adlandırılmış anlık görüntüleri. Bir görüntü veritabanının o anki kopyasıdır;
+// görüntüdeki her sürümün arşivi de görüntü dizinindeki arşiv havuzuna kopyalanır, böylece önbellek
+// temizlense bile görüntüye dönülebilir. Dönüş, şimdiki durumdan görüntüye giden tek bir işlemdir
+// (bkz. KurulumYoneticisi::durumu_geri_yukle) ve işlem geçmişine de yazılır.
+//
+// Dizin düzeni:
+//   <dizin>/goruntuler.bin        tüm görüntüler (postcard)
+//   <dizin>/arsivler/<arsiv adı>  görüntülerin paylaştığı arşiv havuzu
+
+// Anlık görüntü dizininin varsayılan Kaynak ID'si.
+pub const VARSAYILAN_ANLIK_GORUNTU_DIZINI: &str = "sahne://config/anlik_goruntuler";
+
+const GORUNTULER_DOSYASI: &str = "goruntuler.bin";
+const ARSIV_HAVUZU: &str = "arsivler";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnlikGoruntu {
+    pub ad: String,
+    pub olusturma_us: u64,
+    pub veritabani: KuruluVeritabani,
+}
+
+impl AnlikGoruntu {
+    // Görüntüdeki paketlerin arşiv adları (havuzda tutulanlar).
+    pub fn arsivler(&self) -> BTreeSet<&str> {
+        self.veritabani.paketler.values().filter_map(|k| k.arsiv.as_deref()).collect()
+    }
+}
+
+// Görüntü adı dosya adı olarak da güvenli olmalı: harf, rakam, '.', '-', '_'.
+pub fn ad_gecerli_mi(ad: &str) -> Result<(), String> {
+    if ad.is_empty() || ad.starts_with('.') {
+        return Err(format!("'{}' geçerli bir anlık görüntü adı değil", ad));
+    }
+    match ad.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))) {
+        Some(c) => Err(format!("anlık görüntü adında geçersiz karakter: '{}'", c)),
+        None => Ok(()),
+    }
+}
+
+// Şimdiki durumdan görüntüdeki duruma dönüş: görüntüde olmayan paketler kaldırılır (bağımlı olan önce),
+// görüntüdekinden farklı sürümde kurulu veya hiç kurulu olmayan paketler görüntüdeki kayıtlarıyla kurulur.
+pub fn donus_plani(hedef: &KuruluVeritabani, simdiki: &KuruluVeritabani) -> TersIslem {
+    let kaldirilacaklar: BTreeSet<String> = simdiki.paketler.keys().filter(|ad| !hedef.kurulu_mu(ad)).cloned().collect();
+    TersIslem {
+        kurulacaklar: hedef
+            .paketler
+            .values()
+            .filter(|k| simdiki.paket(&k.ad).map(|s| &s.surum) != Some(&k.surum))
+            .cloned()
+            .collect(),
+        kaldirilacaklar: simdiki.kaldirma_sirasi(&kaldirilacaklar),
+    }
+}
+
+// Silinen görüntünün, kalan görüntülerin hiçbirinde kullanılmayan arşivleri (havuzdan silinebilir).
+pub fn kullanilmayan_arsivler(silinen: &AnlikGoruntu, kalanlar: &[AnlikGoruntu]) -> Vec<String> {
+    let kullanilan: BTreeSet<&str> = kalanlar.iter().flat_map(|g| g.arsivler()).collect();
+    silinen.arsivler().into_iter().filter(|a| !kullanilan.contains(a)).map(|a| a.to_string()).collect()
+}
+
+// Tüm anlık görüntüler (eskiden yeniye).
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AnlikGoruntuler {
+    pub goruntuler: Vec<AnlikGoruntu>,
+}
+
+impl AnlikGoruntuler {
+    // Görüntüleri dizinden okur. Henüz görüntü alınmamışsa boş liste döner.
+    pub fn oku(dizin: &str) -> Result<Self, PaketYoneticisiHatasi> {
+        let resource_id = format!("{}/{}", dizin, GORUNTULER_DOSYASI);
+        let buffer = match kaynagi_oku(&resource_id) {
+            Ok(veri) => veri,
+            Err(SahneError::ResourceNotFound) => return Ok(AnlikGoruntuler::default()),
+            Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+        };
+        if buffer.is_empty() {
+            return Ok(AnlikGoruntuler::default());
+        }
+        postcard::from_bytes_copy::<AnlikGoruntuler>(&buffer).map_err(|e| {
+            eprintln!("Anlık görüntüler deserialize hatası ({}): {:?}", resource_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })
+    }
+
+    // Görüntüleri geçici bir Kaynağa yazıp taşıyarak yerine koyar (bkz. KuruluVeritabani::yaz).
+    pub fn yaz(&self, dizin: &str) -> Result<(), PaketYoneticisiHatasi> {
+        let veri = postcard::to_postcard(self).map_err(PaketYoneticisiHatasi::from)?;
+        atomik_yaz(&format!("{}/{}", dizin, GORUNTULER_DOSYASI), &veri).map_err(PaketYoneticisiHatasi::from)
+    }
+
+    pub fn bul(&self, ad: &str) -> Result<&AnlikGoruntu, PaketYoneticisiHatasi> {
+        self.goruntuler
+            .iter()
+            .find(|g| g.ad == ad)
+            .ok_or_else(|| PaketYoneticisiHatasi::GecersizParametre(format!("'{}' adlı anlık görüntü yok", ad)))
+    }
+
+    // Yeni görüntüyü ekler; aynı adlı görüntü varsa hata döner.
+    pub fn ekle(&mut self, goruntu: AnlikGoruntu) -> Result<(), PaketYoneticisiHatasi> {
+        ad_gecerli_mi(&goruntu.ad).map_err(PaketYoneticisiHatasi::GecersizParametre)?;
+        if self.goruntuler.iter().any(|g| g.ad == goruntu.ad) {
+            return Err(PaketYoneticisiHatasi::ConflictError(format!("'{}' adlı anlık görüntü zaten var", goruntu.ad)));
+        }
+        self.goruntuler.push(goruntu);
+        Ok(())
+    }
+
+    pub fn sil(&mut self, ad: &str) -> Result<AnlikGoruntu, PaketYoneticisiHatasi> {
+        let sira = self.goruntuler
+            .iter()
+            .position(|g| g.ad == ad)
+            .ok_or_else(|| PaketYoneticisiHatasi::GecersizParametre(format!("'{}' adlı anlık görüntü yok", ad)))?;
+        Ok(self.goruntuler.remove(sira))
+    }
+}
+
+// Görüntüdeki her arşivi, havuzda yoksa önbellekten havuza kopyalar.
+// Dönüş değeri: Görüntüye dönüşte kullanılamayacak paketler (arşivi ne önbellekte ne havuzda olan veya
+// arşiv adı kaydedilmemiş), "ad sürüm" biçiminde.
+pub fn arsivleri_sakla(dizin: &str, onbellek: &str, goruntu: &AnlikGoruntu) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+    let mut eksikler = Vec::new();
+    for kayit in goruntu.veritabani.paketler.values() {
+        let arsiv = match &kayit.arsiv {
+            Some(arsiv) => arsiv,
+            None => {
+                eksikler.push(format!("{} {}", kayit.ad, kayit.surum));
+                continue;
+            }
+        };
+        let havuz_id = format!("{}/{}/{}", dizin, ARSIV_HAVUZU, arsiv);
+        if kaynak_var_mi(&havuz_id).map_err(PaketYoneticisiHatasi::from)? {
+            continue;
+        }
+        match kaynagi_kopyala(&format!("{}/{}", onbellek, arsiv), &havuz_id) {
+            Ok(()) => {}
+            Err(SahneError::ResourceNotFound) => eksikler.push(format!("{} {}", kayit.ad, kayit.surum)),
+            Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+        }
+    }
+    Ok(eksikler)
+}
+
+// Kurulacak kayıtların önbellekte olmayan arşivlerini havuzdan önbelleğe geri kopyalar. Havuzda da
+// olmayanlar atlanır; eksik arşiv kurulumdan önce durumu_geri_yukle tarafından bildirilir.
+pub fn arsivleri_onbellege_koy(dizin: &str, onbellek: &str, kayitlar: &[KuruluPaket]) -> Result<(), PaketYoneticisiHatasi> {
+    for arsiv in kayitlar.iter().filter_map(|k| k.arsiv.as_deref()) {
+        let onbellek_id = format!("{}/{}", onbellek, arsiv);
+        if kaynak_var_mi(&onbellek_id).map_err(PaketYoneticisiHatasi::from)? {
+            continue;
+        }
+        match kaynagi_kopyala(&format!("{}/{}/{}", dizin, ARSIV_HAVUZU, arsiv), &onbellek_id) {
+            Ok(()) | Err(SahneError::ResourceNotFound) => {}
+            Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+        }
+    }
+    Ok(())
+}
+
+// Havuzdan arşivleri siler; silinemeyenler sadece loglanır.
+pub fn arsivleri_sil(dizin: &str, arsivler: &[String]) {
+    for arsiv in arsivler {
+        let havuz_id = format!("{}/{}/{}", dizin, ARSIV_HAVUZU, arsiv);
+        if let Err(e) = kaynagi_sil(&havuz_id, false) {
+            eprintln!("Havuzdaki arşiv silinemedi ({}): {:?}", havuz_id, e);
+        }
+    }
+}
+
+fn kaynak_var_mi(resource_id: &str) -> Result<bool, SahneError> {
+    match resource::acquire(resource_id, resource::MODE_READ) {
+        Ok(handle) => {
+            let _ = resource::release(handle);
+            Ok(true)
+        }
+        Err(SahneError::ResourceNotFound) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn kaynagi_oku(resource_id: &str) -> Result<Vec<u8>, SahneError> {
+    let handle = resource::acquire(resource_id, resource::MODE_READ)?;
+    let mut buffer = Vec::new();
+    let mut temp_buffer = [0u8; 4096];
+    loop {
+        match resource::read(handle, &mut temp_buffer) {
+            Ok(0) => break,
+            Ok(n) => buffer.extend_from_slice(&temp_buffer[..n]),
+            Err(e) => {
+                let _ = resource::release(handle);
+                return Err(e);
+            }
+        }
+    }
+    let _ = resource::release(handle);
+    Ok(buffer)
+}
+
+// Veriyi "<hedef>.yeni" Kaynağına yazıp hedefin yerine taşır; yarım yazılmış bir hedef hiç oluşmaz.
+fn atomik_yaz(resource_id: &str, veri: &[u8]) -> Result<(), SahneError> {
+    let gecici_id = format!("{}.yeni", resource_id);
+    let handle = resource::acquire(&gecici_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)?;
+    let mut written = 0;
+    while written < veri.len() {
+        match resource::write(handle, &veri[written..]) {
+            Ok(0) => {
+                let _ = resource::release(handle);
+                return Err(SahneError::InvalidOperation);
+            }
+            Ok(n) => written += n,
+            Err(e) => {
+                let _ = resource::release(handle);
+                return Err(e);
+            }
+        }
+    }
+    resource::release(handle)?;
+    kaynagi_tasi(&gecici_id, resource_id)
+}
+
+// Arşivler bellekte tutulabilecek boyutta varsayılır (önbellekten indirilirken de tamamı okunur).
+fn kaynagi_kopyala(kaynak_id: &str, hedef_id: &str) -> Result<(), SahneError> {
+    let veri = kaynagi_oku(kaynak_id)?;
+    atomik_yaz(hedef_id, &veri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Paket;
+
+    fn kurulu(paketler: &[(&str, &str, &[&str])]) -> KuruluVeritabani {
+        let mut vt = KuruluVeritabani::default();
+        for (ad, surum, bagimliliklar) in paketler {
+            let mut paket = Paket::yeni(ad.to_string(), surum.to_string(), bagimliliklar.iter().map(|b| b.to_string()).collect());
+            paket.dosya_adi = Some(format!("{}-{}.tar.gz", ad, surum));
+            vt.ekle(KuruluPaket::paketten(&paket));
+        }
+        vt
+    }
+
+    fn goruntu(ad: &str, veritabani: KuruluVeritabani) -> AnlikGoruntu {
+        AnlikGoruntu { ad: ad.to_string(), olusturma_us: 0, veritabani }
+    }
+
+    #[test]
+    fn test_donus_plani() {
+        let hedef = kurulu(&[("libc", "1.0", &[]), ("kabuk", "1.0", &["libc"]), ("eski", "1.0", &[])]);
+        let simdiki = kurulu(&[("libc", "2.0", &[]), ("kabuk", "1.0", &["libc"]), ("editor", "1.0", &["libyazi"]), ("libyazi", "1.0", &[])]);
+        let plan = donus_plani(&hedef, &simdiki);
+        assert_eq!(plan.kaldirilacaklar, ["editor", "libyazi"]);
+        let kurulacaklar: Vec<(&str, &str)> = plan.kurulacaklar.iter().map(|k| (k.ad.as_str(), k.surum.as_str())).collect();
+        assert_eq!(kurulacaklar, [("eski", "1.0"), ("libc", "1.0")]);
+        assert_eq!(donus_plani(&simdiki, &simdiki), TersIslem::default());
+    }
+
+    #[test]
+    fn test_ad_ve_tekrar() {
+        let mut goruntuler = AnlikGoruntuler::default();
+        goruntuler.ekle(goruntu("guncelleme-oncesi_1.0", KuruluVeritabani::default())).unwrap();
+        assert!(goruntuler.ekle(goruntu("guncelleme-oncesi_1.0", KuruluVeritabani::default())).is_err());
+        assert!(ad_gecerli_mi("../kok").is_err());
+        assert!(ad_gecerli_mi("").is_err());
+        assert!(goruntuler.sil("yok").is_err());
+        assert_eq!(goruntuler.sil("guncelleme-oncesi_1.0").unwrap().ad, "guncelleme-oncesi_1.0");
+    }
+
+    #[test]
+    fn test_kullanilmayan_arsivler() {
+        let silinen = goruntu("a", kurulu(&[("libc", "1.0", &[]), ("kabuk", "1.0", &[])]));
+        let kalan = goruntu("b", kurulu(&[("libc", "1.0", &[]), ("kabuk", "2.0", &[])]));
+        assert_eq!(kullanilmayan_arsivler(&silinen, &[kalan]), ["kabuk-1.0.tar.gz"]);
+    }
+}
@@ -0,0 +1,359 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, format! için
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use alloc::borrow::ToOwned; // &str -> String için
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::task; // Benzersiz hazırlık adı için zaman damgası
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::package::Paket;
+use crate::srcarchive::CikarmaRaporu;
+// Yarıda kalan işlemlerin yedeklerini ayırt etmek için
+use crate::srctransaction;
+use alloc::collections::BTreeSet;
+
+// no_std uyumlu print makroları
+use crate::print_macros::{println, eprintln};
+
+// Hazırlık alanlarının tutulduğu, kurulum dizini altındaki alt dizin.
+// Kurulum dizini ile aynı depolama üzerinde olması, etkinleştirme taşımalarının atomik olması için gereklidir.
+pub const HAZIRLIK_DIZINI: &str = ".hazirlik";
+
+// Kaynak silme, taşıma ve listeleme için Sahne64 Kaynak kontrol komutları (Varsayımsal).
+// Gerçek Sahne64 API'sında tanımlanmalıdır.
+const RESOURCE_CONTROL_CMD_LIST: u64 = 3; // Dönüş: satır başına bir alt Kaynak adı (bkz. srcrepositorypublish.rs)
+const RESOURCE_CONTROL_CMD_DELETE: u64 = 6; // args: [1] ise alt Kaynaklar da silinir
+const RESOURCE_CONTROL_CMD_RENAME: u64 = 7; // args: UTF-8 yeni Kaynak ID'si
+
+// Paket meta verisindeki dosya listesi ile çıkarılan dosyalar arasındaki fark.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DosyaListesiFarki {
+    pub eksik: Vec<String>, // Meta veride olup arşivden çıkmayan dosyalar
+    pub fazla: Vec<String>, // Arşivden çıkıp meta veride olmayan dosyalar
+}
+
+impl DosyaListesiFarki {
+    pub fn bos_mu(&self) -> bool {
+        self.eksik.is_empty() && self.fazla.is_empty()
+    }
+}
+
+// Çıkarılan girdileri paketin beklenen dosya listesiyle karşılaştırır.
+// Dizin girdileri ('/' ile biten) karşılaştırmaya katılmaz; beklenen listedeki baştaki '/' yok sayılır.
+pub fn dosya_listesini_karsilastir(cikarilan: &[String], beklenen: &[String]) -> DosyaListesiFarki {
+    let normalize = |yol: &String| yol.trim_start_matches('/').to_owned(); // alloc
+
+    let mut cikan: Vec<String> = cikarilan.iter().filter(|y| !y.ends_with('/')).map(normalize).collect();
+    let mut istenen: Vec<String> = beklenen.iter().filter(|y| !y.ends_with('/')).map(normalize).collect();
+    cikan.sort();
+    cikan.dedup();
+    istenen.sort();
+    istenen.dedup();
+
+    DosyaListesiFarki {
+        eksik: istenen.iter().filter(|y| cikan.binary_search(y).is_err()).cloned().collect(),
+        fazla: cikan.iter().filter(|y| istenen.binary_search(y).is_err()).cloned().collect(),
+    }
+}
+
+// Tek bir paket kurulumu için hazırlık alanı.
+// Arşiv önce buraya çıkarılır, doğrulanır ve sonra taşıma ile etkinleştirilir; böylece kurulum dizininde
+// paket ya tamamen görünür ya da hiç görünmez. Etkinleştirilmeden bırakılan alanlar bir sonraki
+// çalıştırmada artik_hazirliklari_temizle ile silinir.
+#[derive(Debug)]
+pub struct HazirlikAlani {
+    kurulum_base_id: String,
+    paket_adi: String,
+    hazirlik_id: String, // Her zaman '/' ile biter
+}
+
+impl HazirlikAlani {
+    // Yeni bir hazırlık alanı oluşturur (örn. "sahne://installed_packages/.hazirlik/paket-1700000000/").
+    // Zaman damgası, aynı paketin yarım kalmış eski bir hazırlığıyla çakışmayı önler.
+    pub fn yeni(kurulum_base_id: &str, paket_adi: &str) -> Result<Self, PaketYoneticisiHatasi> {
+        let zaman = task::current_time_us().map_err(PaketYoneticisiHatasi::from)?;
+        let hazirlik_id = format!("{}/{}/{}-{}/", kurulum_base_id, HAZIRLIK_DIZINI, paket_adi, zaman); // alloc
+
+        // Hazırlık dizinini oluştur (acquire(MODE_CREATE) ile, srcarchive'deki varsayımla aynı)
+        let handle = resource::acquire(&hazirlik_id, resource::MODE_CREATE).map_err(|e| {
+            eprintln!("Hazırlık alanı oluşturulamadı ({}): {:?}", hazirlik_id, e);
+            PaketYoneticisiHatasi::from(e)
+        })?;
+        let _ = resource::release(handle);
+
+        Ok(HazirlikAlani {
+            kurulum_base_id: kurulum_base_id.to_owned(),
+            paket_adi: paket_adi.to_owned(),
+            hazirlik_id,
+        })
+    }
+
+    // Arşivin çıkarılacağı Kaynak ID'si.
+    pub fn yol(&self) -> &str {
+        &self.hazirlik_id
+    }
+
+    // Etkinleştirme sonrası paketin bulunacağı Kaynak ID'si.
+    pub fn hedef_yolu(&self) -> String {
+        format!("{}/{}/", self.kurulum_base_id, self.paket_adi)
+    }
+
+    // Çıkarma raporunu paket meta verisindeki dosya listesine karşı doğrular.
+    // Meta veride dosya listesi yoksa (eski depo indeksleri) karşılaştırma atlanır ve uyarı verilir; dosya
+    // çakışmaları yine de çıkarılan yollarla denetlenir (bkz. KurulumYoneticisi::paketi_cikar_ve_etkinlestir).
+    pub fn dogrula(&self, rapor: &CikarmaRaporu, paket: &Paket) -> Result<(), PaketYoneticisiHatasi> {
+        if paket.dosyalar.is_empty() {
+            eprintln!("Uyarı: '{}' paketinin meta verisinde dosya listesi yok, içerik listeyle karşılaştırılmadı.", paket.ad);
+            return Ok(());
+        }
+
+        let fark = dosya_listesini_karsilastir(&rapor.cikarilan, &paket.dosyalar);
+        if fark.bos_mu() {
+            return Ok(());
+        }
+        for yol in &fark.eksik {
+            eprintln!("Eksik dosya ({}): {}", paket.ad, yol);
+        }
+        for yol in &fark.fazla {
+            eprintln!("Beklenmeyen dosya ({}): {}", paket.ad, yol);
+        }
+        Err(PaketYoneticisiHatasi::InstallationError(format!(
+            "'{}' paketinin içeriği meta veriyle uyuşmuyor ({} eksik, {} fazla)", paket.ad, fark.eksik.len(), fark.fazla.len()
+        )))
+    }
+
+    // Hazırlık alanını kurulum hedefine taşıyarak etkinleştirir.
+    // Hedef zaten varsa (güncelleme), önce eski sürüm hazırlık dizinine kenara alınır; yeni sürüm taşınamazsa
+    // eski sürüm geri yerine konur. Kenara alınan eski sürüm silinmez; işlem geri alınırsa
+    // yedegi_geri_yukle ile yerine konabilmesi için Kaynak ID'si döner (yeni kurulumda None).
+    // Yedek, işlem tamamlandığında çağıran tarafından kaynagi_sil ile silinmelidir.
+    // Güncellemede yapılandırma dosyalarındaki kullanıcı değişiklikleri etkinleştirmeden önce hazırlık alanına
+    // aktarılmalıdır; bu yüzden paketler KurulumYoneticisi üzerinden (bkz. paketi_cikar_ve_etkinlestir)
+    // etkinleştirilir.
+    pub fn etkinlestir_yedekli(self) -> Result<Option<String>, PaketYoneticisiHatasi> {
+        let hedef_id = self.hedef_yolu();
+        let eski_id = format!("{}.eski/", self.hazirlik_id.trim_end_matches('/')); // alloc
+
+        let hedef_var = match resource::acquire(&hedef_id, resource::MODE_READ) {
+            Ok(handle) => {
+                let _ = resource::release(handle);
+                true
+            }
+            Err(SahneError::ResourceNotFound) => false,
+            Err(e) => {
+                self.iptal();
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        };
+
+        if hedef_var {
+            if let Err(e) = kaynagi_tasi(&hedef_id, &eski_id) {
+                eprintln!("Mevcut kurulum kenara alınamadı ({} -> {}): {:?}", hedef_id, eski_id, e);
+                self.iptal();
+                return Err(PaketYoneticisiHatasi::from(e));
+            }
+        }
+
+        if let Err(e) = kaynagi_tasi(&self.hazirlik_id, &hedef_id) {
+            eprintln!("Hazırlık alanı etkinleştirilemedi ({} -> {}): {:?}", self.hazirlik_id, hedef_id, e);
+            if hedef_var {
+                if let Err(geri_e) = kaynagi_tasi(&eski_id, &hedef_id) {
+                    eprintln!("Eski kurulum geri yüklenemedi ({} -> {}): {:?}", eski_id, hedef_id, geri_e);
+                    kurtarmaya_ayir(&eski_id);
+                }
+            }
+            self.iptal();
+            return Err(PaketYoneticisiHatasi::from(e));
+        }
+
+        println!("Paket etkinleştirildi: {}", hedef_id);
+        Ok(if hedef_var { Some(eski_id) } else { None })
+    }
+
+    // Hazırlık alanını siler. Hata sadece loglanır; kalan artıklar bir sonraki çalıştırmada temizlenir.
+    pub fn iptal(self) {
+        if let Err(e) = kaynagi_sil(&self.hazirlik_id, true) {
+            eprintln!("Hazırlık alanı silinemedi ({}): {:?}", self.hazirlik_id, e);
+        }
+    }
+}
+
+// Geri alınan bir işlemde, etkinleştirilmiş paketi kaldırıp etkinlestir_yedekli ile kenara alınmış
+// önceki sürümü (varsa) yerine koyar.
+// hedef_id: Paketin kurulum dizini (HazirlikAlani::hedef_yolu).
+// yedek_id: etkinlestir_yedekli'nin döndürdüğü yedek; None ise paket yeni kurulmuştur ve sadece silinir.
+pub fn yedegi_geri_yukle(hedef_id: &str, yedek_id: Option<&str>) -> Result<(), SahneError> {
+    kaynagi_sil(hedef_id, true)?;
+    match yedek_id {
+        Some(yedek) => kaynagi_tasi(yedek, hedef_id),
+        None => Ok(()),
+    }
+}
+
+// Geri yüklenemeyen bir yedeği (".eski") ".kurtarma" adıyla yeniden adlandırır; artik_hazirliklari_temizle
+// bu alanları silmez, elle kontrol edilmeleri için raporlar. Yeniden adlandırma da başarısız olursa
+// yedek ".eski" olarak kalır; işlemi tamamlanmadığı için bir sonraki temizlikte yeniden ayrılır.
+pub fn kurtarmaya_ayir(yedek_id: &str) {
+    let kurtarma_id = kurtarma_adi(yedek_id);
+    match kaynagi_tasi(yedek_id, &kurtarma_id) {
+        Ok(()) => eprintln!("Uyarı: Önceki kurulum elle kurtarılmak üzere ayrıldı: {}", kurtarma_id),
+        Err(e) => eprintln!("Uyarı: Yedek kurtarmaya ayrılamadı, hemen elle kontrol edin ({}): {:?}", yedek_id, e),
+    }
+}
+
+// "<hazırlık>/paket-123.eski/" -> "<hazırlık>/paket-123.kurtarma/"
+fn kurtarma_adi(yedek_id: &str) -> String {
+    let ad = yedek_id.trim_end_matches('/');
+    format!("{}.kurtarma/", ad.strip_suffix(".eski").unwrap_or(ad))
+}
+
+// Temizlikte bir hazırlık dizini girdisine ne yapılacağı.
+#[derive(Debug, PartialEq, Eq)]
+enum ArtikIslemi {
+    Sil,
+    KurtarmayaAyir,
+    Birak,
+}
+
+// ad: Hazırlık dizinindeki girdinin adı, id: Kaynak ID'si ('/' ile biten).
+// Bir ".eski" yedeği ancak kaydedildiği işlem tamamlandıysa silinir. Aksi halde işlem yarıda kesilmiştir ve
+// kurulum dizininde yarım etkinleştirilmiş yeni bir sürüm bulunabilir; yedek tek sağlam kopyadır.
+fn artik_islemi(ad: &str, id: &str, tamamlanan_yedekler: &BTreeSet<String>) -> ArtikIslemi {
+    if ad.ends_with(".kurtarma") {
+        ArtikIslemi::Birak
+    } else if ad.ends_with(".eski") && !tamamlanan_yedekler.contains(id) {
+        ArtikIslemi::KurtarmayaAyir
+    } else {
+        ArtikIslemi::Sil
+    }
+}
+
+// Yarıda kalmış kurulumlardan arta kalan hazırlık alanlarını ve tamamlanan işlemlerden silinemeden kalan
+// yedekleri (".eski") siler. Tamamlandığı işlem günlüğünde görülmeyen yedekler silinmez, ".kurtarma"
+// olarak ayrılır (bkz. srctransaction::tamamlanan_yedekler).
+// ".kurtarma" ile biten alanlar, yerine konamayan önceki sürümlerdir (bkz. kurtarmaya_ayir); veri kaybını
+// önlemek için silinmez, sadece raporlanır.
+// Aynı kurulum dizininde eş zamanlı başka bir kurulum yokken (kilit alınmışken) çağrılmalıdır.
+// Dönüş değeri: Silinen hazırlık alanı sayısı.
+pub fn artik_hazirliklari_temizle(kurulum_base_id: &str, islem_gunlugu_id: &str) -> Result<usize, PaketYoneticisiHatasi> {
+    let hazirlik_kok = format!("{}/{}/", kurulum_base_id, HAZIRLIK_DIZINI);
+
+    let handle = match resource::acquire(&hazirlik_kok, resource::MODE_READ) {
+        Ok(h) => h,
+        Err(SahneError::ResourceNotFound) => return Ok(0), // Hiç hazırlık yapılmamış
+        Err(e) => return Err(PaketYoneticisiHatasi::from(e)),
+    };
+    let liste_sonucu = resource::control(handle, RESOURCE_CONTROL_CMD_LIST, &[]);
+    let _ = resource::release(handle);
+    let ham_liste = liste_sonucu.map_err(PaketYoneticisiHatasi::from)?;
+    let metin = core::str::from_utf8(&ham_liste)
+        .map_err(|_| PaketYoneticisiHatasi::ParsingError(String::from("Dizin listesi UTF-8 değil")))?;
+
+    let tamamlanan_yedekler = srctransaction::tamamlanan_yedekleri_oku(islem_gunlugu_id)?;
+
+    let mut silinen = 0;
+    for ad in metin.lines().map(|satir| satir.trim().trim_end_matches('/')).filter(|ad| !ad.is_empty()) {
+        let id = format!("{}{}/", hazirlik_kok, ad);
+        match artik_islemi(ad, &id, &tamamlanan_yedekler) {
+            ArtikIslemi::Sil => {}
+            ArtikIslemi::KurtarmayaAyir => {
+                eprintln!("Uyarı: Tamamlanmamış bir işlemden kalan yedek bulundu: {}", id);
+                kurtarmaya_ayir(&id);
+                continue;
+            }
+            ArtikIslemi::Birak => {
+                eprintln!("Uyarı: Geri yüklenemeyen eski kurulum bulundu, elle kontrol edin: {}", id);
+                continue;
+            }
+        }
+        match kaynagi_sil(&id, true) {
+            Ok(()) => {
+                println!("Yarım kalmış hazırlık alanı temizlendi: {}", id);
+                silinen += 1;
+            }
+            Err(e) => eprintln!("Hazırlık alanı silinemedi ({}): {:?}", id, e),
+        }
+    }
+    Ok(silinen)
+}
+
+// Bir Kaynağı (ve istenirse alt Kaynaklarını) siler (Varsayımsal control komutu, bkz. srccache.rs).
+// Kaynak zaten yoksa başarılı sayılır.
+pub(crate) fn kaynagi_sil(resource_id: &str, ozyinelemeli: bool) -> Result<(), SahneError> {
+    let handle = match resource::acquire(resource_id, resource::MODE_WRITE) {
+        Ok(h) => h,
+        Err(SahneError::ResourceNotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let sonuc = resource::control(handle, RESOURCE_CONTROL_CMD_DELETE, &[ozyinelemeli as u8]);
+    let _ = resource::release(handle); // Kaynak silinmiş olabilir, release hatası önemsiz
+    sonuc.map(|_| ())
+}
+
+// Bir Kaynağı yeni ID'sine taşır (Varsayımsal control komutu). Çekirdeğin taşımayı aynı depolama
+// üzerinde atomik yaptığı varsayılır.
+pub(crate) fn kaynagi_tasi(eski_id: &str, yeni_id: &str) -> Result<(), SahneError> {
+    let handle = resource::acquire(eski_id, resource::MODE_WRITE)?;
+    let sonuc = resource::control(handle, RESOURCE_CONTROL_CMD_RENAME, yeni_id.as_bytes());
+    let _ = resource::release(handle);
+    sonuc.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn liste(yollar: &[&str]) -> Vec<String> {
+        yollar.iter().map(|y| y.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dosya_listesi_eslesiyor() {
+        let cikarilan = liste(&["bin/", "bin/arac", "share/doc/README"]);
+        let beklenen = liste(&["/bin/arac", "share/doc/README"]);
+        assert!(dosya_listesini_karsilastir(&cikarilan, &beklenen).bos_mu());
+    }
+
+    #[test]
+    fn test_dosya_listesi_fark() {
+        let cikarilan = liste(&["bin/arac", "bin/gizli"]);
+        let beklenen = liste(&["bin/arac", "lib/libarac.so"]);
+        let fark = dosya_listesini_karsilastir(&cikarilan, &beklenen);
+        assert_eq!(fark.eksik, liste(&["lib/libarac.so"]));
+        assert_eq!(fark.fazla, liste(&["bin/gizli"]));
+    }
+
+    #[test]
+    fn test_artik_islemi() {
+        let kok = "sahne://k/.hazirlik/";
+        let tamamlanan: BTreeSet<String> = [format!("{}a-1.eski/", kok)].into_iter().collect();
+        assert_eq!(artik_islemi("a-1.eski", &format!("{}a-1.eski/", kok), &tamamlanan), ArtikIslemi::Sil);
+        assert_eq!(artik_islemi("b-2", &format!("{}b-2/", kok), &tamamlanan), ArtikIslemi::Sil);
+        assert_eq!(artik_islemi("c-3.kurtarma", &format!("{}c-3.kurtarma/", kok), &tamamlanan), ArtikIslemi::Birak);
+    }
+
+    #[test]
+    fn test_hedef_var_islem_tamamlanmamis_yedek_silinmez() {
+        // "<kök>/d/" altında yarım etkinleştirilmiş yeni sürüm varken çöken bir işlemin yedeği: günlükte kayıtlı
+        // ama işlem tamamlanmamış. Yedek tek sağlam kopyadır ve silinmemelidir.
+        let gunluk = "ISLEM BASLADI 0000000000000004\nYEDEK sahne://k/.hazirlik/d-4.eski/\nKURULDU d 2.0\n";
+        let tamamlanan = srctransaction::tamamlanan_yedekler(gunluk);
+        assert_eq!(artik_islemi("d-4.eski", "sahne://k/.hazirlik/d-4.eski/", &tamamlanan), ArtikIslemi::KurtarmayaAyir);
+        // Günlükte hiç kaydı olmayan yedek de (örn. kayıttan önce kesilen işlem) silinmez.
+        assert_eq!(artik_islemi("e-5.eski", "sahne://k/.hazirlik/e-5.eski/", &BTreeSet::new()), ArtikIslemi::KurtarmayaAyir);
+    }
+
+    #[test]
+    fn test_kurtarma_adi() {
+        assert_eq!(kurtarma_adi("sahne://k/.hazirlik/paket-12.eski/"), "sahne://k/.hazirlik/paket-12.kurtarma/");
+        assert_eq!(kurtarma_adi("sahne://k/.hazirlik/paket-12.eski"), "sahne://k/.hazirlik/paket-12.kurtarma/");
+    }
+}
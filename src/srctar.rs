@@ -0,0 +1,381 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // Bellek ayırma için alloc crate'i
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+// tar crate'i ve sıkıştırma crate'leri Read trait'i üzerine kurulu akış (stream) çözücüleri sağlar.
+// srcarchive.rs'deki zip kullanımında olduğu gibi, SahneResourceReader'ın bu crate'lerin beklediği
+// okuma arayüzünü sağladığını VARSAYIYORUZ.
+use tar::{Archive, EntryType};
+// Çözücülerin ve tar::Archive'ın beklediği Read trait'i (srcpipeline ile aynı no_std io katmanı)
+use crate::io::Read;
+
+// Sahne64 API modüllerini içe aktarın
+use crate::resource;
+use crate::SahneError; // Sahne64'ün hata türü
+
+use crate::srcarchive::{
+    self, BekleyenBag, CikarmaRaporu, CikarmaSinirlari, PaketYoneticisiHatasi, RedNedeni, ReddedilenGirdi, SahneResourceReader,
+};
+use crate::srcfeatures::CompressionAlgorithm;
+use crate::srcverify::ManifestGirdisi;
+
+use crate::print_macros::eprintln;
+
+// Okuma trait'i (zip_ac'taki take/read_to_end kullanımı ile aynı varsayım).
+// Ömür parametresi, çözücünün ödünç alınmış bir okuyucu (örn. srcpipeline'daki akış) üzerinde de çalışabilmesi içindir.
+pub(crate) type Okuyucu<'a> = Box<dyn Read + 'a>;
+
+// Sıkıştırılmış akış için uygun çözücüyü (decoder) oluşturur.
+// sikistirma None ise arşiv sıkıştırılmamış (düz) tar'dır.
+pub(crate) fn cozucu_olustur<'a, R: Read + 'a>(reader: R, sikistirma: Option<CompressionAlgorithm>) -> Result<Okuyucu<'a>, PaketYoneticisiHatasi> {
+    match sikistirma {
+        None => Ok(Box::new(reader)),
+        Some(CompressionAlgorithm::Gzip) => Ok(Box::new(flate2::read::GzDecoder::new(reader))),
+        Some(CompressionAlgorithm::Zstd) => zstd::stream::read::Decoder::new(reader)
+            .map(|d| Box::new(d) as Okuyucu<'a>)
+            .map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("zstd çözücü başlatılamadı: {:?}", e))),
+        Some(CompressionAlgorithm::Xz) => Ok(Box::new(xz2::read::XzDecoder::new(reader))),
+        Some(CompressionAlgorithm::Bzip2) => Ok(Box::new(bzip2::read::BzDecoder::new(reader))),
+        Some(diger) => Err(PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(format!(
+            "tar.{:?} çözücüsü mevcut değil", diger
+        ))),
+    }
+}
+
+// Verilen (sıkıştırılmış) tar arşivini belirtilen sınırlarla açar.
+// zip_ac_sinirli ile aynı güvenlik kurallarını uygular: güvensiz yollar, kök dışına işaret eden ve yolu veya
+// hedefi başka bir bağın üzerinden geçen sembolik bağlar atlanıp raporlanır, sembolik bağlar diğer girdilerden
+// sonra oluşturulur; sınırlar aşılırsa ArsivSiniriAsildi, tanınmayan bir girdi türünde DesteklenmeyenArsivBicimi hatası döner.
+// tar bir akış formatı olduğu için girdi sayısı sınırı önceden değil, okundukça kontrol edilir.
+// arsiv_resource_id: Açılacak arşivin Sahne64 Kaynak ID'si.
+// sikistirma: Arşivin sıkıştırma algoritması (düz tar için None).
+// cikartma_base_resource_id: İçeriğin çıkarılacağı ana dizin gibi davranan Sahne64 Kaynak ID'si.
+pub fn tar_ac_sinirli(
+    arsiv_resource_id: &str,
+    sikistirma: Option<CompressionAlgorithm>,
+    cikartma_base_resource_id: &str,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
+    // 1. Arşiv Kaynağını Aç
+    let arsiv_handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    let sonuc = match cozucu_olustur(SahneResourceReader::new(arsiv_handle), sikistirma) {
+        Ok(okuyucu) => tar_girdilerini_cikar(okuyucu, cikartma_base_resource_id, sinirlar),
+        Err(e) => Err(e),
+    };
+
+    // 2. Arşiv Handle'ını Serbest Bırak (hata olsa bile)
+    if let Err(e) = resource::release(arsiv_handle) {
+        eprintln!("Arşiv Kaynağı release hatası ({}): {:?}", arsiv_resource_id, e);
+        if sonuc.is_ok() {
+            return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+        }
+    }
+
+    sonuc
+}
+
+// Tek bir tar girdisi için verilen karar (bkz. girdi_plani).
+#[derive(Debug, PartialEq)]
+enum GirdiPlani {
+    Atla,              // PAX/GNU uzun ad başlıkları; çıkarılacak girdi değildir
+    Reddet(RedNedeni), // Güvensiz yol veya paketlerde kabul edilmeyen tür; raporlanıp atlanır
+    Dizin { yol: String, resource_id: String },
+    Dosya { yol: String, resource_id: String },
+    Bag { yol: String }, // Sembolik bağ; tüm bağlar bilindiğinde denetlenir
+}
+
+// Girdinin adına ve türüne bakarak ne yapılacağını belirler; Kaynak'lara dokunmaz.
+// Tanınmayan bir tür bayrağı (typeflag) arşivin bu sürümce okunamayan bir biçimde olduğunu gösterir;
+// girdi sessizce atlanırsa paket eksik kurulacağı için DesteklenmeyenArsivBicimi hatası döner.
+// cikartma_base_path: '/' ile biten çıkarma kökü.
+fn girdi_plani(ad: &str, tur: EntryType, cikartma_base_path: &str) -> Result<GirdiPlani, PaketYoneticisiHatasi> {
+    match tur {
+        // PAX/GNU uzun ad başlıkları tar crate'i tarafından işlenir.
+        EntryType::XGlobalHeader | EntryType::XHeader | EntryType::GNULongName | EntryType::GNULongLink => {
+            return Ok(GirdiPlani::Atla)
+        }
+        EntryType::Directory | EntryType::Symlink | EntryType::Regular | EntryType::Continuous => {}
+        // Sabit bağlar (hard link), aygıt dosyaları, FIFO'lar ve seyrek dosyalar paketlerden kabul edilmez.
+        EntryType::Link | EntryType::Char | EntryType::Block | EntryType::Fifo | EntryType::GNUSparse => {}
+        _ => {
+            return Err(PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(format!(
+                "{}: tanınmayan tar girdi türü 0x{:02x}", ad, tur.as_byte()
+            )))
+        }
+    }
+
+    let yol = match srcarchive::girdi_yolunu_temizle(ad) {
+        Ok(yol) => yol,
+        Err(neden) => return Ok(GirdiPlani::Reddet(neden)),
+    };
+    let resource_id = format!("{}{}", cikartma_base_path, yol);
+    // Güvenlik kontrolü (savunma derinliği, zip_ac_sinirli ile aynı): temizlenmiş yol her zaman temel yolun altında olmalı.
+    if !resource_id.starts_with(cikartma_base_path) {
+        return Ok(GirdiPlani::Reddet(RedNedeni::UstDizinBileseni));
+    }
+
+    Ok(match tur {
+        EntryType::Directory => GirdiPlani::Dizin { yol, resource_id },
+        EntryType::Symlink => GirdiPlani::Bag { yol },
+        EntryType::Regular | EntryType::Continuous => GirdiPlani::Dosya { yol, resource_id },
+        _ => GirdiPlani::Reddet(RedNedeni::DesteklenmeyenTur),
+    })
+}
+
+// tar_ac_sinirli'nin asıl çıkarma döngüsü. Handle yönetimi çağırana aittir.
+// Akış (stream) üzerinde çalıştığı için srcpipeline tarafından indirme sırasında da kullanılır.
+pub(crate) fn tar_girdilerini_cikar(
+    okuyucu: Okuyucu<'_>,
+    cikartma_base_resource_id: &str,
+    sinirlar: &CikarmaSinirlari,
+) -> Result<CikarmaRaporu, PaketYoneticisiHatasi> {
+    // Temel yol her zaman '/' ile biter (zip_ac ile aynı).
+    let cikartma_base_path = if cikartma_base_resource_id.ends_with('/') {
+        String::from(cikartma_base_resource_id)
+    } else {
+        format!("{}/", cikartma_base_resource_id)
+    };
+
+    let mut arsiv = Archive::new(okuyucu);
+    let girdiler = arsiv.entries().map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))?;
+
+    let mut rapor = CikarmaRaporu::default();
+    let mut bekleyen_baglar: Vec<BekleyenBag> = Vec::new(); // alloc
+    let mut girdi_sayisi: usize = 0;
+
+    for girdi in girdiler {
+        let mut girdi = girdi.map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))?;
+
+        girdi_sayisi += 1;
+        if girdi_sayisi > sinirlar.azami_girdi_sayisi {
+            return Err(PaketYoneticisiHatasi::ArsivSiniriAsildi(format!(
+                "girdi sayısı sınırı ({}) aşıldı", sinirlar.azami_girdi_sayisi
+            )));
+        }
+
+        // tar girdi adları ham bayttır; UTF-8 olmayan adlar reddedilir.
+        let dosya_adi = match core::str::from_utf8(&girdi.path_bytes()) {
+            Ok(ad) => ad.to_string(), // alloc
+            Err(_) => {
+                rapor.reddedilen.push(ReddedilenGirdi {
+                    ad: String::from_utf8_lossy(&girdi.path_bytes()).into_owned(),
+                    neden: RedNedeni::GecersizKarakter,
+                });
+                continue;
+            }
+        };
+
+        let plan = girdi_plani(&dosya_adi, girdi.header().entry_type(), &cikartma_base_path)?;
+        let mod_bitleri = girdi.header().mode().ok();
+
+        match plan {
+            GirdiPlani::Atla => {}
+            GirdiPlani::Reddet(neden) => {
+                eprintln!("Arşiv girdisi atlandı ({}): {}", dosya_adi, neden);
+                rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden });
+            }
+            GirdiPlani::Dizin { yol: temizlenmis_dosya_adi, resource_id: cikartma_resource_id } => {
+                let dir_handle = resource::acquire(&cikartma_resource_id, resource::MODE_CREATE)
+                    .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+                if let Some(m) = mod_bitleri {
+                    srcarchive::izinleri_uygula(dir_handle, m, &cikartma_resource_id);
+                }
+                let _ = resource::release(dir_handle);
+                rapor.manifesto.push(ManifestGirdisi::dizin(&temizlenmis_dosya_adi, mod_bitleri));
+                rapor.cikarilan.push(temizlenmis_dosya_adi);
+            }
+            GirdiPlani::Bag { yol: temizlenmis_dosya_adi } => {
+                let hedef = match girdi.link_name_bytes().map(|b| core::str::from_utf8(&b).map(|s| s.to_string())) {
+                    Some(Ok(h)) => h,
+                    _ => {
+                        rapor.reddedilen.push(ReddedilenGirdi { ad: dosya_adi, neden: RedNedeni::GecersizKarakter });
+                        continue;
+                    }
+                };
+                // Bağ, tüm bağlar bilindiğinde denetlenip oluşturulur (bkz. srcarchive::bekleyen_baglari_olustur).
+                bekleyen_baglar.push(BekleyenBag { ad: dosya_adi, yol: temizlenmis_dosya_adi, hedef });
+            }
+            GirdiPlani::Dosya { yol: temizlenmis_dosya_adi, resource_id: cikartma_resource_id } => {
+                // Açılmış boyut sınırı: okuma "kalan + 1" bayt ile sınırlanır (zip_ac ile aynı yöntem).
+                let kalan = sinirlar.azami_toplam_boyut.saturating_sub(rapor.toplam_boyut);
+                let mut buffer = Vec::new(); // alloc
+                (&mut girdi).take(kalan.saturating_add(1)).read_to_end(&mut buffer)
+                    .map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{} okunamadı: {:?}", dosya_adi, e)))?;
+                if buffer.len() as u64 > kalan {
+                    return Err(PaketYoneticisiHatasi::ArsivSiniriAsildi(format!(
+                        "toplam açılmış boyut {} bayt sınırını aşıyor ({})", sinirlar.azami_toplam_boyut, dosya_adi
+                    )));
+                }
+                rapor.toplam_boyut += buffer.len() as u64;
+
+                ebeveyni_olustur(&cikartma_resource_id)?;
+                dosyaya_yaz(&cikartma_resource_id, &buffer, mod_bitleri)?;
+                rapor.manifesto.push(ManifestGirdisi::dosya(&temizlenmis_dosya_adi, &buffer, mod_bitleri));
+                rapor.cikarilan.push(temizlenmis_dosya_adi);
+            }
+        }
+    }
+
+    srcarchive::bekleyen_baglari_olustur(bekleyen_baglar, &cikartma_base_path, &mut rapor)?;
+    Ok(rapor)
+}
+
+// Verilen (sıkıştırılmış) tar arşivinin içeriğini listeler.
+// arsiv_resource_id: Listelenecek arşivin Sahne64 Kaynak ID'si.
+// sikistirma: Arşivin sıkıştırma algoritması (düz tar için None).
+pub fn tar_icerik_listele(arsiv_resource_id: &str, sikistirma: Option<CompressionAlgorithm>) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+    let arsiv_handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    let sonuc = cozucu_olustur(SahneResourceReader::new(arsiv_handle), sikistirma).and_then(|okuyucu| {
+        let mut arsiv = Archive::new(okuyucu);
+        let mut icerikler = Vec::new(); // alloc
+        for girdi in arsiv.entries().map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))? {
+            let girdi = girdi.map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))?;
+            icerikler.push(String::from_utf8_lossy(&girdi.path_bytes()).into_owned());
+        }
+        Ok(icerikler)
+    });
+
+    if let Err(e) = resource::release(arsiv_handle) {
+        eprintln!("Arşiv Kaynağı release hatası ({}): {:?}", arsiv_resource_id, e);
+        if sonuc.is_ok() {
+            return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+        }
+    }
+
+    sonuc
+}
+
+// Verilen (sıkıştırılmış) tar arşivindeki tek bir girdinin içeriğini belleğe okur (bkz. srcarchive::zip_girdisini_oku).
+// Girdi adları karşılaştırılmadan önce temizlenir; "./.PAKET/paket.bin" de ".PAKET/paket.bin" ile eşleşir.
+// Dönüş değeri: Girdi bulunursa Some(içerik), bulunamazsa None, veya hata.
+pub fn tar_girdisini_oku(
+    arsiv_resource_id: &str,
+    sikistirma: Option<CompressionAlgorithm>,
+    girdi_adi: &str,
+) -> Result<Option<Vec<u8>>, PaketYoneticisiHatasi> {
+    let arsiv_handle = resource::acquire(arsiv_resource_id, resource::MODE_READ)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    let sonuc = cozucu_olustur(SahneResourceReader::new(arsiv_handle), sikistirma).and_then(|okuyucu| {
+        let mut arsiv = Archive::new(okuyucu);
+        for girdi in arsiv.entries().map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))? {
+            let mut girdi = girdi.map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{:?}", e)))?;
+            let eslesti = core::str::from_utf8(&girdi.path_bytes()).ok()
+                .and_then(|ad| srcarchive::girdi_yolunu_temizle(ad).ok())
+                .map_or(false, |yol| yol == girdi_adi);
+            if eslesti && girdi.header().entry_type().is_file() {
+                let mut buffer = Vec::new(); // alloc
+                girdi.read_to_end(&mut buffer)
+                    .map_err(|e| PaketYoneticisiHatasi::TarHatasi(format!("{} okunamadı: {:?}", girdi_adi, e)))?;
+                return Ok(Some(buffer));
+            }
+        }
+        Ok(None)
+    });
+
+    if let Err(e) = resource::release(arsiv_handle) {
+        eprintln!("Arşiv Kaynağı release hatası ({}): {:?}", arsiv_resource_id, e);
+    }
+
+    sonuc
+}
+
+// Helper: Dosyanın ebeveyn Kaynağının varlığını sağlar (zip_ac'taki varsayımla aynı).
+fn ebeveyni_olustur(resource_id: &str) -> Result<(), PaketYoneticisiHatasi> {
+    if let Some(ebeveyn_path) = resource_id.rfind('/').map(|idx| &resource_id[..idx]) {
+        if !ebeveyn_path.is_empty() {
+            let handle = resource::acquire(ebeveyn_path, resource::MODE_CREATE)
+                .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+            let _ = resource::release(handle);
+        }
+    }
+    Ok(())
+}
+
+// Helper: İçeriği Kaynağa yazar ve izinleri uygular.
+fn dosyaya_yaz(resource_id: &str, buffer: &[u8], mod_bitleri: Option<u32>) -> Result<(), PaketYoneticisiHatasi> {
+    let handle = resource::acquire(resource_id, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
+        .map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))?;
+
+    let mut written = 0;
+    while written < buffer.len() {
+        match resource::write(handle, &buffer[written..]) {
+            Ok(0) => {
+                let _ = resource::release(handle);
+                return Err(PaketYoneticisiHatasi::from_sahne_error(SahneError::InvalidOperation));
+            }
+            Ok(n) => written += n,
+            Err(e) => {
+                eprintln!("Çıkarma Kaynağına yazma hatası ({}): {:?}", resource_id, e);
+                let _ = resource::release(handle);
+                return Err(PaketYoneticisiHatasi::from_sahne_error(e));
+            }
+        }
+    }
+
+    if let Some(m) = mod_bitleri {
+        srcarchive::izinleri_uygula(handle, m, resource_id);
+    }
+
+    resource::release(handle).map_err(|e| PaketYoneticisiHatasi::from_sahne_error(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KOK: &str = "sahne://hazirlik/paket/";
+
+    #[test]
+    fn test_girdi_plani_normal_dosya() {
+        assert_eq!(
+            girdi_plani("./usr/bin/araç", EntryType::Regular, KOK).unwrap(),
+            GirdiPlani::Dosya {
+                yol: String::from("usr/bin/araç"),
+                resource_id: String::from("sahne://hazirlik/paket/usr/bin/araç"),
+            }
+        );
+        assert_eq!(
+            girdi_plani("usr/share/", EntryType::Directory, KOK).unwrap(),
+            GirdiPlani::Dizin {
+                yol: String::from("usr/share/"),
+                resource_id: String::from("sahne://hazirlik/paket/usr/share/"),
+            }
+        );
+        assert_eq!(girdi_plani("lib/libx.so", EntryType::Symlink, KOK).unwrap(), GirdiPlani::Bag { yol: String::from("lib/libx.so") });
+        assert_eq!(girdi_plani("././@LongLink", EntryType::GNULongName, KOK).unwrap(), GirdiPlani::Atla);
+    }
+
+    #[test]
+    fn test_girdi_plani_ust_dizin_reddedilir() {
+        assert_eq!(girdi_plani("../etc/passwd", EntryType::Regular, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::UstDizinBileseni));
+        assert_eq!(girdi_plani("usr/../../x", EntryType::Directory, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::UstDizinBileseni));
+        assert_eq!(girdi_plani("a/../b", EntryType::Symlink, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::UstDizinBileseni));
+    }
+
+    #[test]
+    fn test_girdi_plani_mutlak_yol_reddedilir() {
+        assert_eq!(girdi_plani("/etc/passwd", EntryType::Regular, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::MutlakYol));
+        assert_eq!(girdi_plani("C:/Windows", EntryType::Directory, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::MutlakYol));
+    }
+
+    #[test]
+    fn test_girdi_plani_desteklenmeyen_tur() {
+        // Bilinen ama kabul edilmeyen türler raporlanıp atlanır.
+        assert_eq!(girdi_plani("dev/sda", EntryType::Block, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::DesteklenmeyenTur));
+        assert_eq!(girdi_plani("bin/kopya", EntryType::Link, KOK).unwrap(), GirdiPlani::Reddet(RedNedeni::DesteklenmeyenTur));
+        // Tanınmayan tür bayrağı arşivin tamamını reddeder.
+        match girdi_plani("usr/bin/araç", EntryType::new(b'Z'), KOK) {
+            Err(PaketYoneticisiHatasi::DesteklenmeyenArsivBicimi(mesaj)) => assert!(mesaj.contains("0x5a")),
+            diger => panic!("beklenmeyen sonuç: {:?}", diger),
+        }
+    }
+}
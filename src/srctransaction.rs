@@ -23,6 +23,15 @@ use alloc::borrow::ToOwned; // to_string() için
 // no_std uyumlu print makroları (örnek çıktılar için)
 use crate::print_macros::{println, eprintln};
 
+// Betik sonuçlarını günlüğe yazmak için
+use crate::srcscript::{BetikAsamasi, BetikSonucu};
+// İşlem sonunda çalışacak tetikleyicileri toplamak için
+use crate::srctrigger::{self, TetiklenenIs};
+use crate::srcdatabase::KuruluVeritabani;
+use alloc::collections::BTreeSet;
+// İşlem süresince tüm günlük kayıtlarına eklenen işlem kimliği
+use crate::srclogging;
+
 
 // Helper fonksiyon: Sahne64 Kaynağından tüm içeriği Vec<u8> olarak oku.
 // Utils modülünden yeniden kullanıldı veya buraya kopyalandı.
@@ -57,11 +66,60 @@ fn read_resource_to_vec(resource_id: &str) -> Result<Vec<u8>, PaketYoneticisiHat
 }
 
 
+// Bir betik sonucunu işlem günlüğü satırlarına çevirir: bir başlık satırı ve ardından her çıktı satırı
+// akış adıyla girintili olarak (örn. "  [stderr] hata: ...").
+fn betik_gunluk_satirlari(paket_adi: &str, asama: BetikAsamasi, sonuc: &BetikSonucu) -> Vec<String> {
+    let durum = match sonuc.cikis_kodu {
+        Some(kod) => format!("cikis={}", kod),
+        None => "zaman-asimi".to_owned(),
+    };
+    let mut satirlar = Vec::new();
+    satirlar.push(format!("BETIK {} {} {}", paket_adi, asama.ad(), durum));
+    for (akis, cikti) in [("stdout", &sonuc.stdout), ("stderr", &sonuc.stderr)] {
+        for satir in String::from_utf8_lossy(cikti).lines() {
+            satirlar.push(format!("  [{}] {}", akis, satir));
+        }
+    }
+    satirlar
+}
+
+// İşlem günlüğü metninden, "ISLEM TAMAMLANDI" kaydıyla biten işlemlerde kaydedilmiş yedekleri çıkarır.
+// Tamamlanmadan kesilen (çökme, kapanma) veya geri alınan işlemlerin yedekleri kümede yer almaz; bu
+// yedekler tek sağlam kopya olabileceğinden silinmemelidir.
+pub fn tamamlanan_yedekler(gunluk: &str) -> BTreeSet<String> {
+    let mut tamamlanan = BTreeSet::new();
+    let mut bekleyen: Vec<String> = Vec::new();
+    for satir in gunluk.lines() {
+        if let Some(yedek) = satir.strip_prefix("YEDEK ") {
+            bekleyen.push(yedek.to_owned());
+        } else if satir == "ISLEM TAMAMLANDI" {
+            tamamlanan.extend(bekleyen.drain(..));
+        } else if satir.starts_with("ISLEM ") {
+            bekleyen.clear(); // Yeni işlem başladı veya önceki geri alındı
+        }
+    }
+    tamamlanan
+}
+
+// İşlem günlüğünü okuyup tamamlanan işlemlerin yedeklerini döner; günlük yoksa küme boştur.
+pub fn tamamlanan_yedekleri_oku(log_resource_id: &str) -> Result<BTreeSet<String>, PaketYoneticisiHatasi> {
+    match read_resource_to_vec(log_resource_id) {
+        Ok(veri) => Ok(tamamlanan_yedekler(&String::from_utf8_lossy(&veri))),
+        Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => Ok(BTreeSet::new()),
+        Err(e) => Err(e),
+    }
+}
+
+// Paket yöneticisinin varsayılan işlem günlüğü Kaynağı.
+pub const VARSAYILAN_ISLEM_GUNLUGU_KAYNAGI: &str = "sahne://system/pkgmgr_transaction.log";
+
 // Paket yönetim işlemlerini izlemek ve geri almak için bir işlem günlüğü tutar.
 // İşlem günlüğü, bir Sahne64 Kaynağı olarak saklanır.
 pub struct IslemYoneticisi {
     // İşlem günlüğü dosyasının Kaynak ID'si (örn. "sahne://system/pkgmgr_transaction.log")
     log_resource_id: String, // String alloc gerektirir.
+    // İşlem boyunca kurulan/kaldırılan dosya yolları (tetikleyici eşleştirmesi için)
+    degisen_yollar: BTreeSet<String>,
 }
 
 impl IslemYoneticisi {
@@ -70,6 +128,7 @@ impl IslemYoneticisi {
     pub fn yeni(log_resource_id: &str) -> Self { // &str log_resource_id
         IslemYoneticisi {
             log_resource_id: log_resource_id.to_owned(), // to_owned() alloc
+            degisen_yollar: BTreeSet::new(),
         }
     }
 
@@ -128,10 +187,14 @@ impl IslemYoneticisi {
     }
 
 
-    // İşlem günlüğüne "ISLEM BASLADI" kaydını yazar ve işlemi başlatır.
-    pub fn baslat_islem(&self) -> Result<(), PaketYoneticisiHatasi> {
+    // İşlem günlüğüne "ISLEM BASLADI <kimlik>" kaydını yazar ve işlemi başlatır. Kimlik, işlem bitene kadar
+    // tüm modüllerin günlük kayıtlarında da yer alır (bkz. srclogging) ve işlem geçmişine de bu kimlikle yazılır.
+    // Dönüş değeri: İşlem kimliği.
+    pub fn baslat_islem(&self) -> Result<u64, PaketYoneticisiHatasi> {
+        let kimlik = srclogging::islem_kimligi_baslat();
         info!("İşlem başlatılıyor. Günlük Kaynağı: {}", self.log_resource_id); // no_std log
-        self.log_entry("ISLEM BASLADI") // log_entry helper'ını kullan
+        self.log_entry(&format!("ISLEM BASLADI {}", srclogging::kimlik_metni(kimlik)))?; // log_entry helper'ını kullan
+        Ok(kimlik)
     }
 
     // İşlem günlüğüne bir işlem adımı kaydeder.
@@ -141,21 +204,47 @@ impl IslemYoneticisi {
         self.log_entry(adim) // log_entry helper'ını kullan
     }
 
+    // Bir paket betiğinin çıkış durumunu ve yakalanan stdout/stderr çıktısını işlem günlüğüne yazar.
+    pub fn betik_sonucunu_kaydet(&self, paket_adi: &str, asama: BetikAsamasi, sonuc: &BetikSonucu) -> Result<(), PaketYoneticisiHatasi> {
+        debug!("Betik sonucu kaydediliyor: {} {}. Günlük Kaynağı: {}", paket_adi, asama.ad(), self.log_resource_id); // no_std log
+        self.log_entry(&betik_gunluk_satirlari(paket_adi, asama, sonuc).join("\n"))
+    }
+
+    // Etkinleştirme veya kaldırma sırasında kenara alınan bir kurulum dizini yedeğini ("YEDEK <Kaynak ID>")
+    // kaydeder. Yedekler ancak kaydedildikleri işlem tamamlandıysa silinebilir (bkz. tamamlanan_yedekler).
+    pub fn yedegi_kaydet(&self, yedek_id: &str) -> Result<(), PaketYoneticisiHatasi> {
+        debug!("Yedek kaydediliyor: {}. Günlük Kaynağı: {}", yedek_id, self.log_resource_id); // no_std log
+        self.log_entry(&format!("YEDEK {}", yedek_id))
+    }
+
+    // İşlemde kurulan veya kaldırılan dosya yollarını kaydeder (kurulum dizinine göre).
+    pub fn yollari_kaydet(&mut self, yollar: &[String]) {
+        self.degisen_yollar.extend(yollar.iter().cloned());
+    }
+
+    // İşlem sonunda çalıştırılması gereken tetikleyicileri döner: veritabanındaki kurulu paketlerin
+    // tetikleyicilerinden, kaydedilen yollardan en az birine uyanlar, her biri bir kez.
+    pub fn tetiklenenler(&self, veritabani: &KuruluVeritabani) -> Vec<TetiklenenIs> {
+        srctrigger::tetiklenenleri_bul(veritabani, &self.degisen_yollar)
+    }
+
     // İşlem günlüğüne "ISLEM TAMAMLANDI" kaydını yazar ve işlemi tamamlar.
     pub fn tamamla_islem(&self) -> Result<(), PaketYoneticisiHatasi> {
         info!("İşlem tamamlanıyor. Günlük Kaynağı: {}", self.log_resource_id); // no_std log
-        self.log_entry("ISLEM TAMAMLANDI") // log_entry helper'ını kullan
+        let sonuc = self.log_entry("ISLEM TAMAMLANDI"); // log_entry helper'ını kullan
+        srclogging::islem_kimligi_bitir();
+        sonuc
     }
 
-    // İşlemi geri alır. İşlem günlüğünü okur ve adımları tersine çevirmeye çalışır.
-    // Bu basit implementasyonda sadece günlük dosyasını siler/temizler.
-    // Gerçek bir geri alma, günlüktteki adımlara göre yapılan değişiklikleri geri almayı gerektirir.
+    // İşlemi günlükte geri alındı olarak işaretler: "ISLEM GERI ALINDI <kimlik>" kaydı eklenir. Günlük
+    // temizlenmez; betik çıktıları ve önceki kayıtlar, işlemin neden başarısız olduğunu incelemek için kalır.
+    // Dosya ve veritabanı değişikliklerinin geri alınması çağırana aittir (bkz. srcinstaller::IslemDurumu).
+    // Tamamlanmış bir işlem geri alınamaz.
     pub fn geri_al_islem(&self) -> Result<(), PaketYoneticisiHatasi> {
         info!("İşlem geri alma başlatılıyor. Günlük Kaynağı: {}", self.log_resource_id); // no_std log
 
-        // İşlem günlüğü içeriğini oku (Vec<u8> olarak).
         // Kaynak bulunamazsa hata dönmez (geri alınacak işlem yoktur).
-        let islem_gunlugu_icerigi_bytes = match read_resource_to_vec(&self.log_resource_id) {
+        let gunluk = match read_resource_to_vec(&self.log_resource_id) {
              Ok(bytes) => bytes,
              Err(PaketYoneticisiHatasi::SahneApiError(SahneError::ResourceNotFound)) => {
                  warn!("İşlem günlüğü Kaynağı bulunamadı ({}). Geri alınacak bir işlem yok.", self.log_resource_id); // no_std log
@@ -165,59 +254,30 @@ impl IslemYoneticisi {
                  error!("İşlem günlüğü okunurken hata oluştu ({}): {:?}", self.log_resource_id, e); // no_std log
                  return Err(e); // Diğer okuma hatalarını yay
              }
-        }; // read_resource_to_vec PaketYoneticisiHatasi döner.
-
-
-        // Vec<u8>'i String'e çevir (UTF-8). Hatalı karakterler olabilir, lossy çeviri kullanalım veya hata dönelim.
-        let islem_gunlugu_icerigi = String::from_utf8_lossy(&islem_gunlugu_icerigi_bytes); // from_utf8_lossy no_std+alloc
+        };
 
-        // Satırları ayır ve son satırı kontrol et.
-        let islem_adimlari: Vec<&str> = islem_gunlugu_icerigi.lines().collect(); // lines() Iter<'_> over &str no_std, collect Vec<&str> alloc
-
-        // İşlem zaten tamamlanmışsa geri almayı reddet.
-        if islem_adimlari.last() == Some(&"ISLEM TAMAMLANDI") { // last() Option<& &str>, Some(&"...") comp no_std
-            let hata_mesaji = format!("İşlem geri alınamaz, zaten tamamlandı. Günlük Kaynağı: {}", self.log_resource_id); // format! alloc
-            warn!("{}", hata_mesaji); // no_std log
-            return Err(PaketYoneticisiHatasi::IslemYoneticisiHatasi(hata_mesaji)); // PaketYoneticisiHatasi::IslemYoneticisiHatasi alloc
-        }
-
-        // --- Gerçek Geri Alma Mantığı Eksikliği ---
-        // Bu noktada, gerçek bir geri alma mekanizması, `islem_adimlari` listesini
-        // tersten okuyarak her adımda yapılan değişikliği geri almalıdır.
-        // Örneğin: "Paket yuklendi: X" adımını gördüğünde, paketi kaldırma işlemini başlatmalıdır.
-        // Bu, paket yöneticisindeki tüm kurulum, kaldırma gibi eylemlerin geri alınabilir şekilde
-        // implement edilmesini (örn. bir geri alma betiği veya bilgisi tutarak) gerektirir.
-        // Bu modül sadece günlüğü yönetir, adımları nasıl geri alacağını bilmez.
-
-        // Basitlik adına, sadece işlem günlüğü Kaynağını temizleyelim (trunc).
-        // Bu, bir sonraki işlem için temiz bir başlangıç sağlar, ancak önceki
-        // başarısız işlemin neden olduğu yarım kalmış değişiklikleri geri almaz.
-        info!("İşlem günlüğü Kaynağı temizleniyor (geri alma simülasyonu): {}", self.log_resource_id); // no_std log
-
-        // Kaynağı yazma ve truncate izniyle acquire et.
-        let handle_temizle = resource::acquire(
-            &self.log_resource_id,
-            resource::MODE_WRITE | resource::MODE_TRUNCATE // TRUNCATE Kaynak içeriğini siler
-        ).map_err(|e| {
-             error!("İşlem günlüğü Kaynağı temizleme acquire hatası ({}): {:?}", self.log_resource_id, e); // no_std log
-            PaketYoneticisiHatasi::IslemYoneticisiHatasi(format!( // format! alloc
-                "İşlem günlüğü temizlenemedi ({}): {:?}",
-                self.log_resource_id, e
-            ))
+        let kayit = geri_alma_kaydi(&String::from_utf8_lossy(&gunluk), srclogging::islem_kimligi()).map_err(|hata_mesaji| {
+            warn!("{} Günlük Kaynağı: {}", hata_mesaji, self.log_resource_id); // no_std log
+            PaketYoneticisiHatasi::IslemYoneticisiHatasi(hata_mesaji)
         })?;
+        self.log_entry(&kayit)?;
 
-        // Handle'ı serbest bırakmak temizleme işlemini tamamlar.
-        let release_result = resource::release(handle_temizle);
-         if let Err(_e) = release_result {
-              error!("İşlem günlüğü Kaynağı temizleme release hatası ({}): {:?}", self.log_resource_id, _e); // no_std log
-         }
-
-
-        info!("İşlem geri alma tamamlandı (günlük temizlendi). Günlük Kaynağı: {}", self.log_resource_id); // no_std log
+        info!("İşlem geri alındı olarak işaretlendi. Günlük Kaynağı: {}", self.log_resource_id); // no_std log
         Ok(()) // Başarı
     }
 }
 
+// Geri alma için günlüğe eklenecek kaydı döner; son işlem zaten tamamlandıysa hata mesajı döner.
+fn geri_alma_kaydi(gunluk: &str, kimlik: Option<u64>) -> Result<String, String> {
+    if gunluk.lines().last() == Some("ISLEM TAMAMLANDI") {
+        return Err(String::from("İşlem geri alınamaz, zaten tamamlandı."));
+    }
+    Ok(match kimlik {
+        Some(kimlik) => format!("ISLEM GERI ALINDI {}", srclogging::kimlik_metni(kimlik)),
+        None => String::from("ISLEM GERI ALINDI"),
+    })
+}
+
 // #[cfg(test)] bloğu std test runner'ı ve std bağımlılıkları gerektirir.
 // Testler için mock resource veya Sahne64 simülasyonu gereklidir.
 
@@ -225,6 +285,63 @@ impl IslemYoneticisi {
 mod tests {
     // std::io, std::path, std::fs, tempfile kullandığı için no_std'de doğrudan çalışmaz.
     // Mock resource::acquire/read/write/release ve test dosyası oluşturma/okuma helper'ları gerektirir.
+    use super::*;
+
+    #[test]
+    fn test_betik_gunluk_satirlari() {
+        let sonuc = BetikSonucu {
+            cikis_kodu: Some(2),
+            stdout: b"basladi\nbitti\n".to_vec(),
+            stderr: b"hata: dizin yok".to_vec(),
+        };
+        assert_eq!(betik_gunluk_satirlari("editor", BetikAsamasi::KurulumSonrasi, &sonuc), vec![
+            "BETIK editor post-install cikis=2".to_string(),
+            "  [stdout] basladi".to_string(),
+            "  [stdout] bitti".to_string(),
+            "  [stderr] hata: dizin yok".to_string(),
+        ]);
+
+        let zaman_asimi = BetikSonucu { cikis_kodu: None, stdout: Vec::new(), stderr: Vec::new() };
+        assert_eq!(betik_gunluk_satirlari("editor", BetikAsamasi::KaldirmaOncesi, &zaman_asimi), vec![
+            "BETIK editor pre-remove zaman-asimi".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_tamamlanan_yedekler() {
+        let gunluk = "ISLEM BASLADI 0000000000000001\n\
+            YEDEK sahne://k/.hazirlik/a-1.eski/\n\
+            KURULDU a 2.0\n\
+            ISLEM TAMAMLANDI\n\
+            ISLEM BASLADI 0000000000000002\n\
+            YEDEK sahne://k/.hazirlik/b-2.eski/\n\
+            ISLEM GERI ALINDI 0000000000000002\n\
+            ISLEM BASLADI 0000000000000003\n\
+            YEDEK sahne://k/.hazirlik/c-3.eski/\n";
+        let tamamlanan = tamamlanan_yedekler(gunluk);
+        assert!(tamamlanan.contains("sahne://k/.hazirlik/a-1.eski/"));
+        assert!(!tamamlanan.contains("sahne://k/.hazirlik/b-2.eski/")); // Geri alındı
+        assert!(!tamamlanan.contains("sahne://k/.hazirlik/c-3.eski/")); // İşlem yarıda kesildi
+    }
+
+    #[test]
+    fn test_geri_alma_betik_ciktisini_korur() {
+        let sonuc = BetikSonucu { cikis_kodu: Some(1), stdout: Vec::new(), stderr: b"hata: disk dolu".to_vec() };
+        let mut gunluk = String::from("ISLEM BASLADI 0000000000000007\n");
+        for satir in betik_gunluk_satirlari("editor", BetikAsamasi::KurulumSonrasi, &sonuc) {
+            gunluk.push_str(&satir);
+            gunluk.push('\n');
+        }
+
+        // Geri alma günlüğü temizlemez, sonuna kayıt ekler.
+        let kayit = geri_alma_kaydi(&gunluk, Some(7)).unwrap();
+        assert_eq!(kayit, "ISLEM GERI ALINDI 0000000000000007");
+        let sonrasi = format!("{}{}\n", gunluk, kayit);
+        assert!(sonrasi.contains("BETIK editor post-install cikis=1\n  [stderr] hata: disk dolu\n"));
+
+        // Tamamlanmış işlem geri alınamaz.
+        assert!(geri_alma_kaydi("ISLEM BASLADI 0000000000000008\nISLEM TAMAMLANDI\n", Some(8)).is_err());
+    }
 }
 
 // --- PaketYoneticisiHatasi enum tanımı ---
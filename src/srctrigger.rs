@@ -0,0 +1,131 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, BTreeMap için
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::srcdatabase::KuruluVeritabani;
+
+// Sistem genelinde tetikleyiciler.
+// Bir paket, belirli yol desenlerine uyan dosyalar kurulduğunda/kaldırıldığında çalışacak bir betik bildirir
+// (örn. fontconfig: "share/fonts/**" -> yazı tipi önbelleğini yeniden oluştur). İşlem boyunca değişen
+// yollar toplanır ve işlem sonunda eşleşen her tetikleyici, kaç paket tetiklemiş olursa olsun bir kez çalışır.
+
+// Bir paketin bildirdiği tetikleyici.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tetikleyici {
+    pub ad: String, // Paket içinde benzersiz ad (örn. "yazi-tipi-onbellegi")
+    pub desenler: Vec<String>, // Kurulum dizinine göre yol desenleri (bkz. desen_eslesir)
+    pub betik: String, // Çalıştırılacak betik (paketin betik dizinine göre yol veya tam Kaynak ID'si)
+}
+
+// İşlem sonunda çalıştırılacak bir tetikleyici ve onu tetikleyen yollar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TetiklenenIs {
+    pub sahip: String, // Tetikleyiciyi bildiren kurulu paket
+    pub tetikleyici: Tetikleyici,
+    pub yollar: Vec<String>, // Desenlere uyan değişmiş yollar (sıralı)
+}
+
+// Yolun desene uyup uymadığını denetler. Baştaki '/' her ikisinde de yok sayılır.
+// '*' bir yol bileşeni içinde herhangi bir dizgiyle ('/' hariç), '?' '/' dışındaki tek bir karakterle,
+// '**' ise dizin sınırları dahil her şeyle eşleşir ("a/**/b", "a/b" ile de eşleşir).
+pub fn desen_eslesir(desen: &str, yol: &str) -> bool {
+    let desen: Vec<char> = desen.trim_start_matches('/').chars().collect();
+    let yol: Vec<char> = yol.trim_start_matches('/').chars().collect();
+    eslesir(&desen, &yol)
+}
+
+fn eslesir(desen: &[char], yol: &[char]) -> bool {
+    match desen.first() {
+        None => yol.is_empty(),
+        Some('*') if desen.get(1) == Some(&'*') => {
+            let kalan = &desen[2..];
+            // "**/" sıfır dizinle de eşleşebilir
+            (kalan.first() == Some(&'/') && eslesir(&kalan[1..], yol))
+                || (0..=yol.len()).any(|i| eslesir(kalan, &yol[i..]))
+        }
+        Some('*') => {
+            let bilesen_sonu = yol.iter().position(|c| *c == '/').unwrap_or(yol.len());
+            (0..=bilesen_sonu).any(|i| eslesir(&desen[1..], &yol[i..]))
+        }
+        Some('?') => yol.first().is_some_and(|c| *c != '/') && eslesir(&desen[1..], &yol[1..]),
+        Some(c) => yol.first() == Some(c) && eslesir(&desen[1..], &yol[1..]),
+    }
+}
+
+// Kurulu paketlerin tetikleyicilerinden, değişen yollardan en az birine uyanları bulur.
+// Dönüş değeri: (sahip, tetikleyici adı) sırasıyla, her tetikleyici bir kez.
+pub fn tetiklenenleri_bul(veritabani: &KuruluVeritabani, degisen_yollar: &BTreeSet<String>) -> Vec<TetiklenenIs> {
+    let mut isler = Vec::new();
+    for (sahip, kayit) in &veritabani.paketler {
+        let mut tetikleyiciler: Vec<&Tetikleyici> = kayit.tetikleyiciler.iter().collect();
+        tetikleyiciler.sort_by(|a, b| a.ad.cmp(&b.ad));
+        tetikleyiciler.dedup_by(|a, b| a.ad == b.ad);
+        for tetikleyici in tetikleyiciler {
+            let yollar: Vec<String> = degisen_yollar
+                .iter()
+                .filter(|yol| tetikleyici.desenler.iter().any(|d| desen_eslesir(d, yol)))
+                .cloned()
+                .collect();
+            if !yollar.is_empty() {
+                isler.push(TetiklenenIs { sahip: sahip.to_string(), tetikleyici: tetikleyici.clone(), yollar });
+            }
+        }
+    }
+    isler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Paket;
+    use crate::srcdatabase::KuruluPaket;
+
+    #[test]
+    fn test_desen_eslesir() {
+        assert!(desen_eslesir("share/fonts/*.ttf", "share/fonts/a.ttf"));
+        assert!(!desen_eslesir("share/fonts/*.ttf", "share/fonts/alt/a.ttf"));
+        assert!(desen_eslesir("share/fonts/**", "share/fonts/alt/a.ttf"));
+        assert!(desen_eslesir("share/**/index.theme", "share/index.theme"));
+        assert!(desen_eslesir("share/**/index.theme", "share/icons/hicolor/index.theme"));
+        assert!(desen_eslesir("/lib/lib?.so", "lib/libc.so"));
+        assert!(!desen_eslesir("lib/lib?.so", "lib/libcc.so"));
+        assert!(!desen_eslesir("share/fonts/*", "share/icons/a.png"));
+    }
+
+    fn kayit(ad: &str, tetikleyiciler: Vec<Tetikleyici>) -> KuruluPaket {
+        let mut k = KuruluPaket::paketten(&Paket::yeni(ad.to_string(), "1.0".to_string(), vec![]));
+        k.tetikleyiciler = tetikleyiciler;
+        k
+    }
+
+    fn tetikleyici(ad: &str, desenler: &[&str]) -> Tetikleyici {
+        Tetikleyici {
+            ad: ad.to_string(),
+            desenler: desenler.iter().map(|d| d.to_string()).collect(),
+            betik: "tetik.sh".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tetikleyici_bir_kez_calisir() {
+        let mut vt = KuruluVeritabani::default();
+        vt.ekle(kayit("fontconfig", vec![tetikleyici("yazi-tipi-onbellegi", &["share/fonts/**"])]));
+        vt.ekle(kayit("simge-tema", vec![tetikleyici("simge-indeksi", &["share/icons/**"])]));
+        vt.ekle(kayit("yazi-tipi-a", vec![]));
+
+        // İki farklı paketten gelen yazı tipleri tek bir iş üretir; simge tetikleyicisi hiç tetiklenmez.
+        let yollar: BTreeSet<String> = ["share/fonts/a.ttf", "share/fonts/b/b.otf", "bin/arac"].iter().map(|y| y.to_string()).collect();
+        let isler = tetiklenenleri_bul(&vt, &yollar);
+        assert_eq!(isler.len(), 1);
+        assert_eq!(isler[0].sahip, "fontconfig");
+        assert_eq!(isler[0].tetikleyici.ad, "yazi-tipi-onbellegi");
+        assert_eq!(isler[0].yollar, vec!["share/fonts/a.ttf".to_string(), "share/fonts/b/b.otf".to_string()]);
+
+        assert!(tetiklenenleri_bul(&vt, &BTreeSet::new()).is_empty());
+    }
+}
@@ -7,6 +7,9 @@ use alloc::vec::Vec;
 use alloc::format; // format! makrosu için
 use alloc::borrow::ToOwned; // to_owned() için
 
+// Güvenilen anahtarlar hex olarak saklanır
+use hex;
+
 // Sahne64 API modülleri
 use crate::resource; // Kaynak işlemleri (acquire, read, write, release)
 use crate::SahneError; // Sahne64 hata türü
@@ -135,6 +138,55 @@ fn write_string_to_resource(resource_id: &str, content: &str) -> Result<(), Trus
 }
 
 
+// Güvenilen yayıncı ve paket listelerinin varsayılan Kaynak ID'leri.
+pub const VARSAYILAN_YAYINCILAR_KAYNAGI: &str = "sahne://config/trusted_publishers";
+pub const VARSAYILAN_PAKETLER_KAYNAGI: &str = "sahne://config/trusted_packages";
+// Depo imzalarının doğrulandığı Ed25519 açık anahtarları (bkz. srcsigning::imzayi_dogrula).
+pub const VARSAYILAN_ANAHTARLAR_KAYNAGI: &str = "sahne://config/trusted_keys";
+
+// Güvenilen anahtarlar listesini ayrıştırır: her satırda bir açık anahtarın hex hali, isteğe bağlı olarak
+// ardından boşlukla ayrılmış bir ad (örn. "3d40...c2a1 resmi-depo"). Boş ve '#' ile başlayan satırlar atlanır.
+pub fn anahtarlari_ayristir(metin: &str) -> Result<Vec<[u8; 32]>, TrustError> {
+    let mut anahtarlar = Vec::new(); // alloc
+    for (no, satir) in metin.lines().enumerate() {
+        let satir = satir.trim();
+        if satir.is_empty() || satir.starts_with('#') {
+            continue;
+        }
+        let hex_metni = satir.split_whitespace().next().unwrap_or("");
+        let anahtar = hex::decode(hex_metni).ok()
+            .and_then(|baytlar| <[u8; 32]>::try_from(baytlar.as_slice()).ok())
+            .ok_or_else(|| TrustError::ParsingError(format!("{}. satır: 32 baytlık hex açık anahtar bekleniyor", no + 1)))?;
+        anahtarlar.push(anahtar);
+    }
+    Ok(anahtarlar)
+}
+
+// Güvenilen anahtarları Kaynaktan okur. Kaynak yoksa liste boştur; bu durumda hiçbir depo imzası
+// doğrulanamaz (yayıncı ve paket listelerinin aksine boş liste "herkese güven" anlamına gelmez).
+pub fn guvenilen_anahtarlari_oku(resource_id: &str) -> Result<Vec<[u8; 32]>, TrustError> {
+    match read_resource_to_vec(resource_id) {
+        Ok(buffer) => {
+            let anahtarlar = anahtarlari_ayristir(core::str::from_utf8(&buffer)?)?;
+            debug!("{} güvenilen anahtar yüklendi: {}", anahtarlar.len(), resource_id); // no_std log
+            Ok(anahtarlar)
+        }
+        Err(TrustError::Sahne64ResourceError(SahneError::ResourceNotFound)) => {
+            warn!("Güvenilen anahtarlar Kaynağı bulunamadı ({}); depo imzaları doğrulanamayacak.", resource_id); // no_std log
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// Bir paketin (ve betiklerinin) ne kadar güvenilir sayıldığı. Betikler seviyeye göre seçilen
+// sandbox profiliyle çalıştırılır (bkz. srcsecurity::SandboxAyarlari).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuvenSeviyesi {
+    Guvenilir, // Paketin kendisi veya yayıncısı güvenilenler listesinde
+    Guvenilmeyen, // Diğer tüm paketler; en sıkı profil uygulanır
+}
+
 // Güvenilen yayıncıları ve paketleri yönetir.
 // Güvenilen listeleri Sahne64 Kaynaklarında saklar.
 pub struct TrustManager {
@@ -423,6 +475,16 @@ impl TrustManager {
     pub fn is_trusted_package(&self, package_name: &str) -> bool {
         self.trusted_packages.contains(package_name) // contains &str alır no_std
     }
+
+    // Paketin güven seviyesini belirler: paket adı veya yayıncısı güvenilenler listesindeyse Guvenilir.
+    // publisher_name: Paketin yayıncısı (meta verideki yazar); bilinmiyorsa None.
+    pub fn guven_seviyesi(&self, package_name: &str, publisher_name: Option<&str>) -> GuvenSeviyesi {
+        if self.is_trusted_package(package_name) || publisher_name.is_some_and(|y| self.is_trusted_publisher(y)) {
+            GuvenSeviyesi::Guvenilir
+        } else {
+            GuvenSeviyesi::Guvenilmeyen
+        }
+    }
 }
 
 // #[cfg(test)] bloğu std test runner'ı ve std bağımlılıkları gerektirir.
@@ -430,8 +492,19 @@ impl TrustManager {
 
 #[cfg(test)]
 mod tests {
-    // std::collections, std::io, std::path, std::fs, tempfile kullandığı için no_std'de doğrudan çalışmaz.
-    // Mock resource::acquire/read/write/release ve test dosyası oluşturma/okuma helper'ları gerektirir.
+    // TrustManager mock resource::acquire/read/write/release gerektirir; anahtar listesi ayrıştırma saf bir fonksiyondur.
+    use super::*;
+
+    #[test]
+    fn test_anahtarlari_ayristir() {
+        let anahtar = "ab".repeat(32);
+        let metin = format!("# resmi depo\n{} resmi-depo\n\n  {}\n", anahtar, "01".repeat(32));
+        let anahtarlar = anahtarlari_ayristir(&metin).unwrap();
+        assert_eq!(anahtarlar, alloc::vec![[0xab; 32], [0x01; 32]]);
+
+        assert!(anahtarlari_ayristir("abcd").is_err());
+        assert!(anahtarlari_ayristir(&"zz".repeat(32)).is_err());
+    }
 }
 
 // --- TrustError enum tanımı ---
@@ -0,0 +1,386 @@
+#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz (alloc kullanacağız)
+extern crate alloc; // String, Vec, BTreeSet için
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Sahne64 API modülleri
+use crate::resource;
+use crate::SahneError;
+
+// Özel hata enum'ımızı içe aktar
+use crate::paket_yoneticisi_hata::PaketYoneticisiHatasi;
+
+use crate::srcarchive::{IZIN_MASKESI, S_IFDIR, S_IFLNK, S_IFMT, S_IFREG};
+use crate::srcconffiles::YENI_UZANTISI;
+use crate::srcdatabase::{KuruluPaket, KuruluVeritabani};
+
+// Kurulu dosyaların bütünlük denetimi. Arşiv çıkarılırken her girdinin türü, boyutu, SHA-256 özeti,
+// izinleri ve sembolik bağ hedefi manifestoya yazılır (bkz. srcarchive::CikarmaRaporu) ve kurulu paket
+// kaydında saklanır. `dogrula` kurulu dosyaları bu manifestoyla karşılaştırır; `onar` değişmiş veya eksik
+// dosyaları arşivden yeniden çıkarır (bkz. KurulumYoneticisi::dosyalari_onar). Yapılandırma dosyalarının
+// içeriği kullanıcıya ait olduğundan (bkz. srcconffiles) sadece türleri ve izinleri denetlenir.
+
+// Dosya bilgisi için Sahne64 Kaynak kontrol komutları (Varsayımsal).
+const RESOURCE_CONTROL_CMD_LIST: u64 = 3; // Dönüş: satır başına bir alt Kaynak adı, dizinler '/' ile biter
+const RESOURCE_CONTROL_CMD_STAT: u64 = 8; // Dönüş: u32 unix mod bitleri + u64 boyut (little-endian); bağlar izlenmez
+const RESOURCE_CONTROL_CMD_READ_SYMLINK: u64 = 9; // Dönüş: UTF-8 bağ hedefi
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum GirdiTuru {
+    Dizin,
+    Dosya { boyut: u64, sha256: String },
+    SembolikBag { hedef: String },
+}
+
+impl GirdiTuru {
+    fn ad(&self) -> &'static str {
+        match self {
+            GirdiTuru::Dizin => "dizin",
+            GirdiTuru::Dosya { .. } => "dosya",
+            GirdiTuru::SembolikBag { .. } => "sembolik bağ",
+        }
+    }
+}
+
+// Paketin kurduğu tek bir girdinin kurulum anındaki hali.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestGirdisi {
+    pub yol: String, // Paket dizinine göre yol, sonunda '/' olmadan
+    pub tur: GirdiTuru,
+    pub izinler: Option<u32>, // Arşivde izin bilgisi yoksa None (denetlenmez)
+}
+
+impl ManifestGirdisi {
+    pub fn dizin(yol: &str, mod_bitleri: Option<u32>) -> Self {
+        ManifestGirdisi { yol: yol_anahtari(yol), tur: GirdiTuru::Dizin, izinler: mod_bitleri.map(|m| m & IZIN_MASKESI) }
+    }
+
+    pub fn dosya(yol: &str, icerik: &[u8], mod_bitleri: Option<u32>) -> Self {
+        ManifestGirdisi {
+            yol: yol_anahtari(yol),
+            tur: GirdiTuru::Dosya { boyut: icerik.len() as u64, sha256: hex::encode(Sha256::digest(icerik)) },
+            izinler: mod_bitleri.map(|m| m & IZIN_MASKESI),
+        }
+    }
+
+    pub fn sembolik_bag(yol: &str, hedef: &str) -> Self {
+        ManifestGirdisi { yol: yol_anahtari(yol), tur: GirdiTuru::SembolikBag { hedef: hedef.to_string() }, izinler: None }
+    }
+}
+
+fn yol_anahtari(yol: &str) -> String {
+    yol.trim_matches('/').to_string()
+}
+
+// Kurulu bir girdinin şimdiki hali.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gozlenen {
+    pub tur: GirdiTuru,
+    pub izinler: u32,
+}
+
+// Beklenen ve gözlenen hal arasındaki farkların okunabilir açıklamaları (fark yoksa boş).
+pub fn farklar(beklenen: &ManifestGirdisi, gozlenen: &Gozlenen) -> Vec<String> {
+    let mut farklar = Vec::new();
+    match (&beklenen.tur, &gozlenen.tur) {
+        (GirdiTuru::Dizin, GirdiTuru::Dizin) => {}
+        (GirdiTuru::Dosya { boyut: b1, sha256: s1 }, GirdiTuru::Dosya { boyut: b2, sha256: s2 }) => {
+            if b1 != b2 {
+                farklar.push(format!("boyut {} -> {}", b1, b2));
+            } else if s1 != s2 {
+                farklar.push(String::from("içerik (sha256)"));
+            }
+        }
+        (GirdiTuru::SembolikBag { hedef: h1 }, GirdiTuru::SembolikBag { hedef: h2 }) => {
+            if h1 != h2 {
+                farklar.push(format!("bağ hedefi {} -> {}", h1, h2));
+            }
+        }
+        (b, g) => farklar.push(format!("tür {} -> {}", b.ad(), g.ad())),
+    }
+    if let Some(izinler) = beklenen.izinler {
+        if izinler != gozlenen.izinler & IZIN_MASKESI {
+            farklar.push(format!("izinler {:o} -> {:o}", izinler, gozlenen.izinler & IZIN_MASKESI));
+        }
+    }
+    farklar
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SorunTuru {
+    Eksik,
+    Degistirilmis(Vec<String>), // Farkların açıklamaları
+    Fazla, // Paket dizininde olup manifestoda olmayan
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DosyaSorunu {
+    pub yol: String,
+    pub tur: SorunTuru,
+}
+
+#[derive(Debug, Default)]
+pub struct DogrulamaRaporu {
+    pub paket: String,
+    pub sorunlar: Vec<DosyaSorunu>,
+    // Manifestosu olmayan (bu denetimden önce kurulmuş) paketlerde sadece dosyaların varlığına bakılır.
+    pub manifestsiz: bool,
+}
+
+impl DogrulamaRaporu {
+    pub fn temiz_mi(&self) -> bool {
+        self.sorunlar.is_empty()
+    }
+
+    // Arşivden yeniden çıkarılarak onarılabilecek yollar (eksik ve değiştirilmiş olanlar).
+    pub fn onarilacaklar(&self) -> Vec<String> {
+        self.sorunlar.iter().filter(|s| s.tur != SorunTuru::Fazla).map(|s| s.yol.clone()).collect()
+    }
+}
+
+// Manifestoyu gözlenen hallerle karşılaştırıp rapor üretir.
+// gozle: Paket dizinine göre yolun şimdiki hali (yoksa None).
+// listelenen: Paket dizininde bulunan tüm yollar (fazla dosyalar için).
+pub fn karsilastir(
+    kayit: &KuruluPaket,
+    mut gozle: impl FnMut(&str) -> Result<Option<Gozlenen>, PaketYoneticisiHatasi>,
+    listelenen: &[String],
+) -> Result<DogrulamaRaporu, PaketYoneticisiHatasi> {
+    let mut rapor = DogrulamaRaporu { paket: kayit.ad.clone(), manifestsiz: kayit.manifesto.is_empty(), ..Default::default() };
+    let mut bilinen: BTreeSet<String> = BTreeSet::new();
+    if rapor.manifestsiz {
+        for yol in kayit.dosyalar.iter().map(|y| yol_anahtari(y)) {
+            if gozle(&yol)?.is_none() {
+                rapor.sorunlar.push(DosyaSorunu { yol: yol.clone(), tur: SorunTuru::Eksik });
+            }
+            bilinen.insert(yol);
+        }
+    } else {
+        for girdi in &kayit.manifesto {
+            let yapilandirma = kayit.yapilandirma_dosyalari.iter().any(|y| yol_anahtari(y) == girdi.yol);
+            match gozle(&girdi.yol)? {
+                None => rapor.sorunlar.push(DosyaSorunu { yol: girdi.yol.clone(), tur: SorunTuru::Eksik }),
+                Some(mut gozlenen) => {
+                    if yapilandirma && matches!((&girdi.tur, &gozlenen.tur), (GirdiTuru::Dosya { .. }, GirdiTuru::Dosya { .. })) {
+                        gozlenen.tur = girdi.tur.clone();
+                    }
+                    let farklar = farklar(girdi, &gozlenen);
+                    if !farklar.is_empty() {
+                        rapor.sorunlar.push(DosyaSorunu { yol: girdi.yol.clone(), tur: SorunTuru::Degistirilmis(farklar) });
+                    }
+                }
+            }
+            bilinen.insert(girdi.yol.clone());
+        }
+    }
+    // Güncellemede korunan yapılandırma dosyalarının yanına yazılan yeni varsayılanlar da paketindir.
+    bilinen.extend(kayit.yapilandirma_dosyalari.iter().map(|y| format!("{}{}", yol_anahtari(y), YENI_UZANTISI)));
+    // Bilinen yolların üst dizinleri de (arşivde ayrı girdi olmasalar bile) paketindir.
+    let ust_dizinler: Vec<String> = bilinen
+        .iter()
+        .flat_map(|yol| yol.match_indices('/').map(move |(i, _)| yol[..i].to_string()))
+        .collect();
+    bilinen.extend(ust_dizinler);
+    for yol in listelenen.iter().map(|y| yol_anahtari(y)) {
+        if !bilinen.contains(&yol) {
+            rapor.sorunlar.push(DosyaSorunu { yol, tur: SorunTuru::Fazla });
+        }
+    }
+    Ok(rapor)
+}
+
+// Kurulu bir paketin dosyalarını manifestosuyla karşılaştırır.
+// kurulum_base: Paketlerin kurulduğu temel Kaynak ID'si (paket "<kurulum_base>/<ad>/" altında).
+pub fn paketi_dogrula(kurulum_base: &str, kayit: &KuruluPaket) -> Result<DogrulamaRaporu, PaketYoneticisiHatasi> {
+    let kok = format!("{}/{}/", kurulum_base, kayit.ad);
+    let listelenen = alt_kaynaklari_listele(&kok, true).map_err(PaketYoneticisiHatasi::from)?;
+    karsilastir(kayit, |yol| gozle(&format!("{}{}", kok, yol)).map_err(PaketYoneticisiHatasi::from), &listelenen)
+}
+
+// Kurulum kökündeki dizinlerden veritabanında kaydı olmayanlar (örn. kayıt tutmayan eski kurulum yoluyla
+// kurulmuş paketler). Bunların manifestosu olmadığından doğrulanamaz; yeniden kurulmaları gerekir.
+// Gizli dizinler (hazırlık alanı gibi) paket değildir ve atlanır.
+pub fn kayitsiz_dizinleri_bul(listelenen: &[String], kurulu: &KuruluVeritabani) -> Vec<String> {
+    listelenen
+        .iter()
+        .filter_map(|ad| ad.strip_suffix('/'))
+        .filter(|ad| !ad.is_empty() && !ad.starts_with('.') && !kurulu.kurulu_mu(ad))
+        .map(|ad| ad.to_string())
+        .collect()
+}
+
+// Kurulum kökündeki kaydı olmayan paket dizinleri (bkz. kayitsiz_dizinleri_bul).
+pub fn kayitsiz_dizinler(kurulum_base: &str, kurulu: &KuruluVeritabani) -> Result<Vec<String>, PaketYoneticisiHatasi> {
+    let listelenen = alt_kaynaklari_listele(&format!("{}/", kurulum_base), false).map_err(PaketYoneticisiHatasi::from)?;
+    Ok(kayitsiz_dizinleri_bul(&listelenen, kurulu))
+}
+
+// Düzenli bir dosyanın SHA-256 özeti; Kaynak yoksa veya düzenli dosya değilse None.
+pub fn dosya_ozeti(resource_id: &str) -> Result<Option<String>, SahneError> {
+    Ok(match gozle(resource_id)? {
+        Some(Gozlenen { tur: GirdiTuru::Dosya { sha256, .. }, .. }) => Some(sha256),
+        _ => None,
+    })
+}
+
+// Bir Kaynağın şimdiki halini okur; düzenli dosyaların içeriği özet için baştan sona okunur.
+fn gozle(resource_id: &str) -> Result<Option<Gozlenen>, SahneError> {
+    let handle = match resource::acquire(resource_id, resource::MODE_READ) {
+        Ok(h) => h,
+        Err(SahneError::ResourceNotFound) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let sonuc = gozle_handle(handle);
+    let _ = resource::release(handle);
+    sonuc.map(Some)
+}
+
+fn gozle_handle(handle: crate::Handle) -> Result<Gozlenen, SahneError> {
+    let stat = resource::control(handle, RESOURCE_CONTROL_CMD_STAT, &[])?;
+    if stat.len() < 4 {
+        return Err(SahneError::InvalidOperation);
+    }
+    let mod_bitleri = u32::from_le_bytes([stat[0], stat[1], stat[2], stat[3]]);
+    let tur = match mod_bitleri & S_IFMT {
+        S_IFDIR => GirdiTuru::Dizin,
+        S_IFLNK => {
+            let hedef = resource::control(handle, RESOURCE_CONTROL_CMD_READ_SYMLINK, &[])?;
+            GirdiTuru::SembolikBag { hedef: String::from_utf8_lossy(&hedef).into_owned() }
+        }
+        S_IFREG => {
+            let mut sha256 = Sha256::new();
+            let mut boyut: u64 = 0;
+            let mut buffer = [0u8; 4096];
+            loop {
+                match resource::read(handle, &mut buffer)? {
+                    0 => break,
+                    n => {
+                        sha256.update(&buffer[..n]);
+                        boyut += n as u64;
+                    }
+                }
+            }
+            GirdiTuru::Dosya { boyut, sha256: hex::encode(sha256.finalize()) }
+        }
+        // Aygıt/FIFO: paketler bunları kurmaz, her zaman tür farkı olarak görünür.
+        _ => GirdiTuru::Dosya { boyut: 0, sha256: String::new() },
+    };
+    Ok(Gozlenen { tur, izinler: mod_bitleri & IZIN_MASKESI })
+}
+
+// Bir dizin Kaynağının altındaki yollar (dizine göre; ozyinelemeli ise alt dizinlerin içeriği de). Dizin yoksa
+// boş liste döner.
+fn alt_kaynaklari_listele(dizin_id: &str, ozyinelemeli: bool) -> Result<Vec<String>, SahneError> {
+    let mut yollar = Vec::new();
+    let mut yigin: Vec<String> = Vec::new();
+    yigin.push(String::new());
+    while let Some(onek) = yigin.pop() {
+        let handle = match resource::acquire(&format!("{}{}", dizin_id, onek), resource::MODE_READ) {
+            Ok(h) => h,
+            Err(SahneError::ResourceNotFound) => continue,
+            Err(e) => return Err(e),
+        };
+        let liste = resource::control(handle, RESOURCE_CONTROL_CMD_LIST, &[]);
+        let _ = resource::release(handle);
+        let liste = liste?;
+        for ad in String::from_utf8_lossy(&liste).lines().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let yol = format!("{}{}", onek, ad);
+            if ozyinelemeli && yol.ends_with('/') {
+                yigin.push(yol.clone());
+            }
+            yollar.push(yol);
+        }
+    }
+    Ok(yollar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Paket;
+    use alloc::collections::BTreeMap;
+
+    fn kayit(manifesto: Vec<ManifestGirdisi>) -> KuruluPaket {
+        let mut paket = Paket::yeni("arac".to_string(), "1.0".to_string(), Vec::new());
+        paket.dosyalar = vec!["bin/arac".to_string(), "share/arac/veri".to_string()];
+        let mut kayit = KuruluPaket::paketten(&paket);
+        kayit.manifesto = manifesto;
+        kayit
+    }
+
+    fn gozlenen(girdi: &ManifestGirdisi) -> Gozlenen {
+        Gozlenen { tur: girdi.tur.clone(), izinler: girdi.izinler.unwrap_or(0o644) }
+    }
+
+    #[test]
+    fn test_farklar() {
+        let girdi = ManifestGirdisi::dosya("bin/arac", b"#!/bin/sh\n", Some(0o100755));
+        assert_eq!(girdi.izinler, Some(0o755));
+        assert!(farklar(&girdi, &gozlenen(&girdi)).is_empty());
+
+        let degismis = ManifestGirdisi::dosya("bin/arac", b"#!/bin/sx\n", Some(0o777));
+        assert_eq!(farklar(&girdi, &gozlenen(&degismis)), ["içerik (sha256)", "izinler 755 -> 777"]);
+        let bag = ManifestGirdisi::sembolik_bag("bin/arac", "../lib/arac");
+        assert_eq!(farklar(&girdi, &gozlenen(&bag))[0], "tür dosya -> sembolik bağ");
+    }
+
+    #[test]
+    fn test_eksik_degismis_ve_fazla() {
+        let manifesto = vec![
+            ManifestGirdisi::dizin("bin/", Some(0o755)),
+            ManifestGirdisi::dosya("bin/arac", b"ikili", Some(0o755)),
+            ManifestGirdisi::dosya("share/arac/veri", b"veri", None),
+        ];
+        let mut disk: BTreeMap<String, Gozlenen> = manifesto.iter().map(|g| (g.yol.clone(), gozlenen(g))).collect();
+        disk.insert("bin/arac".to_string(), gozlenen(&ManifestGirdisi::dosya("bin/arac", b"yamali", Some(0o755))));
+        disk.remove("share/arac/veri");
+        let listelenen: Vec<String> = ["bin/", "bin/arac", "bin/arac.bak", "share/", "share/arac/"].iter().map(|s| s.to_string()).collect();
+
+        let rapor = karsilastir(&kayit(manifesto), |yol| Ok(disk.get(yol).cloned()), &listelenen).unwrap();
+        assert_eq!(rapor.sorunlar, vec![
+            DosyaSorunu { yol: "bin/arac".to_string(), tur: SorunTuru::Degistirilmis(vec!["boyut 5 -> 6".to_string()]) },
+            DosyaSorunu { yol: "share/arac/veri".to_string(), tur: SorunTuru::Eksik },
+            DosyaSorunu { yol: "bin/arac.bak".to_string(), tur: SorunTuru::Fazla },
+        ]);
+        assert_eq!(rapor.onarilacaklar(), ["bin/arac", "share/arac/veri"]);
+    }
+
+    #[test]
+    fn test_yapilandirma_dosyasi_icerigi_denetlenmez() {
+        let manifesto = vec![
+            ManifestGirdisi::dosya("bin/arac", b"ikili", Some(0o755)),
+            ManifestGirdisi::dosya("share/arac/veri", b"ayar=1", Some(0o644)),
+        ];
+        let mut kayit = kayit(manifesto.clone());
+        kayit.yapilandirma_dosyalari = vec!["share/arac/veri".to_string()];
+        let mut disk: BTreeMap<String, Gozlenen> = manifesto.iter().map(|g| (g.yol.clone(), gozlenen(g))).collect();
+        disk.insert("share/arac/veri".to_string(), gozlenen(&ManifestGirdisi::dosya("share/arac/veri", b"ayar=2\n", Some(0o600))));
+        let listelenen: Vec<String> = ["bin/arac", "share/arac/veri", "share/arac/veri.yeni"].iter().map(|s| s.to_string()).collect();
+
+        let rapor = karsilastir(&kayit, |yol| Ok(disk.get(yol).cloned()), &listelenen).unwrap();
+        assert_eq!(rapor.sorunlar, vec![
+            DosyaSorunu { yol: "share/arac/veri".to_string(), tur: SorunTuru::Degistirilmis(vec!["izinler 644 -> 600".to_string()]) },
+        ]);
+    }
+
+    #[test]
+    fn test_kayitsiz_dizinler() {
+        let mut kurulu = KuruluVeritabani::default();
+        kurulu.ekle(kayit(Vec::new()));
+        let listelenen: Vec<String> = ["arac/", "eski/", ".hazirlik/", "dosya"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(kayitsiz_dizinleri_bul(&listelenen, &kurulu), ["eski"]);
+    }
+
+    #[test]
+    fn test_manifestsiz_paket_sadece_varlik_denetlenir() {
+        let rapor = karsilastir(&kayit(Vec::new()), |yol| Ok((yol == "bin/arac").then(|| Gozlenen { tur: GirdiTuru::Dizin, izinler: 0 })), &[]).unwrap();
+        assert!(rapor.manifestsiz);
+        assert_eq!(rapor.sorunlar, vec![DosyaSorunu { yol: "share/arac/veri".to_string(), tur: SorunTuru::Eksik }]);
+    }
+}